@@ -27,6 +27,9 @@ pub struct Project {
     pub license: String,
     #[serde(default = "default_entry")]
     pub entry: String,
+    /// Names of the contract ABI methods expected to be called most often, most frequent first.
+    /// Their selectors are tested first when dispatching a call to the contract.
+    pub hot_abi_methods: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -34,6 +37,9 @@ pub struct Project {
 pub struct Network {
     #[serde(default = "default_url")]
     pub url: String,
+    /// The maximum size in bytes of the bytecode of a contract deployed to this network.
+    #[serde(default = "default_contract_size_limit")]
+    pub contract_size_limit: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -136,6 +142,14 @@ impl Manifest {
         Ok(Arc::from(entry_string))
     }
 
+    /// The maximum size in bytes of the bytecode of a contract deployed to the network.
+    pub fn contract_size_limit(&self) -> u64 {
+        self.network
+            .as_ref()
+            .map(|network| network.contract_size_limit)
+            .unwrap_or(constants::DEFAULT_CONTRACT_SIZE_LIMIT)
+    }
+
     /// Produce an iterator yielding all listed dependencies.
     pub fn deps(&self) -> impl Iterator<Item = (&String, &Dependency)> {
         self.dependencies
@@ -160,3 +174,7 @@ fn default_entry() -> String {
 fn default_url() -> String {
     constants::DEFAULT_NODE_URL.into()
 }
+
+fn default_contract_size_limit() -> u64 {
+    constants::DEFAULT_CONTRACT_SIZE_LIMIT
+}
@@ -16,8 +16,9 @@ use std::{
     str::FromStr,
 };
 use sway_core::{
-    source_map::SourceMap, BytecodeCompilationResult, CompileAstResult, NamespaceRef,
-    NamespaceWrapper, TreeType, TypedParseTree,
+    debug_info::DebugInfo, source_map::SourceMap, symbol_table::SymbolTable,
+    BytecodeCompilationResult, CompileAstResult, NamespaceRef, NamespaceWrapper, TreeType,
+    TypedParseTree,
 };
 use sway_types::JsonABI;
 use url::Url;
@@ -39,6 +40,8 @@ pub struct PinnedId(u64);
 pub struct Compiled {
     pub json_abi: JsonABI,
     pub bytecode: Vec<u8>,
+    pub symbol_table: SymbolTable,
+    pub debug_info: DebugInfo,
 }
 
 /// A package uniquely identified by name along with its source.
@@ -133,6 +136,9 @@ pub struct BuildConfig {
     pub print_ir: bool,
     pub print_finalized_asm: bool,
     pub print_intermediate_asm: bool,
+    pub skip_bounds_checks: bool,
+    pub optimization_level: sway_core::OptLevel,
+    pub size_report: bool,
     pub silent: bool,
 }
 
@@ -801,7 +807,12 @@ pub fn sway_build_config(
     .use_ir(build_conf.use_ir || build_conf.print_ir) // --print-ir implies --use-ir.
     .print_finalized_asm(build_conf.print_finalized_asm)
     .print_intermediate_asm(build_conf.print_intermediate_asm)
-    .print_ir(build_conf.print_ir);
+    .print_ir(build_conf.print_ir)
+    .skip_bounds_checks(build_conf.skip_bounds_checks)
+    .optimization_level(build_conf.optimization_level)
+    .size_report(build_conf.size_report)
+    .contract_size_limit(manifest.contract_size_limit())
+    .hot_abi_methods(manifest.project.hot_abi_methods.clone().unwrap_or_default());
     Ok(build_config)
 }
 
@@ -890,20 +901,46 @@ pub fn compile(
                     print_on_success_library(silent_mode, &pkg.name, warnings);
                     let bytecode = vec![];
                     let lib_namespace = parse_tree.clone().get_namespace_ref();
-                    let compiled = Compiled { json_abi, bytecode };
+                    let symbol_table = SymbolTable::new();
+                    let debug_info = DebugInfo::new();
+                    let compiled = Compiled {
+                        json_abi,
+                        bytecode,
+                        symbol_table,
+                        debug_info,
+                    };
                     Ok((compiled, Some(lib_namespace)))
                 }
 
                 // For all other program types, we'll compile the bytecode.
                 TreeType::Contract | TreeType::Predicate | TreeType::Script => {
                     let tree_type = tree_type.clone();
-                    let asm_res = sway_core::ast_to_asm(ast_res, &sway_build_config);
+                    let mut symbol_table = SymbolTable::new();
+                    let mut debug_info = DebugInfo::new();
+                    let asm_res = sway_core::ast_to_asm(
+                        ast_res,
+                        &sway_build_config,
+                        &mut symbol_table,
+                        &mut debug_info,
+                    );
                     let bc_res = sway_core::asm_to_bytecode(asm_res, source_map);
                     match bc_res {
-                        BytecodeCompilationResult::Success { bytes, warnings } => {
+                        BytecodeCompilationResult::Success {
+                            bytes,
+                            warnings,
+                            size_report,
+                        } => {
                             print_on_success(silent_mode, &pkg.name, &warnings, &tree_type);
+                            if let Some(size_report) = size_report.filter(|_| !silent_mode) {
+                                println!("{}", size_report);
+                            }
                             let bytecode = bytes;
-                            let compiled = Compiled { json_abi, bytecode };
+                            let compiled = Compiled {
+                                json_abi,
+                                bytecode,
+                                symbol_table,
+                                debug_info,
+                            };
                             Ok((compiled, None))
                         }
                         BytecodeCompilationResult::Library { .. } => {
@@ -930,6 +967,8 @@ pub fn build(plan: &BuildPlan, conf: &BuildConfig) -> anyhow::Result<(Compiled,
     let mut source_map = SourceMap::new();
     let mut json_abi = vec![];
     let mut bytecode = vec![];
+    let mut symbol_table = SymbolTable::new();
+    let mut debug_info = DebugInfo::new();
     for &node in &plan.compilation_order {
         let dep_namespace =
             dependency_namespace(&namespace_map, &plan.graph, &plan.compilation_order, node);
@@ -942,9 +981,16 @@ pub fn build(plan: &BuildPlan, conf: &BuildConfig) -> anyhow::Result<(Compiled,
         }
         json_abi.extend(compiled.json_abi);
         bytecode = compiled.bytecode;
+        symbol_table = compiled.symbol_table;
+        debug_info = compiled.debug_info;
         source_map.insert_dependency(path.clone());
     }
-    let compiled = Compiled { bytecode, json_abi };
+    let compiled = Compiled {
+        bytecode,
+        json_abi,
+        symbol_table,
+        debug_info,
+    };
     Ok((compiled, source_map))
 }
 
@@ -1,3 +1,4 @@
+use crate::utils::symbols::read_symbol_table;
 use anyhow::{anyhow, Result};
 use clap::Parser;
 use std::collections::VecDeque;
@@ -11,6 +12,7 @@ use annotate_snippets::{
 };
 
 use sway_core::source_map::{LocationRange, SourceMap};
+use sway_core::symbol_table::SymbolTable;
 
 /// Show location and context of an opcode address in its source file
 #[derive(Debug, Parser)]
@@ -21,6 +23,9 @@ pub(crate) struct Command {
     /// Source file mapping in JSON format
     #[clap(short = 'g', long)]
     pub sourcemap_path: PathBuf,
+    /// Symbol table in JSON format, to show the functions inlined into one another at the address
+    #[clap(long)]
+    pub symbols_path: Option<PathBuf>,
     /// How many lines of context to show
     #[clap(short, long, default_value = "2")]
     pub context: usize,
@@ -41,6 +46,16 @@ pub(crate) fn exec(command: Command) -> Result<()> {
         )
     })?;
 
+    let functions = match &command.symbols_path {
+        Some(symbols_path) => {
+            inlined_functions(&read_symbol_table(symbols_path)?, command.opcode_index)
+        }
+        None => vec![],
+    };
+    if !functions.is_empty() {
+        println!("in {}", functions.join(", inlined into "));
+    }
+
     if let Some((mut path, range)) = sm.addr_to_span(command.opcode_index) {
         if path.is_relative() {
             path = command.search_dir.join(path);
@@ -71,12 +86,24 @@ pub(crate) fn exec(command: Command) -> Result<()> {
         };
         println!("{}", DisplayList::from(snippet));
 
+        Ok(())
+    } else if !functions.is_empty() {
         Ok(())
     } else {
         Err(anyhow!("Address did not map to any source code location"))
     }
 }
 
+/// The names of the functions inlined into one another at an opcode index, innermost first.
+fn inlined_functions(symbol_table: &SymbolTable, opcode_index: usize) -> Vec<String> {
+    symbol_table
+        .scopes_at(opcode_index)
+        .iter()
+        .rev()
+        .map(|scope| scope.name.clone())
+        .collect()
+}
+
 struct ReadRange {
     source: String,
     source_start_byte: usize,
@@ -139,3 +166,31 @@ fn read_range<P: AsRef<Path>>(
         length,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn functions_from_symbols() {
+        let symbol_table: SymbolTable = serde_json::from_str(
+            r#"{
+  "functions": [
+    { "name": "main", "range": { "start": 6, "end": 14 }, "depth": 0 },
+    { "name": "outer", "range": { "start": 8, "end": 12 }, "depth": 1 },
+    { "name": "inner", "range": { "start": 9, "end": 11 }, "depth": 2 }
+  ],
+  "abi_methods": [],
+  "data": []
+}"#,
+        )
+        .unwrap();
+        assert_eq!(inlined_functions(&symbol_table, 6), ["main"]);
+        assert_eq!(
+            inlined_functions(&symbol_table, 10),
+            ["inner", "outer", "main"]
+        );
+        assert_eq!(inlined_functions(&symbol_table, 11), ["outer", "main"]);
+        assert!(inlined_functions(&symbol_table, 14).is_empty());
+    }
+}
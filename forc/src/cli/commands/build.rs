@@ -1,6 +1,7 @@
 use crate::ops::forc_build;
 use anyhow::Result;
 use clap::Parser;
+use sway_core::OptLevel;
 
 /// Compile the current or target project.
 ///
@@ -13,7 +14,8 @@ pub struct Command {
     /// Path to the project, if not specified, current working directory will be used.
     #[clap(short, long)]
     pub path: Option<String>,
-    /// Whether to compile using the IR pipeline.
+    /// Whether to compile using the IR pipeline.  The debug info then records no variable
+    /// locations.
     #[clap(long)]
     pub use_ir: bool,
     /// Whether to compile to bytecode (false) or to print out the generated ASM (true).
@@ -25,6 +27,20 @@ pub struct Command {
     /// Whether to compile to bytecode (false) or to print out the generated IR (true).
     #[clap(long)]
     pub print_ir: bool,
+    /// Omit the runtime bounds checks on array indices which are proven to be in range by a
+    /// comparison against a constant before the access.  This needs the IR backend, and every
+    /// other index which isn't a constant is still checked.
+    #[clap(long)]
+    pub skip_bounds_checks: bool,
+    /// The optimization level for the IR pipeline, one of `0`, `1` or `s`.  `s` repeats the
+    /// optimizations of `1` until they no longer reduce the number of instructions.
+    #[clap(short = 'O', long = "optimization-level", default_value = "1")]
+    pub optimization_level: OptLevel,
+    /// Print the number of instructions and bytes generated for each source function, the size of
+    /// each data section entry, and an estimate of the worst-case gas used by each loop-free entry
+    /// point.
+    #[clap(long)]
+    pub size_report: bool,
     /// If set, outputs a binary file representing the script bytes.
     #[clap(short = 'o')]
     pub binary_outfile: Option<String>,
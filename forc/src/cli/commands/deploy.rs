@@ -1,6 +1,7 @@
 use crate::ops::forc_deploy;
 use anyhow::{bail, Result};
 use clap::Parser;
+use sway_core::OptLevel;
 
 /// Deploy contract project.
 /// Crafts a contract deployment transaction then sends it to a running node.
@@ -21,6 +22,15 @@ pub struct Command {
     /// Whether to compile to bytecode (false) or to print out the IR (true).
     #[clap(long)]
     pub print_ir: bool,
+    /// Omit the runtime bounds checks on array indices which are proven to be in range by a
+    /// comparison against a constant before the access.  This needs the IR backend, and every
+    /// other index which isn't a constant is still checked.
+    #[clap(long)]
+    pub skip_bounds_checks: bool,
+    /// The optimization level for the IR pipeline, one of `0`, `1` or `s`.  `s` repeats the
+    /// optimizations of `1` until they no longer reduce the number of instructions.
+    #[clap(short = 'O', long = "optimization-level", default_value = "1")]
+    pub optimization_level: OptLevel,
     /// If set, outputs a binary file representing the script bytes.
     #[clap(short = 'o')]
     pub binary_outfile: Option<String>,
@@ -1,7 +1,10 @@
+use crate::utils::symbols::read_symbol_table;
 use anyhow::{anyhow, Result};
 use clap::Parser;
 use std::fs::{self, File};
 use std::io::Read;
+use std::path::PathBuf;
+use sway_core::symbol_table::SymbolTable;
 use term_table::row::Row;
 use term_table::table_cell::{Alignment, TableCell};
 
@@ -9,6 +12,9 @@ use term_table::table_cell::{Alignment, TableCell};
 #[derive(Debug, Parser)]
 pub(crate) struct Command {
     file_path: String,
+    /// Symbol table in JSON format, as written next to the bytecode by `forc build`
+    #[clap(short = 's', long)]
+    symbols_path: Option<PathBuf>,
 }
 
 pub(crate) fn exec(command: Command) -> Result<()> {
@@ -18,6 +24,10 @@ pub(crate) fn exec(command: Command) -> Result<()> {
         .map_err(|_| anyhow!("{}: file not found", command.file_path))?;
     let mut buffer = vec![0; metadata.len() as usize];
     f.read_exact(&mut buffer).expect("buffer overflow");
+    let symbol_table = match &command.symbols_path {
+        Some(symbols_path) => Some(read_symbol_table(symbols_path)?),
+        None => None,
+    };
     let mut instructions = vec![];
 
     for i in (0..buffer.len()).step_by(4) {
@@ -35,6 +45,7 @@ pub(crate) fn exec(command: Command) -> Result<()> {
         TableCell::new("byte"),
         TableCell::new("op"),
         TableCell::new("raw"),
+        TableCell::new("function"),
         TableCell::new("notes"),
     ]));
     table.style = term_table::TableStyle::empty();
@@ -58,11 +69,16 @@ pub(crate) fn exec(command: Command) -> Result<()> {
             }
             _ => "".into(),
         };
+        let (function, notes) = match &symbol_table {
+            Some(symbol_table) => symbol_notes(symbol_table, word_ix, notes),
+            None => ("".into(), notes),
+        };
         table.add_row(Row::new(vec![
             TableCell::new_with_alignment(word_ix, 1, Alignment::Right),
             TableCell::new(word_ix * 4),
             TableCell::new(format!("{:?}", instruction.1)),
             TableCell::new(format!("{:?}", instruction.0)),
+            TableCell::new(function),
             TableCell::new(notes),
         ]));
     }
@@ -71,3 +87,82 @@ pub(crate) fn exec(command: Command) -> Result<()> {
 
     Ok(())
 }
+
+/// The functions inlined into one another at a half-word, and its notes with the ABI method
+/// starting there or the data section entry containing it.
+fn symbol_notes(symbol_table: &SymbolTable, word_ix: usize, notes: String) -> (String, String) {
+    let function = symbol_table
+        .scopes_at(word_ix)
+        .iter()
+        .map(|scope| scope.name.as_str())
+        .collect::<Vec<_>>()
+        .join(" > ");
+    let symbol_note = if let Some(method) = symbol_table.abi_method_at(word_ix) {
+        Some(format!(
+            "entry of ABI method {} (selector {:#010x})",
+            method.name, method.selector
+        ))
+    } else {
+        symbol_table.data_at(word_ix * 4).map(|(data_ix, entry)| {
+            match (&entry.name, entry.pointer_to) {
+                (Some(name), _) => format!("data {} ({})", data_ix, name),
+                (None, Some(target)) => format!("data {} (pointer to data {})", data_ix, target),
+                (None, None) => format!("data {}", data_ix),
+            }
+        })
+    };
+    let notes = match symbol_note {
+        Some(symbol_note) if notes.is_empty() => symbol_note,
+        Some(symbol_note) => format!("{}, {}", notes, symbol_note),
+        None => notes,
+    };
+    (function, notes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYMBOLS: &str = r#"{
+  "functions": [
+    { "name": "get", "range": { "start": 6, "end": 10 }, "depth": 0 },
+    { "name": "helper", "range": { "start": 7, "end": 9 }, "depth": 1 }
+  ],
+  "abi_methods": [
+    { "name": "get", "selector": 1974928471, "entry": 6 }
+  ],
+  "data": [
+    { "offset": 40, "size": 32, "name": null, "pointer_to": null },
+    { "offset": 72, "size": 8, "name": "LIMIT", "pointer_to": null },
+    { "offset": 80, "size": 8, "name": null, "pointer_to": 0 }
+  ]
+}"#;
+
+    #[test]
+    fn notes_from_symbols() {
+        let symbol_table: SymbolTable = serde_json::from_str(SYMBOLS).unwrap();
+        let notes = |word_ix, notes: &str| symbol_notes(&symbol_table, word_ix, notes.into());
+        assert_eq!(
+            notes(6, ""),
+            (
+                "get".into(),
+                "entry of ABI method get (selector 0x75b70457)".into()
+            )
+        );
+        assert_eq!(
+            notes(8, "conditionally jumps to byte 40"),
+            (
+                "get > helper".into(),
+                "conditionally jumps to byte 40".into()
+            )
+        );
+        assert_eq!(notes(10, ""), ("".into(), "data 0".into()));
+        assert_eq!(notes(17, ""), ("".into(), "data 0".into()));
+        assert_eq!(notes(18, ""), ("".into(), "data 1 (LIMIT)".into()));
+        assert_eq!(
+            notes(20, ""),
+            ("".into(), "data 2 (pointer to data 0)".into())
+        );
+        assert_eq!(notes(22, ""), ("".into(), "".into()));
+    }
+}
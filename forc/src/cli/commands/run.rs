@@ -1,6 +1,7 @@
 use crate::ops::forc_run;
 use anyhow::{bail, Result};
 use clap::Parser;
+use sway_core::OptLevel;
 
 /// Run script project.
 /// Crafts a script transaction then sends it to a running node.
@@ -43,6 +44,17 @@ pub struct Command {
     #[clap(long)]
     pub print_ir: bool,
 
+    /// Omit the runtime bounds checks on array indices which are proven to be in range by a
+    /// comparison against a constant before the access.  This needs the IR backend, and every
+    /// other index which isn't a constant is still checked.
+    #[clap(long)]
+    pub skip_bounds_checks: bool,
+
+    /// The optimization level for the IR pipeline, one of `0`, `1` or `s`.  `s` repeats the
+    /// optimizations of `1` until they no longer reduce the number of instructions.
+    #[clap(short = 'O', long = "optimization-level", default_value = "1")]
+    pub optimization_level: OptLevel,
+
     /// If set, outputs a binary file representing the script bytes.
     #[clap(short = 'o')]
     pub binary_outfile: Option<String>,
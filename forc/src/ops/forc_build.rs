@@ -17,6 +17,9 @@ pub fn build(command: BuildCommand) -> Result<pkg::Compiled> {
         print_finalized_asm,
         print_intermediate_asm,
         print_ir,
+        skip_bounds_checks,
+        optimization_level,
+        size_report,
         offline_mode: offline,
         silent_mode,
         output_directory,
@@ -28,6 +31,9 @@ pub fn build(command: BuildCommand) -> Result<pkg::Compiled> {
         print_ir,
         print_finalized_asm,
         print_intermediate_asm,
+        skip_bounds_checks,
+        optimization_level,
+        size_report,
         silent: silent_mode,
     };
 
@@ -107,6 +113,14 @@ pub fn build(command: BuildCommand) -> Result<pkg::Compiled> {
         .join(&manifest.project.name)
         .with_extension("bin");
     fs::write(&bin_path, &compiled.bytecode)?;
+    let symbols_stem = format!("{}-symbols", manifest.project.name);
+    let symbols_path = output_dir.join(&symbols_stem).with_extension("json");
+    let file = File::create(symbols_path)?;
+    serde_json::to_writer_pretty(&file, &compiled.symbol_table)?;
+    let debug_stem = format!("{}-debug", manifest.project.name);
+    let debug_path = output_dir.join(&debug_stem).with_extension("json");
+    let file = File::create(debug_path)?;
+    serde_json::to_writer_pretty(&file, &compiled.debug_info)?;
     if !compiled.json_abi.is_empty() {
         let json_abi_stem = format!("{}-abi", manifest.project.name);
         let json_abi_path = output_dir.join(&json_abi_stem).with_extension("json");
@@ -24,6 +24,8 @@ pub async fn deploy(command: DeployCommand) -> Result<fuel_tx::ContractId, CliEr
         print_finalized_asm,
         print_intermediate_asm,
         print_ir,
+        skip_bounds_checks,
+        optimization_level,
         binary_outfile,
         debug_outfile,
         offline_mode,
@@ -49,6 +51,9 @@ pub async fn deploy(command: DeployCommand) -> Result<fuel_tx::ContractId, CliEr
                             print_finalized_asm,
                             print_intermediate_asm,
                             print_ir,
+                            skip_bounds_checks,
+                            optimization_level,
+                            size_report: false,
                             binary_outfile,
                             offline_mode,
                             debug_outfile,
@@ -40,6 +40,9 @@ pub async fn run(command: RunCommand) -> Result<(), CliError> {
                             print_finalized_asm: command.print_finalized_asm,
                             print_intermediate_asm: command.print_intermediate_asm,
                             print_ir: command.print_ir,
+                            skip_bounds_checks: command.skip_bounds_checks,
+                            optimization_level: command.optimization_level,
+                            size_report: false,
                             binary_outfile: command.binary_outfile,
                             debug_outfile: command.debug_outfile,
                             offline_mode: false,
@@ -1,3 +1,4 @@
 pub mod cli_error;
 pub mod client;
 pub mod defaults;
+pub mod symbols;
@@ -0,0 +1,53 @@
+use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::Path;
+use sway_core::symbol_table::SymbolTable;
+
+/// Read a symbol table in JSON format, as written next to the bytecode by `forc build`.
+pub fn read_symbol_table(symbols_path: &Path) -> Result<SymbolTable> {
+    let contents = fs::read(symbols_path)
+        .map_err(|err| anyhow!("{:?}: could not read: {:?}", symbols_path, err))?;
+    serde_json::from_slice(&contents)
+        .map_err(|err| anyhow!("{:?}: invalid symbol table json: {}", symbols_path, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use sway_core::symbol_table::{FunctionScope, InstructionRange};
+
+    #[test]
+    fn read_written_symbol_table() {
+        let symbol_table = SymbolTable {
+            functions: vec![FunctionScope {
+                name: "main".into(),
+                range: InstructionRange { start: 6, end: 10 },
+                depth: 0,
+            }],
+            ..SymbolTable::new()
+        };
+        let symbols_path = std::env::temp_dir().join("forc-read-written-symbol-table.json");
+        let file = File::create(&symbols_path).unwrap();
+        serde_json::to_writer_pretty(&file, &symbol_table).unwrap();
+
+        let read = read_symbol_table(&symbols_path).unwrap();
+        fs::remove_file(&symbols_path).unwrap();
+        assert_eq!(read.functions.len(), 1);
+        assert_eq!(read.functions[0].name, "main");
+        assert_eq!(read.functions[0].range, symbol_table.functions[0].range);
+        assert!(read.abi_methods.is_empty() && read.data.is_empty());
+    }
+
+    #[test]
+    fn read_invalid_symbol_table() {
+        let symbols_path = std::env::temp_dir().join("forc-read-invalid-symbol-table.json");
+        fs::write(&symbols_path, r#"{ "functions": [] }"#).unwrap();
+        let err = read_symbol_table(&symbols_path).unwrap_err();
+        fs::remove_file(&symbols_path).unwrap();
+        assert!(err.to_string().contains("invalid symbol table json"));
+
+        let err = read_symbol_table(&symbols_path).unwrap_err();
+        assert!(err.to_string().contains("could not read"));
+    }
+}
@@ -19,6 +19,6 @@ pub(crate) fn convert_constant_decl_to_asm(
         &val_register,
         register_sequencer,
     );
-    namespace.insert_variable(const_decl.name.clone(), val_register);
+    namespace.insert_variable(const_decl.name.clone(), val_register, register_sequencer);
     initialization
 }
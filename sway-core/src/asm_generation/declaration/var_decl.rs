@@ -15,6 +15,6 @@ pub(crate) fn convert_variable_decl_to_asm(
     let var_register = register_sequencer.next();
     let initialization =
         convert_expression_to_asm(&var_decl.body, namespace, &var_register, register_sequencer);
-    namespace.insert_variable(var_decl.name.clone(), var_register);
+    namespace.insert_variable(var_decl.name.clone(), var_register, register_sequencer);
     initialization
 }
@@ -259,20 +259,25 @@ pub(super) fn convert_array_index_to_asm(
         errors
     ));
 
-    // Put the last valid array index (count - 1) into a register using a recursive helper.
-    let count_reg = register_sequencer.next();
-    set_large_register_value(count - 1, &count_reg, &mut bytecode, span);
-
-    // Add an assertion that the index is within bounds.  When we have IR we can more easily check
-    // if the index is a) constant, and b) within bounds, removing the need for a runtime
-    // assertion.
-    compile_bounds_assertion(
-        &mut bytecode,
-        &count_reg,
-        &index_reg,
-        span,
-        register_sequencer,
-    );
+    // Add an assertion that the index is within bounds, unless the index is a literal which has
+    // been checked above.  Nothing else is proven to be in range here, so `--skip-bounds-checks`
+    // only has an effect with the IR backend.
+    if !matches!(
+        index.expression,
+        TypedExpressionVariant::Literal(Literal::U64(_))
+    ) {
+        // Put the last valid array index (count - 1) into a register using a recursive helper.
+        let count_reg = register_sequencer.next();
+        set_large_register_value(count - 1, &count_reg, &mut bytecode, span);
+        compile_bounds_assertion(
+            &mut bytecode,
+            &count_reg,
+            &index_reg,
+            span,
+            namespace,
+            register_sequencer,
+        );
+    }
 
     // Get the element size in words first.
     let elem_size_in_words =
@@ -381,6 +386,7 @@ fn compile_bounds_assertion(
     count_reg: &VirtualRegister,
     index_reg: &VirtualRegister,
     span: &Span,
+    namespace: &mut AsmNamespace,
     register_sequencer: &mut RegisterSequencer,
 ) {
     // gt_reg = index_reg > count_reg.
@@ -403,11 +409,18 @@ fn compile_bounds_assertion(
         skip_label.clone(),
     ));
 
-    // Revert.
+    // Revert with the out of bounds code.
+    let revert_code_reg = register_sequencer.next();
+    let revert_code = namespace.insert_data_value(&Literal::U64(
+        crate::constants::INDEX_OUT_OF_BOUNDS_REVERT_CODE,
+    ));
+    bytecode.push(Op {
+        opcode: either::Either::Left(VirtualOp::LWDataId(revert_code_reg.clone(), revert_code)),
+        owning_span: Some(span.clone()),
+        comment: "load out of bounds revert code".into(),
+    });
     bytecode.push(Op {
-        opcode: either::Either::Left(VirtualOp::RVRT(VirtualRegister::Constant(
-            ConstantRegister::One,
-        ))),
+        opcode: either::Either::Left(VirtualOp::RVRT(revert_code_reg)),
         owning_span: Some(span.clone()),
         comment: "aborting due to out of bounds access".into(),
     });
@@ -8,6 +8,7 @@ pub(crate) fn convert_contract_call_to_asm(
     metadata: &ContractCallMetadata,
     contract_call_parameters: &HashMap<String, TypedExpression>,
     arguments: &[(Ident, TypedExpression)],
+    return_type: TypeId,
     register_sequencer: &mut RegisterSequencer,
     return_register: &VirtualRegister,
     namespace: &mut AsmNamespace,
@@ -37,13 +38,13 @@ pub(crate) fn convert_contract_call_to_asm(
     // need to bundle.
     let bundled_arguments = match arguments.len() {
         0 => None,
-        1 => Some(arguments[0].1.clone()),
+        1 => Some(contract_call_argument(arguments[0].1.clone())),
         _ => {
             // create a struct expression that bundles the arguments in order
             let mut typed_fields_buf = vec![];
             for (name, arg) in arguments {
                 typed_fields_buf.push(TypedStructExpressionField {
-                    value: arg.clone(),
+                    value: contract_call_argument(arg.clone()),
                     name: name.clone(),
                 });
             }
@@ -220,13 +221,29 @@ pub(crate) fn convert_contract_call_to_asm(
         owning_span: Some(span.clone()),
     });
 
-    // now, move the return value of the contract call to the return register.
+    // now, move the return value of the contract call to the return register.  A vector is
+    // returned as a pointer to its elements and their size, and needs to be rebuilt on our heap.
     // TODO validate RETL matches the expected type
-    asm_buf.push(Op::register_move(
-        return_register.into(),
-        VirtualRegister::Constant(ConstantRegister::ReturnValue),
-        span,
-    ));
+    if let TypeInfo::Vec(elem_type) = look_up_type_id(return_type) {
+        asm_buf.append(&mut check!(
+            convert_vec_from_return_data_to_asm(
+                elem_type,
+                namespace,
+                return_register,
+                register_sequencer,
+                span,
+            ),
+            return err(warnings, errors),
+            warnings,
+            errors
+        ));
+    } else {
+        asm_buf.push(Op::register_move(
+            return_register.into(),
+            VirtualRegister::Constant(ConstantRegister::ReturnValue),
+            span,
+        ));
+    }
 
     ok(asm_buf, warnings, errors)
 }
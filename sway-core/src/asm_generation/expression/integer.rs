@@ -0,0 +1,1037 @@
+//! Code generation for the built-in operators on the integers which the VM has no instructions
+//! for: the signed integers and the unsigned integers wider than a word.
+//!
+//! Signed integers are held in a word, sign extended from their own width, so that the bitwise
+//! operators and equality are the plain word sized instructions.  Addition, subtraction and
+//! multiplication of two's complement values are too, but their results are wrapped to the width
+//! of the type and sign extended again.  Division and remainder are of the magnitudes of the
+//! operands, with the sign of the result fixed up afterwards, which truncates towards zero.  The
+//! ordering comparisons flip the sign bit of both operands, which maps them to unsigned values in
+//! the same order.
+//!
+//! Wide integers are references to their big-endian words, and each result is written to a new
+//! heap allocation.  Addition and subtraction propagate a carry from the least significant word,
+//! and comparisons decide on the most significant word which differs.  Multiplication and
+//! division loop over the bits of the left operand, shifting and adding or subtracting without
+//! branches.  Unlike the word sized integers these revert on overflow and on division by zero.
+//!
+//! As for `Vec`, the instruction sequences are described by register name so that they may be
+//! shared by both the AST and the IR code generators.
+
+use super::*;
+use crate::constants::{DIVIDE_BY_ZERO_REVERT_CODE, INTEGER_OVERFLOW_REVERT_CODE};
+use crate::semantic_analysis::ast_node::IntIntrinsicOp;
+
+/// Set to one by [`IntIntrinsicAsm::divisor_check`] and [`IntIntrinsicAsm::overflow_check`] if
+/// the operation may continue, and zero if it must revert.
+pub(crate) const INT_VALID_REG: &str = "valid";
+/// Set to one by [`IntIntrinsicAsm::loop_check`] while the loop is to be repeated.
+pub(crate) const INT_LOOP_REG: &str = "more";
+/// Set by [`IntIntrinsicAsm::setup`] to the address of the state of a wide operation.
+pub(crate) const INT_STATE_REG: &str = "st";
+
+/// The instructions required to implement a single [`IntIntrinsic`], in the order they're run.
+/// Any of these may be empty.
+///
+/// Only the operands and [`INT_STATE_REG`] are preserved from one group of instructions to the
+/// next, so anything else which is needed later is kept in the state.
+#[derive(Default)]
+pub(crate) struct IntIntrinsicAsm {
+    /// The registers to be initialised with the operands, in order.
+    pub(crate) arg_names: Vec<&'static str>,
+    /// Instructions which set [`INT_VALID_REG`] to zero if the divisor is zero, in which case the
+    /// code generator must revert with [`DIVIDE_BY_ZERO_REVERT_CODE`].
+    pub(crate) divisor_check: Vec<NamedAsmOp>,
+    /// Instructions which allocate the state of a wide operation and set [`INT_STATE_REG`].
+    pub(crate) setup: Vec<NamedAsmOp>,
+    /// Instructions which set [`INT_LOOP_REG`], and which are run before each repetition of
+    /// `loop_body`.
+    pub(crate) loop_check: Vec<NamedAsmOp>,
+    pub(crate) loop_body: Vec<NamedAsmOp>,
+    /// Instructions which set [`INT_VALID_REG`] to zero if the result overflowed, in which case
+    /// the code generator must revert with [`INTEGER_OVERFLOW_REVERT_CODE`].
+    pub(crate) overflow_check: Vec<NamedAsmOp>,
+    pub(crate) body: Vec<NamedAsmOp>,
+    pub(crate) return_name: &'static str,
+}
+
+impl IntIntrinsicAsm {
+    /// All the registers used by `ops` which are neither operands, the state nor reserved
+    /// registers.  These need to be declared as scratch registers.
+    pub(crate) fn scratch_names(&self, ops: &[NamedAsmOp]) -> Vec<&'static str> {
+        let mut bound = self.arg_names.clone();
+        bound.push(INT_STATE_REG);
+        scratch_register_names(ops.iter(), &bound)
+    }
+
+    /// All the registers used by any of the instructions which aren't operands or reserved
+    /// registers, including the state.
+    pub(crate) fn all_scratch_names(&self) -> Vec<&'static str> {
+        scratch_register_names(
+            self.divisor_check
+                .iter()
+                .chain(self.setup.iter())
+                .chain(self.loop_check.iter())
+                .chain(self.loop_body.iter())
+                .chain(self.overflow_check.iter())
+                .chain(self.body.iter()),
+            &self.arg_names,
+        )
+    }
+
+    pub(crate) fn has_state(&self) -> bool {
+        !self.setup.is_empty()
+    }
+}
+
+/// Describe the instructions for `int_op` applied to two operands of type `int_type`.
+pub(crate) fn int_intrinsic_asm(int_op: IntIntrinsicOp, int_type: &TypeInfo) -> IntIntrinsicAsm {
+    let int_asm = IntIntrinsicAsm {
+        arg_names: vec!["lhs", "rhs"],
+        return_name: "res",
+        ..IntIntrinsicAsm::default()
+    };
+    match int_type {
+        TypeInfo::SignedInteger(bits) => IntIntrinsicAsm {
+            body: signed_asm(int_op, *bits),
+            ..int_asm
+        },
+        TypeInfo::UnsignedInteger(bits) if bits.is_wide() => {
+            wide_asm(int_op, bits.size_in_words(), int_asm)
+        }
+        _ => unreachable!("integer intrinsics are only created for signed and wide integers"),
+    }
+}
+
+fn signed_asm(int_op: IntIntrinsicOp, bits: SignedIntegerBits) -> Vec<NamedAsmOp> {
+    use IntIntrinsicOp::*;
+    match int_op {
+        Add | Subtract | Multiply => {
+            let name = match int_op {
+                Add => "add",
+                Subtract => "sub",
+                _ => "mul",
+            };
+            let mut body = vec![op(name, &["res", "lhs", "rhs"], None)];
+            body.append(&mut wrap_signed(bits));
+            body
+        }
+        Divide => {
+            let mut body = magnitudes();
+            body.append(&mut vec![
+                op("div", &["res", "lmag", "rmag"], None),
+                // The quotient is negative if exactly one operand is.
+                op("xor", &["qsign", "lsign", "rsign"], None),
+                op("xor", &["res", "res", "qsign"], None),
+                op("sub", &["res", "res", "qsign"], None),
+            ]);
+            // Only dividing the minimum value by -1 overflows.
+            body.append(&mut wrap_signed(bits));
+            body
+        }
+        Modulo => {
+            let mut body = magnitudes();
+            body.append(&mut vec![
+                // The remainder has the sign of the dividend.
+                op("mod", &["res", "lmag", "rmag"], None),
+                op("xor", &["res", "res", "lsign"], None),
+                op("sub", &["res", "res", "lsign"], None),
+            ]);
+            body
+        }
+        BinaryAnd => vec![op("and", &["res", "lhs", "rhs"], None)],
+        BinaryOr => vec![op("or", &["res", "lhs", "rhs"], None)],
+        Xor => vec![op("xor", &["res", "lhs", "rhs"], None)],
+        Equals => vec![op("eq", &["res", "lhs", "rhs"], None)],
+        NotEquals => vec![
+            op("eq", &["same", "lhs", "rhs"], None),
+            op("eq", &["res", "same", "zero"], None),
+        ],
+        LessThan | GreaterThan | LessThanOrEqualTo | GreaterThanOrEqualTo => {
+            let mut body = vec![
+                op("slli", &["top", "one"], Some("i63")),
+                op("xor", &["lord", "lhs", "top"], None),
+                op("xor", &["rord", "rhs", "top"], None),
+            ];
+            body.append(&mut match int_op {
+                LessThan => vec![op("lt", &["res", "lord", "rord"], None)],
+                GreaterThan => vec![op("gt", &["res", "lord", "rord"], None)],
+                LessThanOrEqualTo => vec![
+                    op("gt", &["more", "lord", "rord"], None),
+                    op("eq", &["res", "more", "zero"], None),
+                ],
+                _ => vec![
+                    op("lt", &["less", "lord", "rord"], None),
+                    op("eq", &["res", "less", "zero"], None),
+                ],
+            });
+            body
+        }
+    }
+}
+
+/// Set `lsign` and `rsign` to all ones for a negative operand and zero otherwise, and `lmag`
+/// and `rmag` to the magnitudes of the operands.
+fn magnitudes() -> Vec<NamedAsmOp> {
+    vec![
+        op("srli", &["lsign", "lhs"], Some("i63")),
+        op("sub", &["lsign", "zero", "lsign"], None),
+        op("xor", &["lmag", "lhs", "lsign"], None),
+        op("sub", &["lmag", "lmag", "lsign"], None),
+        op("srli", &["rsign", "rhs"], Some("i63")),
+        op("sub", &["rsign", "zero", "rsign"], None),
+        op("xor", &["rmag", "rhs", "rsign"], None),
+        op("sub", &["rmag", "rmag", "rsign"], None),
+    ]
+}
+
+/// Wrap the word in `res` to the width of the type and sign extend it again.  The unused high
+/// bits are cleared, and then flipping the sign bit and subtracting it sets them all if the
+/// sign bit was set.
+fn wrap_signed(bits: SignedIntegerBits) -> Vec<NamedAsmOp> {
+    let (unused_bits, sign_bit) = match bits {
+        SignedIntegerBits::Eight => ("i56", "i7"),
+        SignedIntegerBits::Sixteen => ("i48", "i15"),
+        SignedIntegerBits::ThirtyTwo => ("i32", "i31"),
+        SignedIntegerBits::SixtyFour => return vec![],
+    };
+    vec![
+        op("slli", &["res", "res"], Some(unused_bits)),
+        op("srli", &["res", "res"], Some(unused_bits)),
+        op("slli", &["sign", "one"], Some(sign_bit)),
+        op("xor", &["res", "res", "sign"], None),
+        op("sub", &["res", "res", "sign"], None),
+    ]
+}
+
+/// Fill in `int_asm` for `int_op` applied to unsigned integers of `words` words.
+fn wide_asm(int_op: IntIntrinsicOp, words: u64, int_asm: IntIntrinsicAsm) -> IntIntrinsicAsm {
+    use IntIntrinsicOp::*;
+    match int_op {
+        Add | Subtract => {
+            // The result is followed by the carry out of its most significant word.
+            let mut setup = alloc_state(words + 1);
+            setup.push(op("move", &["carry", "zero"], None));
+            for word in (0..words).rev() {
+                setup.append(&mut vec![
+                    op("lw", &["a", "lhs"], Some(imm(word))),
+                    op("lw", &["b", "rhs"], Some(imm(word))),
+                ]);
+                setup.append(&mut match int_op {
+                    Add => add_with_carry(),
+                    _ => sub_with_borrow(),
+                });
+                setup.push(op("sw", &[INT_STATE_REG, "sum"], Some(imm(word))));
+            }
+            setup.push(op("sw", &[INT_STATE_REG, "carry"], Some(imm(words))));
+            IntIntrinsicAsm {
+                setup,
+                overflow_check: overflow_check(words),
+                return_name: INT_STATE_REG,
+                ..int_asm
+            }
+        }
+        Multiply => {
+            // The result is followed by a flag which is set if it has overflowed and the number
+            // of bits of the left operand still to be multiplied.  For each of those bits, from
+            // the most significant, the result is doubled and then the right operand is added if
+            // the bit is set.
+            let mut setup = alloc_state(words + 2);
+            setup.append(&mut vec![
+                op("addi", &["k", "zero"], Some(imm(words * 64))),
+                op("sw", &[INT_STATE_REG, "k"], Some(imm(words + 1))),
+            ]);
+            let mut loop_body = count_down(words + 1);
+            loop_body.push(op("move", &["carry", "zero"], None));
+            loop_body.append(&mut shift_left(0, words));
+            loop_body.append(&mut accumulate_overflow(words));
+            loop_body.append(&mut lhs_bit(words));
+            loop_body.append(&mut vec![
+                op("sub", &["mask", "zero", "bit"], None),
+                op("move", &["carry", "zero"], None),
+            ]);
+            for word in (0..words).rev() {
+                loop_body.append(&mut vec![
+                    op("lw", &["a", INT_STATE_REG], Some(imm(word))),
+                    op("lw", &["b", "rhs"], Some(imm(word))),
+                    op("and", &["b", "b", "mask"], None),
+                ]);
+                loop_body.append(&mut add_with_carry());
+                loop_body.push(op("sw", &[INT_STATE_REG, "sum"], Some(imm(word))));
+            }
+            loop_body.append(&mut accumulate_overflow(words));
+            IntIntrinsicAsm {
+                setup,
+                loop_check: loop_check(words + 1),
+                loop_body,
+                overflow_check: overflow_check(words),
+                return_name: INT_STATE_REG,
+                ..int_asm
+            }
+        }
+        Divide | Modulo => {
+            // Long division, one bit at a time.  The state is the quotient, followed by the
+            // remainder and the number of bits of the left operand still to be divided.  For each
+            // of those bits, from the most significant, the bit is shifted into the remainder.  If
+            // the remainder is then at least the divisor then the divisor is subtracted from it
+            // and a one is shifted into the quotient, or else a zero.
+            let mut divisor_check = vec![op("move", &[INT_VALID_REG, "zero"], None)];
+            for word in 0..words {
+                divisor_check.append(&mut vec![
+                    op("lw", &["a", "rhs"], Some(imm(word))),
+                    op("or", &[INT_VALID_REG, INT_VALID_REG, "a"], None),
+                ]);
+            }
+            divisor_check.push(op("gt", &[INT_VALID_REG, INT_VALID_REG, "zero"], None));
+
+            let mut setup = alloc_state(words * 2 + 1);
+            setup.append(&mut vec![
+                op("addi", &["k", "zero"], Some(imm(words * 64))),
+                op("sw", &[INT_STATE_REG, "k"], Some(imm(words * 2))),
+            ]);
+            let mut loop_body = count_down(words * 2);
+            loop_body.append(&mut lhs_bit(words));
+            loop_body.push(op("move", &["carry", "bit"], None));
+            loop_body.append(&mut shift_left(words, words));
+            // A bit shifted out of the remainder means it's more than the divisor.
+            loop_body.push(op("move", &["out", "carry"], None));
+            loop_body.append(&mut compare(INT_STATE_REG, words, "rhs", words));
+            loop_body.append(&mut vec![
+                op("eq", &["ge", "below", "zero"], None),
+                op("or", &["ge", "ge", "out"], None),
+                op("sub", &["mask", "zero", "ge"], None),
+                op("move", &["carry", "zero"], None),
+            ]);
+            for word in (0..words).rev() {
+                loop_body.append(&mut vec![
+                    op("lw", &["a", INT_STATE_REG], Some(imm(words + word))),
+                    op("lw", &["b", "rhs"], Some(imm(word))),
+                    op("and", &["b", "b", "mask"], None),
+                ]);
+                loop_body.append(&mut sub_with_borrow());
+                loop_body.push(op("sw", &[INT_STATE_REG, "sum"], Some(imm(words + word))));
+            }
+            loop_body.push(op("move", &["carry", "ge"], None));
+            loop_body.append(&mut shift_left(0, words));
+
+            let (body, return_name) = match int_op {
+                Divide => (vec![], INT_STATE_REG),
+                _ => (
+                    vec![op("addi", &["res", INT_STATE_REG], Some(imm(words * 8)))],
+                    "res",
+                ),
+            };
+            IntIntrinsicAsm {
+                divisor_check,
+                setup,
+                loop_check: loop_check(words * 2),
+                loop_body,
+                body,
+                return_name,
+                ..int_asm
+            }
+        }
+        BinaryAnd | BinaryOr | Xor => {
+            let name = match int_op {
+                BinaryAnd => "and",
+                BinaryOr => "or",
+                _ => "xor",
+            };
+            let mut setup = alloc_state(words);
+            for word in 0..words {
+                setup.append(&mut vec![
+                    op("lw", &["a", "lhs"], Some(imm(word))),
+                    op("lw", &["b", "rhs"], Some(imm(word))),
+                    op(name, &["a", "a", "b"], None),
+                    op("sw", &[INT_STATE_REG, "a"], Some(imm(word))),
+                ]);
+            }
+            IntIntrinsicAsm {
+                setup,
+                return_name: INT_STATE_REG,
+                ..int_asm
+            }
+        }
+        Equals | NotEquals => {
+            let mut body = vec![
+                op("addi", &["size", "zero"], Some(imm(words * 8))),
+                op("meq", &["res", "lhs", "rhs", "size"], None),
+            ];
+            if int_op == NotEquals {
+                body.push(op("eq", &["res", "res", "zero"], None));
+            }
+            IntIntrinsicAsm { body, ..int_asm }
+        }
+        LessThan | GreaterThan | LessThanOrEqualTo | GreaterThanOrEqualTo => {
+            let mut body = compare("lhs", 0, "rhs", words);
+            body.push(match int_op {
+                LessThan => op("move", &["res", "below"], None),
+                GreaterThan => op("move", &["res", "above"], None),
+                LessThanOrEqualTo => op("eq", &["res", "above", "zero"], None),
+                _ => op("eq", &["res", "below", "zero"], None),
+            });
+            IntIntrinsicAsm { body, ..int_asm }
+        }
+    }
+}
+
+/// Allocate and clear `words` words for the state, and set [`INT_STATE_REG`] to its address.
+fn alloc_state(words: u64) -> Vec<NamedAsmOp> {
+    vec![
+        op("addi", &["size", "zero"], Some(imm(words * 8))),
+        op("aloc", &["size"], None),
+        op("addi", &[INT_STATE_REG, "hp"], Some("i1")),
+        op("mcli", &[INT_STATE_REG], Some(imm(words * 8))),
+    ]
+}
+
+/// Set `sum` to `a + b + carry`, and `carry` to the carry out.
+fn add_with_carry() -> Vec<NamedAsmOp> {
+    vec![
+        op("add", &["sum", "a", "b"], None),
+        op("lt", &["c1", "sum", "a"], None),
+        op("add", &["sum", "sum", "carry"], None),
+        op("lt", &["c2", "sum", "carry"], None),
+        op("or", &["carry", "c1", "c2"], None),
+    ]
+}
+
+/// Set `sum` to `a - b - carry`, and `carry` to the borrow out.
+fn sub_with_borrow() -> Vec<NamedAsmOp> {
+    vec![
+        op("sub", &["sum", "a", "b"], None),
+        op("lt", &["c1", "a", "b"], None),
+        op("lt", &["c2", "sum", "carry"], None),
+        op("sub", &["sum", "sum", "carry"], None),
+        op("or", &["carry", "c1", "c2"], None),
+    ]
+}
+
+/// Shift the `words` words of the state at word `offset` left by a bit, shifting in `carry` and
+/// setting `carry` to the bit shifted out.
+fn shift_left(offset: u64, words: u64) -> Vec<NamedAsmOp> {
+    let mut ops = vec![];
+    for word in (offset..offset + words).rev() {
+        ops.append(&mut vec![
+            op("lw", &["a", INT_STATE_REG], Some(imm(word))),
+            op("srli", &["top", "a"], Some("i63")),
+            op("slli", &["a", "a"], Some("i1")),
+            op("or", &["a", "a", "carry"], None),
+            op("sw", &[INT_STATE_REG, "a"], Some(imm(word))),
+            op("move", &["carry", "top"], None),
+        ]);
+    }
+    ops
+}
+
+/// Compare the `words` words at word `offset` of `lhs` with those of `rhs`, setting `below` if
+/// they're less and `above` if they're more.  The first word which differs decides.
+fn compare(lhs: &'static str, offset: u64, rhs: &'static str, words: u64) -> Vec<NamedAsmOp> {
+    let mut ops = vec![
+        op("move", &["below", "zero"], None),
+        op("move", &["above", "zero"], None),
+    ];
+    for word in 0..words {
+        ops.append(&mut vec![
+            op("lw", &["a", lhs], Some(imm(offset + word))),
+            op("lw", &["b", rhs], Some(imm(word))),
+            op("lt", &["l", "a", "b"], None),
+            op("gt", &["g", "a", "b"], None),
+            op("or", &["decided", "below", "above"], None),
+            op("eq", &["undecided", "decided", "zero"], None),
+            op("and", &["l", "l", "undecided"], None),
+            op("and", &["g", "g", "undecided"], None),
+            op("or", &["below", "below", "l"], None),
+            op("or", &["above", "above", "g"], None),
+        ]);
+    }
+    ops
+}
+
+/// Set [`INT_LOOP_REG`] if the count of bits at word `count` of the state isn't zero.
+fn loop_check(count: u64) -> Vec<NamedAsmOp> {
+    vec![
+        op("lw", &["k", INT_STATE_REG], Some(imm(count))),
+        op("gt", &[INT_LOOP_REG, "k", "zero"], None),
+    ]
+}
+
+/// Decrement the count of bits at word `count` of the state, leaving it in `k`, which is then
+/// the index of the bit of the left operand for this repetition.
+fn count_down(count: u64) -> Vec<NamedAsmOp> {
+    vec![
+        op("lw", &["k", INT_STATE_REG], Some(imm(count))),
+        op("subi", &["k", "k"], Some("i1")),
+        op("sw", &[INT_STATE_REG, "k"], Some(imm(count))),
+    ]
+}
+
+/// Set `bit` to bit `k` of the left operand of `words` words, counting from the least
+/// significant bit of the least significant word, which is the last.
+fn lhs_bit(words: u64) -> Vec<NamedAsmOp> {
+    vec![
+        op("srli", &["idx", "k"], Some("i6")),
+        op("addi", &["last", "zero"], Some(imm(words - 1))),
+        op("sub", &["idx", "last", "idx"], None),
+        op("slli", &["idx", "idx"], Some("i3")),
+        op("add", &["addr", "lhs", "idx"], None),
+        op("lw", &["w", "addr"], Some("i0")),
+        op("andi", &["shift", "k"], Some("i63")),
+        op("srl", &["bit", "w", "shift"], None),
+        op("and", &["bit", "bit", "one"], None),
+    ]
+}
+
+/// Add `carry` to the overflow flag, which follows the result of `words` words in the state.
+fn accumulate_overflow(words: u64) -> Vec<NamedAsmOp> {
+    vec![
+        op("lw", &["ovf", INT_STATE_REG], Some(imm(words))),
+        op("or", &["ovf", "ovf", "carry"], None),
+        op("sw", &[INT_STATE_REG, "ovf"], Some(imm(words))),
+    ]
+}
+
+/// Set [`INT_VALID_REG`] if the overflow flag, which follows the result of `words` words in the
+/// state, is clear.
+fn overflow_check(words: u64) -> Vec<NamedAsmOp> {
+    vec![
+        op("lw", &["ovf", INT_STATE_REG], Some(imm(words))),
+        op("eq", &[INT_VALID_REG, "ovf", "zero"], None),
+    ]
+}
+
+/// The immediate for `n`.  Immediates are `'static` names, so only the word offsets and sizes
+/// used by the wide integer sequences are available.
+fn imm(n: u64) -> &'static str {
+    match n {
+        0 => "i0",
+        1 => "i1",
+        2 => "i2",
+        3 => "i3",
+        4 => "i4",
+        5 => "i5",
+        6 => "i6",
+        7 => "i7",
+        8 => "i8",
+        16 => "i16",
+        24 => "i24",
+        32 => "i32",
+        40 => "i40",
+        48 => "i48",
+        72 => "i72",
+        128 => "i128",
+        256 => "i256",
+        _ => unreachable!("no immediate for {}", n),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn convert_int_intrinsic_to_asm(
+    int_op: IntIntrinsicOp,
+    lhs: &TypedExpression,
+    rhs: &TypedExpression,
+    int_type: TypeId,
+    namespace: &mut AsmNamespace,
+    return_register: &VirtualRegister,
+    register_sequencer: &mut RegisterSequencer,
+    span: Span,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut asm_buf = vec![];
+
+    let int_type = check_std_result!(resolve_type(int_type, &span), warnings, errors);
+    let int_asm = int_intrinsic_asm(int_op, &int_type);
+
+    let mut registers: HashMap<&str, VirtualRegister> = HashMap::new();
+    for (name, arg) in int_asm.arg_names.iter().zip([lhs, rhs]) {
+        let arg_reg = register_sequencer.next();
+        asm_buf.append(&mut check!(
+            convert_expression_to_asm(arg, namespace, &arg_reg, register_sequencer),
+            return err(warnings, errors),
+            warnings,
+            errors
+        ));
+        registers.insert(name, arg_reg);
+    }
+    for name in int_asm.all_scratch_names() {
+        registers.insert(name, register_sequencer.next());
+    }
+
+    let comment = format!("{} {}", int_type.friendly_type_str(), int_op.name());
+    let mut convert_ops = |ops: &[NamedAsmOp], asm_buf: &mut Vec<Op>| {
+        for named_op in ops {
+            asm_buf.push(check!(
+                convert_named_asm_op(named_op, &registers, &comment, &span),
+                continue,
+                warnings,
+                errors
+            ));
+        }
+    };
+    if !int_asm.divisor_check.is_empty() {
+        convert_ops(&int_asm.divisor_check, &mut asm_buf);
+        asm_buf.append(&mut revert_unless_valid(
+            &registers[INT_VALID_REG],
+            DIVIDE_BY_ZERO_REVERT_CODE,
+            namespace,
+            register_sequencer,
+            &span,
+        ));
+    }
+    convert_ops(&int_asm.setup, &mut asm_buf);
+    if !int_asm.loop_body.is_empty() {
+        let loop_label = register_sequencer.get_label();
+        let exit_label = register_sequencer.get_label();
+        asm_buf.push(Op::jump_label_comment(
+            loop_label.clone(),
+            span.clone(),
+            "integer loop",
+        ));
+        convert_ops(&int_asm.loop_check, &mut asm_buf);
+        asm_buf.push(Op::jump_if_not_equal(
+            registers[INT_LOOP_REG].clone(),
+            VirtualRegister::Constant(ConstantRegister::One),
+            exit_label.clone(),
+        ));
+        convert_ops(&int_asm.loop_body, &mut asm_buf);
+        asm_buf.push(Op::jump_to_label(loop_label));
+        asm_buf.push(Op::jump_label_comment(
+            exit_label,
+            span.clone(),
+            "after integer loop",
+        ));
+    }
+    if !int_asm.overflow_check.is_empty() {
+        convert_ops(&int_asm.overflow_check, &mut asm_buf);
+        asm_buf.append(&mut revert_unless_valid(
+            &registers[INT_VALID_REG],
+            INTEGER_OVERFLOW_REVERT_CODE,
+            namespace,
+            register_sequencer,
+            &span,
+        ));
+    }
+    convert_ops(&int_asm.body, &mut asm_buf);
+    asm_buf.push(Op::unowned_register_move_comment(
+        return_register.clone(),
+        registers[int_asm.return_name].clone(),
+        "return value from integer intrinsic",
+    ));
+
+    ok(asm_buf, warnings, errors)
+}
+
+/// Revert with `revert_code` unless `valid_reg` is set.
+fn revert_unless_valid(
+    valid_reg: &VirtualRegister,
+    revert_code: u64,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+    span: &Span,
+) -> Vec<Op> {
+    let skip_label = register_sequencer.get_label();
+    let revert_code_reg = register_sequencer.next();
+    let revert_code = namespace.insert_data_value(&Literal::U64(revert_code));
+    vec![
+        Op::jump_if_not_equal(
+            valid_reg.clone(),
+            VirtualRegister::Constant(ConstantRegister::Zero),
+            skip_label.clone(),
+        ),
+        Op {
+            opcode: Either::Left(VirtualOp::LWDataId(revert_code_reg.clone(), revert_code)),
+            owning_span: Some(span.clone()),
+            comment: "load integer revert code".into(),
+        },
+        Op {
+            opcode: Either::Left(VirtualOp::RVRT(revert_code_reg)),
+            owning_span: Some(span.clone()),
+            comment: "aborting due to invalid integer operation".into(),
+        },
+        Op::jump_label_comment(skip_label, span.clone(), "after integer check"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        constants::{DIVIDE_BY_ZERO_REVERT_CODE, INTEGER_OVERFLOW_REVERT_CODE},
+        test_utils::check_with_each_backend,
+    };
+    use fuel_vm::prelude::*;
+
+    fn check(main: &str, expected: ProgramState) {
+        check_with_each_backend(&format!("script;\n{}", main), expected);
+    }
+
+    // Apply `op` to `lhs` and `rhs`, both of type `ty`, and check the result.  The operands are
+    // passed to a function so that nothing is known about them where they're used.
+    fn check_op(ty: &str, lhs: &str, op: &str, rhs: &str, expected: i64) {
+        check(
+            &format!(
+                r#"
+    fn apply(a: {ty}, b: {ty}) -> {ty} {{
+        a {op} b
+    }}
+    fn main() -> {ty} {{
+        apply({lhs}, {rhs})
+    }}"#
+            ),
+            ProgramState::Return(expected as u64),
+        );
+    }
+
+    fn check_cmp(ty: &str, lhs: &str, op: &str, rhs: &str, expected: bool) {
+        check(
+            &format!(
+                r#"
+    fn apply(a: {ty}, b: {ty}) -> bool {{
+        a {op} b
+    }}
+    fn main() -> bool {{
+        apply({lhs}, {rhs})
+    }}"#
+            ),
+            ProgramState::Return(expected as u64),
+        );
+    }
+
+    #[test]
+    fn arithmetic_wraps_to_width() {
+        check_op("i8", "100", "+", "100", -56);
+        check_op("i8", "-128", "-", "1", 127);
+        check_op("i8", "-100", "-", "100", 56);
+        check_op("i16", "300", "*", "300", 24464);
+        check_op("i16", "-300", "*", "300", -24464);
+        check_op("i32", "2147483647", "+", "1", -2147483648);
+        check_op("i32", "-5", "*", "-7", 35);
+        check_op("i64", "9223372036854775807", "+", "1", i64::MIN);
+        check_op("i64", "-3", "-", "4", -7);
+    }
+
+    #[test]
+    fn division_truncates_towards_zero() {
+        check_op("i32", "-7", "/", "2", -3);
+        check_op("i32", "7", "/", "-2", -3);
+        check_op("i32", "-7", "/", "-2", 3);
+        check_op("i64", "7", "/", "2", 3);
+        check_op("i32", "-7", "%", "2", -1);
+        check_op("i32", "7", "%", "-2", 1);
+        check_op("i32", "-7", "%", "-2", -1);
+        check_op("i8", "-128", "/", "-1", -128);
+        check_op("i64", "-9223372036854775808", "/", "-1", i64::MIN);
+        check_op("i64", "-9223372036854775808", "%", "10", -8);
+        check_op("i32", "-7", "/", "0", 0);
+        check_op("i32", "-7", "%", "0", 0);
+    }
+
+    #[test]
+    fn bitwise_keeps_sign() {
+        check_op("i32", "-1", "&", "7", 7);
+        check_op("i16", "-8", "|", "3", -5);
+        check_op("i8", "-1", "^", "5", -6);
+    }
+
+    #[test]
+    fn signed_comparisons() {
+        check_cmp("i64", "-1", "<", "1", true);
+        check_cmp("i64", "1", "<", "-1", false);
+        check_cmp("i32", "-5", ">", "-10", true);
+        check_cmp("i32", "-10", ">", "-5", false);
+        check_cmp("i8", "-5", "<=", "-5", true);
+        check_cmp("i8", "-4", "<=", "-5", false);
+        check_cmp("i16", "-5", ">=", "3", false);
+        check_cmp("i16", "3", ">=", "-5", true);
+        check_cmp(
+            "i64",
+            "-9223372036854775808",
+            "<",
+            "9223372036854775807",
+            true,
+        );
+        check_cmp("i32", "-5", "==", "-5", true);
+        check_cmp("i32", "-5", "!=", "5", true);
+    }
+
+    #[test]
+    fn literal_operands() {
+        // A numeric literal takes the type of the other operand, on either side, and a negative
+        // literal on its own is an `i64`.
+        check(
+            r#"
+    fn main() -> i32 {
+        let a: i32 = -20;
+        let b = 3 * a;
+        b - -1
+    }"#,
+            ProgramState::Return(-59i64 as u64),
+        );
+        check(
+            r#"
+    fn main() -> bool {
+        -2 < -1
+    }"#,
+            ProgramState::Return(1),
+        );
+    }
+
+    #[test]
+    fn match_signed_literals() {
+        check(
+            r#"
+    fn classify(x: i16) -> u64 {
+        match x {
+            -1 => { 10 },
+            0 => { 20 },
+            _ => { 30 },
+        }
+    }
+    fn main() -> u64 {
+        let a = classify(-1);
+        let b = classify(0);
+        let c = classify(-2);
+        asm(a: a, b: b, c: c, r, s) {
+            add r a b;
+            add s r c;
+            s: u64
+        }
+    }"#,
+            ProgramState::Return(60),
+        );
+    }
+
+    // Apply `op` to `lhs` and `rhs`, both of the wide type `ty`, and compare the result with
+    // `expected`, as a wide integer can't be returned in a register.
+    fn check_wide_op(ty: &str, lhs: &str, op: &str, rhs: &str, expected: &str) {
+        check_wide_state(ty, lhs, op, rhs, expected, ProgramState::Return(1));
+    }
+
+    fn check_wide_state(
+        ty: &str,
+        lhs: &str,
+        op: &str,
+        rhs: &str,
+        expected: &str,
+        state: ProgramState,
+    ) {
+        check(
+            &format!(
+                r#"
+    fn apply(a: {ty}, b: {ty}) -> {ty} {{
+        a {op} b
+    }}
+    fn main() -> bool {{
+        let expected: {ty} = {expected};
+        apply({lhs}, {rhs}) == expected
+    }}"#
+            ),
+            state,
+        );
+    }
+
+    #[test]
+    fn wide_addition_carries() {
+        check_wide_op(
+            "u128",
+            "18446744073709551615",
+            "+",
+            "1",
+            "18446744073709551616u128",
+        );
+        check_wide_op(
+            "u256",
+            "6277101735386680763835789423207666416102355444464034512895u256",
+            "+",
+            "1",
+            "6277101735386680763835789423207666416102355444464034512896u256",
+        );
+        check_wide_op(
+            "u128",
+            "18446744073709551616u128",
+            "-",
+            "1",
+            "18446744073709551615",
+        );
+        check_wide_op(
+            "u256",
+            "1606938044258990275541962092341162602522202993782792835301376u256",
+            "-",
+            "1",
+            "1606938044258990275541962092341162602522202993782792835301375u256",
+        );
+    }
+
+    #[test]
+    fn wide_multiplication() {
+        check_wide_op(
+            "u128",
+            "18446744073709551616u128",
+            "*",
+            "18446744073709551615",
+            "340282366920938463444927863358058659840u128",
+        );
+        check_wide_op(
+            "u256",
+            "340282366920938463463374607431768211459u256",
+            "*",
+            "1267650600228229401496703205383u256",
+            "431359146674410236714672241394696067350443831805577470154170251083797u256",
+        );
+        check_wide_op("u128", "0", "*", "12345", "0");
+    }
+
+    #[test]
+    fn wide_division() {
+        check_wide_op(
+            "u128",
+            "340282366920938463463374607431768211455u128",
+            "/",
+            "18446744073709551617u128",
+            "18446744073709551615",
+        );
+        check_wide_op(
+            "u128",
+            "340282366920938463463374607431768211455u128",
+            "%",
+            "10",
+            "5",
+        );
+        check_wide_op(
+            "u256",
+            "123456789012345678901234567890123456789012345678901234567890u256",
+            "/",
+            "98765432109876543210987u256",
+            "1249999988609375000142391093749550070u256",
+        );
+        check_wide_op(
+            "u256",
+            "123456789012345678901234567890123456789012345678901234567890u256",
+            "%",
+            "98765432109876543210987u256",
+            "29599966484956903948800u256",
+        );
+        check_wide_op(
+            "u256",
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935u256",
+            "/",
+            "340282366920938463463374607431768211457u256",
+            "340282366920938463463374607431768211455u256",
+        );
+    }
+
+    #[test]
+    fn wide_overflow_reverts() {
+        let overflow = ProgramState::Revert(INTEGER_OVERFLOW_REVERT_CODE);
+        check_wide_state(
+            "u128",
+            "340282366920938463463374607431768211455u128",
+            "+",
+            "1",
+            "0",
+            overflow,
+        );
+        check_wide_state("u256", "0", "-", "1", "0", overflow);
+        check_wide_state(
+            "u128",
+            "18446744073709551616u128",
+            "*",
+            "18446744073709551616u128",
+            "0",
+            overflow,
+        );
+        check_wide_state(
+            "u256",
+            "57896044618658097711785492504343953926634992332820282019728792003956564819968u256",
+            "*",
+            "2",
+            "0",
+            overflow,
+        );
+        let divide_by_zero = ProgramState::Revert(DIVIDE_BY_ZERO_REVERT_CODE);
+        check_wide_state("u128", "1", "/", "0", "0", divide_by_zero);
+        check_wide_state("u256", "1", "%", "0", "0", divide_by_zero);
+    }
+
+    #[test]
+    fn wide_bitwise() {
+        check_wide_op(
+            "u128",
+            "340282366920938463463374607431768211455u128",
+            "&",
+            "18446744073709551616u128",
+            "18446744073709551616u128",
+        );
+        check_wide_op(
+            "u128",
+            "18446744073709551616u128",
+            "|",
+            "1",
+            "18446744073709551617u128",
+        );
+        check_wide_op("u256", "3", "^", "1", "2");
+    }
+
+    #[test]
+    fn wide_comparisons() {
+        check_cmp(
+            "u128",
+            "18446744073709551616u128",
+            "<",
+            "18446744073709551617u128",
+            true,
+        );
+        check_cmp(
+            "u128",
+            "18446744073709551616u128",
+            ">",
+            "18446744073709551615",
+            true,
+        );
+        check_cmp(
+            "u128",
+            "18446744073709551615",
+            ">",
+            "18446744073709551616u128",
+            false,
+        );
+        check_cmp("u256", "2", "<=", "2", true);
+        check_cmp("u256", "3", "<=", "2", false);
+        check_cmp("u256", "2", ">=", "3", false);
+        check_cmp(
+            "u256",
+            "1606938044258990275541962092341162602522202993782792835301376u256",
+            ">=",
+            "18446744073709551615",
+            true,
+        );
+        check_cmp("u128", "5", "==", "5", true);
+        check_cmp("u128", "5", "!=", "5", false);
+        check_cmp("u256", "18446744073709551616u256", "!=", "1", true);
+    }
+
+    #[test]
+    fn match_wide_literals() {
+        check(
+            r#"
+    fn classify(x: u256) -> u64 {
+        match x {
+            0 => { 10 },
+            18446744073709551616u256 => { 20 },
+            _ => { 30 },
+        }
+    }
+    fn main() -> u64 {
+        let a = classify(0);
+        let b = classify(18446744073709551616u256);
+        let c = classify(1);
+        asm(a: a, b: b, c: c, r, s) {
+            add r a b;
+            add s r c;
+            s: u64
+        }
+    }"#,
+            ProgramState::Return(60),
+        );
+    }
+}
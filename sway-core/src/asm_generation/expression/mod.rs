@@ -17,16 +17,31 @@ mod array;
 mod contract_call;
 mod enums;
 mod if_exp;
+mod integer;
 mod lazy_op;
+mod named_asm;
 mod structs;
 pub(crate) mod subfield;
+mod switch;
+mod vec;
 use contract_call::convert_contract_call_to_asm;
 use enums::convert_enum_instantiation_to_asm;
 use if_exp::convert_if_exp_to_asm;
+use integer::convert_int_intrinsic_to_asm;
+pub(crate) use integer::{int_intrinsic_asm, INT_LOOP_REG, INT_STATE_REG, INT_VALID_REG};
+use named_asm::{convert_named_asm_op, op};
+pub(crate) use named_asm::{scratch_register_names, NamedAsmOp};
 pub(crate) use structs::{
     convert_struct_expression_to_asm, convert_tuple_expression_to_asm, get_contiguous_memory_layout,
 };
 use subfield::convert_subfield_expression_to_asm;
+use switch::convert_integer_switch_to_asm;
+pub(crate) use vec::{
+    contract_call_argument, convert_vec_from_call_data_to_asm, convert_vec_return_to_asm,
+    vec_from_call_data_asm, vec_from_return_data_asm, vec_intrinsic_asm, vec_return_asm,
+    VecIntrinsicAsm, VEC_ELEM_SIZE_REG, VEC_HAS_SPACE_REG, VEC_IN_BOUNDS_REG,
+};
+use vec::{convert_vec_from_return_data_to_asm, convert_vec_intrinsic_to_asm};
 
 /// Given a [TypedExpression], convert it to assembly and put its return value, if any, in the
 /// `return_register`.
@@ -62,6 +77,7 @@ pub(crate) fn convert_expression_to_asm(
                     metadata,
                     contract_call_params,
                     arguments,
+                    exp.return_type,
                     register_sequencer,
                     return_register,
                     namespace,
@@ -300,6 +316,18 @@ pub(crate) fn convert_expression_to_asm(
             namespace,
             register_sequencer,
         ),
+        TypedExpressionVariant::IntegerSwitch {
+            scrutinee,
+            cases,
+            default,
+        } => convert_integer_switch_to_asm(
+            scrutinee,
+            cases,
+            default,
+            return_register,
+            namespace,
+            register_sequencer,
+        ),
         TypedExpressionVariant::CodeBlock(block) => {
             convert_code_block_to_asm(block, namespace, register_sequencer, Some(return_register))
         }
@@ -347,6 +375,29 @@ pub(crate) fn convert_expression_to_asm(
             register_sequencer,
             exp.span.clone(),
         ),
+        TypedExpressionVariant::VecIntrinsic { kind, elem_type } => convert_vec_intrinsic_to_asm(
+            kind,
+            *elem_type,
+            namespace,
+            return_register,
+            register_sequencer,
+            exp.span.clone(),
+        ),
+        TypedExpressionVariant::IntIntrinsic {
+            op,
+            lhs,
+            rhs,
+            int_type,
+        } => convert_int_intrinsic_to_asm(
+            *op,
+            lhs,
+            rhs,
+            *int_type,
+            namespace,
+            return_register,
+            register_sequencer,
+            exp.span.clone(),
+        ),
         _ => {
             errors.push(CompileError::Unimplemented(
                 "ASM generation has not yet been implemented for this.",
@@ -505,7 +556,7 @@ fn convert_fn_app_to_asm(
 
     // insert the arguments into the asm namespace with their registers mapped
     for (name, reg) in args_and_registers {
-        namespace.insert_variable(name, reg);
+        namespace.insert_variable(name, reg, register_sequencer);
     }
 
     // evaluate the function body
@@ -551,7 +602,7 @@ pub(crate) fn convert_abi_fn_to_asm(
 
     // insert the arguments into the asm namespace with their registers mapped
     for arg in arguments {
-        namespace.insert_variable(arg.clone().0, arg.clone().1);
+        namespace.insert_variable(arg.clone().0, arg.clone().1, register_sequencer);
     }
     // evaluate the function body
     let mut body = check!(
@@ -657,6 +708,7 @@ fn convert_if_let_to_asm(
     then_branch_asm_namespace.insert_variable(
         variable_to_assign.clone(),
         variable_to_assign_register.clone(),
+        register_sequencer,
     );
     // load the word that is at the expr return register + 1 word
     // + 1 word is to account for the enum tag
@@ -0,0 +1,90 @@
+//! Instruction sequences described in terms of named registers, for the built-in operations which
+//! are compiled to the same instructions by both the AST and the IR code generators.
+
+use super::*;
+use either::Either;
+
+/// A single instruction in terms of named registers.
+pub(crate) struct NamedAsmOp {
+    pub(crate) name: &'static str,
+    pub(crate) args: Vec<&'static str>,
+    pub(crate) immediate: Option<&'static str>,
+}
+
+pub(super) fn op(
+    name: &'static str,
+    args: &[&'static str],
+    immediate: Option<&'static str>,
+) -> NamedAsmOp {
+    NamedAsmOp {
+        name,
+        args: args.to_vec(),
+        immediate,
+    }
+}
+
+/// All the registers used by `ops` which are neither in `bound` nor reserved registers.  These
+/// need to be declared as scratch registers.
+pub(crate) fn scratch_register_names<'a>(
+    ops: impl Iterator<Item = &'a NamedAsmOp>,
+    bound: &[&'static str],
+) -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = Vec::new();
+    for op in ops {
+        for arg in &op.args {
+            if !bound.contains(arg)
+                && !names.contains(arg)
+                && ConstantRegister::parse_register_name(arg).is_none()
+            {
+                names.push(arg);
+            }
+        }
+    }
+    names
+}
+
+/// Lower a single named op, looking up its registers in `registers`.  Any name which isn't
+/// there must be a reserved register.
+pub(super) fn convert_named_asm_op(
+    named_op: &NamedAsmOp,
+    registers: &HashMap<&str, VirtualRegister>,
+    comment: &str,
+    span: &Span,
+) -> CompileResult<Op> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let args = named_op
+        .args
+        .iter()
+        .map(|name| {
+            registers.get(name).cloned().unwrap_or_else(|| {
+                VirtualRegister::Constant(
+                    ConstantRegister::parse_register_name(name)
+                        .expect("scratch registers are all declared"),
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    let opcode = check!(
+        Op::parse_opcode(
+            &Ident::new_with_override(named_op.name, span.clone()),
+            &args,
+            &named_op
+                .immediate
+                .map(|imm| Ident::new_with_override(imm, span.clone())),
+            span.clone(),
+        ),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    ok(
+        Op {
+            opcode: Either::Left(opcode),
+            comment: comment.to_string(),
+            owning_span: Some(span.clone()),
+        },
+        warnings,
+        errors,
+    )
+}
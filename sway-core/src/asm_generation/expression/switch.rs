@@ -0,0 +1,384 @@
+use crate::asm_generation::{
+    convert_expression_to_asm, AsmNamespace,
+    Either::{Left, Right},
+    RegisterSequencer,
+};
+use crate::asm_lang::{
+    ConstantRegister, Label, Op, VirtualImmediate12, VirtualOp, VirtualRegister,
+};
+use crate::error::*;
+use crate::parse_tree::Literal;
+
+use crate::semantic_analysis::{integer_switch_table, IntegerSwitchTable, TypedExpression};
+
+use crate::CompileResult;
+
+/// The number of cases below which the search just compares against each case in turn.
+const MAX_LINEAR_CASES: usize = 3;
+
+pub(crate) fn convert_integer_switch_to_asm(
+    scrutinee: &TypedExpression,
+    cases: &[(u64, TypedExpression)],
+    default: &TypedExpression,
+    return_register: &VirtualRegister,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<Op>> {
+    // The asm order is: scrutinee evaluation,
+    //         the search, which jumps to the label of the matching arm or to the default arm,
+    //         each arm in turn, followed by moving its result to the return register and jumping
+    //           to after the switch,
+    //         the default arm, also moving its result to the return register,
+    //         after switch label
+    // If the results can be looked up in a table instead then that replaces the search and the
+    // arms, jumping to the default arm if the scrutinee is outside the table.
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut asm_buf = vec![];
+
+    let scrutinee_result = register_sequencer.next();
+    let mut scrutinee_asm = check!(
+        convert_expression_to_asm(scrutinee, namespace, &scrutinee_result, register_sequencer),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    asm_buf.push(Op::new_comment("begin integer switch"));
+    asm_buf.append(&mut scrutinee_asm);
+
+    let default_label = register_sequencer.get_label();
+    let after_switch_label = register_sequencer.get_label();
+
+    let table = integer_switch_table(cases, default).and_then(|table| {
+        namespace
+            .append_data_table(&table.results)
+            .map(|offset_in_words| (table, offset_in_words))
+    });
+    let (cases, case_labels) = match table {
+        Some((table, offset_in_words)) => {
+            asm_buf.append(&mut look_up_table(
+                &scrutinee_result,
+                &table,
+                offset_in_words,
+                return_register,
+                &default_label,
+                &after_switch_label,
+                namespace,
+                register_sequencer,
+            ));
+            (&[][..], Vec::new())
+        }
+        None => {
+            let case_labels = cases
+                .iter()
+                .map(|(value, _)| (*value, register_sequencer.get_label()))
+                .collect::<Vec<_>>();
+            asm_buf.append(&mut search_cases(
+                &scrutinee_result,
+                &case_labels,
+                &default_label,
+                namespace,
+                register_sequencer,
+            ));
+            (cases, case_labels)
+        }
+    };
+
+    let arms = cases
+        .iter()
+        .zip(case_labels)
+        .map(|((_, body), (_, label))| (body, label))
+        .chain(std::iter::once((default, default_label)));
+    for (body, label) in arms {
+        asm_buf.push(Op::jump_label_comment(
+            label,
+            body.span.clone(),
+            "switch arm",
+        ));
+        let arm_result = register_sequencer.next();
+        let mut arm_asm = check!(
+            convert_expression_to_asm(body, namespace, &arm_result, register_sequencer),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+
+        // has arm_result register been defined?
+        let return_reg_defined = arm_asm.iter().any(|op| {
+            match &op.opcode {
+                Left(inst) => inst.def_registers().contains(&arm_result),
+                Right(_) => false, // Organizational Ops don't def registers
+            }
+        });
+
+        asm_buf.append(&mut arm_asm);
+        if return_reg_defined {
+            asm_buf.push(Op::register_move(
+                return_register.clone(),
+                arm_result,
+                body.span.clone(),
+            ));
+        }
+        asm_buf.push(Op::jump_to_label_comment(
+            after_switch_label.clone(),
+            "end of switch arm",
+        ));
+    }
+
+    asm_buf.push(Op::unowned_jump_label_comment(
+        after_switch_label,
+        "End of integer switch",
+    ));
+
+    ok(asm_buf, warnings, errors)
+}
+
+/// Loads the result for `scrutinee` from `table`, which is `offset_in_words` into the data
+/// section, into `return_register` and jumps to `after_switch_label`, or jumps to
+/// `default_label` if the scrutinee is outside the table.
+#[allow(clippy::too_many_arguments)]
+fn look_up_table(
+    scrutinee: &VirtualRegister,
+    table: &IntegerSwitchTable,
+    offset_in_words: u64,
+    return_register: &VirtualRegister,
+    default_label: &Label,
+    after_switch_label: &Label,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+) -> Vec<Op> {
+    let mut asm_buf = vec![];
+    let index = if table.first_value == 0 {
+        scrutinee.clone()
+    } else {
+        let first_value = register_sequencer.next();
+        let data_id = namespace.insert_data_value(&Literal::U64(table.first_value));
+        asm_buf.push(Op::unowned_load_data_comment(
+            first_value.clone(),
+            data_id,
+            "switch table first value",
+        ));
+        let is_below = register_sequencer.next();
+        asm_buf.push(Op::unowned_new_with_comment(
+            VirtualOp::LT(is_below.clone(), scrutinee.clone(), first_value.clone()),
+            "compare against switch table first value",
+        ));
+        asm_buf.push(Op::jump_if_not_equal(
+            is_below,
+            VirtualRegister::Constant(ConstantRegister::Zero),
+            default_label.clone(),
+        ));
+        let index = register_sequencer.next();
+        asm_buf.push(Op::unowned_new_with_comment(
+            VirtualOp::SUB(index.clone(), scrutinee.clone(), first_value),
+            "switch table index",
+        ));
+        index
+    };
+
+    let len = register_sequencer.next();
+    let data_id = namespace.insert_data_value(&Literal::U64(table.results.len() as u64));
+    asm_buf.push(Op::unowned_load_data_comment(
+        len.clone(),
+        data_id,
+        "switch table length",
+    ));
+    let is_in_table = register_sequencer.next();
+    asm_buf.push(Op::unowned_new_with_comment(
+        VirtualOp::LT(is_in_table.clone(), index.clone(), len),
+        "compare against switch table length",
+    ));
+    asm_buf.push(Op::jump_if_not_equal(
+        is_in_table,
+        VirtualRegister::Constant(ConstantRegister::One),
+        default_label.clone(),
+    ));
+
+    let entry_offset = register_sequencer.next();
+    asm_buf.push(Op::unowned_new_with_comment(
+        VirtualOp::MULI(
+            entry_offset.clone(),
+            index,
+            VirtualImmediate12::new_unchecked(8, "infallible"),
+        ),
+        "switch table entry offset",
+    ));
+    let entry_address = register_sequencer.next();
+    asm_buf.push(Op::unowned_new_with_comment(
+        VirtualOp::ADD(
+            entry_address.clone(),
+            VirtualRegister::Constant(ConstantRegister::DataSectionStart),
+            entry_offset,
+        ),
+        "switch table entry address",
+    ));
+    asm_buf.push(Op::unowned_new_with_comment(
+        VirtualOp::LW(
+            return_register.clone(),
+            entry_address,
+            VirtualImmediate12::new_unchecked(offset_in_words, "checked by append_table()"),
+        ),
+        "load switch table entry",
+    ));
+    asm_buf.push(Op::jump_to_label_comment(
+        after_switch_label.clone(),
+        "end of switch table lookup",
+    ));
+    asm_buf
+}
+
+/// Jumps to the label of whichever of the sorted `cases` is equal to `scrutinee`, or to
+/// `default_label` if none are.  The cases are halved with an unsigned less-than until only a few
+/// remain, which are then compared one at a time.
+fn search_cases(
+    scrutinee: &VirtualRegister,
+    cases: &[(u64, Label)],
+    default_label: &Label,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+) -> Vec<Op> {
+    let mut asm_buf = vec![];
+    if cases.len() > MAX_LINEAR_CASES {
+        let (lower_cases, upper_cases) = cases.split_at(cases.len() / 2);
+        let upper_label = register_sequencer.get_label();
+
+        let pivot = register_sequencer.next();
+        let data_id = namespace.insert_data_value(&Literal::U64(upper_cases[0].0));
+        asm_buf.push(Op::unowned_load_data_comment(
+            pivot.clone(),
+            data_id,
+            "switch pivot",
+        ));
+        let is_lower = register_sequencer.next();
+        asm_buf.push(Op::unowned_new_with_comment(
+            VirtualOp::LT(is_lower.clone(), scrutinee.clone(), pivot),
+            "compare against switch pivot",
+        ));
+        asm_buf.push(Op::jump_if_not_equal(
+            is_lower,
+            VirtualRegister::Constant(ConstantRegister::One),
+            upper_label.clone(),
+        ));
+        asm_buf.append(&mut search_cases(
+            scrutinee,
+            lower_cases,
+            default_label,
+            namespace,
+            register_sequencer,
+        ));
+        asm_buf.push(Op::unowned_jump_label(upper_label));
+        asm_buf.append(&mut search_cases(
+            scrutinee,
+            upper_cases,
+            default_label,
+            namespace,
+            register_sequencer,
+        ));
+        return asm_buf;
+    }
+
+    for (value, label) in cases {
+        let case_value = register_sequencer.next();
+        let data_id = namespace.insert_data_value(&Literal::U64(*value));
+        asm_buf.push(Op::unowned_load_data_comment(
+            case_value.clone(),
+            data_id,
+            "switch case value",
+        ));
+        let is_equal = register_sequencer.next();
+        asm_buf.push(Op::unowned_new_with_comment(
+            VirtualOp::EQ(is_equal.clone(), scrutinee.clone(), case_value),
+            "compare against switch case",
+        ));
+        asm_buf.push(Op::jump_if_not_equal(
+            is_equal,
+            VirtualRegister::Constant(ConstantRegister::Zero),
+            label.clone(),
+        ));
+    }
+    asm_buf.push(Op::jump_to_label(default_label.clone()));
+    asm_buf
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::{check_with_each_backend, ASM_OPERATORS};
+    use fuel_vm::prelude::*;
+
+    // Sum `f` of each value below `limit`, weighting each result by its position so that the
+    // order matters.  The loop keeps the values from being known where the switch is.
+    fn check_sum(switch_fn: &str, limit: u64, expected: u64) {
+        check_with_each_backend(
+            &format!(
+                r#"
+    script;{ASM_OPERATORS}
+    {switch_fn}
+    fn main() -> u64 {{
+        let mut i = 0;
+        let mut sum = 0;
+        while lt(i, {limit}) {{
+            sum = add(mul(sum, 2), f(i));
+            i = add(i, 1);
+        }}
+        sum
+    }}"#
+            ),
+            ProgramState::Return(expected),
+        );
+    }
+
+    fn expected_sum(f: impl Fn(u64) -> u64, limit: u64) -> u64 {
+        (0..limit).fold(0, |sum, i| sum * 2 + f(i))
+    }
+
+    #[test]
+    fn dense_switch_table() {
+        let days = |month| match month {
+            1 | 3 | 5 => 31,
+            2 => 28,
+            4 | 6 => 30,
+            _ => 0,
+        };
+        check_sum(
+            r#"
+    fn f(month: u64) -> u64 {
+        match month {
+            1 => { 31 },
+            2 => { 28 },
+            3 => { 31 },
+            4 => { 30 },
+            5 => { 31 },
+            6 => { 30 },
+            _ => { 0 },
+        }
+    }"#,
+            9,
+            expected_sum(days, 9),
+        );
+    }
+
+    #[test]
+    fn sparse_switch_search() {
+        let sparse = |x| match x {
+            1000000 => 1,
+            3 => 2,
+            42 => 3,
+            0 => 4,
+            _ => 5,
+        };
+        check_sum(
+            r#"
+    fn f(x: u64) -> u64 {
+        match x {
+            1000000 => { 1 },
+            3 => { 2 },
+            42 => { 3 },
+            0 => { 4 },
+            _ => { 5 },
+        }
+    }"#,
+            40,
+            expected_sum(sparse, 40),
+        );
+    }
+}
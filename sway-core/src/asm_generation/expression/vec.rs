@@ -0,0 +1,769 @@
+//! Code generation for the built-in heap allocated `Vec` type.
+//!
+//! A `Vec` value is a single word which points at a three word header on the heap:
+//!
+//! ```text
+//! [ element buffer pointer | capacity in elements | length in elements ]
+//! ```
+//!
+//! The element buffer is allocated on the heap too, and elements are stored unpacked, one after
+//! the other.  When a `push` finds the buffer full a new buffer with twice the capacity, plus one,
+//! is allocated and the existing elements are copied across.  Memory allocated with `ALOC` is
+//! never freed, so the old buffer is simply abandoned.
+//!
+//! A vector passed to a contract method is encoded as a pointer to two words, the pointer to its
+//! elements and its length, and the method rebuilds it from a copy of the elements.  A vector
+//! returned from a contract method is returned as its elements, the length being implied by the
+//! size of the return data.
+//!
+//! The instruction sequences are described by register name so that they may be shared by both
+//! the AST and the IR code generators.  The sequences themselves are branch free; the bounds
+//! checks and the reallocation in `push` are separate sequences which the code generators guard
+//! with a branch.
+//!
+//! `MCP` can't copy zero bytes, so every copy is of at least one element, even from an empty
+//! vector, and every element takes at least a word.
+
+use super::*;
+use crate::semantic_analysis::{ast_node::VecIntrinsicKind, IsConstant};
+use either::Either;
+
+/// The name of the register which holds the size of a single element, in bytes.  It is always
+/// initialised by the code generator.
+pub(crate) const VEC_ELEM_SIZE_REG: &str = "esz";
+
+/// The name of the register which is set to non-zero by a bounds check when the access is valid.
+pub(crate) const VEC_IN_BOUNDS_REG: &str = "in_bounds";
+
+/// The name of the register which is set to non-zero by a space check when the buffer has room
+/// for another element.
+pub(crate) const VEC_HAS_SPACE_REG: &str = "has_space";
+
+/// The instructions required to implement a single [`VecIntrinsic`].
+pub(crate) struct VecIntrinsicAsm {
+    /// The registers to be initialised with the intrinsic's arguments, in order.
+    pub(crate) arg_names: Vec<&'static str>,
+    /// Instructions which set [`VEC_IN_BOUNDS_REG`] to non-zero if the access is within the
+    /// bounds of the vector.  If the flag is zero then the code generator must revert before
+    /// executing the body.  This is empty for the intrinsics which can't fail.
+    pub(crate) bounds_check: Vec<NamedAsmOp>,
+    /// Instructions which set [`VEC_HAS_SPACE_REG`] to non-zero if the buffer has room for
+    /// another element.  If the flag is zero then the code generator must execute `grow` before
+    /// the body.  Both are empty for the intrinsics which don't add elements.
+    pub(crate) space_check: Vec<NamedAsmOp>,
+    pub(crate) grow: Vec<NamedAsmOp>,
+    pub(crate) body: Vec<NamedAsmOp>,
+    pub(crate) return_name: Option<&'static str>,
+}
+
+impl VecIntrinsicAsm {
+    /// All the registers used by the instructions which are neither arguments nor reserved
+    /// registers.  These need to be declared as scratch registers.
+    pub(crate) fn scratch_names(&self) -> Vec<&'static str> {
+        let mut bound = self.arg_names.clone();
+        bound.push(VEC_ELEM_SIZE_REG);
+        scratch_register_names(
+            self.bounds_check
+                .iter()
+                .chain(self.space_check.iter())
+                .chain(self.grow.iter())
+                .chain(self.body.iter()),
+            &bound,
+        )
+    }
+}
+
+/// Allocate a zeroed header and leave a pointer to it in `hdr`.
+fn alloc_header() -> Vec<NamedAsmOp> {
+    vec![
+        op("addi", &["hsz", "zero"], Some("i24")),
+        op("aloc", &["hsz"], None),
+        op("addi", &["hdr", "hp"], Some("i1")),
+        op("sw", &["hdr", "zero"], Some("i0")),
+        op("sw", &["hdr", "zero"], Some("i1")),
+        op("sw", &["hdr", "zero"], Some("i2")),
+    ]
+}
+
+/// Describe the instructions for `kind`.  Elements of a copy type are loaded and stored by
+/// value, anything larger is passed around by reference and copied in and out of the buffer.
+pub(crate) fn vec_intrinsic_asm(kind: &VecIntrinsicKind, elem_is_copy: bool) -> VecIntrinsicAsm {
+    match kind {
+        VecIntrinsicKind::New => VecIntrinsicAsm {
+            arg_names: vec![],
+            bounds_check: vec![],
+            space_check: vec![],
+            grow: vec![],
+            body: alloc_header(),
+            return_name: Some("hdr"),
+        },
+        VecIntrinsicKind::WithCapacity(_) => {
+            let mut body = alloc_header();
+            body.append(&mut vec![
+                op("mul", &["size", "cap", VEC_ELEM_SIZE_REG], None),
+                op("aloc", &["size"], None),
+                op("addi", &["buf", "hp"], Some("i1")),
+                op("sw", &["hdr", "buf"], Some("i0")),
+                op("sw", &["hdr", "cap"], Some("i1")),
+            ]);
+            VecIntrinsicAsm {
+                arg_names: vec!["cap"],
+                bounds_check: vec![],
+                space_check: vec![],
+                grow: vec![],
+                body,
+                return_name: Some("hdr"),
+            }
+        }
+        VecIntrinsicKind::Len(_) => VecIntrinsicAsm {
+            arg_names: vec!["hdr"],
+            bounds_check: vec![],
+            space_check: vec![],
+            grow: vec![],
+            body: vec![op("lw", &["len", "hdr"], Some("i2"))],
+            return_name: Some("len"),
+        },
+        VecIntrinsicKind::Push { .. } => {
+            let mut body = vec![
+                op("lw", &["buf", "hdr"], Some("i0")),
+                op("lw", &["len", "hdr"], Some("i2")),
+                op("mul", &["offs", "len", VEC_ELEM_SIZE_REG], None),
+                op("add", &["addr", "buf", "offs"], None),
+            ];
+            body.push(if elem_is_copy {
+                op("sw", &["addr", "val"], Some("i0"))
+            } else {
+                op("mcp", &["addr", "val", VEC_ELEM_SIZE_REG], None)
+            });
+            body.append(&mut vec![
+                op("addi", &["len", "len"], Some("i1")),
+                op("sw", &["hdr", "len"], Some("i2")),
+            ]);
+            VecIntrinsicAsm {
+                arg_names: vec!["hdr", "val"],
+                bounds_check: vec![],
+                space_check: vec![
+                    op("lw", &["cap", "hdr"], Some("i1")),
+                    op("lw", &["len", "hdr"], Some("i2")),
+                    op("lt", &[VEC_HAS_SPACE_REG, "len", "cap"], None),
+                ],
+                grow: vec![
+                    op("lw", &["buf", "hdr"], Some("i0")),
+                    op("lw", &["cap", "hdr"], Some("i1")),
+                    // ncap = cap * 2 + 1
+                    op("add", &["ncap", "cap", "cap"], None),
+                    op("addi", &["ncap", "ncap"], Some("i1")),
+                    op("mul", &["size", "ncap", VEC_ELEM_SIZE_REG], None),
+                    op("aloc", &["size"], None),
+                    op("addi", &["nbuf", "hp"], Some("i1")),
+                    // The buffer is full, so copy all of it, or a single element from an empty
+                    // one.  That element is overwritten by the push.
+                    op("eq", &["empty", "cap", "zero"], None),
+                    op("add", &["size", "cap", "empty"], None),
+                    op("mul", &["size", "size", VEC_ELEM_SIZE_REG], None),
+                    op("mcp", &["nbuf", "buf", "size"], None),
+                    op("sw", &["hdr", "nbuf"], Some("i0")),
+                    op("sw", &["hdr", "ncap"], Some("i1")),
+                ],
+                body,
+                return_name: None,
+            }
+        }
+        VecIntrinsicKind::Pop(_) => {
+            let mut body = vec![
+                op("lw", &["buf", "hdr"], Some("i0")),
+                op("lw", &["len", "hdr"], Some("i2")),
+                op("subi", &["len", "len"], Some("i1")),
+                op("sw", &["hdr", "len"], Some("i2")),
+                op("mul", &["offs", "len", VEC_ELEM_SIZE_REG], None),
+                op("add", &["addr", "buf", "offs"], None),
+            ];
+            if elem_is_copy {
+                body.push(op("lw", &["val", "addr"], Some("i0")));
+            } else {
+                // The popped slot will be reused by the next push, so the element is copied out
+                // to a fresh allocation.
+                body.append(&mut vec![
+                    op("aloc", &[VEC_ELEM_SIZE_REG], None),
+                    op("addi", &["val", "hp"], Some("i1")),
+                    op("mcp", &["val", "addr", VEC_ELEM_SIZE_REG], None),
+                ]);
+            }
+            VecIntrinsicAsm {
+                arg_names: vec!["hdr"],
+                bounds_check: vec![
+                    op("lw", &["len", "hdr"], Some("i2")),
+                    op("lt", &[VEC_IN_BOUNDS_REG, "zero", "len"], None),
+                ],
+                space_check: vec![],
+                grow: vec![],
+                body,
+                return_name: Some("val"),
+            }
+        }
+        VecIntrinsicKind::Index { .. } => {
+            let mut body = vec![
+                op("lw", &["buf", "hdr"], Some("i0")),
+                op("mul", &["offs", "idx", VEC_ELEM_SIZE_REG], None),
+                op("add", &["addr", "buf", "offs"], None),
+            ];
+            // Like array indexing, larger elements are returned by reference.
+            let return_name = if elem_is_copy {
+                body.push(op("lw", &["val", "addr"], Some("i0")));
+                "val"
+            } else {
+                "addr"
+            };
+            VecIntrinsicAsm {
+                arg_names: vec!["hdr", "idx"],
+                bounds_check: vec![
+                    op("lw", &["len", "hdr"], Some("i2")),
+                    op("lt", &[VEC_IN_BOUNDS_REG, "idx", "len"], None),
+                ],
+                space_check: vec![],
+                grow: vec![],
+                body,
+                return_name: Some(return_name),
+            }
+        }
+        VecIntrinsicKind::ToCallData(_) => VecIntrinsicAsm {
+            arg_names: vec!["hdr"],
+            bounds_check: vec![],
+            space_check: vec![],
+            grow: vec![],
+            body: vec![
+                op("addi", &["dsz", "zero"], Some("i16")),
+                op("aloc", &["dsz"], None),
+                op("addi", &["data", "hp"], Some("i1")),
+                op("lw", &["buf", "hdr"], Some("i0")),
+                op("lw", &["len", "hdr"], Some("i2")),
+                op("sw", &["data", "buf"], Some("i0")),
+                op("sw", &["data", "len"], Some("i1")),
+            ],
+            return_name: Some("data"),
+        },
+    }
+}
+
+/// Encode an argument of a contract call.  A vector is passed as a pointer to its element pointer
+/// and length, as the callee can't write to the caller's heap and so needs a copy of the
+/// elements rather than the header.  Any other argument is passed as it is.
+pub(crate) fn contract_call_argument(arg: TypedExpression) -> TypedExpression {
+    match look_up_type_id(arg.return_type) {
+        TypeInfo::Vec(elem_type) => TypedExpression {
+            span: arg.span.clone(),
+            expression: TypedExpressionVariant::VecIntrinsic {
+                kind: VecIntrinsicKind::ToCallData(Box::new(arg)),
+                elem_type,
+            },
+            return_type: insert_type(TypeInfo::UnsignedInteger(IntegerBits::SixtyFour)),
+            is_constant: IsConstant::No,
+        },
+        _ => arg,
+    }
+}
+
+/// Describe the instructions for rebuilding a vector passed to a contract method from the pointer
+/// to its element pointer and length in `data`.  The elements are in the caller's memory, which
+/// the callee may read but not write, so they are copied into a new buffer on the callee's heap.
+pub(crate) fn vec_from_call_data_asm() -> VecIntrinsicAsm {
+    let mut body = alloc_header();
+    body.append(&mut vec![
+        op("lw", &["src", "data"], Some("i0")),
+        op("lw", &["len", "data"], Some("i1")),
+        // Room for at least one element, so that the copy isn't empty.
+        op("eq", &["empty", "len", "zero"], None),
+        op("add", &["cap", "len", "empty"], None),
+        op("mul", &["size", "cap", VEC_ELEM_SIZE_REG], None),
+        op("aloc", &["size"], None),
+        op("addi", &["buf", "hp"], Some("i1")),
+        op("mcp", &["buf", "src", "size"], None),
+        op("sw", &["hdr", "buf"], Some("i0")),
+        op("sw", &["hdr", "cap"], Some("i1")),
+        op("sw", &["hdr", "len"], Some("i2")),
+    ]);
+    VecIntrinsicAsm {
+        arg_names: vec!["data"],
+        bounds_check: vec![],
+        space_check: vec![],
+        grow: vec![],
+        body,
+        return_name: Some("hdr"),
+    }
+}
+
+/// Describe the instructions for returning the vector in `hdr` from a contract call.  The
+/// element buffer is returned with `RETD`, the length being implied by the size of the return
+/// data.
+pub(crate) fn vec_return_asm() -> VecIntrinsicAsm {
+    VecIntrinsicAsm {
+        arg_names: vec!["hdr"],
+        bounds_check: vec![],
+        space_check: vec![],
+        grow: vec![],
+        body: vec![
+            op("lw", &["buf", "hdr"], Some("i0")),
+            op("lw", &["len", "hdr"], Some("i2")),
+            op("mul", &["size", "len", VEC_ELEM_SIZE_REG], None),
+            op("retd", &["buf", "size"], None),
+        ],
+        return_name: None,
+    }
+}
+
+/// Describe the instructions for rebuilding a vector from the data returned by a contract call.
+/// The return data belongs to the callee so it is copied into a new buffer on the caller's heap.
+pub(crate) fn vec_from_return_data_asm() -> VecIntrinsicAsm {
+    let mut body = alloc_header();
+    body.append(&mut vec![
+        op("div", &["len", "retl", VEC_ELEM_SIZE_REG], None),
+        // Room for at least one element, so that the copy isn't empty.
+        op("eq", &["empty", "len", "zero"], None),
+        op("add", &["cap", "len", "empty"], None),
+        op("mul", &["size", "cap", VEC_ELEM_SIZE_REG], None),
+        op("aloc", &["size"], None),
+        op("addi", &["buf", "hp"], Some("i1")),
+        op("mcp", &["buf", "ret", "size"], None),
+        op("sw", &["hdr", "buf"], Some("i0")),
+        op("sw", &["hdr", "cap"], Some("i1")),
+        op("sw", &["hdr", "len"], Some("i2")),
+    ]);
+    VecIntrinsicAsm {
+        arg_names: vec![],
+        bounds_check: vec![],
+        space_check: vec![],
+        grow: vec![],
+        body,
+        return_name: Some("hdr"),
+    }
+}
+
+pub(super) fn convert_vec_intrinsic_to_asm(
+    kind: &VecIntrinsicKind,
+    elem_type: TypeId,
+    namespace: &mut AsmNamespace,
+    return_register: &VirtualRegister,
+    register_sequencer: &mut RegisterSequencer,
+    span: Span,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut asm_buf = vec![];
+
+    let elem_type = check_std_result!(resolve_type(elem_type, &span), warnings, errors);
+    let vec_asm = vec_intrinsic_asm(kind, elem_type.is_copy_type());
+
+    let mut arg_registers = vec![];
+    for arg in kind.arguments() {
+        let arg_reg = register_sequencer.next();
+        asm_buf.append(&mut check!(
+            convert_expression_to_asm(arg, namespace, &arg_reg, register_sequencer),
+            return err(warnings, errors),
+            warnings,
+            errors
+        ));
+        arg_registers.push(arg_reg);
+    }
+    asm_buf.append(&mut check!(
+        convert_vec_asm(
+            &vec_asm,
+            arg_registers,
+            &elem_type,
+            Some(return_register),
+            &format!("vec {}", kind.name()),
+            namespace,
+            register_sequencer,
+            span,
+        ),
+        return err(warnings, errors),
+        warnings,
+        errors
+    ));
+
+    ok(asm_buf, warnings, errors)
+}
+
+/// Return the vector in `vec_register` from a contract method.
+pub(crate) fn convert_vec_return_to_asm(
+    vec_register: VirtualRegister,
+    elem_type: TypeId,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+    span: Span,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let elem_type = check_std_result!(resolve_type(elem_type, &span), warnings, errors);
+    let asm_buf = check!(
+        convert_vec_asm(
+            &vec_return_asm(),
+            vec![vec_register],
+            &elem_type,
+            None,
+            "return vec",
+            namespace,
+            register_sequencer,
+            span,
+        ),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    ok(asm_buf, warnings, errors)
+}
+
+/// Rebuild a vector passed to a contract method, from the call data in `data_register`, into
+/// `return_register`.
+pub(crate) fn convert_vec_from_call_data_to_asm(
+    data_register: VirtualRegister,
+    elem_type: TypeId,
+    namespace: &mut AsmNamespace,
+    return_register: &VirtualRegister,
+    register_sequencer: &mut RegisterSequencer,
+    span: Span,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let elem_type = check_std_result!(resolve_type(elem_type, &span), warnings, errors);
+    let asm_buf = check!(
+        convert_vec_asm(
+            &vec_from_call_data_asm(),
+            vec![data_register],
+            &elem_type,
+            Some(return_register),
+            "vec from call data",
+            namespace,
+            register_sequencer,
+            span,
+        ),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    ok(asm_buf, warnings, errors)
+}
+
+/// Rebuild a vector from the data returned by a contract call into `return_register`.
+pub(super) fn convert_vec_from_return_data_to_asm(
+    elem_type: TypeId,
+    namespace: &mut AsmNamespace,
+    return_register: &VirtualRegister,
+    register_sequencer: &mut RegisterSequencer,
+    span: Span,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let elem_type = check_std_result!(resolve_type(elem_type, &span), warnings, errors);
+    let asm_buf = check!(
+        convert_vec_asm(
+            &vec_from_return_data_asm(),
+            vec![],
+            &elem_type,
+            Some(return_register),
+            "vec from return data",
+            namespace,
+            register_sequencer,
+            span,
+        ),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+    ok(asm_buf, warnings, errors)
+}
+
+/// Lower `vec_asm` to ops, with its arguments already evaluated into `arg_registers`.  If
+/// `return_register` is given then the result is moved into it.
+#[allow(clippy::too_many_arguments)]
+fn convert_vec_asm(
+    vec_asm: &VecIntrinsicAsm,
+    arg_registers: Vec<VirtualRegister>,
+    elem_type: &TypeInfo,
+    return_register: Option<&VirtualRegister>,
+    comment: &str,
+    namespace: &mut AsmNamespace,
+    register_sequencer: &mut RegisterSequencer,
+    span: Span,
+) -> CompileResult<Vec<Op>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let mut asm_buf = vec![];
+
+    let elem_size_in_bytes =
+        check_std_result!(elem_type.size_in_bytes(&span), warnings, errors).max(8);
+
+    // Bind the arguments to their named registers, then set up the element size and the
+    // scratch registers.
+    let mut registers: HashMap<&str, VirtualRegister> = vec_asm
+        .arg_names
+        .iter()
+        .copied()
+        .zip(arg_registers)
+        .collect();
+    let elem_size_reg = register_sequencer.next();
+    asm_buf.append(&mut convert_literal_to_asm(
+        &Literal::U64(elem_size_in_bytes),
+        namespace,
+        &elem_size_reg,
+        register_sequencer,
+        span.clone(),
+    ));
+    registers.insert(VEC_ELEM_SIZE_REG, elem_size_reg);
+    for name in vec_asm.scratch_names() {
+        registers.insert(name, register_sequencer.next());
+    }
+
+    let mut convert_ops = |ops: &[NamedAsmOp], asm_buf: &mut Vec<Op>| {
+        for vec_op in ops {
+            asm_buf.push(check!(
+                convert_named_asm_op(vec_op, &registers, comment, &span),
+                continue,
+                warnings,
+                errors
+            ));
+        }
+    };
+    if !vec_asm.bounds_check.is_empty() {
+        convert_ops(&vec_asm.bounds_check, &mut asm_buf);
+
+        // Jump past the RVRT if the access is in bounds.
+        let skip_label = register_sequencer.get_label();
+        asm_buf.push(Op::jump_if_not_equal(
+            registers[VEC_IN_BOUNDS_REG].clone(),
+            VirtualRegister::Constant(ConstantRegister::Zero),
+            skip_label.clone(),
+        ));
+        let revert_code_reg = register_sequencer.next();
+        let revert_code = namespace.insert_data_value(&Literal::U64(
+            crate::constants::INDEX_OUT_OF_BOUNDS_REVERT_CODE,
+        ));
+        asm_buf.push(Op {
+            opcode: Either::Left(VirtualOp::LWDataId(revert_code_reg.clone(), revert_code)),
+            owning_span: Some(span.clone()),
+            comment: "load out of bounds revert code".into(),
+        });
+        asm_buf.push(Op {
+            opcode: Either::Left(VirtualOp::RVRT(revert_code_reg)),
+            owning_span: Some(span.clone()),
+            comment: "aborting due to out of bounds access".into(),
+        });
+        asm_buf.push(Op::jump_label_comment(
+            skip_label,
+            span.clone(),
+            "after bounds check",
+        ));
+    }
+    if !vec_asm.space_check.is_empty() {
+        convert_ops(&vec_asm.space_check, &mut asm_buf);
+
+        // Jump past the reallocation if the buffer has space.
+        let skip_label = register_sequencer.get_label();
+        asm_buf.push(Op::jump_if_not_equal(
+            registers[VEC_HAS_SPACE_REG].clone(),
+            VirtualRegister::Constant(ConstantRegister::Zero),
+            skip_label.clone(),
+        ));
+        convert_ops(&vec_asm.grow, &mut asm_buf);
+        asm_buf.push(Op::jump_label_comment(
+            skip_label,
+            span.clone(),
+            "after vec reallocation",
+        ));
+    }
+    convert_ops(&vec_asm.body, &mut asm_buf);
+
+    if let (Some(return_register), Some(return_name)) = (return_register, vec_asm.return_name) {
+        asm_buf.push(Op::unowned_register_move_comment(
+            return_register.clone(),
+            registers[return_name].clone(),
+            "return value from vec intrinsic",
+        ));
+    }
+
+    ok(asm_buf, warnings, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        constants::INDEX_OUT_OF_BOUNDS_REVERT_CODE,
+        test_utils::{build_config, check_with_each_backend, compile_to_bytes, ASM_OPERATORS},
+    };
+    use fuel_vm::{interpreter::Interpreter, prelude::*};
+
+    fn compile(kind: &str, source: &str, use_ir: bool) -> Vec<u8> {
+        compile_to_bytes(
+            &format!("{};{}\n{}", kind, ASM_OPERATORS, source),
+            build_config().use_ir(use_ir),
+        )
+    }
+
+    fn script(script: Vec<u8>, inputs: Vec<Input>, outputs: Vec<Output>) -> Transaction {
+        Transaction::script(0, 1_000_000, 0, 0, script, vec![], inputs, outputs, vec![])
+    }
+
+    fn check(main: &str, expected: ProgramState) {
+        check_with_each_backend(&format!("script;{}\n{}", ASM_OPERATORS, main), expected);
+    }
+
+    #[test]
+    fn push_pop_len_and_index() {
+        // The vector starts with no buffer and is reallocated as it grows, keeping the elements
+        // it already has.
+        check(
+            r#"
+    fn main() -> u64 {
+        let v: Vec<u64> = Vec::new();
+        let mut i = 0;
+        while lt(i, 10) {
+            v.push(mul(i, i));
+            i = add(i, 1);
+        }
+        let last = v.pop();
+        let mut sum = 0;
+        let mut j = 0;
+        while lt(j, v.len()) {
+            sum = add(sum, v[j]);
+            j = add(j, 1);
+        }
+        add(mul(last, 1000), add(mul(v.len(), 100000), sum))
+    }"#,
+            ProgramState::Return(981204),
+        );
+    }
+
+    #[test]
+    fn with_capacity() {
+        check(
+            r#"
+    fn main() -> u64 {
+        let v: Vec<u64> = Vec::with_capacity(2);
+        let w = v;
+        v.push(3);
+        w.push(4);
+        v.push(5);
+        add(mul(w.len(), 100), add(mul(w[0], 10), v[2]))
+    }"#,
+            ProgramState::Return(335),
+        );
+    }
+
+    #[test]
+    fn index_out_of_bounds() {
+        check(
+            r#"
+    fn main() -> u64 {
+        let v: Vec<u64> = Vec::new();
+        v.push(1);
+        v[v.len()]
+    }"#,
+            ProgramState::Revert(INDEX_OUT_OF_BOUNDS_REVERT_CODE),
+        );
+    }
+
+    #[test]
+    fn pop_empty() {
+        check(
+            r#"
+    fn main() -> u64 {
+        let v: Vec<u64> = Vec::new();
+        v.push(1);
+        v.pop();
+        v.pop()
+    }"#,
+            ProgramState::Revert(INDEX_OUT_OF_BOUNDS_REVERT_CODE),
+        );
+    }
+
+    #[test]
+    fn contract_call_round_trip() {
+        let abi = r#"
+    abi Test {
+        fn append(vals: Vec<u64>, val: u64) -> Vec<u64>;
+        fn doubled(vals: Vec<u64>) -> Vec<u64>;
+    }"#;
+        let contract = format!(
+            r#"{}
+    impl Test for Contract {{
+        fn append(vals: Vec<u64>, val: u64) -> Vec<u64> {{
+            vals.push(val);
+            vals
+        }}
+        fn doubled(vals: Vec<u64>) -> Vec<u64> {{
+            let doubled: Vec<u64> = Vec::new();
+            let mut i = 0;
+            while lt(i, vals.len()) {{
+                doubled.push(mul(vals[i], 2));
+                i = add(i, 1);
+            }}
+            doubled
+        }}
+    }}"#,
+            abi
+        );
+        for use_ir in [false, true] {
+            let program: Witness = compile("contract", &contract, use_ir).into();
+            let salt = Salt::default();
+            let code = Contract::from(program.as_ref());
+            let state_root = Contract::default_state_root();
+            let contract_id = code.id(&salt, &code.root(), &state_root);
+            let deploy = Transaction::create(
+                0,
+                1_000_000,
+                0,
+                0,
+                0,
+                salt,
+                vec![],
+                vec![],
+                vec![],
+                vec![Output::contract_created(contract_id, state_root)],
+                vec![program],
+            );
+
+            // The callee pushes to its own copy of `v`, and the vectors it returns are copied
+            // into the caller.  An empty vector makes the round trip too.
+            let main = format!(
+                r#"{}
+    fn main() -> u64 {{
+        let caller = abi(Test, 0x{:x});
+        let v: Vec<u64> = Vec::new();
+        v.push(1);
+        v.push(2);
+        let w = caller.append {{
+            gas: 50000
+        }}
+        (v, 3);
+        let d = caller.doubled {{
+            gas: 50000
+        }}
+        (w);
+        let e: Vec<u64> = Vec::new();
+        let de = caller.doubled {{
+            gas: 50000
+        }}
+        (e);
+        de.push(7);
+        let r = add(mul(v.len(), 1000), add(mul(d.len(), 100), add(mul(w[2], 10), d[2])));
+        add(mul(add(de.len(), de[0]), 10000), r)
+    }}"#,
+                abi, contract_id
+            );
+            let call = script(
+                compile("script", &main, use_ir),
+                vec![Input::contract(
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                    contract_id,
+                )],
+                vec![Output::contract(0, Default::default(), Default::default())],
+            );
+
+            let mut vm = Interpreter::with_storage(MemoryStorage::default());
+            vm.transact(deploy).unwrap();
+            let state = *vm.transact(call).unwrap().state();
+            assert_eq!(state, ProgramState::Return(82336), "use_ir: {}", use_ir);
+        }
+    }
+}
@@ -7,7 +7,7 @@
 // But this is not ideal and needs to be refactored:
 // - AsmNamespace is tied to data structures from other stages like Ident and Literal.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     asm_generation::{
@@ -39,7 +39,7 @@ pub fn compile_ir_to_asm(ir: &Context, build_config: &BuildConfig) -> CompileRes
     assert!(ir.module_iter().count() == 1);
     let module = ir.module_iter().next().unwrap();
     let (data_section, mut ops, mut reg_seqr) = check!(
-        compile_module_to_asm(reg_seqr, ir, module),
+        compile_module_to_asm(reg_seqr, ir, module, build_config),
         return err(warnings, errors),
         warnings,
         errors
@@ -55,7 +55,11 @@ pub fn compile_ir_to_asm(ir: &Context, build_config: &BuildConfig) -> CompileRes
             program_section: AbstractInstructionSet { ops: bytecode },
             data_section,
         },
-        Kind::Library | Kind::Predicate => todo!("libraries and predicates coming soon!"),
+        Kind::Predicate => SwayAsmSet::PredicateMain {
+            program_section: AbstractInstructionSet { ops: bytecode },
+            data_section,
+        },
+        Kind::Library => todo!("libraries coming soon!"),
     };
 
     if build_config.print_intermediate_asm {
@@ -85,10 +89,13 @@ fn compile_module_to_asm(
     reg_seqr: RegisterSequencer,
     context: &Context,
     module: Module,
+    build_config: &BuildConfig,
 ) -> CompileResult<(DataSection, Vec<Op>, RegisterSequencer)> {
     let mut builder = AsmBuilder::new(DataSection::default(), reg_seqr, context);
+    builder.skip_bounds_checks = build_config.skip_bounds_checks;
+    builder.is_predicate = matches!(module.get_kind(context), Kind::Predicate);
     match module.get_kind(context) {
-        Kind::Script => {
+        Kind::Script | Kind::Predicate => {
             // We can't do function calls yet, so we expect everything to be inlined into `main`.
             let function = module
                 .function_iter(context)
@@ -125,8 +132,22 @@ fn compile_module_to_asm(
                 errors
             );
 
-            let mut bytecode_with_switch =
-                build_contract_abi_switch(&mut reg_seqr, &mut data_section, selectors_and_labels);
+            let hot_selectors = build_config
+                .hot_abi_methods
+                .iter()
+                .filter_map(|method_name| {
+                    module
+                        .function_iter(context)
+                        .find(|function| &context.functions[function.0].name == method_name)
+                        .and_then(|function| function.get_selector(context))
+                })
+                .collect::<Vec<_>>();
+            let mut bytecode_with_switch = build_contract_abi_switch(
+                &mut reg_seqr,
+                &mut data_section,
+                selectors_and_labels,
+                &hot_selectors,
+            );
             bytecode_with_switch.append(&mut funcs_bytecode);
             ok(
                 (data_section, bytecode_with_switch, reg_seqr),
@@ -134,7 +155,7 @@ fn compile_module_to_asm(
                 errors,
             )
         }
-        Kind::Library | Kind::Predicate => todo!("libraries and predicates coming soon!"),
+        Kind::Library => todo!("libraries coming soon!"),
     }
 }
 
@@ -171,11 +192,30 @@ struct AsmBuilder<'ir> {
     // Stack base register, copied from $SP at the start, but only if we have stack storage.
     stack_base_reg: Option<VirtualRegister>,
 
+    // Values in the current function which may be written through if they're pointers.  See
+    // `escaping_values()`.
+    escaping_values: HashSet<Value>,
+
     // IR context we're compiling.
     context: &'ir Context,
 
     // Final resulting VM bytecode ops.
     bytecode: Vec<Op>,
+
+    // Whether the module is a predicate, whose main() reads its arguments from the predicate data.
+    is_predicate: bool,
+
+    // Whether to omit the runtime checks on array indices which are proven to be in range by a
+    // dominating comparison.
+    skip_bounds_checks: bool,
+
+    // The array element instructions in the current function whose index is proven to be in range.
+    in_bounds_accesses: HashSet<Value>,
+
+    // Constant arrays in the current function which are only read from with `extract_element`, so
+    // may be left in the data section, and the word offset of each once it has been placed there.
+    read_only_tables: HashSet<Value>,
+    table_offsets: HashMap<Value, u64>,
 }
 
 // NOTE: For stack storage we need to be aware:
@@ -203,8 +243,14 @@ impl<'ir> AsmBuilder<'ir> {
             reg_map: HashMap::new(),
             ptr_map: HashMap::new(),
             stack_base_reg: None,
+            escaping_values: HashSet::new(),
             context,
             bytecode: Vec::new(),
+            is_predicate: false,
+            skip_bounds_checks: false,
+            in_bounds_accesses: HashSet::new(),
+            read_only_tables: HashSet::new(),
+            table_offsets: HashMap::new(),
         }
     }
 
@@ -221,10 +267,12 @@ impl<'ir> AsmBuilder<'ir> {
         }
     }
 
-    // Handle loading the arguments of a contract call
+    // Handle loading the arguments of a contract call, or of a predicate from its predicate data
     fn compile_fn_args(&mut self, function: Function) {
-        // Do this only for contract methods. Contract methods have selectors.
-        if !function.has_selector(self.context) {
+        // Contract methods have selectors, and find their arguments using the $fp register.  The
+        // only function in a predicate is main(), whose arguments are in the predicate data.
+        let has_selector = function.has_selector(self.context);
+        if !has_selector && !self.is_predicate {
             return;
         }
 
@@ -233,18 +281,25 @@ impl<'ir> AsmBuilder<'ir> {
             return;
         }
 
-        // Base pointer for the arumgnets using the $fp register
         let args_base_reg = self.reg_seqr.next();
-        self.bytecode.push(Op {
-            opcode: Either::Left(VirtualOp::LW(
-                args_base_reg.clone(),
-                VirtualRegister::Constant(ConstantRegister::FramePointer),
-                // see https://github.com/FuelLabs/fuel-specs/pull/193#issuecomment-876496372
-                VirtualImmediate12 { value: 74 },
-            )),
-            comment: "Base register for method parameter".into(),
-            owning_span: None,
-        });
+        if has_selector {
+            self.bytecode.push(Op {
+                opcode: Either::Left(VirtualOp::LW(
+                    args_base_reg.clone(),
+                    VirtualRegister::Constant(ConstantRegister::FramePointer),
+                    // see https://github.com/FuelLabs/fuel-specs/pull/193#issuecomment-876496372
+                    VirtualImmediate12 { value: 74 },
+                )),
+                comment: "Base register for method parameter".into(),
+                owning_span: None,
+            });
+        } else {
+            self.bytecode
+                .append(&mut crate::asm_generation::load_predicate_data_pointer(
+                    args_base_reg.clone(),
+                    &mut self.reg_seqr,
+                ));
+        }
 
         // Successively load each argument. The asm generated depends on the arg type size and
         // whether the offset fits in a 12-bit immediate.
@@ -254,39 +309,17 @@ impl<'ir> AsmBuilder<'ir> {
             let arg_type_size_bytes =
                 ir_type_size_in_bytes(self.context, &val.get_type(self.context).unwrap());
             if arg_type_size_bytes <= 8 {
-                if arg_word_offset > crate::asm_generation::compiler_constants::TWELVE_BITS {
-                    let offs_reg = self.reg_seqr.next();
-                    self.bytecode.push(Op {
-                        opcode: Either::Left(VirtualOp::ADD(
-                            args_base_reg.clone(),
-                            args_base_reg.clone(),
-                            offs_reg.clone(),
-                        )),
-                        comment: format!("Get offset for arg {}", name),
-                        owning_span: None,
-                    });
-                    self.bytecode.push(Op {
-                        opcode: Either::Left(VirtualOp::LW(
-                            current_arg_reg.clone(),
-                            offs_reg,
-                            VirtualImmediate12 { value: 0 },
-                        )),
-                        comment: format!("Get arg {}", name),
-                        owning_span: None,
-                    });
-                } else {
-                    self.bytecode.push(Op {
-                        opcode: Either::Left(VirtualOp::LW(
-                            current_arg_reg.clone(),
-                            args_base_reg.clone(),
-                            VirtualImmediate12 {
-                                value: arg_word_offset as u16,
-                            },
-                        )),
-                        comment: format!("Get arg {}", name),
-                        owning_span: None,
-                    });
-                }
+                let (arg_base_reg, arg_offs_imm) =
+                    self.word_offset_to_base_and_imm(&args_base_reg, arg_word_offset, None);
+                self.bytecode.push(Op {
+                    opcode: Either::Left(VirtualOp::LW(
+                        current_arg_reg.clone(),
+                        arg_base_reg,
+                        arg_offs_imm,
+                    )),
+                    comment: format!("Get arg {}", name),
+                    owning_span: None,
+                });
             } else if arg_word_offset * 8 > crate::asm_generation::compiler_constants::TWELVE_BITS {
                 let offs_reg = self.reg_seqr.next();
                 self.number_to_reg(arg_word_offset * 8, &offs_reg, None);
@@ -322,10 +355,24 @@ impl<'ir> AsmBuilder<'ir> {
         // Otherwise they go in runtime allocated space, either a register or on the stack.
         //
         // Stack offsets are in words to both enforce alignment and simplify use with LW/SW.
+        //
+        // Constant structs and arrays can't be put in the data section, so they're allocated on the
+        // stack like any other aggregate and initialised once the stack space is reserved.
+        let context = self.context;
         let mut stack_base = 0_u64;
-        for (_name, ptr) in function.locals_iter(self.context) {
-            let ptr_content = &self.context.pointers[ptr.0];
-            if !ptr_content.is_mutable && ptr_content.initializer.is_some() {
+        let mut stack_initialisers = Vec::new();
+        for (_name, ptr) in function.locals_iter(context) {
+            let ptr_content = &context.pointers[ptr.0];
+            let const_aggregate = ptr_content.initializer.as_ref().filter(|constant| {
+                matches!(
+                    constant.value,
+                    ConstantValue::Array(_) | ConstantValue::Struct(_)
+                )
+            });
+            if !ptr_content.is_mutable
+                && ptr_content.initializer.is_some()
+                && const_aggregate.is_none()
+            {
                 let constant = ptr_content.initializer.as_ref().unwrap();
                 let lit = ir_constant_to_ast_literal(constant);
                 let data_id = self.data_section.insert_data_value(&lit);
@@ -350,6 +397,9 @@ impl<'ir> AsmBuilder<'ir> {
                     Type::Array(_) | Type::Struct(_) | Type::Union(_) => {
                         // Store this aggregate at the current stack base.
                         self.ptr_map.insert(*ptr, Storage::Stack(stack_base));
+                        if let Some(constant) = const_aggregate {
+                            stack_initialisers.push((stack_base, constant));
+                        }
 
                         // Reserve space by incrementing the base.
                         stack_base += size_bytes_in_words!(ir_type_size_in_bytes(
@@ -372,14 +422,14 @@ impl<'ir> AsmBuilder<'ir> {
 
             // It's possible (though undesirable) to have empty local data structures only.
             if stack_base != 0 {
-                if stack_base * 8 > crate::asm_generation::compiler_constants::TWENTY_FOUR_BITS {
-                    todo!("Enormous stack usage for locals.");
-                }
-                let mut alloc_op = Op::unowned_stack_allocate_memory(VirtualImmediate24 {
-                    value: (stack_base * 8) as u32,
-                });
-                alloc_op.comment = format!("allocate {} bytes for all locals", stack_base * 8);
-                self.bytecode.push(alloc_op);
+                self.stack_allocate(
+                    stack_base * 8,
+                    format!("allocate {} bytes for all locals", stack_base * 8),
+                    None,
+                );
+            }
+            for (word_offs, constant) in stack_initialisers {
+                self.initialise_constant_memory(constant, &base_reg, word_offs, None);
             }
             self.stack_base_reg = Some(base_reg);
         }
@@ -408,6 +458,14 @@ impl<'ir> AsmBuilder<'ir> {
     }
 
     fn compile_function(&mut self, function: Function) -> CompileResult<()> {
+        self.escaping_values = escaping_values(self.context, &function);
+        self.in_bounds_accesses = if self.skip_bounds_checks {
+            in_bounds_accesses(self.context, &function)
+        } else {
+            HashSet::new()
+        };
+        self.read_only_tables = read_only_tables(self.context, &function);
+
         // Compile instructions.
         self.add_locals(function);
         self.compile_fn_args(function);
@@ -440,6 +498,9 @@ impl<'ir> AsmBuilder<'ir> {
                         errors
                     )
                 }
+                Instruction::BinaryOp { op, lhs, rhs } => {
+                    self.compile_binary_op(instr_val, op, lhs, rhs)
+                }
                 Instruction::Branch(to_block) => self.compile_branch(block, to_block),
                 Instruction::Call(..) => {
                     errors.push(CompileError::Internal(
@@ -476,7 +537,12 @@ impl<'ir> AsmBuilder<'ir> {
                     base_ptr,
                     ptr_ty,
                     offset,
-                } => self.compile_get_pointer(instr_val, base_ptr, ptr_ty, *offset),
+                } => check!(
+                    self.compile_get_pointer(instr_val, base_ptr, ptr_ty, *offset),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                ),
                 Instruction::InsertElement {
                     array,
                     ty,
@@ -496,6 +562,7 @@ impl<'ir> AsmBuilder<'ir> {
                     errors
                 ),
                 Instruction::Nop => (),
+                Instruction::Not(value) => self.compile_not(instr_val, value),
                 Instruction::Phi(_) => (), // Managing the phi value is done in br and cbr compilation.
                 Instruction::ReadRegister(reg) => self.compile_read_register(instr_val, reg),
                 Instruction::Ret(ret_val, ty) => self.compile_ret(instr_val, ret_val, ty),
@@ -693,8 +760,38 @@ impl<'ir> AsmBuilder<'ir> {
         ok((), warnings, errors)
     }
 
+    fn compile_binary_op(
+        &mut self,
+        instr_val: &Value,
+        op: &BinaryOpKind,
+        lhs_value: &Value,
+        rhs_value: &Value,
+    ) {
+        let lhs_reg = self.value_to_register(lhs_value);
+        let rhs_reg = self.value_to_register(rhs_value);
+        let res_reg = self.reg_seqr.next();
+        let opcode = match op {
+            BinaryOpKind::Add => VirtualOp::ADD(res_reg.clone(), lhs_reg, rhs_reg),
+            BinaryOpKind::Sub => VirtualOp::SUB(res_reg.clone(), lhs_reg, rhs_reg),
+            BinaryOpKind::Mul => VirtualOp::MUL(res_reg.clone(), lhs_reg, rhs_reg),
+            BinaryOpKind::Div => VirtualOp::DIV(res_reg.clone(), lhs_reg, rhs_reg),
+            BinaryOpKind::Mod => VirtualOp::MOD(res_reg.clone(), lhs_reg, rhs_reg),
+            BinaryOpKind::And => VirtualOp::AND(res_reg.clone(), lhs_reg, rhs_reg),
+            BinaryOpKind::Or => VirtualOp::OR(res_reg.clone(), lhs_reg, rhs_reg),
+            BinaryOpKind::Xor => VirtualOp::XOR(res_reg.clone(), lhs_reg, rhs_reg),
+            BinaryOpKind::Shl => VirtualOp::SLL(res_reg.clone(), lhs_reg, rhs_reg),
+            BinaryOpKind::Shr => VirtualOp::SRL(res_reg.clone(), lhs_reg, rhs_reg),
+        };
+        self.bytecode.push(Op {
+            opcode: Either::Left(opcode),
+            comment: String::new(),
+            owning_span: instr_val.get_span(self.context),
+        });
+        self.reg_map.insert(*instr_val, res_reg);
+    }
+
     fn compile_branch(&mut self, from_block: &Block, to_block: &Block) {
-        self.compile_branch_to_phi_value(from_block, to_block);
+        self.compile_branch_to_phi_values(from_block, &[*to_block]);
 
         let label = self.block_to_label(to_block);
         self.bytecode.push(Op::jump_to_label(label));
@@ -718,6 +815,20 @@ impl<'ir> AsmBuilder<'ir> {
                     owning_span: instr_val.get_span(self.context),
                 });
             }
+            Predicate::LessThan => {
+                self.bytecode.push(Op {
+                    opcode: Either::Left(VirtualOp::LT(res_reg.clone(), lhs_reg, rhs_reg)),
+                    comment: String::new(),
+                    owning_span: instr_val.get_span(self.context),
+                });
+            }
+            Predicate::GreaterThan => {
+                self.bytecode.push(Op {
+                    opcode: Either::Left(VirtualOp::GT(res_reg.clone(), lhs_reg, rhs_reg)),
+                    comment: String::new(),
+                    owning_span: instr_val.get_span(self.context),
+                });
+            }
         }
         self.reg_map.insert(*instr_val, res_reg);
     }
@@ -729,8 +840,11 @@ impl<'ir> AsmBuilder<'ir> {
         true_block: &Block,
         false_block: &Block,
     ) {
-        self.compile_branch_to_phi_value(from_block, true_block);
-        self.compile_branch_to_phi_value(from_block, false_block);
+        if true_block == false_block {
+            self.compile_branch_to_phi_values(from_block, &[*true_block]);
+        } else {
+            self.compile_branch_to_phi_values(from_block, &[*true_block, *false_block]);
+        }
 
         let cond_reg = self.value_to_register(cond_value);
 
@@ -745,10 +859,36 @@ impl<'ir> AsmBuilder<'ir> {
         self.bytecode.push(Op::jump_to_label(true_label));
     }
 
-    fn compile_branch_to_phi_value(&mut self, from_block: &Block, to_block: &Block) {
-        if let Some(local_val) = to_block.get_phi_val_coming_from(self.context, from_block) {
-            let local_reg = self.value_to_register(&local_val);
-            let phi_reg = self.value_to_register(&to_block.get_phi(self.context));
+    fn compile_branch_to_phi_values(&mut self, from_block: &Block, to_blocks: &[Block]) {
+        let phi_moves = to_blocks
+            .iter()
+            .flat_map(|to_block| to_block.get_phi_vals_coming_from(self.context, from_block))
+            .filter(|(phi_val, local_val)| phi_val != local_val)
+            .collect::<Vec<_>>();
+
+        // The phis are all assigned at once, so if any of them is also a value being assigned,
+        // e.g., when swapping values in a loop, then it must be read before it's overwritten.  In
+        // that case the values are copied to temporary registers first.
+        let needs_temps = phi_moves
+            .iter()
+            .any(|(_, local_val)| phi_moves.iter().any(|(phi_val, _)| phi_val == local_val));
+
+        let phi_moves = phi_moves
+            .into_iter()
+            .map(|(phi_val, local_val)| {
+                let local_reg = self.value_to_register(&local_val);
+                let local_reg = if needs_temps {
+                    let temp_reg = self.reg_seqr.next();
+                    self.bytecode
+                        .push(Op::unowned_register_move(temp_reg.clone(), local_reg));
+                    temp_reg
+                } else {
+                    local_reg
+                };
+                (self.value_to_register(&phi_val), local_reg)
+            })
+            .collect::<Vec<_>>();
+        for (phi_reg, local_reg) in phi_moves {
             self.bytecode
                 .push(Op::unowned_register_move(phi_reg, local_reg));
         }
@@ -801,9 +941,7 @@ impl<'ir> AsmBuilder<'ir> {
 
         // Index value is the array element index, not byte nor word offset.
         let index_reg = self.value_to_register(index_val);
-
-        // We could put the OOB check here, though I'm now thinking it would be too wasteful.
-        // See compile_bounds_assertion() in expression/array.rs (or look in Git history).
+        self.compile_bounds_check(instr_val, ty, index_val, &index_reg);
 
         let instr_reg = self.reg_seqr.next();
         let elem_size =
@@ -877,6 +1015,78 @@ impl<'ir> AsmBuilder<'ir> {
         self.reg_map.insert(*instr_val, instr_reg);
     }
 
+    // Revert if the index is beyond the end of the array.  Constant indices which are known to be
+    // in bounds don't need checking, and with `--skip-bounds-checks` neither do indices which are
+    // proven to be in bounds by a comparison before the access.
+    fn compile_bounds_check(
+        &mut self,
+        instr_val: &Value,
+        ty: &Aggregate,
+        index_val: &Value,
+        index_reg: &VirtualRegister,
+    ) {
+        let count = *self.context.aggregates[ty.0].array_type().1;
+        let is_const_in_bounds = matches!(
+            &self.context.values[index_val.0].value,
+            ValueDatum::Constant(Constant {
+                value: ConstantValue::Uint(idx),
+                ..
+            }) if *idx < count
+        );
+        if is_const_in_bounds || self.in_bounds_accesses.contains(instr_val) {
+            return;
+        }
+
+        let last_idx_reg = self.reg_seqr.next();
+        let last_idx_data_id = self
+            .data_section
+            .insert_data_value(&Literal::U64(count.saturating_sub(1)));
+        self.bytecode.push(Op {
+            opcode: Either::Left(VirtualOp::LWDataId(last_idx_reg.clone(), last_idx_data_id)),
+            comment: "load last valid array index".into(),
+            owning_span: instr_val.get_span(self.context),
+        });
+        let gt_reg = self.reg_seqr.next();
+        self.bytecode.push(Op {
+            opcode: Either::Left(VirtualOp::GT(
+                gt_reg.clone(),
+                index_reg.clone(),
+                last_idx_reg,
+            )),
+            comment: "compare array index for out of bounds".into(),
+            owning_span: instr_val.get_span(self.context),
+        });
+
+        // Jump past the RVRT if the index is in bounds.
+        let skip_label = self.reg_seqr.get_label();
+        self.bytecode.push(Op::jump_if_not_equal(
+            gt_reg,
+            VirtualRegister::Constant(ConstantRegister::One),
+            skip_label.clone(),
+        ));
+        let revert_code_reg = self.reg_seqr.next();
+        let revert_code_data_id = self.data_section.insert_data_value(&Literal::U64(
+            crate::constants::INDEX_OUT_OF_BOUNDS_REVERT_CODE,
+        ));
+        self.bytecode.push(Op {
+            opcode: Either::Left(VirtualOp::LWDataId(
+                revert_code_reg.clone(),
+                revert_code_data_id,
+            )),
+            comment: "load out of bounds revert code".into(),
+            owning_span: instr_val.get_span(self.context),
+        });
+        self.bytecode.push(Op {
+            opcode: Either::Left(VirtualOp::RVRT(revert_code_reg)),
+            comment: "aborting due to out of bounds access".into(),
+            owning_span: instr_val.get_span(self.context),
+        });
+        self.bytecode.push(Op::unowned_jump_label_comment(
+            skip_label,
+            "after bounds check",
+        ));
+    }
+
     fn compile_extract_value(&mut self, instr_val: &Value, aggregate_val: &Value, indices: &[u64]) {
         // Base register should pointer to some stack allocated memory.
         let base_reg = self.value_to_register(aggregate_val);
@@ -888,58 +1098,27 @@ impl<'ir> AsmBuilder<'ir> {
 
         let instr_reg = self.reg_seqr.next();
         if value_size <= 8 {
-            if extract_offset > crate::asm_generation::compiler_constants::TWELVE_BITS {
-                let offset_reg = self.reg_seqr.next();
-                self.number_to_reg(
-                    extract_offset,
-                    &offset_reg,
-                    instr_val.get_span(self.context),
-                );
-                self.bytecode.push(Op {
-                    opcode: Either::Left(VirtualOp::ADD(
-                        offset_reg.clone(),
-                        base_reg.clone(),
-                        base_reg,
-                    )),
-                    comment: "add array base to offset".into(),
-                    owning_span: instr_val.get_span(self.context),
-                });
-                self.bytecode.push(Op {
-                    opcode: Either::Left(VirtualOp::LW(
-                        instr_reg.clone(),
-                        offset_reg,
-                        VirtualImmediate12 { value: 0 },
-                    )),
-                    comment: format!(
-                        "extract_value @ {}",
-                        indices
-                            .iter()
-                            .map(|idx| format!("{}", idx))
-                            .collect::<Vec<String>>()
-                            .join(",")
-                    ),
-                    owning_span: instr_val.get_span(self.context),
-                });
-            } else {
-                self.bytecode.push(Op {
-                    opcode: Either::Left(VirtualOp::LW(
-                        instr_reg.clone(),
-                        base_reg,
-                        VirtualImmediate12 {
-                            value: extract_offset as u16,
-                        },
-                    )),
-                    comment: format!(
-                        "extract_value @ {}",
-                        indices
-                            .iter()
-                            .map(|idx| format!("{}", idx))
-                            .collect::<Vec<String>>()
-                            .join(",")
-                    ),
-                    owning_span: instr_val.get_span(self.context),
-                });
-            }
+            let (extract_base_reg, extract_offs_imm) = self.word_offset_to_base_and_imm(
+                &base_reg,
+                extract_offset,
+                instr_val.get_span(self.context),
+            );
+            self.bytecode.push(Op {
+                opcode: Either::Left(VirtualOp::LW(
+                    instr_reg.clone(),
+                    extract_base_reg,
+                    extract_offs_imm,
+                )),
+                comment: format!(
+                    "extract_value @ {}",
+                    indices
+                        .iter()
+                        .map(|idx| format!("{}", idx))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                ),
+                owning_span: instr_val.get_span(self.context),
+            });
         } else {
             // Value too big for a register, so we return the memory offset.
             if extract_offset * 8 > crate::asm_generation::compiler_constants::TWELVE_BITS {
@@ -982,14 +1161,14 @@ impl<'ir> AsmBuilder<'ir> {
         base_ptr: &Pointer,
         ptr_ty: &Type,
         offset: u64,
-    ) {
+    ) -> CompileResult<()> {
         // `get_ptr` is like a `load` except the value isn't dereferenced.
         match self.ptr_map.get(base_ptr) {
             None => unimplemented!("BUG? Uninitialised pointer."),
             Some(storage) => match storage.clone() {
-                Storage::Data(_data_id) => {
-                    // Not sure if we'll ever need this.
-                    unimplemented!("TODO get_ptr() into the data section.");
+                Storage::Data(data_id) => {
+                    return self
+                        .compile_get_data_pointer(instr_val, base_ptr, ptr_ty, offset, data_id);
                 }
                 Storage::Register(var_reg) => {
                     // Not expecting an offset here nor a pointer cast
@@ -1008,6 +1187,15 @@ impl<'ir> AsmBuilder<'ir> {
                             &instr_reg,
                             instr_val.get_span(self.context),
                         );
+                        self.bytecode.push(Op {
+                            opcode: either::Either::Left(VirtualOp::ADD(
+                                instr_reg.clone(),
+                                self.stack_base_reg.as_ref().unwrap().clone(),
+                                instr_reg.clone(),
+                            )),
+                            comment: "get_ptr".into(),
+                            owning_span: instr_val.get_span(self.context),
+                        });
                     } else {
                         self.bytecode.push(Op {
                             opcode: either::Either::Left(VirtualOp::ADDI(
@@ -1025,6 +1213,103 @@ impl<'ir> AsmBuilder<'ir> {
                 }
             },
         }
+        ok((), Vec::new(), Vec::new())
+    }
+
+    fn compile_get_data_pointer(
+        &mut self,
+        instr_val: &Value,
+        base_ptr: &Pointer,
+        ptr_ty: &Type,
+        offset: u64,
+        data_id: DataId,
+    ) -> CompileResult<()> {
+        // For a single word constant LWDataId loads the value itself, which is what we use for
+        // pointers to register storage too.  Otherwise it loads the address of the data.
+        let span = instr_val.get_span(self.context);
+        let data_reg = self.reg_seqr.next();
+        self.bytecode.push(Op {
+            opcode: Either::Left(VirtualOp::LWDataId(data_reg.clone(), data_id)),
+            comment: "get_ptr to constant".into(),
+            owning_span: span.clone(),
+        });
+        let data_size_in_bytes =
+            ir_type_size_in_bytes(self.context, base_ptr.get_type(self.context));
+        if data_size_in_bytes <= 8 {
+            // Not expecting an offset here nor a pointer cast, since we have the value rather than
+            // its address.
+            if offset != 0 || !ptr_ty.eq(self.context, base_ptr.get_type(self.context)) {
+                return err(
+                    Vec::new(),
+                    vec![CompileError::Internal(
+                        "Offset or cast get_ptr to a single word constant.",
+                        span.unwrap_or_else(Self::empty_span),
+                    )],
+                );
+            }
+            self.reg_map.insert(*instr_val, data_reg);
+            return ok((), Vec::new(), Vec::new());
+        }
+
+        let ptr_reg = self.writable_data_address(instr_val, data_reg, data_size_in_bytes);
+        let offset_in_bytes = ir_type_size_in_bytes(self.context, ptr_ty) * offset;
+        let instr_reg = if offset_in_bytes == 0 {
+            ptr_reg
+        } else {
+            self.offset_reg(&ptr_reg, offset_in_bytes, span)
+        };
+        self.reg_map.insert(*instr_val, instr_reg);
+        ok((), Vec::new(), Vec::new())
+    }
+
+    // The data section should be read only, so if the address of a constant may be written
+    // through or leaves the function then the constant is copied to a temporary on the stack and
+    // the address of the copy is used instead.
+    fn writable_data_address(
+        &mut self,
+        instr_val: &Value,
+        data_reg: VirtualRegister,
+        size_in_bytes: u64,
+    ) -> VirtualRegister {
+        if !self.escaping_values.contains(instr_val) {
+            return data_reg;
+        }
+
+        let span = instr_val.get_span(self.context);
+        let total_size = size_bytes_round_up_to_word_alignment!(size_in_bytes);
+        let start_reg = self.reg_seqr.next();
+        self.bytecode.push(Op::unowned_register_move_comment(
+            start_reg.clone(),
+            VirtualRegister::Constant(ConstantRegister::StackPointer),
+            "save register for temporary stack value",
+        ));
+        self.stack_allocate(
+            total_size,
+            format!("allocate {} bytes for temporary constant", total_size),
+            span.clone(),
+        );
+        if total_size > crate::asm_generation::compiler_constants::TWELVE_BITS {
+            let size_reg = self.reg_seqr.next();
+            self.number_to_reg(total_size, &size_reg, span.clone());
+            self.bytecode.push(Op {
+                opcode: Either::Left(VirtualOp::MCP(start_reg.clone(), data_reg, size_reg)),
+                comment: "copy constant to temporary".into(),
+                owning_span: span,
+            });
+        } else {
+            self.bytecode.push(Op {
+                opcode: Either::Left(VirtualOp::MCPI(
+                    start_reg.clone(),
+                    data_reg,
+                    VirtualImmediate12 {
+                        value: total_size as u16,
+                    },
+                )),
+                comment: "copy constant to temporary".into(),
+                owning_span: span,
+            });
+        }
+        start_reg
     }
 
     fn compile_insert_element(
@@ -1041,6 +1326,7 @@ impl<'ir> AsmBuilder<'ir> {
 
         // Index value is the array element index, not byte nor word offset.
         let index_reg = self.value_to_register(index_val);
+        self.compile_bounds_check(instr_val, ty, index_val, &index_reg);
 
         let elem_size =
             ir_type_size_in_bytes(self.context, &ty.get_elem_type(self.context).unwrap());
@@ -1076,7 +1362,32 @@ impl<'ir> AsmBuilder<'ir> {
         } else {
             // Element size is larger than 8; we switch to bytewise offsets and sizes and use MCP.
             if elem_size > crate::asm_generation::compiler_constants::TWELVE_BITS {
-                todo!("array element size bigger than 4k")
+                let size_reg = self.reg_seqr.next();
+                self.number_to_reg(elem_size, &size_reg, instr_val.get_span(self.context));
+                let elem_index_offs_reg = self.reg_seqr.next();
+                self.bytecode.push(Op {
+                    opcode: Either::Left(VirtualOp::MUL(
+                        elem_index_offs_reg.clone(),
+                        index_reg,
+                        size_reg.clone(),
+                    )),
+                    comment: "insert_element relative offset".into(),
+                    owning_span: instr_val.get_span(self.context),
+                });
+                self.bytecode.push(Op {
+                    opcode: Either::Left(VirtualOp::ADD(
+                        elem_index_offs_reg.clone(),
+                        base_reg.clone(),
+                        elem_index_offs_reg.clone(),
+                    )),
+                    comment: "insert_element absolute offset".into(),
+                    owning_span: instr_val.get_span(self.context),
+                });
+                self.bytecode.push(Op {
+                    opcode: Either::Left(VirtualOp::MCP(elem_index_offs_reg, insert_reg, size_reg)),
+                    comment: "insert_element store value".into(),
+                    owning_span: instr_val.get_span(self.context),
+                });
             } else {
                 let elem_index_offs_reg = self.reg_seqr.next();
                 self.bytecode.push(Op {
@@ -1141,48 +1452,29 @@ impl<'ir> AsmBuilder<'ir> {
             .collect::<Vec<String>>()
             .join(",");
         if value_size <= 8 {
-            if insert_offs > crate::asm_generation::compiler_constants::TWELVE_BITS {
-                let insert_offs_reg = self.reg_seqr.next();
-                self.number_to_reg(
-                    insert_offs,
-                    &insert_offs_reg,
-                    instr_val.get_span(self.context),
-                );
-                self.bytecode.push(Op {
-                    opcode: Either::Left(VirtualOp::ADD(
-                        base_reg.clone(),
-                        base_reg.clone(),
-                        insert_offs_reg,
-                    )),
-                    comment: "insert_value absolute offset".into(),
-                    owning_span: instr_val.get_span(self.context),
-                });
+            let (insert_base_reg, insert_offs_imm) = self.word_offset_to_base_and_imm(
+                &base_reg,
+                insert_offs,
+                instr_val.get_span(self.context),
+            );
+            self.bytecode.push(Op {
+                opcode: Either::Left(VirtualOp::SW(insert_base_reg, insert_reg, insert_offs_imm)),
+                comment: format!("insert_value @ {}", indices_str),
+                owning_span: instr_val.get_span(self.context),
+            });
+        } else {
+            let offs_reg = self.reg_seqr.next();
+            if insert_offs * 8 > crate::asm_generation::compiler_constants::TWELVE_BITS {
+                self.number_to_reg(insert_offs * 8, &offs_reg, instr_val.get_span(self.context));
                 self.bytecode.push(Op {
-                    opcode: Either::Left(VirtualOp::SW(
+                    opcode: either::Either::Left(VirtualOp::ADD(
+                        offs_reg.clone(),
                         base_reg.clone(),
-                        insert_reg,
-                        VirtualImmediate12 { value: 0 },
+                        offs_reg.clone(),
                     )),
-                    comment: format!("insert_value @ {}", indices_str),
+                    comment: format!("get struct field(s) {} offset", indices_str),
                     owning_span: instr_val.get_span(self.context),
                 });
-            } else {
-                self.bytecode.push(Op {
-                    opcode: Either::Left(VirtualOp::SW(
-                        base_reg.clone(),
-                        insert_reg,
-                        VirtualImmediate12 {
-                            value: insert_offs as u16,
-                        },
-                    )),
-                    comment: format!("insert_value @ {}", indices_str),
-                    owning_span: instr_val.get_span(self.context),
-                });
-            }
-        } else {
-            let offs_reg = self.reg_seqr.next();
-            if insert_offs * 8 > crate::asm_generation::compiler_constants::TWELVE_BITS {
-                self.number_to_reg(insert_offs * 8, &offs_reg, instr_val.get_span(self.context));
             } else {
                 self.bytecode.push(Op {
                     opcode: either::Either::Left(VirtualOp::ADDI(
@@ -1234,7 +1526,7 @@ impl<'ir> AsmBuilder<'ir> {
             self.context,
             ptr.get_type(self.context)
         ));
-        let instr_reg = self.reg_seqr.next();
+        let mut instr_reg = self.reg_seqr.next();
         match self.ptr_map.get(&ptr) {
             None => unimplemented!("BUG? Uninitialised pointer."),
             Some(storage) => match storage.clone() {
@@ -1244,6 +1536,14 @@ impl<'ir> AsmBuilder<'ir> {
                         comment: "load constant".into(),
                         owning_span: instr_val.get_span(self.context),
                     });
+                    if load_size_in_words > 1 {
+                        // We have the address of the constant rather than its value.
+                        instr_reg = self.writable_data_address(
+                            instr_val,
+                            instr_reg,
+                            load_size_in_words * 8,
+                        );
+                    }
                 }
                 Storage::Register(var_reg) => {
                     self.bytecode.push(Op {
@@ -1257,39 +1557,20 @@ impl<'ir> AsmBuilder<'ir> {
                     // XXX Need to check for zero sized types?
                     if load_size_in_words == 1 {
                         // Value can fit in a register, so we load the value.
-                        if word_offs > crate::asm_generation::compiler_constants::TWELVE_BITS {
-                            let offs_reg = self.reg_seqr.next();
-                            self.bytecode.push(Op {
-                                opcode: Either::Left(VirtualOp::ADD(
-                                    base_reg.clone(),
-                                    base_reg,
-                                    offs_reg.clone(),
-                                )),
-                                comment: "insert_value absolute offset".into(),
-                                owning_span: instr_val.get_span(self.context),
-                            });
-                            self.bytecode.push(Op {
-                                opcode: Either::Left(VirtualOp::LW(
-                                    instr_reg.clone(),
-                                    offs_reg,
-                                    VirtualImmediate12 { value: 0 },
-                                )),
-                                comment: "load value".into(),
-                                owning_span: instr_val.get_span(self.context),
-                            });
-                        } else {
-                            self.bytecode.push(Op {
-                                opcode: Either::Left(VirtualOp::LW(
-                                    instr_reg.clone(),
-                                    base_reg,
-                                    VirtualImmediate12 {
-                                        value: word_offs as u16,
-                                    },
-                                )),
-                                comment: "load value".into(),
-                                owning_span: instr_val.get_span(self.context),
-                            });
-                        }
+                        let (load_base_reg, load_offs_imm) = self.word_offset_to_base_and_imm(
+                            &base_reg,
+                            word_offs,
+                            instr_val.get_span(self.context),
+                        );
+                        self.bytecode.push(Op {
+                            opcode: Either::Left(VirtualOp::LW(
+                                instr_reg.clone(),
+                                load_base_reg,
+                                load_offs_imm,
+                            )),
+                            comment: "load value".into(),
+                            owning_span: instr_val.get_span(self.context),
+                        });
                     } else {
                         // Value too big for a register, so we return the memory offset.  This is
                         // what LW to the data section does, via LWDataId.
@@ -1331,6 +1612,27 @@ impl<'ir> AsmBuilder<'ir> {
         ok((), Vec::new(), Vec::new())
     }
 
+    fn compile_not(&mut self, instr_val: &Value, value: &Value) {
+        let val_reg = self.value_to_register(value);
+        let res_reg = self.reg_seqr.next();
+        // A bool is negated by comparing it with zero, a full bitwise NOT would leave the
+        // upper bits set.
+        let opcode = match value.get_type(self.context) {
+            Some(Type::Bool) => VirtualOp::EQ(
+                res_reg.clone(),
+                val_reg,
+                VirtualRegister::Constant(ConstantRegister::Zero),
+            ),
+            _otherwise => VirtualOp::NOT(res_reg.clone(), val_reg),
+        };
+        self.bytecode.push(Op {
+            opcode: Either::Left(opcode),
+            comment: String::new(),
+            owning_span: instr_val.get_span(self.context),
+        });
+        self.reg_map.insert(*instr_val, res_reg);
+    }
+
     fn compile_read_register(&mut self, instr_val: &Value, reg: &sway_ir::Register) {
         let instr_reg = self.reg_seqr.next();
         self.bytecode.push(Op {
@@ -1442,6 +1744,47 @@ impl<'ir> AsmBuilder<'ir> {
         offset_reg
     }
 
+    // Get the base register and word offset for a LW or SW of the word at `word_offs` from
+    // `base_reg`.  If the offset won't fit in the 12-bit immediate the address is computed into a
+    // new register instead.
+    fn word_offset_to_base_and_imm(
+        &mut self,
+        base_reg: &VirtualRegister,
+        word_offs: u64,
+        span: Option<Span>,
+    ) -> (VirtualRegister, VirtualImmediate12) {
+        if word_offs > crate::asm_generation::compiler_constants::TWELVE_BITS {
+            (
+                self.offset_reg(base_reg, word_offs * 8, span),
+                VirtualImmediate12 { value: 0 },
+            )
+        } else {
+            (
+                base_reg.clone(),
+                VirtualImmediate12 {
+                    value: word_offs as u16,
+                },
+            )
+        }
+    }
+
+    // Allocate `size_in_bytes` on the stack.  CFEI takes a 24-bit immediate so anything bigger is
+    // allocated in several word aligned chunks.
+    fn stack_allocate(&mut self, size_in_bytes: u64, comment: String, span: Option<Span>) {
+        const MAX_CHUNK: u64 = crate::asm_generation::compiler_constants::TWENTY_FOUR_BITS & !7;
+        let mut remaining = size_in_bytes;
+        while remaining > 0 {
+            let chunk = std::cmp::min(remaining, MAX_CHUNK);
+            let mut alloc_op = Op::unowned_stack_allocate_memory(VirtualImmediate24 {
+                value: chunk as u32,
+            });
+            alloc_op.comment = comment.clone();
+            alloc_op.owning_span = span.clone();
+            self.bytecode.push(alloc_op);
+            remaining -= chunk;
+        }
+    }
+
     fn compile_state_access_quad_word(
         &mut self,
         instr_val: &Value,
@@ -1649,44 +1992,21 @@ impl<'ir> AsmBuilder<'ir> {
                                 });
                                 tmp_reg
                             };
-                            if word_offs > crate::asm_generation::compiler_constants::TWELVE_BITS {
-                                let offs_reg = self.reg_seqr.next();
-                                self.number_to_reg(
+                            let (store_base_reg, store_offs_imm) = self
+                                .word_offset_to_base_and_imm(
+                                    &base_reg,
                                     word_offs,
-                                    &offs_reg,
                                     instr_val.get_span(self.context),
                                 );
-                                self.bytecode.push(Op {
-                                    opcode: Either::Left(VirtualOp::ADD(
-                                        base_reg.clone(),
-                                        base_reg,
-                                        offs_reg.clone(),
-                                    )),
-                                    comment: "store absolute offset".into(),
-                                    owning_span: instr_val.get_span(self.context),
-                                });
-                                self.bytecode.push(Op {
-                                    opcode: Either::Left(VirtualOp::SW(
-                                        offs_reg,
-                                        stored_reg,
-                                        VirtualImmediate12 { value: 0 },
-                                    )),
-                                    comment: "store value".into(),
-                                    owning_span: instr_val.get_span(self.context),
-                                });
-                            } else {
-                                self.bytecode.push(Op {
-                                    opcode: Either::Left(VirtualOp::SW(
-                                        base_reg,
-                                        stored_reg,
-                                        VirtualImmediate12 {
-                                            value: word_offs as u16,
-                                        },
-                                    )),
-                                    comment: "store value".into(),
-                                    owning_span: instr_val.get_span(self.context),
-                                });
-                            }
+                            self.bytecode.push(Op {
+                                opcode: Either::Left(VirtualOp::SW(
+                                    store_base_reg,
+                                    stored_reg,
+                                    store_offs_imm,
+                                )),
+                                comment: "store value".into(),
+                                owning_span: instr_val.get_span(self.context),
+                            });
                         }
                         _ => {
                             let base_reg = self.stack_base_reg.as_ref().unwrap().clone();
@@ -1790,6 +2110,12 @@ impl<'ir> AsmBuilder<'ir> {
                     // Handle constants.
                     ValueDatum::Constant(constant) => {
                         match &constant.value {
+                            ConstantValue::Array(elems)
+                                if self.read_only_tables.contains(value) =>
+                            {
+                                self.table_address(value, elems)
+                            }
+
                             ConstantValue::Struct(_) | ConstantValue::Array(_) => {
                                 // A constant struct or array.  We still allocate space for it on
                                 // the stack, but create the field or element initialisers
@@ -1799,11 +2125,6 @@ impl<'ir> AsmBuilder<'ir> {
                                 let total_size = size_bytes_round_up_to_word_alignment!(
                                     self.constant_size_in_bytes(constant)
                                 );
-                                if total_size
-                                    > crate::asm_generation::compiler_constants::TWENTY_FOUR_BITS
-                                {
-                                    todo!("Enormous stack usage for locals.");
-                                }
 
                                 let start_reg = self.reg_seqr.next();
 
@@ -1816,20 +2137,19 @@ impl<'ir> AsmBuilder<'ir> {
                                         "save register for temporary stack value",
                                     ));
 
-                                    let mut alloc_op =
-                                        Op::unowned_stack_allocate_memory(VirtualImmediate24 {
-                                            value: total_size as u32,
-                                        });
-                                    alloc_op.comment = format!(
-                                        "allocate {} bytes for temporary {}",
+                                    self.stack_allocate(
                                         total_size,
-                                        if matches!(&constant.value, ConstantValue::Struct(_)) {
-                                            "struct"
-                                        } else {
-                                            "array"
-                                        },
+                                        format!(
+                                            "allocate {} bytes for temporary {}",
+                                            total_size,
+                                            if matches!(&constant.value, ConstantValue::Struct(_)) {
+                                                "struct"
+                                            } else {
+                                                "array"
+                                            },
+                                        ),
+                                        None,
                                     );
-                                    self.bytecode.push(alloc_op);
 
                                     // Fill in the fields.
                                     self.initialise_constant_memory(
@@ -1900,7 +2220,14 @@ impl<'ir> AsmBuilder<'ir> {
 
     fn number_to_reg(&mut self, offset: u64, offset_reg: &VirtualRegister, span: Option<Span>) {
         if offset > crate::asm_generation::compiler_constants::TWENTY_FOUR_BITS {
-            todo!("Absolutely giant arrays.");
+            // Anything bigger than 24 bits is loaded from the data section.
+            let offset_data_id = self.data_section.insert_data_value(&Literal::U64(offset));
+            self.bytecode.push(Op {
+                opcode: Either::Left(VirtualOp::LWDataId(offset_reg.clone(), offset_data_id)),
+                comment: "get extract offset".into(),
+                owning_span: span,
+            });
+            return;
         }
 
         // Use bitwise ORs and SHIFTs to crate a 24 bit value in a register.
@@ -1937,6 +2264,44 @@ impl<'ir> AsmBuilder<'ir> {
         });
     }
 
+    // Load the address of a constant array which is only read from, placing it in the data
+    // section the first time.  If the data section is too big for the array to be loaded from
+    // then it's copied to the stack as usual.
+    fn table_address(&mut self, value: &Value, elems: &[Constant]) -> VirtualRegister {
+        let offset_in_words = match self.table_offsets.get(value) {
+            Some(offset_in_words) => *offset_in_words,
+            None => {
+                let words = elems
+                    .iter()
+                    .map(|elem| match elem.value {
+                        ConstantValue::Bool(b) => b as u64,
+                        ConstantValue::Uint(n) => n,
+                        _ => unreachable!("Read only tables only hold integers and bools."),
+                    })
+                    .collect::<Vec<_>>();
+                match self.data_section.append_table(&words) {
+                    Some(offset_in_words) => {
+                        self.table_offsets.insert(*value, offset_in_words);
+                        offset_in_words
+                    }
+                    None => {
+                        self.read_only_tables.remove(value);
+                        return self.value_to_register(value);
+                    }
+                }
+            }
+        };
+
+        let span = value.get_span(self.context);
+        let offset_in_bytes = offset_in_words * 8;
+        let data_start_reg = VirtualRegister::Constant(ConstantRegister::DataSectionStart);
+        if offset_in_bytes == 0 {
+            data_start_reg
+        } else {
+            self.offset_reg(&data_start_reg, offset_in_bytes, span)
+        }
+    }
+
     fn constant_size_in_bytes(&mut self, constant: &Constant) -> u64 {
         match &constant.value {
             ConstantValue::Undef => ir_type_size_in_bytes(self.context, &constant.ty),
@@ -2027,40 +2392,17 @@ impl<'ir> AsmBuilder<'ir> {
 
                     4 // 32 bytes is 4 words.
                 } else {
-                    if offs_in_words > crate::asm_generation::compiler_constants::TWELVE_BITS {
-                        let offs_reg = self.reg_seqr.next();
-                        self.number_to_reg(offs_in_words, &offs_reg, span.clone());
-                        self.bytecode.push(Op {
-                            opcode: either::Either::Left(VirtualOp::ADD(
-                                start_reg.clone(),
-                                start_reg.clone(),
-                                offs_reg.clone(),
-                            )),
-                            comment: "calculate byte offset to aggregate field".into(),
-                            owning_span: span.clone(),
-                        });
-                        self.bytecode.push(Op {
-                            opcode: Either::Left(VirtualOp::SW(
-                                start_reg.clone(),
-                                init_reg,
-                                VirtualImmediate12 { value: 0 },
-                            )),
-                            comment: "initialise aggregate field".into(),
-                            owning_span: span,
-                        });
-                    } else {
-                        self.bytecode.push(Op {
-                            opcode: Either::Left(VirtualOp::SW(
-                                start_reg.clone(),
-                                init_reg,
-                                VirtualImmediate12 {
-                                    value: offs_in_words as u16,
-                                },
-                            )),
-                            comment: "initialise aggregate field".into(),
-                            owning_span: span,
-                        });
-                    }
+                    let (field_base_reg, field_offs_imm) =
+                        self.word_offset_to_base_and_imm(start_reg, offs_in_words, span.clone());
+                    self.bytecode.push(Op {
+                        opcode: Either::Left(VirtualOp::SW(
+                            field_base_reg,
+                            init_reg,
+                            field_offs_imm,
+                        )),
+                        comment: "initialise aggregate field".into(),
+                        owning_span: span,
+                    });
 
                     1
                 }
@@ -2202,9 +2544,180 @@ pub fn aggregate_idcs_to_field_layout(context: &Context, ty: &Type, idcs: &[u64]
 
 // -------------------------------------------------------------------------------------------------
 
+// Find the array element instructions in `function` whose index is proven to be less than the
+// length of the array, by a comparison against a constant which the instruction is only reached
+// through.
+fn in_bounds_accesses(context: &Context, function: &Function) -> HashSet<Value> {
+    let dom_tree = DominatorTree::compute(context, function);
+    let preds = compute_predecessors(context, function);
+    function
+        .instruction_iter(context)
+        .filter(
+            |(block, instr_val)| match &context.values[instr_val.0].value {
+                ValueDatum::Instruction(Instruction::ExtractElement { ty, index_val, .. })
+                | ValueDatum::Instruction(Instruction::InsertElement { ty, index_val, .. }) => {
+                    let count = *context.aggregates[ty.0].array_type().1;
+                    dom_tree.is_reachable(block)
+                        && is_index_bounded(context, &dom_tree, &preds, *block, index_val, count)
+                }
+                _otherwise => false,
+            },
+        )
+        .map(|(_, instr_val)| instr_val)
+        .collect()
+}
+
+// Find the values in `function` which, if they're the address of a constant, may be written
+// through.  These are the values passed to ASM blocks or written to in place by `insert_value` or
+// `insert_element`, along with every value they may have been derived from through a `phi` or by
+// extracting a field or element.  Storing a value copies it, so neither a store nor a later
+// reload exposes the address.  Calls are all inlined by now and the entry functions return
+// straight to the VM, so no address leaves the function any other way.
+fn escaping_values(context: &Context, function: &Function) -> HashSet<Value> {
+    let instruction = |value: Value| match &context.values[value.0].value {
+        ValueDatum::Instruction(instruction) => Some(instruction),
+        _otherwise => None,
+    };
+
+    let mut worklist = Vec::new();
+    for (_, instr_val) in function.instruction_iter(context) {
+        match instruction(instr_val) {
+            Some(instruction @ Instruction::AsmBlock(..)) => {
+                worklist.extend(instruction.get_operands())
+            }
+            Some(Instruction::InsertValue { aggregate, .. }) => worklist.push(*aggregate),
+            Some(Instruction::InsertElement { array, .. }) => worklist.push(*array),
+            _otherwise => (),
+        }
+    }
+
+    let mut escaping = HashSet::new();
+    while let Some(value) = worklist.pop() {
+        if !escaping.insert(value) {
+            continue;
+        }
+        match instruction(value) {
+            Some(Instruction::Phi(pairs)) => worklist.extend(pairs.iter().map(|(_, val)| *val)),
+            Some(Instruction::ExtractValue { aggregate, .. }) => worklist.push(*aggregate),
+            Some(Instruction::ExtractElement { array, .. }) => worklist.push(*array),
+            Some(Instruction::InsertValue { aggregate, .. }) => worklist.push(*aggregate),
+            Some(Instruction::InsertElement { array, .. }) => worklist.push(*array),
+            _otherwise => (),
+        }
+    }
+    escaping
+}
+
+// Find the constant arrays of words in `function` which are only ever the array operand of an
+// `extract_element`, and so are never written to or have their address taken.
+fn read_only_tables(context: &Context, function: &Function) -> HashSet<Value> {
+    let mut tables = HashSet::new();
+    let mut other_uses = HashSet::new();
+    for (_, instr_val) in function.instruction_iter(context) {
+        let instruction = match &context.values[instr_val.0].value {
+            ValueDatum::Instruction(instruction) => instruction,
+            _otherwise => continue,
+        };
+        let read_array = match instruction {
+            Instruction::ExtractElement { array, .. } => Some(*array),
+            _otherwise => None,
+        };
+        for operand in instruction.get_operands() {
+            let is_word_array = matches!(
+                &context.values[operand.0].value,
+                ValueDatum::Constant(Constant {
+                    value: ConstantValue::Array(elems),
+                    ..
+                }) if elems.iter().all(|elem| matches!(
+                    elem.value,
+                    ConstantValue::Bool(_) | ConstantValue::Uint(_)
+                ))
+            );
+            if !is_word_array {
+                continue;
+            }
+            if Some(operand) == read_array {
+                tables.insert(operand);
+            } else {
+                other_uses.insert(operand);
+            }
+        }
+    }
+    &tables - &other_uses
+}
+
+// Whether `block` or one of its dominators is only entered from a conditional branch whose
+// condition, when the branch is taken that way, means `index_val` is less than `count`.
+fn is_index_bounded(
+    context: &Context,
+    dom_tree: &DominatorTree,
+    preds: &HashMap<Block, Vec<Block>>,
+    mut block: Block,
+    index_val: &Value,
+    count: u64,
+) -> bool {
+    loop {
+        if let [pred] = preds[&block].as_slice() {
+            if let Some(Instruction::ConditionalBranch {
+                cond_value,
+                true_block,
+                false_block,
+            }) = pred.get_term_inst(context)
+            {
+                if true_block != false_block
+                    && matches!(
+                        comparison_bound(context, cond_value, *true_block == block, index_val),
+                        Some(bound) if bound <= count
+                    )
+                {
+                    return true;
+                }
+            }
+        }
+        match dom_tree.immediate_dominator(&block) {
+            Some(idom) => block = idom,
+            None => return false,
+        }
+    }
+}
+
+// The value which `index_val` is known to be less than when `cond_value` is `is_true`, if it's
+// compared against a constant.
+fn comparison_bound(
+    context: &Context,
+    cond_value: &Value,
+    is_true: bool,
+    index_val: &Value,
+) -> Option<u64> {
+    let (pred, lhs, rhs) = match &context.values[cond_value.0].value {
+        ValueDatum::Instruction(Instruction::Cmp(pred, lhs, rhs)) => (pred, lhs, rhs),
+        _otherwise => return None,
+    };
+    let constant = |value: &Value| match &context.values[value.0].value {
+        ValueDatum::Constant(Constant {
+            value: ConstantValue::Uint(value),
+            ..
+        }) => Some(*value),
+        _otherwise => None,
+    };
+    match (pred, is_true) {
+        // index < n, or n > index.
+        (Predicate::LessThan, true) if lhs == index_val => constant(rhs),
+        (Predicate::GreaterThan, true) if rhs == index_val => constant(lhs),
+        // !(index > n), or !(n < index), or index == n, so index <= n.
+        (Predicate::GreaterThan, false) if lhs == index_val => constant(rhs)?.checked_add(1),
+        (Predicate::LessThan, false) if rhs == index_val => constant(lhs)?.checked_add(1),
+        (Predicate::Equal, true) if lhs == index_val => constant(rhs)?.checked_add(1),
+        (Predicate::Equal, true) if rhs == index_val => constant(lhs)?.checked_add(1),
+        _otherwise => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils;
+    use fuel_vm::prelude::ProgramState;
     use sway_ir::parser::parse;
 
     use std::path::PathBuf;
@@ -2253,6 +2766,11 @@ mod tests {
                 print_intermediate_asm: false,
                 print_finalized_asm: false,
                 print_ir: false,
+                skip_bounds_checks: false,
+                optimization_level: sway_ir::OptLevel::O1,
+                hot_abi_methods: Vec::new(),
+                size_report: false,
+                contract_size_limit: None,
                 generated_names: Default::default(),
             },
         );
@@ -2268,6 +2786,157 @@ mod tests {
             panic!();
         }
     }
+
+    // Compile a script with the IR backend and return the number of array bounds checks in it.
+    fn count_bounds_checks(main_body: &str, skip_bounds_checks: bool) -> usize {
+        let build_config = test_utils::build_config()
+            .use_ir(true)
+            .skip_bounds_checks(skip_bounds_checks);
+        let source = format!(
+            "script;{}
+    fn main() -> u64 {{
+        let a = [1, 2, 3];
+        let i = asm(r1) {{
+            bhei r1;
+            r1: u64
+        }};
+        {}
+    }}",
+            test_utils::ASM_OPERATORS,
+            main_body
+        );
+        match test_utils::compile_to_asm(&source, build_config) {
+            crate::CompilationResult::Success { asm, .. } => format!("{}", asm)
+                .matches("compare array index for out of bounds")
+                .count(),
+            crate::CompilationResult::Failure { errors, .. } => {
+                panic!("Failed to compile: {:?}", errors)
+            }
+            _ => panic!("Program failed to compile."),
+        }
+    }
+
+    #[test]
+    fn skip_proven_bounds_checks() {
+        // Indices bounded by a comparison which the access is only reached through.
+        for main_body in [
+            "if lt(i, 3) { a[i] } else { 0 }",
+            "if gt(3, i) { a[i] } else { 0 }",
+            "if gt(i, 2) { 0 } else { a[i] }",
+            "if eq(i, 2) { a[i] } else { 0 }",
+            "let mut j = 0;
+        let mut sum = 0;
+        while lt(j, 3) {
+            sum = add(sum, a[j]);
+            j = add(j, 1);
+        }
+        sum",
+        ] {
+            assert_eq!(count_bounds_checks(main_body, false), 1, "{}", main_body);
+            assert_eq!(count_bounds_checks(main_body, true), 0, "{}", main_body);
+        }
+
+        // Indices which aren't proven to be in range are still checked.
+        for main_body in [
+            "a[i]",
+            "if lt(i, 4) { a[i] } else { 0 }",
+            "if lt(i, 3) { 0 } else { a[i] }",
+            "if gt(i, 2) { a[i] } else { 0 }",
+            "if lt(i, 3) { 0 } else { 1 };
+        a[i]",
+        ] {
+            assert_eq!(count_bounds_checks(main_body, true), 1, "{}", main_body);
+        }
+    }
+
+    // Run `main()` in the IR interpreter and, once compiled, in the VM, and return both results.
+    fn run_in_interpreter_and_vm(input: &str) -> (u64, u64) {
+        let ir = parse(input).expect("parsed ir");
+        let main_fn = ir
+            .functions
+            .iter()
+            .map(|(idx, _)| sway_ir::function::Function(idx))
+            .find(|function| function.get_name(&ir) == "main")
+            .unwrap();
+        let interpreted = match sway_ir::Interpreter::new(&ir).call(&main_fn, &[]) {
+            Ok(Constant {
+                value: ConstantValue::Uint(value),
+                ..
+            }) => value,
+            result => panic!("Unexpected interpreter result: {:?}", result),
+        };
+
+        let build_config = BuildConfig::root_from_file_name_and_manifest_path("".into(), "".into());
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let asm = compile_ir_to_asm(&ir, &build_config).unwrap(&mut warnings, &mut errors);
+        let executed = match test_utils::run_script(test_utils::assemble(asm)) {
+            ProgramState::Return(value) => value,
+            state => panic!("Unexpected VM state: {:?}", state),
+        };
+        (interpreted, executed)
+    }
+
+    #[test]
+    fn interpreter_arithmetic_matches_vm() {
+        // Every binary operation, with operands which overflow, divide by zero and shift by more
+        // than a word, returning the result and then each of `$of` and `$err` straight after it.
+        let operands: [(u64, u64); 7] = [
+            (u64::MAX, 2),
+            (0, 1),
+            (1 << 63, 1 << 63),
+            (7, 0),
+            (3, 64),
+            (3, 65),
+            (3, 1 << 32),
+        ];
+        let ops = [
+            "add", "sub", "mul", "div", "mod", "and", "or", "xor", "shl", "shr",
+        ];
+        let reads = ["", "move r of", "move r err"];
+        for (lhs, rhs) in operands {
+            for op in ops {
+                for read in reads {
+                    let input = format!(
+                        "script {{
+    fn main() -> u64 {{
+        entry:
+        v0 = const u64 {lhs}
+        v1 = const u64 {rhs}
+        v2 = {op} v0 v1
+        v3 = asm(r: v2) -> u64 r {{
+            {read}
+        }}
+        ret u64 v3
+    }}
+}}"
+                    );
+                    let (interpreted, executed) = run_in_interpreter_and_vm(&input);
+                    assert_eq!(
+                        interpreted, executed,
+                        "{op} {lhs}, {rhs} gave different results ({read})"
+                    );
+                }
+            }
+        }
+
+        // The flags set within an asm block.
+        let input = "script {
+    fn main() -> u64 {
+        entry:
+        v0 = const u64 18446744073709551615
+        v1 = const u64 0
+        v2 = asm(a: v0, b: v1, r, s) -> u64 r {
+            muli   r a i2
+            move   s of
+            div    r a b
+            add    r err s
+        }
+        ret u64 v2
+    }
+}";
+        assert_eq!(run_in_interpreter_and_vm(input), (2, 2));
+    }
 }
 
 // =================================================================================================
@@ -6,11 +6,11 @@ use std::{
 use crate::semantic_analysis::ast_node::{
     TypedStructField, TypedVariableDeclaration, VariableMutability,
 };
-use crate::type_engine::resolve_type;
+use crate::type_engine::{look_up_type_id, resolve_type};
 use crate::{
     asm_generation::expression::convert_abi_fn_to_asm,
     asm_lang::{
-        allocated_ops::{AllocatedOp, AllocatedRegister},
+        allocated_ops::{AllocatedOp, AllocatedRegister, VariableHome},
         virtual_register::*,
         Label, Op, OrganizationalOp, RealizedOp, VirtualImmediate12, VirtualImmediate24, VirtualOp,
     },
@@ -33,8 +33,11 @@ mod declaration;
 mod expression;
 mod finalized_asm;
 pub(crate) mod from_ir;
+mod peephole;
+pub(crate) mod program_functions;
 pub(crate) mod register_allocator;
 mod register_sequencer;
+pub(crate) mod size_report;
 mod while_loop;
 
 pub(crate) use declaration::*;
@@ -114,39 +117,137 @@ impl RealizedAbstractInstructionSet {
     /// algorithm (https://en.wikipedia.org/wiki/Chaitin%27s_algorithm). The individual steps of
     /// the algorithm are thoroughly explained in register_allocator.rs.
     ///
+    /// The register of each named variable is followed through the allocation, and each op which
+    /// writes to it is marked with where the variable ends up for the debug info.
+    ///
     fn allocate_registers(self, register_sequencer: &mut RegisterSequencer) -> InstructionSet {
-        // Step 1: Liveness Analysis.
-        let live_out = register_allocator::liveness_analysis(&self.ops);
-
-        // Step 2: Construct the interference graph.
-        let (mut interference_graph, mut reg_to_node_ix) =
-            register_allocator::create_interference_graph(&self.ops, &live_out);
-
-        // Step 3: Remove redundant MOVE instructions using the interference graph.
-        let reduced_ops = register_allocator::coalesce_registers(
-            &self.ops,
-            &mut interference_graph,
-            &mut reg_to_node_ix,
-            register_sequencer,
-        );
+        let mut ops = self.ops;
+        let mut num_spill_slots = 0;
+        let mut unspillable = BTreeSet::new();
+        let mut slot_addresses = HashMap::new();
+        // The virtual register holding each named variable, or its spill slot once spilled.
+        let mut variable_homes: Vec<Either<VirtualRegister, u64>> = register_sequencer
+            .variables()
+            .iter()
+            .map(|(_, reg)| Either::Left(reg.clone()))
+            .collect();
+        let (reduced_ops, mut stack) = loop {
+            // Step 1: Liveness Analysis.
+            let live_out = register_allocator::liveness_analysis(&ops);
+
+            // Step 2: Construct the interference graph.
+            let (mut interference_graph, mut reg_to_node_ix) =
+                register_allocator::create_interference_graph(&ops, &live_out);
+
+            // Step 3: Remove redundant MOVE instructions using the interference graph.
+            let (reduced_ops, reg_to_reg_map) = register_allocator::coalesce_registers(
+                &ops,
+                &mut interference_graph,
+                &mut reg_to_node_ix,
+                register_sequencer,
+            );
+            for home in &mut variable_homes {
+                if let Either::Left(reg) = home {
+                    if let Some(new_reg) = reg_to_reg_map.get(reg) {
+                        *reg = new_reg.clone();
+                    }
+                }
+            }
 
-        // Step 4: Simplify - i.e. color the interference graph and return a stack that contains
-        // each colorable node and its neighbors.
-        let mut stack = register_allocator::color_interference_graph(
-            &mut interference_graph,
-            compiler_constants::NUM_ALLOCATABLE_REGISTERS,
-        );
+            // Step 4: Simplify - i.e. color the interference graph and return a stack that
+            // contains each colorable node and its neighbors.
+            match register_allocator::color_interference_graph(
+                &mut interference_graph,
+                compiler_constants::NUM_ALLOCATABLE_REGISTERS,
+                &reduced_ops,
+                &unspillable,
+            ) {
+                Ok(stack) => break (reduced_ops, stack),
+
+                // Step 4a: Spill the uncolorable nodes to the stack and start again with the
+                // rewritten instructions.
+                Err(spills) => {
+                    for home in &mut variable_homes {
+                        let spill_ix = home
+                            .as_ref()
+                            .left()
+                            .and_then(|reg| spills.iter().position(|spill| spill == reg));
+                        if let Some(spill_ix) = spill_ix {
+                            *home = Either::Right(num_spill_slots + spill_ix as u64);
+                        }
+                    }
+                    ops = register_allocator::spill_registers(
+                        &reduced_ops,
+                        &spills,
+                        &mut num_spill_slots,
+                        &mut unspillable,
+                        &mut slot_addresses,
+                        register_sequencer,
+                    );
+                }
+            }
+        };
+
+        // Step 4b: Reserve the stack space for any spilled registers.
+        let reduced_ops = if num_spill_slots > 0 {
+            register_allocator::reserve_spill_slots(&reduced_ops, num_spill_slots)
+        } else {
+            reduced_ops
+        };
 
         // Step 5: Use the stack to assign a register for each virtual register.
         let pool = register_allocator::assign_registers(&mut stack);
 
-        // Steph 6: Update all instructions to use the resulting register pool.
+        // Steph 6: Update all instructions to use the resulting register pool.  A variable in a
+        // register is written by any op defining the register, and a spilled variable by the
+        // store to its spill slot.
+        let mut variables_by_reg: HashMap<&VirtualRegister, Vec<usize>> = HashMap::new();
+        let mut variables_by_slot: HashMap<u64, Vec<usize>> = HashMap::new();
+        for (ix, home) in variable_homes.iter().enumerate() {
+            match home {
+                Either::Left(reg) => variables_by_reg.entry(reg).or_default().push(ix),
+                Either::Right(slot) => variables_by_slot.entry(*slot).or_default().push(ix),
+            }
+        }
+        let variables = register_sequencer.variables();
         let mut buf = vec![];
         for op in &reduced_ops {
+            let mut defines = Vec::new();
+            for reg in op.opcode.def_registers() {
+                if let (Some(var_ixs), Some(allocated_reg)) =
+                    (variables_by_reg.get(reg), pool.get_register(reg))
+                {
+                    defines.extend(var_ixs.iter().map(|ix| {
+                        (
+                            variables[*ix].0.clone(),
+                            VariableHome::Register(allocated_reg.clone()),
+                        )
+                    }));
+                }
+            }
+            let stored_slot = match &op.opcode {
+                VirtualOp::SW(
+                    VirtualRegister::Constant(ConstantRegister::StackStartPointer),
+                    _,
+                    slot,
+                ) => Some(slot.value as u64),
+                VirtualOp::SW(address_reg, _, _) => slot_addresses.get(address_reg).copied(),
+                _ => None,
+            };
+            if let Some(slot) = stored_slot {
+                if let Some(var_ixs) = variables_by_slot.get(&slot) {
+                    defines.extend(
+                        var_ixs
+                            .iter()
+                            .map(|ix| (variables[*ix].0.clone(), VariableHome::SpillSlot(slot))),
+                    );
+                }
+            }
             buf.push(AllocatedOp {
                 opcode: op.opcode.allocate_registers(&pool),
                 comment: op.comment.clone(),
                 owning_span: op.owning_span.clone(),
+                defines,
             })
         }
 
@@ -157,7 +258,7 @@ impl RealizedAbstractInstructionSet {
 /// An [InstructionSet] is produced by allocating registers on an [AbstractInstructionSet].
 #[derive(Clone)]
 pub struct InstructionSet {
-    ops: Vec<AllocatedOp>,
+    pub(crate) ops: Vec<AllocatedOp>,
 }
 
 type Data = Literal;
@@ -399,6 +500,21 @@ impl DataSection {
         self.insert_data_value(&pointer_as_data)
     }
 
+    /// Appends `words` to the data section as a table, even where identical values are already
+    /// there, and returns the offset of the table from the start of the data section in words.
+    /// Data is only ever appended, so the table doesn't move.  If the table would start too far
+    /// in for an `lw` to reach it then the data section is left unchanged and `None` is returned.
+    pub(crate) fn append_table(&mut self, words: &[u64]) -> Option<u64> {
+        let offset_in_words =
+            (self.offset_to_id(&DataId(self.value_pairs.len() as u32)) / 8) as u64;
+        if offset_in_words > compiler_constants::TWELVE_BITS {
+            return None;
+        }
+        self.value_pairs
+            .extend(words.iter().map(|word| Literal::U64(*word)));
+        Some(offset_in_words)
+    }
+
     /// Given any data in the form of a [Literal] (using this type mainly because it includes type
     /// information and debug spans), insert it into the data section and return its offset as a
     /// [DataId].
@@ -419,23 +535,7 @@ impl fmt::Display for DataSection {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut data_buf = String::new();
         for (ix, data) in self.value_pairs.iter().enumerate() {
-            let data_val = match data {
-                Literal::U8(num) => format!(".u8 {:#04x}", num),
-                Literal::U16(num) => format!(".u16 {:#04x}", num),
-                Literal::U32(num) => format!(".u32 {:#04x}", num),
-                Literal::U64(num) => format!(".u64 {:#04x}", num),
-                Literal::Numeric(num) => format!(".u64 {:#04x}", num),
-                Literal::Boolean(b) => format!(".bool {}", if *b { "0x01" } else { "0x00" }),
-                Literal::String(st) => format!(".str \"{}\"", st.as_str()),
-                Literal::Byte(b) => format!(".byte {:#08b}", b),
-                Literal::B256(b) => format!(
-                    ".b256 0x{}",
-                    b.iter()
-                        .map(|x| format!("{:02x}", x))
-                        .collect::<Vec<_>>()
-                        .join("")
-                ),
-            };
+            let data_val = display_data_value(data);
             let data_label = DataId(ix as u32);
             data_buf.push_str(&format!("{} {}\n", data_label, data_val));
         }
@@ -444,6 +544,40 @@ impl fmt::Display for DataSection {
     }
 }
 
+/// The assembly syntax for a data section value, e.g. `.u64 0x20`.
+fn display_data_value(data: &Literal) -> String {
+    match data {
+        Literal::U8(num) => format!(".u8 {:#04x}", num),
+        Literal::U16(num) => format!(".u16 {:#04x}", num),
+        Literal::U32(num) => format!(".u32 {:#04x}", num),
+        Literal::U64(num) => format!(".u64 {:#04x}", num),
+        Literal::U128(num) => format!(".u128 {:#04x}", num),
+        Literal::U256(b) => format!(
+            ".u256 0x{}",
+            b.iter()
+                .map(|x| format!("{:02x}", x))
+                .collect::<Vec<_>>()
+                .join("")
+        ),
+        Literal::I8(num) => format!(".i8 {}", num),
+        Literal::I16(num) => format!(".i16 {}", num),
+        Literal::I32(num) => format!(".i32 {}", num),
+        Literal::I64(num) => format!(".i64 {}", num),
+        Literal::Numeric(num) => format!(".u64 {:#04x}", num),
+        Literal::NegativeNumeric(num) => format!(".i64 {}", num),
+        Literal::Boolean(b) => format!(".bool {}", if *b { "0x01" } else { "0x00" }),
+        Literal::String(st) => format!(".str \"{}\"", st.as_str()),
+        Literal::Byte(b) => format!(".byte {:#08b}", b),
+        Literal::B256(b) => format!(
+            ".b256 0x{}",
+            b.iter()
+                .map(|x| format!("{:02x}", x))
+                .collect::<Vec<_>>()
+                .join("")
+        ),
+    }
+}
+
 impl fmt::Display for SwayAsmSet {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -579,12 +713,21 @@ impl fmt::Display for DataId {
 }
 
 impl AsmNamespace {
-    pub(crate) fn insert_variable(&mut self, var_name: Ident, register_location: VirtualRegister) {
+    pub(crate) fn insert_variable(
+        &mut self,
+        var_name: Ident,
+        register_location: VirtualRegister,
+        register_sequencer: &mut RegisterSequencer,
+    ) {
+        register_sequencer.name_register(var_name.clone(), register_location.clone());
         self.variables.insert(var_name, register_location);
     }
     pub(crate) fn insert_data_value(&mut self, data: &Data) -> DataId {
         self.data_section.insert_data_value(data)
     }
+    pub(crate) fn append_data_table(&mut self, words: &[u64]) -> Option<u64> {
+        self.data_section.append_table(words)
+    }
     /// Finds the register which contains variable `var_name`
     /// The `get` is unwrapped, because invalid variable expressions are
     /// checked for in the type checking stage.
@@ -656,7 +799,11 @@ pub(crate) fn compile_ast_to_asm(
                             errors
                         );
                         asm_buf.append(&mut buf);
-                        namespace.insert_variable(name.clone(), return_register);
+                        namespace.insert_variable(
+                            name.clone(),
+                            return_register,
+                            &mut register_sequencer,
+                        );
                     }
                     ok((), warnings, errors)
                 },
@@ -736,25 +883,63 @@ pub(crate) fn compile_ast_to_asm(
                             errors
                         );
                         asm_buf.append(&mut buf);
-                        namespace.insert_variable(name.clone(), return_register);
+                        namespace.insert_variable(
+                            name.clone(),
+                            return_register,
+                            &mut register_sequencer,
+                        );
                     }
                     ok((), warnings, errors)
                 },
                 ast_namespace,
             );
-            // start generating from the main function
+            // the arguments of main are bundled together in the predicate data
+            if !main_function.parameters.is_empty() {
+                let predicate_data_register = register_sequencer.next();
+                asm_buf.append(&mut load_predicate_data_pointer(
+                    predicate_data_register.clone(),
+                    &mut register_sequencer,
+                ));
+                let arguments = check!(
+                    unbundle_arguments(
+                        &main_function,
+                        predicate_data_register,
+                        &mut asm_buf,
+                        &mut register_sequencer
+                    ),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                );
+                for (name, register) in arguments {
+                    namespace.insert_variable(name, register, &mut register_sequencer);
+                }
+            }
+            // start generating from the main function, which returns the predicate result
+            let return_register = register_sequencer.next();
             let mut body = check!(
                 convert_code_block_to_asm(
                     &main_function.body,
                     &mut namespace,
                     &mut register_sequencer,
-                    None,
+                    Some(&return_register),
                 ),
                 vec![],
                 warnings,
                 errors
             );
             asm_buf.append(&mut body);
+            asm_buf.append(&mut check!(
+                ret_or_retd_value(
+                    &main_function,
+                    return_register,
+                    &mut register_sequencer,
+                    &mut namespace
+                ),
+                return err(warnings, errors),
+                warnings,
+                errors
+            ));
 
             (
                 SwayAsmSet::PredicateMain {
@@ -803,12 +988,26 @@ pub(crate) fn compile_ast_to_asm(
                             errors
                         );
                         asm_buf.append(&mut buf);
-                        namespace.insert_variable(name.clone(), return_register);
+                        namespace.insert_variable(
+                            name.clone(),
+                            return_register,
+                            &mut register_sequencer,
+                        );
                     }
                     ok((), warnings, errors)
                 },
                 ast_namespace,
             );
+            let hot_selectors = build_config
+                .hot_abi_methods
+                .iter()
+                .filter_map(|method_name| {
+                    abi_entries
+                        .iter()
+                        .find(|decl| decl.name.as_str() == method_name)
+                        .and_then(|decl| decl.to_fn_selector_value().value)
+                })
+                .collect::<Vec<_>>();
             let (selectors_and_labels, mut contract_asm) = check!(
                 compile_contract_to_selectors(abi_entries, &mut namespace, &mut register_sequencer),
                 return err(warnings, errors),
@@ -819,6 +1018,7 @@ pub(crate) fn compile_ast_to_asm(
                 &mut register_sequencer,
                 &mut namespace.data_section,
                 selectors_and_labels,
+                &hot_selectors,
             ));
             asm_buf.append(&mut contract_asm);
 
@@ -966,13 +1166,14 @@ pub enum RegisterAllocatedAsmSet {
 
 impl RegisterAllocatedAsmSet {
     fn optimize(self) -> FinalizedAsm {
-        // TODO implement this -- noop for now
         match self {
             RegisterAllocatedAsmSet::Library => FinalizedAsm::Library,
             RegisterAllocatedAsmSet::ScriptMain {
                 mut program_section,
                 data_section,
             } => {
+                program_section.ops = peephole::optimize(program_section.ops, &data_section);
+
                 // ensure there's an even number of ops so the
                 // data section offset is valid
                 if program_section.ops.len() & 1 != 0 {
@@ -980,6 +1181,7 @@ impl RegisterAllocatedAsmSet {
                         opcode: crate::asm_lang::allocated_ops::AllocatedOpcode::NOOP,
                         comment: "word-alignment of data section".into(),
                         owning_span: None,
+                        defines: Vec::new(),
                     });
                 }
                 FinalizedAsm::ScriptMain {
@@ -991,6 +1193,8 @@ impl RegisterAllocatedAsmSet {
                 mut program_section,
                 data_section,
             } => {
+                program_section.ops = peephole::optimize(program_section.ops, &data_section);
+
                 // ensure there's an even number of ops so the
                 // data section offset is valid
                 if program_section.ops.len() & 1 != 0 {
@@ -998,6 +1202,7 @@ impl RegisterAllocatedAsmSet {
                         opcode: crate::asm_lang::allocated_ops::AllocatedOpcode::NOOP,
                         comment: "word-alignment of data section".into(),
                         owning_span: None,
+                        defines: Vec::new(),
                     });
                 }
                 FinalizedAsm::PredicateMain {
@@ -1009,6 +1214,8 @@ impl RegisterAllocatedAsmSet {
                 mut program_section,
                 data_section,
             } => {
+                program_section.ops = peephole::optimize(program_section.ops, &data_section);
+
                 // ensure there's an even number of ops so the
                 // data section offset is valid
                 if program_section.ops.len() & 1 != 0 {
@@ -1016,6 +1223,7 @@ impl RegisterAllocatedAsmSet {
                         opcode: crate::asm_lang::allocated_ops::AllocatedOpcode::NOOP,
                         comment: "word-alignment of data section".into(),
                         owning_span: None,
+                        defines: Vec::new(),
                     });
                 }
                 FinalizedAsm::ContractAbi {
@@ -1188,10 +1396,15 @@ fn build_preamble(register_sequencer: &mut RegisterSequencer) -> [Op; 6] {
 /// Builds the contract switch statement, or function selector, which takes the selector
 /// stored in the call frame (see https://github.com/FuelLabs/sway/issues/97#issuecomment-870150684
 /// for an explanation of its location)
+///
+/// The selectors in `hot_selectors` are tested first, in order, as they're expected to be called
+/// most often.  The rest are sorted and found with a binary search, so that the gas spent on
+/// dispatch grows only logarithmically with the number of ABI methods.
 fn build_contract_abi_switch(
     register_sequencer: &mut RegisterSequencer,
     data_section: &mut DataSection,
     selectors_and_labels: Vec<([u8; 4], Label)>,
+    hot_selectors: &[[u8; 4]],
 ) -> Vec<Op> {
     let input_selector_register = register_sequencer.next();
     let mut asm_buf = vec![Op {
@@ -1213,43 +1426,102 @@ fn build_contract_abi_switch(
         owning_span: None,
     });
 
-    for (selector, label) in selectors_and_labels {
-        // put the selector in the data section
-        let data_label =
-            data_section.insert_data_value(&Literal::U32(u32::from_be_bytes(selector)));
-        // load the data into a register for comparison
-        let prog_selector_register = register_sequencer.next();
+    let (hot, mut cold): (Vec<_>, Vec<_>) = selectors_and_labels
+        .into_iter()
+        .map(|(selector, label)| (u32::from_be_bytes(selector), label))
+        .partition(|(selector, _)| hot_selectors.contains(&selector.to_be_bytes()));
+    for hot_selector in hot_selectors {
+        let hot_selector = u32::from_be_bytes(*hot_selector);
+        if let Some((selector, label)) = hot.iter().find(|(selector, _)| *selector == hot_selector)
+        {
+            asm_buf.append(&mut build_selector_comparison(
+                register_sequencer,
+                data_section,
+                &input_selector_register,
+                *selector,
+                label.clone(),
+            ));
+        }
+    }
+
+    cold.sort_by_key(|(selector, _)| *selector);
+    asm_buf.append(&mut build_selector_search(
+        register_sequencer,
+        data_section,
+        &input_selector_register,
+        &cold,
+    ));
+
+    asm_buf
+}
+
+/// Jumps to the label of whichever of the sorted `selectors_and_labels` matches the input
+/// selector, reverting if none do.
+fn build_selector_search(
+    register_sequencer: &mut RegisterSequencer,
+    data_section: &mut DataSection,
+    input_selector_register: &VirtualRegister,
+    selectors_and_labels: &[(u32, Label)],
+) -> Vec<Op> {
+    // Below this many selectors it's cheaper to just compare against each one in turn.
+    const MAX_LINEAR_SELECTORS: usize = 3;
+
+    let mut asm_buf = vec![];
+    if selectors_and_labels.len() > MAX_LINEAR_SELECTORS {
+        let (lower, upper) = selectors_and_labels.split_at(selectors_and_labels.len() / 2);
+        let upper_label = register_sequencer.get_label();
+
+        let pivot_register = register_sequencer.next();
+        let data_label = data_section.insert_data_value(&Literal::U32(upper[0].0));
         asm_buf.push(Op {
-            opcode: Either::Left(VirtualOp::LWDataId(
-                prog_selector_register.clone(),
-                data_label,
-            )),
-            comment: "load fn selector for comparison".into(),
+            opcode: Either::Left(VirtualOp::LWDataId(pivot_register.clone(), data_label)),
+            comment: "load fn selector for binary search".into(),
             owning_span: None,
         });
-        // compare with the input selector
         let comparison_result_register = register_sequencer.next();
         asm_buf.push(Op {
-            opcode: Either::Left(VirtualOp::EQ(
+            opcode: Either::Left(VirtualOp::LT(
                 comparison_result_register.clone(),
                 input_selector_register.clone(),
-                prog_selector_register,
+                pivot_register,
             )),
-            comment: "function selector comparison".into(),
+            comment: "function selector binary search".into(),
             owning_span: None,
         });
-
-        // jump to the function label if the selector was equal
         asm_buf.push(Op {
-            // if the comparison result is _not_ equal to 0, then it was indeed equal.
+            // if the input selector is _not_ less than the pivot, search the upper half.
             opcode: Either::Right(OrganizationalOp::JumpIfNotEq(
-                VirtualRegister::Constant(ConstantRegister::Zero),
+                VirtualRegister::Constant(ConstantRegister::One),
                 comparison_result_register,
-                label,
+                upper_label.clone(),
             )),
-            comment: "jump to selected function".into(),
+            comment: "search upper function selectors".into(),
             owning_span: None,
         });
+        asm_buf.append(&mut build_selector_search(
+            register_sequencer,
+            data_section,
+            input_selector_register,
+            lower,
+        ));
+        asm_buf.push(Op::unowned_jump_label(upper_label));
+        asm_buf.append(&mut build_selector_search(
+            register_sequencer,
+            data_section,
+            input_selector_register,
+            upper,
+        ));
+        return asm_buf;
+    }
+
+    for (selector, label) in selectors_and_labels {
+        asm_buf.append(&mut build_selector_comparison(
+            register_sequencer,
+            data_section,
+            input_selector_register,
+            *selector,
+            label.clone(),
+        ));
     }
 
     // if none of the selectors matched, then revert
@@ -1266,6 +1538,52 @@ fn build_contract_abi_switch(
     asm_buf
 }
 
+/// Jumps to `label` if the input selector is `selector`.
+fn build_selector_comparison(
+    register_sequencer: &mut RegisterSequencer,
+    data_section: &mut DataSection,
+    input_selector_register: &VirtualRegister,
+    selector: u32,
+    label: Label,
+) -> Vec<Op> {
+    // put the selector in the data section
+    let data_label = data_section.insert_data_value(&Literal::U32(selector));
+    // load the data into a register for comparison
+    let prog_selector_register = register_sequencer.next();
+    let comparison_result_register = register_sequencer.next();
+    vec![
+        Op {
+            opcode: Either::Left(VirtualOp::LWDataId(
+                prog_selector_register.clone(),
+                data_label,
+            )),
+            comment: "load fn selector for comparison".into(),
+            owning_span: None,
+        },
+        // compare with the input selector
+        Op {
+            opcode: Either::Left(VirtualOp::EQ(
+                comparison_result_register.clone(),
+                input_selector_register.clone(),
+                prog_selector_register,
+            )),
+            comment: "function selector comparison".into(),
+            owning_span: None,
+        },
+        // jump to the function label if the selector was equal
+        Op {
+            // if the comparison result is _not_ equal to 0, then it was indeed equal.
+            opcode: Either::Right(OrganizationalOp::JumpIfNotEq(
+                VirtualRegister::Constant(ConstantRegister::Zero),
+                comparison_result_register,
+                label,
+            )),
+            comment: "jump to selected function".into(),
+            owning_span: None,
+        },
+    ]
+}
+
 /// The function selector value and corresponding label.
 type JumpDestination = Vec<([u8; 4], Label)>;
 /// A vector of opcodes representing the body of a contract ABI function.
@@ -1304,57 +1622,40 @@ fn compile_contract_to_selectors(
             _ => {
                 // load the call frame argument into the function argument register
                 let bundled_arguments_register = register_sequencer.next();
-                let bundled_arguments_span = decl
-                    .parameters
-                    .iter()
-                    .fold(decl.parameters[0].name.span().clone(), |acc, x| {
-                        join_spans(acc, x.name.span().clone())
-                    });
-
-                // Create a new struct type that contains all the arguments. Then, for each argument,
-                // create a register for it and load it using some utilities from expression::subfield.
-                let bundled_arguments_type = crate::type_engine::insert_type(TypeInfo::Struct {
-                    name: Ident::new(bundled_arguments_span),
-                    fields: decl
-                        .parameters
-                        .iter()
-                        .map(|p| TypedStructField {
-                            name: p.name.clone(),
-                            r#type: p.r#type,
-                            span: p.name.span().clone(),
-                        })
-                        .collect::<Vec<_>>(),
-                });
-
-                let subfields_for_layout = get_subfields_for_layout(bundled_arguments_type);
-
-                let descriptor = check!(
-                    get_contiguous_memory_layout(&subfields_for_layout.clone()[..]),
+                asm_buf.push(load_bundled_arguments(bundled_arguments_register.clone()));
+                arguments = check!(
+                    unbundle_arguments(
+                        &decl,
+                        bundled_arguments_register,
+                        &mut asm_buf,
+                        register_sequencer
+                    ),
                     return err(warnings, errors),
                     warnings,
                     errors
                 );
+            }
+        }
 
-                asm_buf.push(load_bundled_arguments(bundled_arguments_register.clone()));
-
-                for param in &decl.parameters {
-                    let arg_register = register_sequencer.next();
-
-                    asm_buf.append(&mut check!(
-                        convert_subfield_to_asm(
-                            bundled_arguments_register.clone(),
-                            &param.name,
-                            arg_register.clone(),
-                            &subfields_for_layout,
-                            &descriptor,
-                        ),
-                        vec![],
-                        warnings,
-                        errors
-                    ));
-
-                    arguments.push((param.name.clone(), arg_register));
-                }
+        // A vector is passed as a pointer to its elements in the caller's memory, and is rebuilt
+        // on this contract's heap.
+        for (param, (_, arg_register)) in decl.parameters.iter().zip(arguments.iter_mut()) {
+            if let TypeInfo::Vec(elem_type) = look_up_type_id(param.r#type) {
+                let vec_register = register_sequencer.next();
+                asm_buf.append(&mut check!(
+                    convert_vec_from_call_data_to_asm(
+                        arg_register.clone(),
+                        elem_type,
+                        namespace,
+                        &vec_register,
+                        register_sequencer,
+                        param.name.span().clone(),
+                    ),
+                    vec![],
+                    warnings,
+                    errors
+                ));
+                *arg_register = vec_register;
             }
         }
 
@@ -1369,6 +1670,107 @@ fn compile_contract_to_selectors(
 
     ok((selectors_labels_buf, asm_buf), warnings, errors)
 }
+/// Given a register pointing to all of the arguments of `decl` laid out like a struct, load each
+/// argument into its own register, returning the argument names and registers.
+fn unbundle_arguments(
+    decl: &TypedFunctionDeclaration,
+    bundled_arguments_register: VirtualRegister,
+    asm_buf: &mut Vec<Op>,
+    register_sequencer: &mut RegisterSequencer,
+) -> CompileResult<Vec<(Ident, VirtualRegister)>> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+    let bundled_arguments_span = decl
+        .parameters
+        .iter()
+        .fold(decl.parameters[0].name.span().clone(), |acc, x| {
+            join_spans(acc, x.name.span().clone())
+        });
+
+    // Create a new struct type that contains all the arguments. Then, for each argument,
+    // create a register for it and load it using some utilities from expression::subfield.
+    let bundled_arguments_type = crate::type_engine::insert_type(TypeInfo::Struct {
+        name: Ident::new(bundled_arguments_span),
+        fields: decl
+            .parameters
+            .iter()
+            .map(|p| TypedStructField {
+                name: p.name.clone(),
+                r#type: p.r#type,
+                span: p.name.span().clone(),
+            })
+            .collect::<Vec<_>>(),
+    });
+
+    let subfields_for_layout = get_subfields_for_layout(bundled_arguments_type);
+
+    let descriptor = check!(
+        get_contiguous_memory_layout(&subfields_for_layout.clone()[..]),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+
+    let mut arguments = vec![];
+    for param in &decl.parameters {
+        let arg_register = register_sequencer.next();
+
+        asm_buf.append(&mut check!(
+            convert_subfield_to_asm(
+                bundled_arguments_register.clone(),
+                &param.name,
+                arg_register.clone(),
+                &subfields_for_layout,
+                &descriptor,
+            ),
+            vec![],
+            warnings,
+            errors
+        ));
+
+        arguments.push((param.name.clone(), arg_register));
+    }
+    ok(arguments, warnings, errors)
+}
+
+/// Given a register, load the address of the predicate data into it.  The predicate data follows
+/// the predicate, whose length is two words before it in the transaction's coin input.
+pub(crate) fn load_predicate_data_pointer(
+    return_register: VirtualRegister,
+    register_sequencer: &mut RegisterSequencer,
+) -> Vec<Op> {
+    let length_register = register_sequencer.next();
+    vec![
+        Op {
+            opcode: Either::Left(VirtualOp::SUBI(
+                length_register.clone(),
+                VirtualRegister::Constant(ConstantRegister::InstructionStart),
+                VirtualImmediate12::new_unchecked(16, "infallible constant 16"),
+            )),
+            comment: "address of the predicate length".into(),
+            owning_span: None,
+        },
+        Op {
+            opcode: Either::Left(VirtualOp::LW(
+                length_register.clone(),
+                length_register.clone(),
+                VirtualImmediate12::new_unchecked(0, "infallible constant 0"),
+            )),
+            comment: "load the predicate length".into(),
+            owning_span: None,
+        },
+        // The predicate bytecode is a whole number of words, so the data isn't padded.
+        Op {
+            opcode: Either::Left(VirtualOp::ADD(
+                return_register,
+                VirtualRegister::Constant(ConstantRegister::InstructionStart),
+                length_register,
+            )),
+            comment: "address of the predicate data".into(),
+            owning_span: None,
+        },
+    ]
+}
 /// Given a register, load the user-provided argument into it
 fn load_bundled_arguments(return_register: VirtualRegister) -> Op {
     Op {
@@ -1404,7 +1806,7 @@ fn ret_or_retd_value(
     namespace: &mut AsmNamespace,
 ) -> CompileResult<Vec<Op>> {
     let mut errors = vec![];
-    let warnings = vec![];
+    let mut warnings = vec![];
     let mut asm_buf = vec![];
     let main_func_ret_ty: TypeInfo = match resolve_type(func.return_type, &func.return_type_span) {
         Ok(o) => o,
@@ -1431,7 +1833,21 @@ fn ret_or_retd_value(
         );
     }
 
-    if main_func_ret_ty.is_copy_type() {
+    if let TypeInfo::Vec(elem_type) = main_func_ret_ty {
+        // vectors are returned as their elements, the length is implied by the size.
+        asm_buf.append(&mut check!(
+            convert_vec_return_to_asm(
+                return_register,
+                elem_type,
+                namespace,
+                register_sequencer,
+                func.return_type_span.clone(),
+            ),
+            return err(warnings, errors),
+            warnings,
+            errors
+        ));
+    } else if main_func_ret_ty.is_copy_type() {
         asm_buf.push(Op {
             owning_span: None,
             opcode: Either::Left(VirtualOp::RET(return_register)),
@@ -0,0 +1,455 @@
+//! Peephole optimizations over the final, register allocated, instructions.
+//!
+//! Register allocation and the naive instruction selection leave behind a number of small
+//! inefficiencies which are easy to spot by looking at a few instructions at a time:
+//!
+//! * `move $rA $rA`, and `addi` or `subi` of zero, are removed or become a plain `move`.
+//! * A `move` from a register which is itself a copy of another register copies from the
+//!   original instead, and a `move` into a register already holding the same value is removed.
+//! * A `lw` from the address just written to by the preceding `sw` becomes a `move` of the stored
+//!   register.
+//! * A `ji` or `jnei` to the very next instruction is removed.
+//! * A `move` or `lw` of a data section value into a register which is never read is removed.
+//!
+//! Jumps are to absolute instruction offsets, so whenever instructions are removed the jumps are
+//! updated to the new offsets. The optimizations are repeated until none of them apply.
+
+use super::DataSection;
+use crate::asm_lang::{
+    allocated_ops::{AllocatedOp, AllocatedOpcode, AllocatedRegister},
+    VirtualImmediate12, VirtualImmediate24,
+};
+
+use std::collections::{HashMap, HashSet};
+
+/// Apply the peephole optimizations to `ops` until no more apply.
+pub(crate) fn optimize(mut ops: Vec<AllocatedOp>, data_section: &DataSection) -> Vec<AllocatedOp> {
+    loop {
+        let (simplified_ops, simplified) = simplify_blocks(ops, data_section);
+        let (reduced_ops, reduced) = remove_dead_writes(simplified_ops, data_section);
+        ops = reduced_ops;
+        if !simplified && !reduced {
+            return ops;
+        }
+    }
+}
+
+// The offset of each op, and the offset just past the last op.  Most ops are a single
+// instruction, but loads of large data section values and the data section offset are two.
+pub(super) fn compute_offsets(ops: &[AllocatedOp], data_section: &DataSection) -> Vec<u64> {
+    let mut offsets = Vec::with_capacity(ops.len() + 1);
+    let mut offset = 0;
+    for op in ops {
+        offsets.push(offset);
+        offset += match &op.opcode {
+            AllocatedOpcode::LWDataId(_, data_id)
+                if data_section
+                    .type_of_data(data_id)
+                    .expect("data label references non existent data -- internal error")
+                    .stack_size_of()
+                    > 1 =>
+            {
+                2
+            }
+            AllocatedOpcode::DataSectionOffsetPlaceholder => 2,
+            _ => 1,
+        };
+    }
+    offsets.push(offset);
+    offsets
+}
+
+fn jump_target(opcode: &AllocatedOpcode) -> Option<u64> {
+    match opcode {
+        AllocatedOpcode::JI(imm) => Some(imm.value as u64),
+        AllocatedOpcode::JNEI(_, _, imm) => Some(imm.value as u64),
+        _ => None,
+    }
+}
+
+// The indices of the ops which may be executed after `ops[ix]`.
+pub(super) fn successors(
+    ops: &[AllocatedOp],
+    ix: usize,
+    offset_to_ix: &HashMap<u64, usize>,
+) -> Vec<usize> {
+    let next_op = if ix + 1 < ops.len() {
+        Some(ix + 1)
+    } else {
+        None
+    };
+    let target_op = |imm: u64| offset_to_ix.get(&imm).copied();
+    match &ops[ix].opcode {
+        AllocatedOpcode::RET(_) | AllocatedOpcode::RETD(..) | AllocatedOpcode::RVRT(_) => vec![],
+        AllocatedOpcode::JI(imm) => target_op(imm.value as u64).into_iter().collect(),
+        AllocatedOpcode::JNEI(_, _, imm) => target_op(imm.value as u64)
+            .into_iter()
+            .chain(next_op)
+            .collect(),
+        _ => next_op.into_iter().collect(),
+    }
+}
+
+// Keep the ops for which `keep` is set, updating the jumps.  A jump to a removed op now jumps to
+// the next op which is kept, which is equivalent as removed ops have no effect.
+fn remove_ops(
+    ops: Vec<AllocatedOp>,
+    keep: &[bool],
+    data_section: &DataSection,
+) -> Vec<AllocatedOp> {
+    let old_offsets = compute_offsets(&ops, data_section);
+    let mut offset_map: HashMap<u64, u64> = HashMap::new();
+    let mut new_offset = 0;
+    for (ix, op_offset) in old_offsets.iter().enumerate() {
+        offset_map.insert(*op_offset, new_offset);
+        if ix < ops.len() && keep[ix] {
+            new_offset += old_offsets[ix + 1] - op_offset;
+        }
+    }
+
+    let new_offset = |imm: u64| {
+        *offset_map
+            .get(&imm)
+            .expect("Jump targets must be the offset of an op.")
+    };
+    ops.into_iter()
+        .zip(keep)
+        .filter(|(_, keep)| **keep)
+        .map(|(op, _)| op)
+        .map(|mut op| {
+            op.opcode = match op.opcode {
+                AllocatedOpcode::JI(imm) => AllocatedOpcode::JI(VirtualImmediate24 {
+                    value: new_offset(imm.value as u64) as u32,
+                }),
+                AllocatedOpcode::JNEI(r1, r2, imm) => AllocatedOpcode::JNEI(
+                    r1,
+                    r2,
+                    VirtualImmediate12 {
+                        value: new_offset(imm.value as u64) as u16,
+                    },
+                ),
+                opcode => opcode,
+            };
+            op
+        })
+        .collect()
+}
+
+// Apply the optimizations which only need to look within a basic block.  Returns the new ops and
+// whether any were modified.
+fn simplify_blocks(
+    mut ops: Vec<AllocatedOp>,
+    data_section: &DataSection,
+) -> (Vec<AllocatedOp>, bool) {
+    let offsets = compute_offsets(&ops, data_section);
+    let jump_targets: HashSet<u64> = ops
+        .iter()
+        .filter_map(|op| jump_target(&op.opcode))
+        .collect();
+
+    let mut keep = vec![true; ops.len()];
+    let mut modified = false;
+
+    // The registers known to hold a copy of another register, mapped to the original register.
+    // Only allocated registers are tracked as the constant registers may change implicitly.
+    let mut copies: HashMap<AllocatedRegister, AllocatedRegister> = HashMap::new();
+    let mut prev_kept_ix: Option<usize> = None;
+
+    for ix in 0..ops.len() {
+        // Nothing is known at the start of a basic block.
+        if jump_targets.contains(&offsets[ix]) {
+            copies.clear();
+            prev_kept_ix = None;
+        }
+
+        let opcode = &ops[ix].opcode;
+        let new_opcode = match opcode {
+            // Adding or subtracting zero is a move.
+            AllocatedOpcode::ADDI(r1, r2, imm) | AllocatedOpcode::SUBI(r1, r2, imm)
+                if imm.value == 0 =>
+            {
+                Some(AllocatedOpcode::MOVE(r1.clone(), r2.clone()))
+            }
+
+            // Loading the value just stored is a move.
+            AllocatedOpcode::LW(dst, base, imm) => match prev_kept_ix.map(|ix| &ops[ix].opcode) {
+                Some(AllocatedOpcode::SW(sw_base, stored, sw_imm))
+                    if sw_base == base && sw_imm.value == imm.value =>
+                {
+                    Some(AllocatedOpcode::MOVE(dst.clone(), stored.clone()))
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(new_opcode) = new_opcode {
+            ops[ix].opcode = new_opcode;
+            modified = true;
+        }
+
+        match &ops[ix].opcode {
+            AllocatedOpcode::MOVE(dst, src) => {
+                let (dst, src) = (dst.clone(), src.clone());
+                let original = copies.get(&src).cloned().unwrap_or_else(|| src.clone());
+                if dst == original || copies.get(&dst) == Some(&original) {
+                    // The destination already holds the value, so any variables the move writes
+                    // are held there from the op before it.
+                    if let Some(prev_ix) = prev_kept_ix {
+                        let defines = std::mem::take(&mut ops[ix].defines);
+                        ops[prev_ix].defines.extend(defines);
+                    }
+                    keep[ix] = false;
+                    modified = true;
+                    continue;
+                }
+                if original != src {
+                    ops[ix].opcode = AllocatedOpcode::MOVE(dst, original);
+                    modified = true;
+                }
+            }
+            opcode if jump_target(opcode) == Some(offsets[ix + 1]) => {
+                // A jump to the next op, which does nothing whether or not it's taken.
+                keep[ix] = false;
+                modified = true;
+                continue;
+            }
+            _ => (),
+        }
+
+        // Forget the copies invalidated by this op.
+        let opcode = &ops[ix].opcode;
+        for def_reg in opcode.def_registers() {
+            copies.retain(|copy, original| copy != def_reg && original != def_reg);
+        }
+        if let AllocatedOpcode::MOVE(
+            dst @ AllocatedRegister::Allocated(_),
+            src @ AllocatedRegister::Allocated(_),
+        ) = opcode
+        {
+            copies.insert(dst.clone(), src.clone());
+        }
+        prev_kept_ix = Some(ix);
+    }
+
+    if modified {
+        (remove_ops(ops, &keep, data_section), true)
+    } else {
+        (ops, false)
+    }
+}
+
+// Remove the moves and data section loads into allocated registers which are never read.  These
+// are the only ops considered as they can never fail or have any other effect.  Returns the new
+// ops and whether any were removed.
+fn remove_dead_writes(
+    ops: Vec<AllocatedOp>,
+    data_section: &DataSection,
+) -> (Vec<AllocatedOp>, bool) {
+    let offsets = compute_offsets(&ops, data_section);
+    let offset_to_ix: HashMap<u64, usize> = offsets
+        .iter()
+        .enumerate()
+        .map(|(ix, offset)| (*offset, ix))
+        .collect();
+    let successors: Vec<Vec<usize>> = (0..ops.len())
+        .map(|ix| successors(&ops, ix, &offset_to_ix))
+        .collect();
+
+    // The usual backwards liveness analysis, over the allocated registers only.
+    let mut live_in: Vec<HashSet<&AllocatedRegister>> = vec![HashSet::new(); ops.len()];
+    let mut live_out: Vec<HashSet<&AllocatedRegister>> = vec![HashSet::new(); ops.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for ix in (0..ops.len()).rev() {
+            let mut new_live_out = HashSet::new();
+            for succ_ix in &successors[ix] {
+                new_live_out.extend(live_in[*succ_ix].iter().copied());
+            }
+
+            let mut new_live_in = new_live_out.clone();
+            for def_reg in ops[ix].opcode.def_registers() {
+                new_live_in.remove(def_reg);
+            }
+            new_live_in.extend(
+                ops[ix]
+                    .opcode
+                    .use_registers()
+                    .into_iter()
+                    .filter(|reg| matches!(reg, AllocatedRegister::Allocated(_))),
+            );
+
+            if new_live_in != live_in[ix] || new_live_out != live_out[ix] {
+                live_in[ix] = new_live_in;
+                live_out[ix] = new_live_out;
+                changed = true;
+            }
+        }
+    }
+
+    let keep: Vec<bool> = ops
+        .iter()
+        .zip(&live_out)
+        .map(|(op, live_out)| match &op.opcode {
+            AllocatedOpcode::MOVE(dst @ AllocatedRegister::Allocated(_), _)
+            | AllocatedOpcode::LWDataId(dst @ AllocatedRegister::Allocated(_), _) => {
+                live_out.contains(dst)
+            }
+            _ => true,
+        })
+        .collect();
+
+    if keep.iter().all(|keep| *keep) {
+        (ops, false)
+    } else {
+        (remove_ops(ops, &keep, data_section), true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        asm_lang::{allocated_ops::VariableHome, ConstantRegister},
+        test_utils::run_ops,
+    };
+    use fuel_vm::prelude::ProgramState;
+    use sway_types::ident::Ident;
+
+    fn reg(ix: u8) -> AllocatedRegister {
+        AllocatedRegister::Allocated(ix)
+    }
+
+    fn constant(reg: ConstantRegister) -> AllocatedRegister {
+        AllocatedRegister::Constant(reg)
+    }
+
+    fn imm12(value: u16) -> VirtualImmediate12 {
+        VirtualImmediate12 { value }
+    }
+
+    fn op(opcode: AllocatedOpcode) -> AllocatedOp {
+        AllocatedOp {
+            opcode,
+            comment: String::new(),
+            owning_span: None,
+            defines: Vec::new(),
+        }
+    }
+
+    // Optimize `ops`, which must reduce them to `expected_len` ops, and check they return the
+    // same `expected` result before and after.
+    fn check(ops: Vec<AllocatedOp>, expected_len: usize, expected: u64) {
+        let optimized_ops = optimize(ops.clone(), &DataSection::default());
+        assert_eq!(optimized_ops.len(), expected_len);
+        assert_eq!(run_ops(ops), ProgramState::Return(expected));
+        assert_eq!(run_ops(optimized_ops), ProgramState::Return(expected));
+    }
+
+    #[test]
+    fn redundant_moves() {
+        use AllocatedOpcode::*;
+        let zero = constant(ConstantRegister::Zero);
+        check(
+            vec![
+                op(ADDI(reg(0), zero, imm12(5))),
+                op(MOVE(reg(0), reg(0))),
+                op(MOVE(reg(1), reg(0))),
+                op(MOVE(reg(2), reg(1))),
+                op(MOVE(reg(0), reg(2))),
+                op(ADDI(reg(3), reg(2), imm12(0))),
+                op(SUBI(reg(4), reg(3), imm12(0))),
+                op(RET(reg(4))),
+            ],
+            3,
+            5,
+        );
+    }
+
+    #[test]
+    fn load_after_store() {
+        use AllocatedOpcode::*;
+        let zero = constant(ConstantRegister::Zero);
+        let ssp = constant(ConstantRegister::StackStartPointer);
+        check(
+            vec![
+                op(CFEI(VirtualImmediate24 { value: 16 })),
+                op(ADDI(reg(0), zero, imm12(9))),
+                op(SW(ssp.clone(), reg(0), imm12(1))),
+                op(LW(reg(0), ssp.clone(), imm12(1))),
+                op(LW(reg(1), ssp, imm12(1))),
+                op(ADD(reg(2), reg(0), reg(1))),
+                op(RET(reg(2))),
+            ],
+            6,
+            18,
+        );
+    }
+
+    #[test]
+    fn jumps() {
+        use AllocatedOpcode::*;
+        let zero = constant(ConstantRegister::Zero);
+        let one = constant(ConstantRegister::One);
+        // Sum 0..4 in a loop, with dead moves before and within the loop which move the loop
+        // start, and jumps to the next op which are removed.
+        check(
+            vec![
+                op(ADDI(reg(0), zero.clone(), imm12(0))),
+                op(MOVE(reg(5), reg(0))),
+                op(ADDI(reg(1), zero.clone(), imm12(0))),
+                op(ADDI(reg(2), zero, imm12(4))),
+                op(JI(VirtualImmediate24 { value: 5 })),
+                op(ADD(reg(1), reg(1), reg(0))),
+                op(MOVE(reg(6), reg(1))),
+                op(ADD(reg(0), reg(0), one.clone())),
+                op(JNEI(reg(0), reg(2), imm12(5))),
+                op(JNEI(reg(0), one, imm12(10))),
+                op(RET(reg(1))),
+            ],
+            7,
+            6,
+        );
+    }
+
+    #[test]
+    fn removed_move_defines() {
+        use AllocatedOpcode::*;
+        let zero = constant(ConstantRegister::Zero);
+        let source: std::sync::Arc<str> = "let x = 5; let y = x; let z = x;".into();
+        let ident = |start: usize| {
+            Ident::new(sway_types::span::Span {
+                span: pest::Span::new(source.clone(), start, start + 1).unwrap(),
+                path: None,
+            })
+        };
+        let (x, y, z) = (ident(4), ident(15), ident(26));
+        let defining = |opcode, defines: Vec<(Ident, AllocatedRegister)>| AllocatedOp {
+            defines: defines
+                .into_iter()
+                .map(|(name, reg)| (name, VariableHome::Register(reg)))
+                .collect(),
+            ..op(opcode)
+        };
+        // The second move into `$r1` is removed, and `z` is then held in `$r1` from the first.
+        let ops = vec![
+            defining(ADDI(reg(0), zero, imm12(5)), vec![(x.clone(), reg(0))]),
+            defining(MOVE(reg(1), reg(0)), vec![(y.clone(), reg(1))]),
+            defining(MOVE(reg(1), reg(0)), vec![(z.clone(), reg(1))]),
+            op(RET(reg(1))),
+        ];
+        let optimized_ops = optimize(ops.clone(), &DataSection::default());
+        let defines = optimized_ops
+            .iter()
+            .map(|op| {
+                op.defines
+                    .iter()
+                    .map(|(name, _)| name.span().start())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(defines, [vec![4], vec![15, 26], vec![]]);
+        assert_eq!(run_ops(ops), ProgramState::Return(5));
+        assert_eq!(run_ops(optimized_ops), ProgramState::Return(5));
+    }
+}
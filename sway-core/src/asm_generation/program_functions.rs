@@ -0,0 +1,724 @@
+//! The functions making up the finalized bytecode of a program.
+//!
+//! Functions are inlined during code generation, so rather than tracking function boundaries
+//! through every stage of the backend each instruction is attributed to the innermost function
+//! body which contains its owning span.  Instructions without a span, or with a span outside of
+//! any function body such as the value of a constant, belong to the same function as the
+//! instruction before them, or to the method itself at the entry point of a contract ABI method.
+//! Any at the start of the program, such as the contract selector switch, are attributed to the
+//! compiler.
+
+use super::{peephole, DataSection, FinalizedAsm};
+use crate::{
+    asm_lang::{
+        allocated_ops::{AllocatedOp, AllocatedOpcode, AllocatedRegister},
+        virtual_register::ConstantRegister,
+    },
+    parse_tree::Literal,
+    semantic_analysis::{
+        ast_node::{
+            SizeOfVariant, TypedCodeBlock, TypedExpression, TypedExpressionVariant,
+            TypedVariableDeclaration, VecIntrinsicKind,
+        },
+        TypedAstNode, TypedAstNodeContent, TypedDeclaration, TypedParseTree,
+    },
+    source_map::SourceMap,
+    symbol_table::InstructionRange,
+};
+
+use sway_types::{ident::Ident, span::Span};
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+/// The functions of a program which may appear in its bytecode, gathered from the typed AST
+/// before code generation.
+pub(crate) struct ProgramFunctions {
+    /// The name and body span of every function which is called, and of the entry points.
+    pub(crate) bodies: Vec<(String, Span)>,
+    /// The indices of the bodies of the functions called from each body.
+    pub(crate) callees: Vec<BTreeSet<usize>>,
+    /// The span of each call from one function to another, with the indices of the bodies of the
+    /// caller and the callee.
+    pub(crate) calls: Vec<(usize, usize, Span)>,
+    /// The selector of each contract ABI method, with the index of its body.
+    pub(crate) abi_selectors: Vec<(u32, usize)>,
+    /// The name of each constant with the span of its value.
+    pub(crate) constants: Vec<(String, Span)>,
+    /// The span of each code block with the index of the body it is in, starting with the body
+    /// itself.
+    pub(crate) blocks: Vec<(usize, Span)>,
+    /// The span of the name of each function parameter with the index of the function's body.
+    pub(crate) parameters: Vec<(Span, usize)>,
+    /// The index of the body of `main`, for scripts and predicates.
+    pub(crate) main: Option<usize>,
+    /// The index of the body being gathered.
+    current_body: Option<usize>,
+}
+
+impl ProgramFunctions {
+    pub(crate) fn new(tree: &TypedParseTree) -> Self {
+        let mut functions = ProgramFunctions {
+            bodies: Vec::new(),
+            callees: Vec::new(),
+            calls: Vec::new(),
+            abi_selectors: Vec::new(),
+            constants: Vec::new(),
+            blocks: Vec::new(),
+            parameters: Vec::new(),
+            main: None,
+            current_body: None,
+        };
+        match tree {
+            TypedParseTree::Script { declarations, .. }
+            | TypedParseTree::Predicate { declarations, .. }
+            | TypedParseTree::Contract { declarations, .. } => {
+                // Constants are declared as variables.
+                for decl in declarations {
+                    if let TypedDeclaration::VariableDeclaration(decl) = decl {
+                        if decl.const_decl_origin {
+                            functions.gather_constant(&decl.name, &decl.body);
+                        }
+                    }
+                }
+            }
+            TypedParseTree::Library { .. } => (),
+        }
+        match tree {
+            TypedParseTree::Script { main_function, .. }
+            | TypedParseTree::Predicate { main_function, .. } => {
+                functions.main = Some(functions.gather_function(
+                    main_function.name.as_str(),
+                    main_function.parameters.iter().map(|param| &param.name),
+                    &main_function.body,
+                ));
+            }
+            TypedParseTree::Contract { abi_entries, .. } => {
+                for entry in abi_entries {
+                    let body_ix = functions.gather_function(
+                        entry.name.as_str(),
+                        entry.parameters.iter().map(|param| &param.name),
+                        &entry.body,
+                    );
+                    if let Some(selector) = entry.to_fn_selector_value().value {
+                        functions
+                            .abi_selectors
+                            .push((u32::from_be_bytes(selector), body_ix));
+                    }
+                }
+            }
+            TypedParseTree::Library { .. } => (),
+        }
+        functions
+    }
+
+    /// Adds the body of a function called from the current body, returning its index.
+    fn gather_function<'p>(
+        &mut self,
+        name: &str,
+        parameters: impl IntoIterator<Item = &'p Ident>,
+        body: &TypedCodeBlock,
+    ) -> usize {
+        // A function called many times has the same body each time.
+        let span = &body.whole_block_span;
+        let ix = match self.bodies.iter().position(|(_, body_span)| {
+            body_span.path == span.path
+                && body_span.start() == span.start()
+                && body_span.end() == span.end()
+        }) {
+            Some(ix) => ix,
+            None => {
+                self.bodies.push((name.to_owned(), span.clone()));
+                self.callees.push(BTreeSet::new());
+                let ix = self.bodies.len() - 1;
+                self.parameters.extend(
+                    parameters
+                        .into_iter()
+                        .map(|param| (param.span().clone(), ix)),
+                );
+                let caller = self.current_body.replace(ix);
+                self.gather_code_block(body);
+                self.current_body = caller;
+                ix
+            }
+        };
+        if let Some(caller) = self.current_body {
+            self.callees[caller].insert(ix);
+        }
+        ix
+    }
+
+    fn gather_constant(&mut self, name: &Ident, value: &TypedExpression) {
+        self.constants
+            .push((name.as_str().to_owned(), value.span.clone()));
+        self.gather_expression(value);
+    }
+
+    fn gather_code_block(&mut self, block: &TypedCodeBlock) {
+        if let Some(body_ix) = self.current_body {
+            self.blocks.push((body_ix, block.whole_block_span.clone()));
+        }
+        for node in &block.contents {
+            self.gather_node(node);
+        }
+    }
+
+    fn gather_node(&mut self, node: &TypedAstNode) {
+        match &node.content {
+            TypedAstNodeContent::ReturnStatement(stmt) => self.gather_expression(&stmt.expr),
+            TypedAstNodeContent::ImplicitReturnExpression(exp)
+            | TypedAstNodeContent::Expression(exp) => self.gather_expression(exp),
+            TypedAstNodeContent::WhileLoop(r#loop) => {
+                self.gather_expression(&r#loop.condition);
+                self.gather_code_block(&r#loop.body);
+            }
+            TypedAstNodeContent::Declaration(decl) => match decl {
+                TypedDeclaration::VariableDeclaration(TypedVariableDeclaration {
+                    name,
+                    body,
+                    const_decl_origin,
+                    ..
+                }) => {
+                    if *const_decl_origin {
+                        self.gather_constant(name, body);
+                    } else {
+                        self.gather_expression(body);
+                    }
+                }
+                TypedDeclaration::ConstantDeclaration(decl) => {
+                    self.gather_constant(&decl.name, &decl.value)
+                }
+                TypedDeclaration::Reassignment(reassignment) => {
+                    self.gather_expression(&reassignment.rhs)
+                }
+                TypedDeclaration::StorageReassignment(reassignment) => {
+                    self.gather_expression(&reassignment.rhs)
+                }
+                _ => (),
+            },
+            TypedAstNodeContent::SideEffect => (),
+        }
+    }
+
+    fn gather_expression(&mut self, exp: &TypedExpression) {
+        use TypedExpressionVariant::*;
+        match &exp.expression {
+            FunctionApplication {
+                name,
+                contract_call_params,
+                arguments,
+                function_body,
+                selector,
+            } => {
+                for param in contract_call_params.values() {
+                    self.gather_expression(param);
+                }
+                for (_, arg) in arguments {
+                    self.gather_expression(arg);
+                }
+                match selector {
+                    // A contract call doesn't include the body of the called method.
+                    Some(metadata) => self.gather_expression(&metadata.contract_address),
+                    None => {
+                        let callee = self.gather_function(
+                            name.suffix.as_str(),
+                            arguments.iter().map(|(param, _)| param),
+                            function_body,
+                        );
+                        if let Some(caller) = self.current_body {
+                            // The span of the application is that of the called function.
+                            self.calls.push((caller, callee, name.span()));
+                        }
+                    }
+                }
+            }
+            LazyOperator { lhs, rhs, .. } => {
+                self.gather_expression(lhs);
+                self.gather_expression(rhs);
+            }
+            Tuple { fields } => {
+                for field in fields {
+                    self.gather_expression(field);
+                }
+            }
+            Array { contents } => {
+                for elem in contents {
+                    self.gather_expression(elem);
+                }
+            }
+            ArrayIndex { prefix, index } => {
+                self.gather_expression(prefix);
+                self.gather_expression(index);
+            }
+            StructExpression { fields, .. } => {
+                for field in fields {
+                    self.gather_expression(&field.value);
+                }
+            }
+            CodeBlock(block) => self.gather_code_block(block),
+            IfExp {
+                condition,
+                then,
+                r#else,
+            } => {
+                self.gather_expression(condition);
+                self.gather_expression(then);
+                if let Some(r#else) = r#else {
+                    self.gather_expression(r#else);
+                }
+            }
+            IntegerSwitch {
+                scrutinee,
+                cases,
+                default,
+            } => {
+                self.gather_expression(scrutinee);
+                for (_, body) in cases {
+                    self.gather_expression(body);
+                }
+                self.gather_expression(default);
+            }
+            AsmExpression { registers, .. } => {
+                for initializer in registers.iter().filter_map(|reg| reg.initializer.as_ref()) {
+                    self.gather_expression(initializer);
+                }
+            }
+            StructFieldAccess { prefix, .. } | TupleElemAccess { prefix, .. } => {
+                self.gather_expression(prefix)
+            }
+            IfLet {
+                expr, then, r#else, ..
+            } => {
+                self.gather_expression(expr);
+                self.gather_code_block(then);
+                if let Some(r#else) = r#else {
+                    self.gather_expression(r#else);
+                }
+            }
+            EnumInstantiation { contents, .. } => {
+                if let Some(contents) = contents {
+                    self.gather_expression(contents);
+                }
+            }
+            AbiCast { address, .. } => self.gather_expression(address),
+            SizeOf {
+                variant: SizeOfVariant::Val(exp),
+            } => self.gather_expression(exp),
+            VecIntrinsic { kind, .. } => match kind {
+                VecIntrinsicKind::New => (),
+                VecIntrinsicKind::WithCapacity(exp)
+                | VecIntrinsicKind::Len(exp)
+                | VecIntrinsicKind::Pop(exp)
+                | VecIntrinsicKind::ToCallData(exp) => self.gather_expression(exp),
+                VecIntrinsicKind::Push { vec, value } => {
+                    self.gather_expression(vec);
+                    self.gather_expression(value);
+                }
+                VecIntrinsicKind::Index { vec, index } => {
+                    self.gather_expression(vec);
+                    self.gather_expression(index);
+                }
+            },
+            IntIntrinsic { lhs, rhs, .. } => {
+                self.gather_expression(lhs);
+                self.gather_expression(rhs);
+            }
+            Literal(_)
+            | VariableExpression { .. }
+            | FunctionParameter
+            | StorageAccess(_)
+            | SizeOf {
+                variant: SizeOfVariant::Type(_),
+            } => (),
+        }
+    }
+
+    /// The bodies of the functions called in turn to reach the function with body `to` from the
+    /// function with body `from`, ending with `to`.
+    pub(crate) fn call_path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut callers = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(caller) = queue.pop_front() {
+            for callee in &self.callees[caller] {
+                if *callee != from && !callers.contains_key(callee) {
+                    callers.insert(*callee, caller);
+                    queue.push_back(*callee);
+                }
+            }
+        }
+        let mut path = vec![to];
+        let mut body = to;
+        while body != from {
+            body = *callers.get(&body)?;
+            path.push(body);
+        }
+        path.pop();
+        path.reverse();
+        Some(path)
+    }
+
+    /// The index of the innermost function body containing `span`.
+    fn owning_function(&self, span: &Span) -> Option<usize> {
+        self.bodies
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, body))| span_contains(body, span))
+            .min_by_key(|(_, (_, body))| body.end() - body.start())
+            .map(|(ix, _)| ix)
+    }
+
+    /// The index of the block a variable is declared in, which for a parameter is the body of its
+    /// function, and whether it is a parameter.  Constants declared outside of any function have
+    /// no block.
+    pub(crate) fn variable_block(&self, name: &Ident) -> Option<(usize, bool)> {
+        let span = name.span();
+        if let Some((_, body_ix)) = self
+            .parameters
+            .iter()
+            .find(|(param, _)| param.path == span.path && param.start() == span.start())
+        {
+            let block_ix = self.blocks.iter().position(|(ix, _)| ix == body_ix)?;
+            return Some((block_ix, true));
+        }
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, block))| span_contains(block, span))
+            .min_by_key(|(_, (_, block))| block.end() - block.start())
+            .map(|(ix, _)| (ix, false))
+    }
+}
+
+fn span_contains(outer: &Span, inner: &Span) -> bool {
+    outer.path == inner.path && outer.start() <= inner.start() && inner.end() <= outer.end()
+}
+
+/// A finalized program once it has been serialised, with the function owning each op.
+pub(crate) struct ProgramLayout<'a> {
+    pub(crate) functions: &'a ProgramFunctions,
+    pub(crate) ops: Vec<AllocatedOp>,
+    /// The data section, including the pointers appended to it during serialisation.
+    pub(crate) data_section: DataSection,
+    pub(crate) bytes: Vec<u8>,
+    /// The instruction index of each op, followed by the number of instructions.
+    pub(crate) offsets: Vec<u64>,
+    /// The index of the body of the function owning each op.
+    pub(crate) owners: Vec<Option<usize>>,
+    /// The index of the body of each contract ABI method with the index of its entry point op.
+    pub(crate) abi_entries: Vec<(usize, usize)>,
+}
+
+impl<'a> ProgramLayout<'a> {
+    pub(crate) fn new(asm: &FinalizedAsm, functions: &'a ProgramFunctions) -> Option<Self> {
+        // Serialising the bytecode appends pointers to the data section for any values larger
+        // than a word, so the program is laid out once they have been added.
+        let mut serialized_asm = asm.clone();
+        let bytes = serialized_asm
+            .to_bytecode_mut(&mut SourceMap::new())
+            .value?;
+        let (program_section, data_section) = match serialized_asm {
+            FinalizedAsm::ContractAbi {
+                program_section,
+                data_section,
+            }
+            | FinalizedAsm::ScriptMain {
+                program_section,
+                data_section,
+            }
+            | FinalizedAsm::PredicateMain {
+                program_section,
+                data_section,
+            } => (program_section, data_section),
+            FinalizedAsm::Library => return None,
+        };
+        let ops = program_section.ops;
+        let offsets = peephole::compute_offsets(&ops, &data_section);
+        let abi_entries = abi_method_entries(&ops, &data_section, &offsets, functions);
+
+        // An op without a span, or with a span outside of any function such as the value of a
+        // constant, belongs to the same function as the op before it as it was generated along
+        // with it, unless it is the entry point of an ABI method.
+        let mut owners: Vec<Option<usize>> = Vec::with_capacity(ops.len());
+        for (ix, op) in ops.iter().enumerate() {
+            let owner = op
+                .owning_span
+                .as_ref()
+                .and_then(|span| functions.owning_function(span))
+                .or_else(|| {
+                    abi_entries
+                        .iter()
+                        .find(|(_, entry)| *entry == ix)
+                        .map(|(body_ix, _)| *body_ix)
+                })
+                .or_else(|| owners.last().copied().flatten());
+            owners.push(owner);
+        }
+
+        Some(ProgramLayout {
+            functions,
+            ops,
+            data_section,
+            bytes,
+            offsets,
+            owners,
+            abi_entries,
+        })
+    }
+
+    /// The index of the body of each function inlined into the program, with the range of
+    /// instructions generated for it and the number of scopes it is nested within.  The scopes
+    /// are ordered by their first instruction, from the outermost to the innermost.
+    pub(crate) fn function_scopes(&self) -> Vec<(usize, InstructionRange, usize)> {
+        // The scopes are tracked with a stack of the functions being inlined into one another.
+        // An op owned by a function on the stack returns to it, and one owned by a function
+        // called from a scope on the stack enters new scopes for it and for any functions called
+        // in between.  The stack starts from `main`, and is reset to the method at the entry
+        // point of each ABI method and emptied for ops generated by the compiler.
+        let mut scopes = Vec::new();
+        let mut stack: Vec<(usize, usize)> = Vec::new();
+        let mut close_scopes = |stack: &mut Vec<(usize, usize)>, depth: usize, end: usize| {
+            while stack.len() > depth {
+                let (body_ix, start) = stack.pop().unwrap();
+                scopes.push((body_ix, InstructionRange { start, end }, stack.len()));
+            }
+        };
+        for (ix, owner) in self.owners.iter().enumerate() {
+            let pc = self.offsets[ix] as usize;
+            if let Some((body_ix, _)) = self.abi_entries.iter().find(|(_, entry)| *entry == ix) {
+                close_scopes(&mut stack, 0, pc);
+                stack.push((*body_ix, pc));
+            }
+            let owner = match owner {
+                Some(owner) => *owner,
+                None => {
+                    close_scopes(&mut stack, 0, pc);
+                    continue;
+                }
+            };
+            if stack.is_empty() {
+                stack.extend(self.functions.main.map(|main| (main, pc)));
+            }
+            if let Some(depth) = stack.iter().rposition(|(body_ix, _)| *body_ix == owner) {
+                close_scopes(&mut stack, depth + 1, pc);
+            } else {
+                let (depth, call_path) = stack
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .find_map(|(depth, (body_ix, _))| {
+                        self.functions
+                            .call_path(*body_ix, owner)
+                            .map(|call_path| (depth + 1, call_path))
+                    })
+                    .unwrap_or((0, vec![owner]));
+                close_scopes(&mut stack, depth, pc);
+                stack.extend(call_path.into_iter().map(|body_ix| (body_ix, pc)));
+            }
+        }
+        close_scopes(&mut stack, 0, *self.offsets.last().unwrap() as usize);
+        scopes.sort_by_key(|(_, range, depth)| (range.start, *depth));
+        scopes
+    }
+
+    /// The index of each code block generated within the scope of its function, with the range
+    /// of instructions from the first to the last op generated for the block.  The body of a
+    /// function has the range of the whole function scope, including the functions inlined into
+    /// it.  A block is in scope once for each time its function is inlined.
+    ///
+    /// The ops of a function called from within a block have spans in the called function, so
+    /// they are in the block when they follow on from the block's own ops.
+    pub(crate) fn block_scopes(
+        &self,
+        function_scopes: &[(usize, InstructionRange, usize)],
+    ) -> Vec<(usize, InstructionRange)> {
+        let mut scopes = Vec::new();
+        for (body_ix, range, depth) in function_scopes {
+            let blocks = self
+                .functions
+                .blocks
+                .iter()
+                .enumerate()
+                .filter(|(_, (block_body_ix, _))| block_body_ix == body_ix)
+                .collect::<Vec<_>>();
+            let mut ranges: Vec<Option<InstructionRange>> = vec![None; blocks.len()];
+            // The body is the first block of the function.
+            if let Some(body_range) = ranges.first_mut() {
+                *body_range = Some(*range);
+            }
+            let first = self
+                .offsets
+                .partition_point(|offset| (*offset as usize) < range.start);
+            let last = self
+                .offsets
+                .partition_point(|offset| (*offset as usize) < range.end)
+                .min(self.ops.len());
+            for ix in first..last {
+                let pc = self.offsets[ix] as usize;
+                let next_pc = self.offsets[ix + 1] as usize;
+                if self.owners[ix] == Some(*body_ix) {
+                    let span = match &self.ops[ix].owning_span {
+                        Some(span) => span,
+                        None => continue,
+                    };
+                    for ((_, (_, block)), block_range) in
+                        blocks.iter().zip(ranges.iter_mut()).skip(1)
+                    {
+                        if span_contains(block, span) {
+                            let block_range =
+                                block_range.get_or_insert(InstructionRange { start: pc, end: pc });
+                            block_range.end = next_pc;
+                        }
+                    }
+                } else if let Some((callee, _, _)) =
+                    function_scopes
+                        .iter()
+                        .find(|(_, callee_range, callee_depth)| {
+                            *callee_depth == depth + 1
+                                && range.start <= callee_range.start
+                                && callee_range.end <= range.end
+                                && callee_range.contains(pc)
+                        })
+                {
+                    for ((_, (_, block)), block_range) in
+                        blocks.iter().zip(ranges.iter_mut()).skip(1)
+                    {
+                        let is_called_from_block =
+                            self.functions
+                                .calls
+                                .iter()
+                                .any(|(caller, called, call_span)| {
+                                    caller == body_ix
+                                        && called == callee
+                                        && span_contains(block, call_span)
+                                });
+                        match block_range {
+                            Some(block_range) if is_called_from_block && block_range.end == pc => {
+                                block_range.end = next_pc;
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+            }
+            scopes.extend(
+                blocks
+                    .iter()
+                    .zip(ranges)
+                    .filter_map(|((block_ix, _), range)| Some((*block_ix, range?))),
+            );
+        }
+        scopes.sort_by_key(|(_, range)| (range.start, std::cmp::Reverse(range.end)));
+        scopes
+    }
+
+    /// The size in bytes of the instructions, which are followed by the data section.
+    pub(crate) fn program_bytes(&self) -> usize {
+        *self.offsets.last().unwrap() as usize * 4
+    }
+
+    /// The name of the constant loaded by each op which loads a data section entry, by the index
+    /// of the entry.
+    pub(crate) fn data_entry_names(&self) -> HashMap<usize, String> {
+        let mut names = HashMap::new();
+        for op in &self.ops {
+            if let (AllocatedOpcode::LWDataId(_, data_id), Some(span)) =
+                (&op.opcode, &op.owning_span)
+            {
+                if let Some((name, _)) = self.functions.constants.iter().find(|(_, const_span)| {
+                    const_span.path == span.path
+                        && const_span.start() <= span.start()
+                        && span.end() <= const_span.end()
+                }) {
+                    names
+                        .entry(data_id.0 as usize)
+                        .or_insert_with(|| name.clone());
+                }
+            }
+        }
+        names
+    }
+
+    /// The index of the entry pointed to by each of the pointers appended to the data section,
+    /// by the index of the pointer.
+    pub(crate) fn data_pointer_targets(&self) -> HashMap<usize, usize> {
+        let program_bytes = self.program_bytes();
+        self.data_section
+            .value_pairs
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, value)| {
+                pointer_target(&self.data_section, value, program_bytes).map(|target| (ix, target))
+            })
+            .collect()
+    }
+}
+
+/// The index of the entry pointed to by a pointer appended to the data section during
+/// serialisation.
+fn pointer_target(
+    data_section: &DataSection,
+    value: &Literal,
+    program_bytes: usize,
+) -> Option<usize> {
+    let pointer = match value {
+        Literal::U64(pointer) => *pointer as usize,
+        _ => return None,
+    };
+    let mut offset = program_bytes;
+    for (ix, entry) in data_section.value_pairs.iter().enumerate() {
+        if offset == pointer && entry.as_type().stack_size_of() > 1 {
+            return Some(ix);
+        }
+        offset += entry.to_bytes().len();
+    }
+    None
+}
+
+/// Finds the target of each comparison in the contract selector switch, which is an `lw` of the
+/// selector from the data section, an `eq` with the input selector and a `jnei` to the method.
+/// Returns the index of the body of each method with the index of the op at its entry point.
+fn abi_method_entries(
+    ops: &[AllocatedOp],
+    data_section: &DataSection,
+    offsets: &[u64],
+    functions: &ProgramFunctions,
+) -> Vec<(usize, usize)> {
+    let offset_to_ix: HashMap<u64, usize> = offsets
+        .iter()
+        .enumerate()
+        .map(|(ix, offset)| (*offset, ix))
+        .collect();
+    let zero = AllocatedRegister::Constant(ConstantRegister::Zero);
+
+    let mut entries = Vec::new();
+    for window in ops.windows(3) {
+        if let (
+            AllocatedOpcode::LWDataId(selector_reg, data_id),
+            AllocatedOpcode::EQ(eq_reg, lhs, rhs),
+            AllocatedOpcode::JNEI(jnei_lhs, jnei_rhs, target),
+        ) = (&window[0].opcode, &window[1].opcode, &window[2].opcode)
+        {
+            let selector = match data_section.value_pairs.get(data_id.0 as usize) {
+                Some(Literal::U32(selector)) => selector,
+                _ => continue,
+            };
+            let is_selector_comparison = (lhs == selector_reg || rhs == selector_reg)
+                && ((jnei_lhs == &zero && jnei_rhs == eq_reg)
+                    || (jnei_lhs == eq_reg && jnei_rhs == &zero));
+            if !is_selector_comparison {
+                continue;
+            }
+            let body_ix = functions
+                .abi_selectors
+                .iter()
+                .find(|(abi_selector, _)| abi_selector == selector);
+            if let (Some((_, body_ix)), Some(entry)) =
+                (body_ix, offset_to_ix.get(&(target.value as u64)))
+            {
+                entries.push((*body_ix, *entry));
+            }
+        }
+    }
+    entries.sort();
+    entries.dedup();
+    entries
+}
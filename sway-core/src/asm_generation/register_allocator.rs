@@ -1,7 +1,10 @@
 use crate::asm_generation::{
-    register_sequencer::RegisterSequencer, RegisterAllocationStatus, RegisterPool,
+    compiler_constants, register_sequencer::RegisterSequencer, RegisterAllocationStatus,
+    RegisterPool,
+};
+use crate::asm_lang::{
+    virtual_register::*, RealizedOp, VirtualImmediate12, VirtualImmediate24, VirtualOp,
 };
-use crate::asm_lang::{virtual_register::*, RealizedOp, VirtualOp};
 use petgraph::graph::NodeIndex;
 use std::collections::{BTreeSet, HashMap};
 
@@ -110,7 +113,7 @@ pub(crate) fn liveness_analysis(ops: &[RealizedOp]) -> HashMap<usize, BTreeSet<V
             }
 
             // Did anything change in this iteration?
-            modified = (prev_live_in_op != *live_in_op) || (prev_live_out_op != *live_out_op);
+            modified |= (prev_live_in_op != *live_in_op) || (prev_live_out_op != *live_out_op);
         }
         modified
     } {}
@@ -207,12 +210,15 @@ pub(crate) fn create_interference_graph(
 ///   updated, as well as the immediate values for some or all jump instructions (`ji` and `jnei`
 ///   for now).
 ///
+/// Returns the reduced ops with a map from each register that was coalesced to the register that
+/// replaced it.
+///
 pub(crate) fn coalesce_registers(
     ops: &[RealizedOp],
     interference_graph: &mut InterferenceGraph,
     reg_to_node_map: &mut HashMap<VirtualRegister, NodeIndex>,
     register_sequencer: &mut RegisterSequencer,
-) -> Vec<RealizedOp> {
+) -> (Vec<RealizedOp>, HashMap<VirtualRegister, VirtualRegister>) {
     // A map from the virtual registers that are removed to the virtual registers that they are
     // replaced with during the coalescing process.
     let mut reg_to_reg_map: HashMap<VirtualRegister, VirtualRegister> = HashMap::new();
@@ -346,7 +352,7 @@ pub(crate) fn coalesce_registers(
         new_op.opcode = new_op.opcode.update_register(&final_reg_to_reg_map);
     }
 
-    reduced_ops
+    (reduced_ops, final_reg_to_reg_map)
 }
 
 /// Given an interference graph and a integer k, figure out if the graph k-colorable. Graph
@@ -359,41 +365,317 @@ pub(crate) fn coalesce_registers(
 /// 1. Pick any node n such that degree(n) < k and put it on the stack along with its neighbors.
 /// 2. Remove node n and all its edges from the graph
 ///    - This may make some new nodes have fewer than k neighbours which is nice.
-/// 3. If some vertex n still has k or more neighbors, then the graph is not k colorable, and we
-///    have to spill. For now, we will just error out, but we may be able to do better in the
-///    future.
+/// 3. If every remaining node n has k or more neighbors, then the graph may not be k colorable,
+///    and a node must be spilled to memory. Remove the node with the lowest spill cost from the
+///    graph, add it to the spill list and go back to step 1.
 /// ===============================================================================================
 ///
+/// The spill cost of a node is the number of times its virtual register is used or defined in
+/// `ops`, divided by its degree. Registers in `unspillable` were created by an earlier round of
+/// spilling and are never spilled again.
+///
+/// Returns the stack if no nodes were spilled, otherwise the spilled virtual registers.
+///
 pub(crate) fn color_interference_graph(
     interference_graph: &mut InterferenceGraph,
     k: u8,
-) -> Vec<(VirtualRegister, BTreeSet<VirtualRegister>)> {
+    ops: &[RealizedOp],
+    unspillable: &BTreeSet<VirtualRegister>,
+) -> Result<Vec<(VirtualRegister, BTreeSet<VirtualRegister>)>, Vec<VirtualRegister>> {
     let mut stack: Vec<(VirtualRegister, BTreeSet<VirtualRegister>)> = vec![];
+    let mut spills: Vec<VirtualRegister> = vec![];
+
+    // The number of times each virtual register is accessed.
+    let mut num_accesses: HashMap<&VirtualRegister, usize> = HashMap::new();
+    for op in ops {
+        for reg in op.opcode.registers() {
+            *num_accesses.entry(reg).or_default() += 1;
+        }
+    }
+
+    while interference_graph.node_count() > 0 {
+        if let Some(node) = pick_node(interference_graph, k) {
+            let neighbors = interference_graph
+                .neighbors(node)
+                .map(|n| interference_graph[n].clone())
+                .collect();
+            stack.push((
+                interference_graph
+                    .remove_node(node)
+                    .expect("Node must exist"),
+                neighbors,
+            ));
+            continue;
+        }
 
-    while let Some(node) = pick_node(interference_graph, k) {
-        let neighbors = interference_graph
-            .neighbors(node)
-            .map(|n| interference_graph[n].clone())
-            .collect();
-        stack.push((
+        // Compare the spill costs accesses_a / degree_a < accesses_b / degree_b without
+        // dividing.
+        let spill_cost = |n: NodeIndex| {
+            let reg = &interference_graph[n];
+            (
+                num_accesses.get(reg).copied().unwrap_or(0),
+                interference_graph.neighbors(n).count(),
+            )
+        };
+        let node = interference_graph
+            .node_indices()
+            .filter(|&n| !unspillable.contains(&interference_graph[n]))
+            .min_by(|&a, &b| {
+                let ((accesses_a, degree_a), (accesses_b, degree_b)) =
+                    (spill_cost(a), spill_cost(b));
+                (accesses_a * degree_b).cmp(&(accesses_b * degree_a))
+            })
+            .expect("Registers created by spilling are short lived and must be colorable.");
+        spills.push(
             interference_graph
                 .remove_node(node)
                 .expect("Node must exist"),
-            neighbors,
+        );
+    }
+
+    if spills.is_empty() {
+        Ok(stack)
+    } else {
+        Err(spills)
+    }
+}
+
+/// Given a list of instructions `ops` and a list of virtual registers `spills` which could not be
+/// colored, give each spilled register its own word in the spill area at the start of the stack,
+/// i.e., at `$ssp`, and rewrite `ops` so that the spilled registers are no longer used:
+/// * Each instruction which uses a spilled register is preceded by a load from its slot into a
+///   new virtual register, which the instruction uses instead.
+/// * Each instruction which defines a spilled register defines a new virtual register instead,
+///   and is followed by a store of it to the slot.
+///
+/// The slots beyond the reach of the 12-bit immediate of `lw` and `sw` are addressed through a new
+/// virtual register holding `$ssp` plus the offset of the slot, which is recorded with its slot in
+/// `slot_addresses`.
+///
+/// The new virtual registers are only live around a single instruction and are added to
+/// `unspillable`. `num_spill_slots` is the number of slots already taken by earlier spills and is
+/// updated to include the new ones.
+///
+pub(crate) fn spill_registers(
+    ops: &[RealizedOp],
+    spills: &[VirtualRegister],
+    num_spill_slots: &mut u64,
+    unspillable: &mut BTreeSet<VirtualRegister>,
+    slot_addresses: &mut HashMap<VirtualRegister, u64>,
+    register_sequencer: &mut RegisterSequencer,
+) -> Vec<RealizedOp> {
+    let mut spill_slots: HashMap<&VirtualRegister, u64> = HashMap::new();
+    for reg in spills {
+        spill_slots.insert(reg, *num_spill_slots);
+        *num_spill_slots += 1;
+    }
+
+    insert_ops(ops, |op| {
+        let mut before = vec![];
+        let mut after = vec![];
+        let mut reg_to_reg_map = HashMap::new();
+        for reg in op.opcode.registers() {
+            if let Some(slot) = spill_slots.get(reg) {
+                let temp_reg = register_sequencer.next();
+                unspillable.insert(temp_reg.clone());
+                if op.opcode.use_registers().contains(reg) {
+                    let (base, imm) = spill_slot_address(
+                        *slot,
+                        &mut before,
+                        unspillable,
+                        slot_addresses,
+                        register_sequencer,
+                    );
+                    before.push(VirtualOp::LW(temp_reg.clone(), base, imm));
+                }
+                if op.opcode.def_registers().contains(reg) {
+                    let (base, imm) = spill_slot_address(
+                        *slot,
+                        &mut after,
+                        unspillable,
+                        slot_addresses,
+                        register_sequencer,
+                    );
+                    after.push(VirtualOp::SW(base, temp_reg.clone(), imm));
+                }
+                reg_to_reg_map.insert(reg.clone(), temp_reg);
+            }
+        }
+        let opcode = if reg_to_reg_map.is_empty() {
+            op.opcode.clone()
+        } else {
+            op.opcode.clone().update_register(&reg_to_reg_map)
+        };
+        (before, opcode, after)
+    })
+}
+
+/// The base register and immediate with which `lw` and `sw` address the spill `slot`.  If the slot
+/// is beyond the reach of the immediate its address is computed into a new register by appending
+/// to `ops`.
+fn spill_slot_address(
+    slot: u64,
+    ops: &mut Vec<VirtualOp>,
+    unspillable: &mut BTreeSet<VirtualRegister>,
+    slot_addresses: &mut HashMap<VirtualRegister, u64>,
+    register_sequencer: &mut RegisterSequencer,
+) -> (VirtualRegister, VirtualImmediate12) {
+    let spill_base = VirtualRegister::Constant(ConstantRegister::StackStartPointer);
+    if slot > compiler_constants::TWELVE_BITS {
+        let address_reg = register_sequencer.next();
+        unspillable.insert(address_reg.clone());
+        slot_addresses.insert(address_reg.clone(), slot);
+        ops.append(&mut number_to_reg(slot * 8, &address_reg));
+        ops.push(VirtualOp::ADD(
+            address_reg.clone(),
+            address_reg.clone(),
+            spill_base,
         ));
+        (address_reg, VirtualImmediate12 { value: 0 })
+    } else {
+        (spill_base, VirtualImmediate12 { value: slot as u16 })
+    }
+}
+
+/// The ops which put `value` into `reg`, 12 bits at a time.
+fn number_to_reg(value: u64, reg: &VirtualRegister) -> Vec<VirtualOp> {
+    let num_chunks = std::cmp::max(1, (64 - value.leading_zeros()).div_ceil(12));
+    let mut ops = vec![];
+    for chunk_ix in (0..num_chunks).rev() {
+        let chunk = VirtualImmediate12 {
+            value: ((value >> (chunk_ix * 12)) & compiler_constants::TWELVE_BITS) as u16,
+        };
+        if ops.is_empty() {
+            ops.push(VirtualOp::ORI(
+                reg.clone(),
+                VirtualRegister::Constant(ConstantRegister::Zero),
+                chunk,
+            ));
+        } else {
+            ops.push(VirtualOp::SLLI(
+                reg.clone(),
+                reg.clone(),
+                VirtualImmediate12 { value: 12 },
+            ));
+            ops.push(VirtualOp::ORI(reg.clone(), reg.clone(), chunk));
+        }
     }
+    ops
+}
 
-    // If any nodes are left in the graph, then the must still have a degree larger than k. In this
-    // case, the graph is not colorable.
-    if interference_graph.node_count() > 0 {
-        unimplemented!(
-            "The allocator cannot resolve a register mapping for this program. 
-                This is a temporary artifact of the extremely early stage version of this language. 
-                Try to lower the number of variables you use."
-        );
+/// Reserve `num_spill_slots` words for the spill area, which must be done at the start of the
+/// program before anything else is put on the stack. The program starts at the target of the
+/// preamble's jump over the data section offset, if there is one. CFEI takes a 24-bit immediate
+/// so a bigger spill area is reserved in several word aligned chunks.
+///
+/// Only the preamble jumps to the reservation. Any other jump to the start of the program, such
+/// as the back edge of a loop at the very start of `main`, jumps past it so the spill area is
+/// reserved once.
+///
+pub(crate) fn reserve_spill_slots(ops: &[RealizedOp], num_spill_slots: u64) -> Vec<RealizedOp> {
+    let (preamble_jump, program_start) = match ops.first().map(|op| &op.opcode) {
+        Some(VirtualOp::JI(imm)) => (true, imm.value as u64),
+        _ => (false, 0),
+    };
+
+    const MAX_CHUNK: u64 = compiler_constants::TWENTY_FOUR_BITS & !7;
+    let mut alloc_ops = vec![];
+    let mut remaining = num_spill_slots * 8;
+    while remaining > 0 {
+        let chunk = std::cmp::min(remaining, MAX_CHUNK);
+        alloc_ops.push(VirtualOp::CFEI(VirtualImmediate24 {
+            value: chunk as u32,
+        }));
+        remaining -= chunk;
     }
+    let num_alloc_ops = alloc_ops.len() as u64;
+
+    let offset_map: HashMap<u64, u64> = ops
+        .iter()
+        .map(|op| {
+            if op.offset >= program_start {
+                (op.offset, op.offset + num_alloc_ops)
+            } else {
+                (op.offset, op.offset)
+            }
+        })
+        .collect();
 
-    stack
+    let mut new_ops = Vec::with_capacity(ops.len() + alloc_ops.len());
+    for (ix, op) in ops.iter().enumerate() {
+        if op.offset == program_start {
+            for (alloc_ix, alloc_op) in alloc_ops.drain(..).enumerate() {
+                new_ops.push(RealizedOp {
+                    opcode: alloc_op,
+                    comment: String::new(),
+                    owning_span: op.owning_span.clone(),
+                    offset: program_start + alloc_ix as u64,
+                });
+            }
+        }
+        let opcode = if ix == 0 && preamble_jump {
+            op.opcode.clone()
+        } else {
+            op.opcode.clone().update_jump_immediate_values(&offset_map)
+        };
+        new_ops.push(RealizedOp {
+            opcode,
+            comment: op.comment.clone(),
+            owning_span: op.owning_span.clone(),
+            offset: offset_map[&op.offset],
+        });
+    }
+    new_ops
+}
+
+/// Rebuild `ops`, replacing each op with the ops returned by `expand`: the ops to insert before
+/// it, its new opcode and the ops to insert after it. Each inserted op is a single word, owned by
+/// the op it was inserted for. The offset of each op and the immediate values of the jump
+/// instructions are updated so that a jump to an op now jumps to the first op inserted before it.
+///
+fn insert_ops(
+    ops: &[RealizedOp],
+    mut expand: impl FnMut(&RealizedOp) -> (Vec<VirtualOp>, VirtualOp, Vec<VirtualOp>),
+) -> Vec<RealizedOp> {
+    let mut new_ops: Vec<RealizedOp> = vec![];
+    let mut offset_map: HashMap<u64, u64> = HashMap::new();
+    let mut num_ops_inserted = 0;
+
+    for (ix, op) in ops.iter().enumerate() {
+        // Some ops, e.g. the data section offset, are more than a single word.
+        let op_size = ops
+            .get(ix + 1)
+            .map(|next_op| next_op.offset - op.offset)
+            .unwrap_or(1);
+
+        let (before, opcode, after) = expand(op);
+        offset_map.insert(op.offset, op.offset + num_ops_inserted);
+        let new_op = |opcode: VirtualOp, offset: u64, comment: &str| RealizedOp {
+            opcode,
+            comment: comment.into(),
+            owning_span: op.owning_span.clone(),
+            offset,
+        };
+        for inserted_opcode in before {
+            new_ops.push(new_op(inserted_opcode, op.offset + num_ops_inserted, ""));
+            num_ops_inserted += 1;
+        }
+        new_ops.push(new_op(opcode, op.offset + num_ops_inserted, &op.comment));
+        for inserted_opcode in after {
+            new_ops.push(new_op(
+                inserted_opcode,
+                op.offset + op_size + num_ops_inserted,
+                "",
+            ));
+            num_ops_inserted += 1;
+        }
+    }
+
+    for new_op in &mut new_ops {
+        new_op.opcode = new_op.opcode.update_jump_immediate_values(&offset_map);
+    }
+
+    new_ops
 }
 
 /// Use the stack generated by the coloring algorithm to figure out a register assignment for each
@@ -435,3 +717,147 @@ pub(crate) fn pick_node(interference_graph: &InterferenceGraph, k: u8) -> Option
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{asm_generation::RealizedAbstractInstructionSet, test_utils};
+    use fuel_vm::prelude::ProgramState;
+
+    fn constant(reg: ConstantRegister) -> VirtualRegister {
+        VirtualRegister::Constant(reg)
+    }
+
+    fn imm12(value: u16) -> VirtualImmediate12 {
+        VirtualImmediate12 { value }
+    }
+
+    fn realize(opcodes: Vec<VirtualOp>) -> Vec<RealizedOp> {
+        opcodes
+            .into_iter()
+            .enumerate()
+            .map(|(offset, opcode)| RealizedOp {
+                opcode,
+                comment: String::new(),
+                owning_span: None,
+                offset: offset as u64,
+            })
+            .collect()
+    }
+
+    // Allocate the registers of `ops`, run them as a script in the VM and return its final state.
+    fn run(ops: Vec<RealizedOp>, register_sequencer: &mut RegisterSequencer) -> ProgramState {
+        let program_section =
+            RealizedAbstractInstructionSet { ops }.allocate_registers(register_sequencer);
+        test_utils::run_ops(program_section.ops)
+    }
+
+    #[test]
+    fn live_across_loop_back_edge() {
+        let mut register_sequencer = RegisterSequencer::new();
+        let limit = register_sequencer.next();
+        let counter = register_sequencer.next();
+        let done = register_sequencer.next();
+        let step = register_sequencer.next();
+
+        // `limit` is only read at the head of the loop, so it's live throughout the body because
+        // of the jump back, and `step` mustn't share its register.  The program starts with a
+        // jump, like the preamble, whose liveness never changes.
+        let ops = realize(vec![
+            VirtualOp::JI(VirtualImmediate24 { value: 1 }),
+            VirtualOp::ADDI(limit.clone(), constant(ConstantRegister::Zero), imm12(3)),
+            VirtualOp::ADDI(counter.clone(), constant(ConstantRegister::Zero), imm12(0)),
+            VirtualOp::EQ(done.clone(), counter.clone(), limit.clone()),
+            VirtualOp::JNEI(done, constant(ConstantRegister::Zero), imm12(8)),
+            VirtualOp::ADDI(step.clone(), constant(ConstantRegister::Zero), imm12(1)),
+            VirtualOp::ADD(counter.clone(), counter.clone(), step),
+            VirtualOp::JI(VirtualImmediate24 { value: 3 }),
+            VirtualOp::RET(counter),
+        ]);
+        let live_out = liveness_analysis(&ops);
+        assert!(live_out[&5].contains(&limit));
+        assert!(live_out[&7].contains(&limit));
+        assert_eq!(run(ops, &mut register_sequencer), ProgramState::Return(3));
+    }
+
+    #[test]
+    fn spill_slots_beyond_immediate() {
+        let mut register_sequencer = RegisterSequencer::new();
+        let value = register_sequencer.next();
+        let address = register_sequencer.next();
+        let loaded = register_sequencer.next();
+
+        // Store a value in a slot past the reach of the `sw` immediate and load it back from
+        // where that slot should be.
+        let slot = compiler_constants::TWELVE_BITS + 1000;
+        let mut opcodes = vec![VirtualOp::ADDI(
+            value.clone(),
+            constant(ConstantRegister::Zero),
+            imm12(42),
+        )];
+        opcodes.append(&mut number_to_reg(slot * 8, &address));
+        opcodes.push(VirtualOp::ADD(
+            address.clone(),
+            address.clone(),
+            constant(ConstantRegister::StackStartPointer),
+        ));
+        opcodes.push(VirtualOp::LW(loaded.clone(), address, imm12(0)));
+        opcodes.push(VirtualOp::RET(loaded));
+
+        let mut num_spill_slots = slot;
+        let mut unspillable = BTreeSet::new();
+        let mut slot_addresses = HashMap::new();
+        let ops = spill_registers(
+            &realize(opcodes),
+            &[value],
+            &mut num_spill_slots,
+            &mut unspillable,
+            &mut slot_addresses,
+            &mut register_sequencer,
+        );
+        assert_eq!(num_spill_slots, slot + 1);
+        assert_eq!(slot_addresses.values().collect::<Vec<_>>(), vec![&slot]);
+
+        let ops = reserve_spill_slots(&ops, num_spill_slots);
+        assert_eq!(run(ops, &mut register_sequencer), ProgramState::Return(42));
+    }
+
+    #[test]
+    fn spill_slots_reserved_once_for_loop_at_program_start() {
+        let mut register_sequencer = RegisterSequencer::new();
+        let counter = register_sequencer.next();
+        let limit = register_sequencer.next();
+        let done = register_sequencer.next();
+        let reserved = register_sequencer.next();
+
+        // The loop at the start of the program runs three times, and only the preamble's jump
+        // must run the reservation.
+        let ops = realize(vec![
+            VirtualOp::JI(VirtualImmediate24 { value: 1 }),
+            VirtualOp::ADDI(counter.clone(), counter.clone(), imm12(1)),
+            VirtualOp::ADDI(limit.clone(), constant(ConstantRegister::Zero), imm12(3)),
+            VirtualOp::EQ(done.clone(), counter, limit),
+            VirtualOp::JNEI(done, constant(ConstantRegister::One), imm12(1)),
+            VirtualOp::SUB(
+                reserved.clone(),
+                constant(ConstantRegister::StackPointer),
+                constant(ConstantRegister::StackStartPointer),
+            ),
+            VirtualOp::RET(reserved),
+        ]);
+        let ops = reserve_spill_slots(&ops, 2);
+        assert!(matches!(
+            ops[0].opcode,
+            VirtualOp::JI(VirtualImmediate24 { value: 1 })
+        ));
+        assert!(matches!(
+            ops[1].opcode,
+            VirtualOp::CFEI(VirtualImmediate24 { value: 16 })
+        ));
+        assert!(matches!(
+            ops[5].opcode,
+            VirtualOp::JNEI(_, _, VirtualImmediate12 { value: 2 })
+        ));
+        assert_eq!(run(ops, &mut register_sequencer), ProgramState::Return(16));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::asm_lang::{Label, VirtualRegister};
+use sway_types::ident::Ident;
 /// The [RegisterSequencer] is basically an iterator over integers -- it distributes unique ids in
 /// the form of integers while ASM is being generated to ensure a monotonically increasing unique
 /// register Id for every virtual register that is used.
@@ -6,6 +7,8 @@ use crate::asm_lang::{Label, VirtualRegister};
 pub(crate) struct RegisterSequencer {
     next_register: usize,
     next_jump_label: usize,
+    /// Each named variable with the virtual register holding it, for the debug info.
+    variables: Vec<(Ident, VirtualRegister)>,
 }
 
 impl RegisterSequencer {
@@ -25,4 +28,14 @@ impl RegisterSequencer {
         self.next_jump_label += 1;
         Label(next_val)
     }
+
+    /// Records that `reg` holds the variable `name`.  As functions are inlined, a variable of a
+    /// function called more than once is held in a different register for each call.
+    pub(crate) fn name_register(&mut self, name: Ident, reg: VirtualRegister) {
+        self.variables.push((name, reg));
+    }
+
+    pub(crate) fn variables(&self) -> &[(Ident, VirtualRegister)] {
+        &self.variables
+    }
 }
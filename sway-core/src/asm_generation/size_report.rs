@@ -0,0 +1,662 @@
+//! The `--size-report` breakdown of the finalized bytecode, by the function owning each
+//! instruction.
+//!
+//! The worst-case gas for each entry point, which is `main` or each contract ABI method once it
+//! has been selected, is the most expensive path through the instructions reachable from it,
+//! priced with the FuelVM gas schedule.  Entry points which may loop are not estimated, and the
+//! estimate is only a lower bound if a reachable instruction has a cost which depends on its
+//! operands at runtime.
+//!
+//! The same breakdown is used to check the size of a contract against the size limit of the
+//! network it is deployed to, naming the largest contributors when it is too large.
+
+use super::{display_data_value, peephole, program_functions::ProgramLayout, DataSection};
+use crate::{
+    asm_lang::allocated_ops::{AllocatedOp, AllocatedOpcode},
+    error::*,
+};
+
+use sway_types::span::Span;
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+};
+
+/// The bytes of bytecode generated for a function, including any inlined into it at the call
+/// sites of other functions.
+struct FunctionSize {
+    name: String,
+    location: String,
+    instructions: u64,
+}
+
+/// A data section entry, with the functions which load it.
+struct DataEntrySize {
+    label: String,
+    value: String,
+    bytes: usize,
+    used_by: BTreeSet<String>,
+}
+
+/// The most gas an entry point may use.
+enum GasEstimate {
+    Exact(u64),
+    /// Some instructions use an amount of gas which can't be known until runtime.
+    AtLeast(u64),
+    /// The entry point may loop.
+    Unbounded,
+}
+
+/// The `--size-report` breakdown of a program, which is displayed as a table.
+pub struct SizeReport {
+    functions: Vec<FunctionSize>,
+    data_entries: Vec<DataEntrySize>,
+    program_bytes: usize,
+    data_bytes: usize,
+    entry_points: Vec<(String, GasEstimate)>,
+}
+
+const COMPILER_GENERATED: &str = "(compiler generated)";
+
+/// A contract larger than this percentage of the size limit is warned about.
+const CONTRACT_SIZE_WARNING_PERCENT: u64 = 90;
+
+/// The number of functions and data section entries named when a contract is too large.
+const LARGEST_CONTRIBUTORS_COUNT: usize = 5;
+
+impl SizeReport {
+    pub(crate) fn new(layout: &ProgramLayout) -> Self {
+        let ProgramLayout {
+            functions,
+            ops,
+            data_section,
+            bytes,
+            offsets,
+            owners,
+            abi_entries,
+        } = layout;
+        let program_bytes = layout.program_bytes();
+        let owner_name = |owner: Option<usize>| match owner {
+            Some(ix) => functions.bodies[ix].0.clone(),
+            None => COMPILER_GENERATED.to_owned(),
+        };
+
+        let mut instructions: HashMap<Option<usize>, u64> = HashMap::new();
+        for (ix, owner) in owners.iter().enumerate() {
+            *instructions.entry(*owner).or_default() += offsets[ix + 1] - offsets[ix];
+        }
+        let mut function_sizes = instructions
+            .into_iter()
+            .map(|(owner, instructions)| FunctionSize {
+                name: owner_name(owner),
+                location: owner
+                    .map(|ix| display_location(&functions.bodies[ix].1))
+                    .unwrap_or_default(),
+                instructions,
+            })
+            .collect::<Vec<_>>();
+        function_sizes.sort_by(|a, b| {
+            b.instructions
+                .cmp(&a.instructions)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        // The pointers appended during serialisation are described by the entry they point to.
+        let mut used_by: HashMap<usize, BTreeSet<String>> = HashMap::new();
+        for (op, owner) in ops.iter().zip(owners) {
+            if let AllocatedOpcode::LWDataId(_, data_id) = &op.opcode {
+                used_by
+                    .entry(data_id.0 as usize)
+                    .or_default()
+                    .insert(owner_name(*owner));
+            }
+        }
+        for (pointer, target) in layout.data_pointer_targets() {
+            used_by
+                .entry(pointer)
+                .or_default()
+                .insert(format!("pointer to data_{}", target));
+        }
+        let data_entries = data_section
+            .value_pairs
+            .iter()
+            .enumerate()
+            .map(|(ix, value)| DataEntrySize {
+                label: format!("data_{}", ix),
+                value: display_data_value(value),
+                bytes: value.to_bytes().len(),
+                used_by: used_by.remove(&ix).unwrap_or_default(),
+            })
+            .collect();
+
+        let entry_points = if let Some(main) = functions.main {
+            vec![(
+                functions.bodies[main].0.clone(),
+                worst_case_gas(ops, data_section, 0),
+            )]
+        } else {
+            abi_entries
+                .iter()
+                .map(|(body_ix, entry)| {
+                    (
+                        functions.bodies[*body_ix].0.clone(),
+                        worst_case_gas(ops, data_section, *entry),
+                    )
+                })
+                .collect()
+        };
+
+        SizeReport {
+            functions: function_sizes,
+            data_entries,
+            program_bytes,
+            data_bytes: bytes.len() - program_bytes,
+            entry_points,
+        }
+    }
+
+    /// Checks the size of the bytecode of a contract against `limit`, reporting any warning or
+    /// error at `span`.
+    pub(crate) fn check_contract_size(&self, limit: u64, span: Span) -> CompileResult<()> {
+        let mut warnings = Vec::new();
+        let mut errors = Vec::new();
+        let size = self.program_bytes + self.data_bytes;
+        if size as u64 > limit {
+            let mut contributors = self
+                .functions
+                .iter()
+                .map(|function| (function.name.clone(), function.instructions as usize * 4))
+                .chain(self.data_entries.iter().map(|entry| {
+                    let used_by = entry.used_by.iter().cloned().collect::<Vec<_>>();
+                    let description = if used_by.is_empty() {
+                        entry.label.clone()
+                    } else {
+                        format!("{} used by {}", entry.label, used_by.join(", "))
+                    };
+                    (description, entry.bytes)
+                }))
+                .collect::<Vec<_>>();
+            contributors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            let largest_contributors = contributors
+                .into_iter()
+                .take(LARGEST_CONTRIBUTORS_COUNT)
+                .map(|(description, bytes)| format!("{} ({} bytes)", description, bytes))
+                .collect::<Vec<_>>()
+                .join(", ");
+            errors.push(CompileError::ContractSizeLimitExceeded {
+                size,
+                limit,
+                largest_contributors,
+                span,
+            });
+            return err(warnings, errors);
+        }
+        if size as u64 * 100 > limit * CONTRACT_SIZE_WARNING_PERCENT {
+            warnings.push(CompileWarning {
+                span,
+                warning_content: Warning::ContractSizeNearLimit { size, limit },
+            });
+        }
+        ok((), warnings, errors)
+    }
+}
+
+fn display_location(span: &Span) -> String {
+    let (line, _) = span.start_pos().line_col();
+    let file_name = span
+        .path
+        .as_ref()
+        .and_then(|path| path.file_name())
+        .map(|file_name| file_name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    format!("{}:{}", file_name, line)
+}
+
+/// The gas used by an instruction, and whether this is only the part of it which is known before
+/// runtime.  The prices are those of the FuelVM gas schedule, in which all of the instructions not
+/// listed, including the jumps, cost the same as `add`.
+fn gas_cost(opcode: &AllocatedOpcode, data_section: &DataSection) -> (u64, bool) {
+    use AllocatedOpcode::*;
+    const ATOM: u64 = 1;
+    const ARITHMETIC: u64 = 5;
+    const ARITHMETIC_EXPENSIVE: u64 = 7;
+    const REGISTER_WRITE: u64 = 2;
+    const BRANCHING: u64 = 10;
+    const MEMORY_OWNERSHIP: u64 = 9;
+    const MEMORY_WRITE: u64 = 8;
+
+    const ADD_COST: u64 = ARITHMETIC + 3 * REGISTER_WRITE;
+    const MEMORY_CLEAR_BASE: u64 = ARITHMETIC + MEMORY_OWNERSHIP;
+    const MEMORY_COPY_BASE: u64 = 2 * ARITHMETIC + MEMORY_OWNERSHIP;
+    match opcode {
+        MLOG(..) | MROO(..) => (ARITHMETIC_EXPENSIVE + 3 * REGISTER_WRITE, true),
+        AND(..) | EQ(..) | GT(..) | LT(..) | OR(..) | XOR(..) | NOT(..) | ANDI(..) | MOVE(..)
+        | ORI(..) | XORI(..) => (3 * REGISTER_WRITE, true),
+        DIV(..) | MOD(..) | DIVI(..) | MODI(..) => (ARITHMETIC + BRANCHING, true),
+        NOOP => (ATOM, true),
+        MCLI(_, len) => (MEMORY_CLEAR_BASE + MEMORY_WRITE * len.value as u64, true),
+        MCPI(_, _, len) => (MEMORY_COPY_BASE + MEMORY_WRITE * len.value as u64, true),
+        MCL(..) => (MEMORY_CLEAR_BASE, false),
+        MCP(..) => (MEMORY_COPY_BASE, false),
+        // The gas forwarded to the called contract is spent there.
+        CALL(..) => (ADD_COST, false),
+        LWDataId(_, data_id)
+            if data_section
+                .type_of_data(data_id)
+                .is_some_and(|ty| ty.stack_size_of() > 1) =>
+        {
+            // This is an `lw` of the pointer to the data followed by an `add`.
+            (2 * ADD_COST, true)
+        }
+        // The data section offset is jumped over rather than executed.
+        DataSectionOffsetPlaceholder => (0, true),
+        _ => (ADD_COST, true),
+    }
+}
+
+/// The most gas used by any path from `ops[entry]` to the end of the program.
+fn worst_case_gas(ops: &[AllocatedOp], data_section: &DataSection, entry: usize) -> GasEstimate {
+    let offsets = peephole::compute_offsets(ops, data_section);
+    let offset_to_ix: HashMap<u64, usize> = offsets
+        .iter()
+        .enumerate()
+        .map(|(ix, offset)| (*offset, ix))
+        .collect();
+
+    // Visit the reachable ops in a depth first search, recording them in post order so that each
+    // op comes after all of its successors.  Reaching an op which is still being visited means
+    // there is a loop.
+    let mut on_stack = vec![false; ops.len()];
+    let mut visited = vec![false; ops.len()];
+    let mut post_order = Vec::new();
+    let mut stack = vec![(entry, peephole::successors(ops, entry, &offset_to_ix))];
+    visited[entry] = true;
+    on_stack[entry] = true;
+    while let Some((ix, successors)) = stack.last_mut() {
+        match successors.pop() {
+            Some(succ) if on_stack[succ] => return GasEstimate::Unbounded,
+            Some(succ) if !visited[succ] => {
+                visited[succ] = true;
+                on_stack[succ] = true;
+                stack.push((succ, peephole::successors(ops, succ, &offset_to_ix)));
+            }
+            Some(_) => (),
+            None => {
+                on_stack[*ix] = false;
+                post_order.push(*ix);
+                stack.pop();
+            }
+        }
+    }
+
+    let mut worst_case = vec![0; ops.len()];
+    let mut is_exact = true;
+    for ix in post_order {
+        let (cost, is_static) = gas_cost(&ops[ix].opcode, data_section);
+        is_exact &= is_static;
+        worst_case[ix] = cost
+            + peephole::successors(ops, ix, &offset_to_ix)
+                .into_iter()
+                .map(|succ| worst_case[succ])
+                .max()
+                .unwrap_or(0);
+    }
+    if is_exact {
+        GasEstimate::Exact(worst_case[entry])
+    } else {
+        GasEstimate::AtLeast(worst_case[entry])
+    }
+}
+
+impl fmt::Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Size report:")?;
+        writeln!(
+            f,
+            "  {:<32} {:<24} {:>12} {:>8}",
+            "Function", "Location", "Instructions", "Bytes"
+        )?;
+        for function in &self.functions {
+            writeln!(
+                f,
+                "  {:<32} {:<24} {:>12} {:>8}",
+                function.name,
+                function.location,
+                function.instructions,
+                function.instructions * 4
+            )?;
+        }
+        writeln!(f, "  {:<70} {:>8}", "Program total", self.program_bytes)?;
+        writeln!(f)?;
+
+        writeln!(f, "  {:<32} {:<37} {:>8}", "Data", "Used by", "Bytes")?;
+        for entry in &self.data_entries {
+            let used_by = entry.used_by.iter().cloned().collect::<Vec<_>>().join(", ");
+            writeln!(
+                f,
+                "  {:<32} {:<37} {:>8}",
+                format!("{} {}", entry.label, entry.value),
+                used_by,
+                entry.bytes
+            )?;
+        }
+        writeln!(f, "  {:<70} {:>8}", "Data section total", self.data_bytes)?;
+        writeln!(f)?;
+
+        writeln!(
+            f,
+            "  {:<70} {:>8}",
+            "Bytecode total",
+            self.program_bytes + self.data_bytes
+        )?;
+
+        if !self.entry_points.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "  {:<57} {:>21}", "Entry point", "Worst-case gas")?;
+            for (name, estimate) in &self.entry_points {
+                let estimate = match estimate {
+                    GasEstimate::Exact(gas) => gas.to_string(),
+                    GasEstimate::AtLeast(gas) => format!("at least {}", gas),
+                    GasEstimate::Unbounded => "unbounded (loops)".to_owned(),
+                };
+                writeln!(f, "  {:<57} {:>21}", name, estimate)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        asm_lang::{
+            allocated_ops::AllocatedRegister, virtual_register::ConstantRegister,
+            VirtualImmediate12,
+        },
+        test_utils, CompilationResult, CompileError,
+    };
+
+    fn reg(ix: u8) -> AllocatedRegister {
+        AllocatedRegister::Allocated(ix)
+    }
+
+    fn constant(reg: ConstantRegister) -> AllocatedRegister {
+        AllocatedRegister::Constant(reg)
+    }
+
+    fn op(opcode: AllocatedOpcode) -> AllocatedOp {
+        AllocatedOp {
+            opcode,
+            comment: String::new(),
+            owning_span: None,
+            defines: Vec::new(),
+        }
+    }
+
+    fn cost(opcodes: &[AllocatedOpcode]) -> u64 {
+        opcodes
+            .iter()
+            .map(|opcode| gas_cost(opcode, &DataSection::default()).0)
+            .sum()
+    }
+
+    fn estimate(ops: &[AllocatedOp]) -> GasEstimate {
+        worst_case_gas(ops, &DataSection::default(), 0)
+    }
+
+    #[test]
+    fn gas_loop_free() {
+        let opcodes = vec![
+            AllocatedOpcode::ADD(reg(0), reg(0), reg(0)),
+            AllocatedOpcode::DIV(reg(0), reg(0), reg(0)),
+            AllocatedOpcode::RET(reg(0)),
+        ];
+        let expected = cost(&opcodes);
+        let ops = opcodes.into_iter().map(op).collect::<Vec<_>>();
+        assert!(matches!(estimate(&ops), GasEstimate::Exact(gas) if gas == expected));
+    }
+
+    #[test]
+    fn gas_most_expensive_branch() {
+        let jump = AllocatedOpcode::JNEI(
+            reg(0),
+            constant(ConstantRegister::Zero),
+            VirtualImmediate12 { value: 3 },
+        );
+        let cheap = AllocatedOpcode::ADD(reg(0), reg(0), reg(0));
+        let expensive = AllocatedOpcode::DIV(reg(0), reg(0), reg(0));
+        let ret = AllocatedOpcode::RET(reg(0));
+        let expected = cost(&[jump.clone(), expensive.clone(), ret.clone()]);
+        assert!(expected > cost(&[jump.clone(), cheap.clone(), ret.clone()]));
+
+        // The expensive branch is taken whichever way round the branches are.
+        let ops = vec![
+            op(jump.clone()),
+            op(expensive.clone()),
+            op(ret.clone()),
+            op(cheap.clone()),
+            op(ret.clone()),
+        ];
+        assert!(matches!(estimate(&ops), GasEstimate::Exact(gas) if gas == expected));
+        let ops = vec![op(jump), op(cheap), op(ret.clone()), op(expensive), op(ret)];
+        assert!(matches!(estimate(&ops), GasEstimate::Exact(gas) if gas == expected));
+    }
+
+    #[test]
+    fn gas_loop_is_unbounded() {
+        let ops = vec![
+            op(AllocatedOpcode::ADD(reg(0), reg(0), reg(0))),
+            op(AllocatedOpcode::JNEI(
+                reg(0),
+                constant(ConstantRegister::Zero),
+                VirtualImmediate12 { value: 0 },
+            )),
+            op(AllocatedOpcode::RET(reg(0))),
+        ];
+        assert!(matches!(estimate(&ops), GasEstimate::Unbounded));
+    }
+
+    #[test]
+    fn gas_with_runtime_cost() {
+        let opcodes = vec![
+            AllocatedOpcode::MCP(reg(0), reg(1), reg(2)),
+            AllocatedOpcode::RET(reg(0)),
+        ];
+        let expected = cost(&opcodes);
+        let ops = opcodes.into_iter().map(op).collect::<Vec<_>>();
+        assert!(matches!(estimate(&ops), GasEstimate::AtLeast(gas) if gas == expected));
+    }
+
+    #[test]
+    fn attribution() {
+        let build_config = test_utils::build_config().size_report(true);
+        let result = test_utils::compile_to_asm(
+            r#"
+    script;
+    fn one() -> b256 {
+        0x0000000000000000000000000000000000000000000000000000000000000001
+    }
+    fn main() -> b256 {
+        if true {
+            one()
+        } else {
+            0x0000000000000000000000000000000000000000000000000000000000000002
+        }
+    }"#,
+            build_config,
+        );
+        let report = match result {
+            CompilationResult::Success {
+                size_report: Some(size_report),
+                ..
+            } => size_report,
+            _ => panic!("Script failed to compile with a size report."),
+        };
+
+        // Every instruction and data section entry is attributed to something.
+        let function_names = report
+            .functions
+            .iter()
+            .map(|function| function.name.as_str())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(
+            function_names,
+            BTreeSet::from([COMPILER_GENERATED, "main", "one"])
+        );
+        assert_eq!(
+            report
+                .functions
+                .iter()
+                .map(|function| function.instructions as usize * 4)
+                .sum::<usize>(),
+            report.program_bytes
+        );
+        assert_eq!(
+            report
+                .data_entries
+                .iter()
+                .map(|entry| entry.bytes)
+                .sum::<usize>(),
+            report.data_bytes
+        );
+
+        // Each b256 literal is loaded by the function it is in.
+        let b256_users = report
+            .data_entries
+            .iter()
+            .filter(|entry| entry.bytes == 32)
+            .map(|entry| entry.used_by.iter().map(String::as_str).collect::<Vec<_>>())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(b256_users, BTreeSet::from([vec!["main"], vec!["one"]]));
+
+        assert_eq!(report.entry_points.len(), 1);
+        assert_eq!(report.entry_points[0].0, "main");
+        assert!(matches!(report.entry_points[0].1, GasEstimate::Exact(_)));
+    }
+
+    const CONTRACT: &str = r#"
+    contract;
+    abi Store {
+        fn key() -> b256;
+        fn count() -> u64;
+    }
+    impl Store for Contract {
+        fn key() -> b256 {
+            0x0000000000000000000000000000000000000000000000000000000000000001
+        }
+        fn count() -> u64 {
+            7
+        }
+    }"#;
+
+    fn compile_contract(source: &str, size_limit: Option<u64>) -> CompilationResult {
+        let build_config = test_utils::build_config().size_report(true);
+        let build_config = match size_limit {
+            Some(limit) => build_config.contract_size_limit(limit),
+            None => build_config,
+        };
+        test_utils::compile_to_asm(source, build_config)
+    }
+
+    fn contract_size(source: &str) -> usize {
+        match compile_contract(source, None) {
+            CompilationResult::Success {
+                size_report: Some(report),
+                ..
+            } => report.program_bytes + report.data_bytes,
+            _ => panic!("Contract failed to compile with a size report."),
+        }
+    }
+
+    fn size_warnings(result: &CompilationResult) -> Vec<(usize, u64, &str)> {
+        let warnings = match result {
+            CompilationResult::Success { warnings, .. } => warnings,
+            _ => panic!("Contract failed to compile."),
+        };
+        warnings
+            .iter()
+            .filter_map(|warning| match &warning.warning_content {
+                Warning::ContractSizeNearLimit { size, limit } => {
+                    Some((*size, *limit, warning.span.as_str()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn contract_size_within_limit() {
+        let size = contract_size(CONTRACT);
+        let result = compile_contract(CONTRACT, Some(size as u64 * 2));
+        assert!(size_warnings(&result).is_empty());
+    }
+
+    #[test]
+    fn contract_size_near_limit() {
+        let size = contract_size(CONTRACT);
+        let result = compile_contract(CONTRACT, Some(size as u64));
+        let warnings = size_warnings(&result);
+        assert_eq!(warnings.len(), 1);
+        let (warning_size, warning_limit, warning_span) = warnings[0];
+        assert_eq!((warning_size, warning_limit), (size, size as u64));
+        // The warning is about the whole contract rather than any one method.
+        assert_eq!(warning_span, CONTRACT.trim());
+    }
+
+    #[test]
+    fn contract_size_above_limit() {
+        let size = contract_size(CONTRACT);
+        let errors = match compile_contract(CONTRACT, Some(size as u64 - 1)) {
+            CompilationResult::Failure { errors, .. } => errors,
+            _ => panic!("Contract above the size limit compiled."),
+        };
+        assert_eq!(errors.len(), 1);
+        match &errors[0] {
+            CompileError::ContractSizeLimitExceeded {
+                size: error_size,
+                limit,
+                largest_contributors,
+                span,
+            } => {
+                assert_eq!((*error_size, *limit), (size, size as u64 - 1));
+                assert_eq!(span.as_str(), CONTRACT.trim());
+                // The contributors are the largest first, and include the b256 literal and the
+                // method which loads it.
+                let contributors = largest_contributors.split(", ").collect::<Vec<_>>();
+                assert!(contributors.len() <= LARGEST_CONTRIBUTORS_COUNT);
+                let bytes = contributors
+                    .iter()
+                    .map(|contributor| {
+                        let bytes = contributor.rsplit('(').next().unwrap();
+                        bytes.trim_end_matches(" bytes)").parse::<usize>().unwrap()
+                    })
+                    .collect::<Vec<_>>();
+                assert!(bytes.windows(2).all(|pair| pair[0] >= pair[1]));
+                assert!(contributors.contains(&"data_0 used by key (32 bytes)"));
+                assert!(contributors
+                    .iter()
+                    .any(|contributor| contributor.starts_with("key (")));
+            }
+            error => panic!("Unexpected error: {}", error),
+        }
+    }
+
+    #[test]
+    fn contract_without_abi_methods_size_checked() {
+        let source = r#"
+    contract;
+    fn unused() -> u64 {
+        0
+    }"#;
+        let errors = match compile_contract(source, Some(1)) {
+            CompilationResult::Failure { errors, .. } => errors,
+            _ => panic!("Contract above the size limit compiled."),
+        };
+        assert!(matches!(
+            &errors[..],
+            [CompileError::ContractSizeLimitExceeded { span, .. }] if span.as_str() == source.trim()
+        ));
+    }
+}
@@ -3,6 +3,8 @@ use std::{
     sync::{Arc, Mutex},
 };
 
+use sway_ir::OptLevel;
+
 /// Configuration for the overall build and compilation process.
 #[derive(Clone)]
 pub struct BuildConfig {
@@ -13,6 +15,11 @@ pub struct BuildConfig {
     pub(crate) print_intermediate_asm: bool,
     pub(crate) print_finalized_asm: bool,
     pub(crate) print_ir: bool,
+    pub(crate) skip_bounds_checks: bool,
+    pub(crate) optimization_level: OptLevel,
+    pub(crate) hot_abi_methods: Vec<String>,
+    pub(crate) size_report: bool,
+    pub(crate) contract_size_limit: Option<u64>,
     pub(crate) generated_names: Arc<Mutex<Vec<&'static str>>>,
 }
 
@@ -33,6 +40,11 @@ impl BuildConfig {
             print_intermediate_asm: false,
             print_finalized_asm: false,
             print_ir: false,
+            skip_bounds_checks: false,
+            optimization_level: OptLevel::O1,
+            hot_abi_methods: Vec::new(),
+            size_report: false,
+            contract_size_limit: None,
             generated_names: Arc::new(Mutex::new(vec![])),
         }
     }
@@ -62,6 +74,47 @@ impl BuildConfig {
         }
     }
 
+    pub fn skip_bounds_checks(self, a: bool) -> Self {
+        Self {
+            skip_bounds_checks: a,
+            ..self
+        }
+    }
+
+    pub fn optimization_level(self, a: OptLevel) -> Self {
+        Self {
+            optimization_level: a,
+            ..self
+        }
+    }
+
+    /// The names of the contract ABI methods expected to be called most often, most frequent
+    /// first.  Their selectors are tested before searching for any of the others.
+    pub fn hot_abi_methods(self, a: Vec<String>) -> Self {
+        Self {
+            hot_abi_methods: a,
+            ..self
+        }
+    }
+
+    /// Print the size of the bytecode generated for each function and of each data section entry,
+    /// with an estimate of the worst-case gas used by each loop-free entry point.
+    pub fn size_report(self, a: bool) -> Self {
+        Self {
+            size_report: a,
+            ..self
+        }
+    }
+
+    /// The maximum size in bytes of the bytecode of a contract.  A contract which is larger is an
+    /// error, and one which is nearly as large is warned about.
+    pub fn contract_size_limit(self, a: u64) -> Self {
+        Self {
+            contract_size_limit: Some(a),
+            ..self
+        }
+    }
+
     pub fn path(&self) -> Arc<PathBuf> {
         self.file_name.clone()
     }
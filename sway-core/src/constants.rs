@@ -6,6 +6,12 @@ pub const DEFAULT_FILE_EXTENSION: &str = "sw";
 pub const LANGUAGE_NAME: &str = "Sway";
 /// The size, in bytes, of a single word in the FuelVM.
 pub const VM_WORD_SIZE: u64 = 8;
+/// The revert code used when an array or vector is indexed out of bounds at runtime.
+pub const INDEX_OUT_OF_BOUNDS_REVERT_CODE: u64 = 0xffff_ffff_ffff_0001;
+/// The revert code used when arithmetic on a `u128` or `u256` overflows.
+pub const INTEGER_OVERFLOW_REVERT_CODE: u64 = 0xffff_ffff_ffff_0002;
+/// The revert code used when a `u128` or `u256` is divided by zero.
+pub const DIVIDE_BY_ZERO_REVERT_CODE: u64 = 0xffff_ffff_ffff_0003;
 
 // Keywords
 pub const INVALID_NAMES: &[&str] = &["storage"];
@@ -792,6 +792,38 @@ fn connect_expression(
 
             Ok([then_expr, else_expr].concat())
         }
+        IntegerSwitch {
+            scrutinee,
+            cases,
+            default,
+        } => {
+            let scrutinee_expr = connect_expression(
+                &scrutinee.expression,
+                graph,
+                leaves,
+                exit_node,
+                "",
+                tree_type,
+                scrutinee.span.clone(),
+            )?;
+            let mut arm_exprs = vec![];
+            for body in cases
+                .iter()
+                .map(|(_, body)| body)
+                .chain(std::iter::once(&**default))
+            {
+                arm_exprs.append(&mut connect_expression(
+                    &body.expression,
+                    graph,
+                    &scrutinee_expr,
+                    exit_node,
+                    "switch arm",
+                    tree_type,
+                    body.span.clone(),
+                )?);
+            }
+            Ok(arm_exprs)
+        }
         CodeBlock(a @ TypedCodeBlock { .. }) => {
             connect_code_block(a, graph, leaves, exit_node, tree_type)
         }
@@ -1022,6 +1054,41 @@ fn connect_expression(
                 Ok(exp)
             }
         },
+        VecIntrinsic { kind, .. } => {
+            let mut current_leaf = leaves.to_vec();
+            for arg in kind.arguments() {
+                current_leaf = connect_expression(
+                    &arg.expression,
+                    graph,
+                    &current_leaf,
+                    exit_node,
+                    kind.name(),
+                    tree_type,
+                    arg.span.clone(),
+                )?;
+            }
+            Ok(current_leaf)
+        }
+        IntIntrinsic { op, lhs, rhs, .. } => {
+            let lhs_exprs = connect_expression(
+                &lhs.expression,
+                graph,
+                leaves,
+                exit_node,
+                op.name(),
+                tree_type,
+                lhs.span.clone(),
+            )?;
+            connect_expression(
+                &rhs.expression,
+                graph,
+                &lhs_exprs,
+                exit_node,
+                op.name(),
+                tree_type,
+                rhs.span.clone(),
+            )
+        }
         a => {
             println!("Unimplemented: {:?}", a);
             Err(CompileError::Unimplemented(
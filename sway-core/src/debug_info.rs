@@ -0,0 +1,432 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use sway_types::{ident::Ident, span::Span, Instruction, Position, Range};
+
+use crate::{
+    asm_generation::program_functions::ProgramLayout, asm_lang::allocated_ops::VariableHome,
+    symbol_table::InstructionRange,
+};
+
+/// The lexical scopes of a program and where its named variables are held, by instruction, for
+/// debuggers to show the variables in scope at a breakpoint.  Instruction indices count 4 byte
+/// instructions from the start of the bytecode, as in the
+/// [SymbolTable](crate::symbol_table::SymbolTable).
+///
+/// Variables are recorded by the default code generator only; a program compiled using the IR
+/// pipeline has its scopes but no variables.  A variable is held from the instruction after the
+/// op writing its value until its scope is exited, or until an instruction overwrites its
+/// register with the value of something else, such as once it is no longer used.
+///
+/// Consecutive calls of the same function, with no ops of the caller between them, are not told
+/// apart and so share one scope.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DebugInfo {
+    /// Paths to source code files, defined separately to avoid repetition.
+    pub paths: Vec<PathBuf>,
+    /// Each code block, once for each time its function is inlined.  The scopes are ordered by
+    /// their first instruction, from the outermost to the innermost.
+    pub scopes: Vec<LexicalScope>,
+    /// Each range of instructions over which a variable is held in one location.
+    pub variables: Vec<VariableLocation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LexicalScope {
+    /// The name of the function the scope is in.
+    pub function: String,
+    /// The index of the path of the source file containing the block.
+    pub path: Option<usize>,
+    /// The first instruction of the scope, with the source range of the block.
+    pub enter: Instruction,
+    /// The instruction after the last of the scope, with the source range of the block.
+    pub exit: Instruction,
+}
+
+impl LexicalScope {
+    pub fn range(&self) -> InstructionRange {
+        InstructionRange {
+            start: self.enter.pc as usize,
+            end: self.exit.pc as usize,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariableLocation {
+    pub name: String,
+    /// The index of the scope the variable is declared in, or `None` for a constant declared
+    /// outside of any function.
+    pub scope: Option<usize>,
+    pub location: Location,
+    pub range: InstructionRange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Location {
+    /// The index of the VM register holding the variable.
+    Register(usize),
+    /// A word of the stack which the variable was spilled to, as an offset in words from the
+    /// stack start pointer `$ssp`.
+    Stack { offset: u64 },
+}
+
+impl DebugInfo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The scopes containing an instruction, from the outermost to the innermost.
+    pub fn scopes_at(&self, pc: usize) -> Vec<&LexicalScope> {
+        let mut scopes = self
+            .scopes
+            .iter()
+            .filter(|scope| scope.range().contains(pc))
+            .collect::<Vec<_>>();
+        scopes.sort_by_key(|scope| {
+            let range = scope.range();
+            std::cmp::Reverse(range.end - range.start)
+        });
+        scopes
+    }
+
+    /// The variables held when stopped at an instruction, before it is executed.
+    pub fn variables_at(&self, pc: usize) -> Vec<&VariableLocation> {
+        self.variables
+            .iter()
+            .filter(|variable| variable.range.contains(pc))
+            .collect()
+    }
+
+    pub(crate) fn insert_program(&mut self, layout: &ProgramLayout) {
+        let functions = layout.functions;
+        let function_scopes = layout.function_scopes();
+        let block_scopes = layout.block_scopes(&function_scopes);
+        let first_scope = self.scopes.len();
+        for (block_ix, range) in &block_scopes {
+            let (body_ix, span) = &functions.blocks[*block_ix];
+            let path = span.path.as_ref().map(|path| self.path_index(path));
+            let source = source_range(span);
+            self.scopes.push(LexicalScope {
+                function: functions.bodies[*body_ix].0.clone(),
+                path,
+                enter: Instruction {
+                    pc: range.start as u64,
+                    range: source,
+                    exit: false,
+                },
+                exit: Instruction {
+                    pc: range.end as u64,
+                    range: source,
+                    exit: true,
+                },
+            });
+        }
+
+        // The ops are scanned in order, opening a range for a variable at each op writing it
+        // which isn't already held there, and closing it at the end of its scope or when its
+        // register is overwritten by an op which doesn't write it.
+        let program_end = *layout.offsets.last().unwrap() as usize;
+        let mut held: Vec<HeldVariable> = Vec::new();
+        for (ix, op) in layout.ops.iter().enumerate() {
+            let pc = layout.offsets[ix] as usize;
+            let next_pc = layout.offsets[ix + 1] as usize;
+            let overwritten = op.opcode.def_registers();
+            let mut closed = Vec::new();
+            held.retain(|var| {
+                let rewritten = op
+                    .defines
+                    .iter()
+                    .any(|(name, home)| same_variable(name, &var.name) && *home == var.home);
+                let end = if pc >= var.end {
+                    var.end
+                } else if !rewritten
+                    && matches!(&var.home, VariableHome::Register(reg) if overwritten.contains(&reg))
+                {
+                    // The value is still there when stopped at the op overwriting it.
+                    pc + 1
+                } else {
+                    return true;
+                };
+                closed.push((var.clone(), end));
+                false
+            });
+            for (name, home) in &op.defines {
+                let is_held = held
+                    .iter()
+                    .any(|var| same_variable(name, &var.name) && *home == var.home);
+                if is_held {
+                    continue;
+                }
+                // A parameter is written before the scope of its function is entered.
+                let (scope, start, end) = match functions.variable_block(name) {
+                    Some((block_ix, is_parameter)) => {
+                        let scope_ix = block_scopes
+                            .iter()
+                            .position(|(ix, range)| *ix == block_ix && range.contains(pc))
+                            .or_else(|| {
+                                block_scopes.iter().position(|(ix, range)| {
+                                    is_parameter && *ix == block_ix && range.start > pc
+                                })
+                            });
+                        match scope_ix {
+                            Some(scope_ix) => {
+                                let range = block_scopes[scope_ix].1;
+                                (
+                                    Some(first_scope + scope_ix),
+                                    range.start.max(next_pc),
+                                    range.end,
+                                )
+                            }
+                            None => continue,
+                        }
+                    }
+                    None => (None, next_pc, program_end),
+                };
+                held.push(HeldVariable {
+                    name: name.clone(),
+                    home: home.clone(),
+                    scope,
+                    start,
+                    end,
+                });
+            }
+            for (var, end) in closed {
+                self.insert_variable(var, end);
+            }
+        }
+        for var in held {
+            let end = var.end.min(program_end);
+            self.insert_variable(var, end);
+        }
+        self.variables
+            .sort_by_key(|variable| (variable.range.start, variable.range.end));
+    }
+
+    fn insert_variable(&mut self, var: HeldVariable, end: usize) {
+        if var.start >= end {
+            return;
+        }
+        let location = match var.home {
+            VariableHome::Register(reg) => Location::Register(reg.to_register_id()),
+            VariableHome::SpillSlot(offset) => Location::Stack { offset },
+        };
+        self.variables.push(VariableLocation {
+            name: var.name.as_str().to_owned(),
+            scope: var.scope,
+            location,
+            range: InstructionRange {
+                start: var.start,
+                end,
+            },
+        });
+    }
+
+    fn path_index(&mut self, path: &Path) -> usize {
+        self.paths
+            .iter()
+            .position(|p| *p == *path)
+            .unwrap_or_else(|| {
+                self.paths.push(path.to_owned());
+                self.paths.len() - 1
+            })
+    }
+}
+
+/// A variable being held while the ops are scanned, from the instruction `start` until at most
+/// the end of its scope.
+#[derive(Clone)]
+struct HeldVariable {
+    name: Ident,
+    home: VariableHome,
+    scope: Option<usize>,
+    start: usize,
+    end: usize,
+}
+
+/// Variables are compared by name and where they are declared, as a function's variables are
+/// declared once however many times it is inlined.
+fn same_variable(lhs: &Ident, rhs: &Ident) -> bool {
+    lhs == rhs && lhs.span().path == rhs.span().path && lhs.span().start() == rhs.span().start()
+}
+
+/// The lines and columns of a span, with the end inclusive.
+fn source_range(span: &Span) -> Range {
+    let (start_line, start_col) = span.start_pos().line_col();
+    let (end_line, end_col) = span.end_pos().line_col();
+    Range {
+        start: Position {
+            line: start_line,
+            col: start_col,
+        },
+        end: Position {
+            line: end_line,
+            col: end_col.saturating_sub(1),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        symbol_table::SymbolTable,
+        test_utils::{self, ASM_OPERATORS},
+        CompilationResult,
+    };
+    use fuel_asm::Opcode;
+    use fuel_vm::consts::REG_SSP;
+
+    // Compile the script `main`, with the ASM operator functions, to its debug info and
+    // instructions.
+    fn compile(main: &str) -> (DebugInfo, Vec<Opcode>) {
+        let source = format!("script;{}\n{}", ASM_OPERATORS, main);
+        let mut debug_info = DebugInfo::new();
+        let result = test_utils::compile_to_asm_with_tables(
+            &source,
+            test_utils::build_config(),
+            &mut SymbolTable::new(),
+            &mut debug_info,
+        );
+        let bytes = match result {
+            CompilationResult::Success { asm, .. } => test_utils::assemble(asm),
+            _ => panic!("Script failed to compile."),
+        };
+        (debug_info, Opcode::from_bytes_iter(bytes))
+    }
+
+    // The line `main` starts on in a script compiled by `compile()`, after the operator functions
+    // and the blank line which starts the raw string `main` is written in.
+    fn main_line() -> usize {
+        ASM_OPERATORS.lines().count() + 2
+    }
+
+    fn variable<'a>(debug_info: &'a DebugInfo, name: &str) -> &'a VariableLocation {
+        let mut variables = debug_info
+            .variables
+            .iter()
+            .filter(|variable| variable.name == name);
+        let variable = variables.next().expect("variable is recorded");
+        assert!(variables.next().is_none());
+        variable
+    }
+
+    // Each variable is held within its scope, and from the instruction after one writing its
+    // location.
+    fn check_variables(debug_info: &DebugInfo, ops: &[Opcode], names: &[&str]) {
+        for name in names {
+            let variable = variable(debug_info, name);
+            let scope = &debug_info.scopes[variable.scope.unwrap()];
+            assert!(scope.range().start <= variable.range.start);
+            assert!(variable.range.end <= scope.range().end);
+            let writer = ops[variable.range.start - 1];
+            match variable.location {
+                Location::Register(reg) => assert!(
+                    matches!(writer, Opcode::ADD(ra, ..) | Opcode::MOVE(ra, _) if ra == reg),
+                    "{} is written by {:?}",
+                    name,
+                    writer
+                ),
+                Location::Stack { offset } => assert!(
+                    matches!(writer, Opcode::SW(REG_SSP, _, imm) if imm as u64 == offset),
+                    "{} is written by {:?}",
+                    name,
+                    writer
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn scopes_and_coalesced_variables() {
+        let (debug_info, ops) = compile(
+            r#"
+    fn main() -> u64 {
+        let x = add(1, 2);
+        let y = x;
+        let z = {
+            let w = add(y, 3);
+            add(w, 4)
+        };
+        add(z, 5)
+    }"#,
+        );
+
+        // The block of `z` is within the body of `main`, and each call of `add` is within the
+        // block it is called from.
+        let body = &debug_info.scopes[0];
+        assert_eq!(body.function, "main");
+        assert!(debug_info
+            .scopes
+            .iter()
+            .all(|scope| body.range().start <= scope.range().start
+                && scope.range().end <= body.range().end));
+        let w = variable(&debug_info, "w");
+        let block = &debug_info.scopes[w.scope.unwrap()];
+        assert_eq!(block.function, "main");
+        assert_eq!(block.enter.range.start.line, main_line() + 3);
+        assert_eq!(block.exit.range.end.line, main_line() + 6);
+        let calls = debug_info
+            .scopes
+            .iter()
+            .filter(|scope| scope.function == "add")
+            .collect::<Vec<_>>();
+        assert_eq!(calls.len(), 4);
+        for call in &calls[1..3] {
+            let scopes = debug_info.scopes_at(call.range().start);
+            assert_eq!(
+                scopes
+                    .iter()
+                    .map(|scope| scope.function.as_str())
+                    .collect::<Vec<_>>(),
+                ["main", "main", "add"]
+            );
+            assert_eq!(scopes[1].range(), block.range());
+        }
+        assert_eq!(debug_info.scopes_at(calls[3].range().start).len(), 2);
+
+        // `y` is a copy of `x`, so they are held in the same register.
+        let (x, y) = (variable(&debug_info, "x"), variable(&debug_info, "y"));
+        assert_eq!(x.location, y.location);
+        assert_eq!(x.range, y.range);
+        assert!(debug_info
+            .variables_at(w.range.start)
+            .iter()
+            .any(|variable| variable.name == "x"));
+        check_variables(&debug_info, &ops, &["x", "y", "z", "w"]);
+    }
+
+    #[test]
+    fn spilled_variables() {
+        // More variables are live at once than there are registers.
+        let count = 60;
+        let mut main = String::from("fn main() -> u64 {\n    let s0 = add(0, 1);\n");
+        for ix in 1..count {
+            main.push_str(&format!("    let v{} = add({}, 1);\n", ix, ix));
+        }
+        for ix in 1..count {
+            main.push_str(&format!("    let s{} = add(s{}, v{});\n", ix, ix - 1, ix));
+        }
+        main.push_str(&format!("    s{}\n}}", count - 1));
+        let (debug_info, ops) = compile(&main);
+
+        let spilled = debug_info
+            .variables
+            .iter()
+            .filter(|variable| matches!(variable.location, Location::Stack { .. }))
+            .map(|variable| variable.name.as_str())
+            .collect::<Vec<_>>();
+        assert!(spilled.iter().filter(|name| name.starts_with('v')).count() > 1);
+        let names = (1..count).map(|ix| format!("v{}", ix)).collect::<Vec<_>>();
+        let names = names.iter().map(String::as_str).collect::<Vec<_>>();
+        check_variables(&debug_info, &ops, &names);
+
+        // Nothing else is written to a spill slot, so a spilled variable stays there until the
+        // end of its scope.
+        for name in spilled.iter().filter(|name| name.starts_with('v')) {
+            let variable = variable(&debug_info, name);
+            let scope = &debug_info.scopes[variable.scope.unwrap()];
+            assert_eq!(variable.range.end, scope.range().end);
+        }
+    }
+}
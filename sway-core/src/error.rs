@@ -276,6 +276,14 @@ pub enum Warning {
     },
     DeadStorageDeclaration,
     MatchExpressionUnreachableArm,
+    ContractSizeNearLimit {
+        size: usize,
+        limit: u64,
+    },
+    UnknownHotAbiMethod {
+        method_name: String,
+    },
+    BoundsChecksNotSkipped,
 }
 
 impl fmt::Display for Warning {
@@ -384,6 +392,22 @@ impl fmt::Display for Warning {
             DeadStorageDeclaration => write!(f, "This storage declaration is never accessed and can be removed."
             ),
             MatchExpressionUnreachableArm => write!(f, "This match arm is unreachable."),
+            ContractSizeNearLimit { size, limit } => write!(
+                f,
+                "This contract's bytecode is {} bytes, which is close to the limit of {} bytes.",
+                size, limit
+            ),
+            UnknownHotAbiMethod { method_name } => write!(
+                f,
+                "\"{}\" is listed in `hot-abi-methods` but this contract has no ABI method with \
+                 that name, so it is ignored.",
+                method_name
+            ),
+            BoundsChecksNotSkipped => write!(
+                f,
+                "`--skip-bounds-checks` needs the IR backend, so without `--use-ir` every array \
+                 index is still checked at runtime."
+            ),
         }
     }
 }
@@ -902,6 +926,18 @@ pub enum CompileError {
     MultipleStorageDeclarations { span: Span },
     #[error("Expected identifier, found keyword \"{name}\" ")]
     InvalidVariableName { name: String, span: Span },
+    #[error(
+        "This contract's bytecode is {size} bytes, which exceeds the limit of {limit} bytes. The \
+         largest contributors to its size are {largest_contributors}."
+    )]
+    ContractSizeLimitExceeded {
+        size: usize,
+        limit: u64,
+        largest_contributors: String,
+        span: Span,
+    },
+    #[error("The value of this constant overflows its type or divides by zero.")]
+    ConstantOverflow { span: Span },
 }
 
 impl std::convert::From<TypeError> for CompileError {
@@ -1114,6 +1150,8 @@ impl CompileError {
             NoDeclaredStorage { span, .. } => span,
             MultipleStorageDeclarations { span, .. } => span,
             InvalidVariableName { span, .. } => span,
+            ContractSizeLimitExceeded { span, .. } => span,
+            ConstantOverflow { span } => span,
         }
     }
 
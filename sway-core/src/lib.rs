@@ -9,21 +9,29 @@ mod build_config;
 mod concurrent_slab;
 pub mod constants;
 mod control_flow_analysis;
+pub mod debug_info;
 mod optimize;
 pub mod parse_tree;
 mod parser;
 pub mod semantic_analysis;
 pub mod source_map;
 mod style;
+pub mod symbol_table;
+#[cfg(test)]
+mod test_utils;
 pub mod type_engine;
 
 pub use crate::parser::{Rule, SwayParser};
 use crate::{
     asm_generation::{checks, compile_ast_to_asm},
+    debug_info::DebugInfo,
     error::*,
     source_map::SourceMap,
+    symbol_table::SymbolTable,
+};
+pub use asm_generation::{
+    size_report::SizeReport, AbstractInstructionSet, FinalizedAsm, SwayAsmSet,
 };
-pub use asm_generation::{AbstractInstructionSet, FinalizedAsm, SwayAsmSet};
 pub use build_config::BuildConfig;
 use control_flow_analysis::{ControlFlowGraph, Graph};
 use pest::iterators::Pair;
@@ -39,6 +47,7 @@ pub mod types;
 pub use crate::parse_tree::{Declaration, Expression, UseStatement, WhileLoop, *};
 
 pub use error::{CompileError, CompileResult, CompileWarning};
+pub use sway_ir::OptLevel;
 use sway_types::{ident::Ident, span};
 pub use type_engine::TypeInfo;
 
@@ -160,6 +169,8 @@ pub enum CompilationResult {
     Success {
         asm: FinalizedAsm,
         warnings: Vec<CompileWarning>,
+        /// The size report of the program, if the build config asks for one.
+        size_report: Option<SizeReport>,
     },
     Library {
         name: Ident,
@@ -190,6 +201,8 @@ pub enum BytecodeCompilationResult {
     Success {
         bytes: Vec<u8>,
         warnings: Vec<CompileWarning>,
+        /// The size report of the program, if the build config asks for one.
+        size_report: Option<SizeReport>,
     },
     Library {
         warnings: Vec<CompileWarning>,
@@ -363,14 +376,23 @@ pub fn compile_to_asm(
     input: Arc<str>,
     initial_namespace: crate::semantic_analysis::NamespaceRef,
     build_config: BuildConfig,
+    symbol_table: &mut SymbolTable,
+    debug_info: &mut DebugInfo,
 ) -> CompilationResult {
     let ast_res = compile_to_ast(input, initial_namespace, &build_config);
-    ast_to_asm(ast_res, &build_config)
+    ast_to_asm(ast_res, &build_config, symbol_table, debug_info)
 }
 
 /// Given an AST compilation result, compile to a [CompilationResult] which contains the asm in
-/// opcode form (not raw bytes/bytecode).
-pub fn ast_to_asm(ast_res: CompileAstResult, build_config: &BuildConfig) -> CompilationResult {
+/// opcode form (not raw bytes/bytecode).  The functions, ABI methods and data section entries of
+/// the bytecode it is serialised to are added to the `symbol_table`, and its lexical scopes and
+/// variable locations to the `debug_info`.
+pub fn ast_to_asm(
+    ast_res: CompileAstResult,
+    build_config: &BuildConfig,
+    symbol_table: &mut SymbolTable,
+    debug_info: &mut DebugInfo,
+) -> CompilationResult {
     match ast_res {
         CompileAstResult::Failure { warnings, errors } => {
             CompilationResult::Failure { warnings, errors }
@@ -383,6 +405,53 @@ pub fn ast_to_asm(ast_res: CompileAstResult, build_config: &BuildConfig) -> Comp
             let mut errors = vec![];
             match tree_type {
                 TreeType::Contract | TreeType::Script | TreeType::Predicate => {
+                    // The size limit applies to contracts, and is reported at the whole contract.
+                    let size_limit = match &*parse_tree {
+                        TypedParseTree::Contract { span, .. } => build_config
+                            .contract_size_limit
+                            .map(|limit| (limit, span.clone())),
+                        _ => None,
+                    };
+                    // A hot ABI method which the contract doesn't have is most likely a typo in
+                    // the manifest.
+                    if let TypedParseTree::Contract {
+                        abi_entries, span, ..
+                    } = &*parse_tree
+                    {
+                        for method_name in &build_config.hot_abi_methods {
+                            if !abi_entries
+                                .iter()
+                                .any(|entry| entry.name.as_str() == method_name)
+                            {
+                                warnings.push(CompileWarning {
+                                    span: span.clone(),
+                                    warning_content: Warning::UnknownHotAbiMethod {
+                                        method_name: method_name.clone(),
+                                    },
+                                });
+                            }
+                        }
+                    }
+                    // Only the IR backend proves indices to be in range, so the legacy backend
+                    // keeps every bounds check.
+                    if build_config.skip_bounds_checks && !build_config.use_ir {
+                        let span = match &*parse_tree {
+                            TypedParseTree::Script { main_function, .. }
+                            | TypedParseTree::Predicate { main_function, .. } => {
+                                Some(main_function.span.clone())
+                            }
+                            TypedParseTree::Contract { span, .. } => Some(span.clone()),
+                            TypedParseTree::Library { .. } => None,
+                        };
+                        if let Some(span) = span {
+                            warnings.push(CompileWarning {
+                                span,
+                                warning_content: Warning::BoundsChecksNotSkipped,
+                            });
+                        }
+                    }
+                    let program_functions =
+                        asm_generation::program_functions::ProgramFunctions::new(&parse_tree);
                     let asm = check!(
                         if build_config.use_ir {
                             compile_ast_to_ir_to_asm(*parse_tree, tree_type, build_config)
@@ -396,7 +465,31 @@ pub fn ast_to_asm(ast_res: CompileAstResult, build_config: &BuildConfig) -> Comp
                     if !errors.is_empty() {
                         return CompilationResult::Failure { errors, warnings };
                     }
-                    CompilationResult::Success { asm, warnings }
+                    let mut size_report = None;
+                    if let Some(layout) = asm_generation::program_functions::ProgramLayout::new(
+                        &asm,
+                        &program_functions,
+                    ) {
+                        symbol_table.insert_program(&layout);
+                        debug_info.insert_program(&layout);
+                        let report = SizeReport::new(&layout);
+                        if let Some((limit, span)) = size_limit {
+                            check!(
+                                report.check_contract_size(limit, span),
+                                return CompilationResult::Failure { errors, warnings },
+                                warnings,
+                                errors
+                            );
+                        }
+                        if build_config.size_report {
+                            size_report = Some(report);
+                        }
+                    }
+                    CompilationResult::Success {
+                        asm,
+                        warnings,
+                        size_report,
+                    }
                 }
                 TreeType::Library { name } => CompilationResult::Library {
                     warnings,
@@ -408,33 +501,27 @@ pub fn ast_to_asm(ast_res: CompileAstResult, build_config: &BuildConfig) -> Comp
     }
 }
 
-use sway_ir::{context::Context, function::Function};
-
 pub(crate) fn compile_ast_to_ir_to_asm(
     ast: TypedParseTree,
     tree_type: TreeType,
     build_config: &BuildConfig,
 ) -> CompileResult<FinalizedAsm> {
-    let mut warnings = Vec::new();
+    let warnings = Vec::new();
     let mut errors = Vec::new();
 
     let mut ir = match optimize::compile_ast(ast) {
         Ok(ir) => ir,
-        Err(msg) => {
-            errors.push(CompileError::InternalOwned(
-                msg,
-                span::Span {
-                    span: pest::Span::new(" ".into(), 0, 0).unwrap(),
-                    path: None,
-                },
-            ));
+        Err(error) => {
+            errors.push(error);
             return err(warnings, errors);
         }
     };
 
-    // Inline function calls since we don't support them yet.  For scripts and predicates we inline
-    // into main(), and for contracts we inline into ABI impls, which are found due to them having
-    // a selector.
+    // The backend can't yet compile calls, so every pipeline starts by inlining all of them rather
+    // than leaving the choice to the `inlinecost` pass.  For scripts and predicates we inline into
+    // main(), and for contracts we inline into ABI impls, which are found due to them having a
+    // selector.  The rest of the pipeline for the optimization level is run over the same
+    // functions.
     let mut functions_to_inline_to = Vec::new();
     for (idx, fc) in &ir.functions {
         if (matches!(tree_type, TreeType::Script | TreeType::Predicate) && fc.name == "main")
@@ -443,22 +530,20 @@ pub(crate) fn compile_ast_to_ir_to_asm(
             functions_to_inline_to.push(::sway_ir::function::Function(idx));
         }
     }
-    check!(
-        inline_function_calls(&mut ir, &functions_to_inline_to),
-        return err(warnings, errors),
-        warnings,
-        errors
-    );
-
-    // The only other optimisation we have at the moment is constant combining.  In lieu of a
-    // forthcoming pass manager we can just call it here now.  We can re-use the inline functions
-    // list.
-    check!(
-        combine_constants(&mut ir, &functions_to_inline_to),
-        return err(warnings, errors),
-        warnings,
-        errors
-    );
+    if let Err(ir_error) = sway_ir::PassManager::default().run_pipeline(
+        &mut ir,
+        build_config.optimization_level,
+        &functions_to_inline_to,
+    ) {
+        errors.push(CompileError::InternalOwned(
+            ir_error.to_string(),
+            span::Span {
+                span: pest::Span::new("".into(), 0, 0).unwrap(),
+                path: None,
+            },
+        ));
+        return err(warnings, errors);
+    }
 
     if build_config.print_ir {
         println!("{}", ir);
@@ -467,42 +552,6 @@ pub(crate) fn compile_ast_to_ir_to_asm(
     crate::asm_generation::from_ir::compile_ir_to_asm(&ir, build_config)
 }
 
-fn inline_function_calls(ir: &mut Context, functions: &[Function]) -> CompileResult<()> {
-    for function in functions {
-        if let Err(ir_error) = sway_ir::optimize::inline_all_function_calls(ir, function) {
-            return err(
-                Vec::new(),
-                vec![CompileError::InternalOwned(
-                    ir_error.to_string(),
-                    span::Span {
-                        span: pest::Span::new("".into(), 0, 0).unwrap(),
-                        path: None,
-                    },
-                )],
-            );
-        }
-    }
-    ok((), Vec::new(), Vec::new())
-}
-
-fn combine_constants(ir: &mut Context, functions: &[Function]) -> CompileResult<()> {
-    for function in functions {
-        if let Err(ir_error) = sway_ir::optimize::combine_constants(ir, function) {
-            return err(
-                Vec::new(),
-                vec![CompileError::InternalOwned(
-                    ir_error.to_string(),
-                    span::Span {
-                        span: pest::Span::new("".into(), 0, 0).unwrap(),
-                        path: None,
-                    },
-                )],
-            );
-        }
-    }
-    ok((), Vec::new(), Vec::new())
-}
-
 /// Given input Sway source code, compile to a [BytecodeCompilationResult] which contains the asm in
 /// bytecode form.
 pub fn compile_to_bytecode(
@@ -510,8 +559,16 @@ pub fn compile_to_bytecode(
     initial_namespace: crate::semantic_analysis::NamespaceRef,
     build_config: BuildConfig,
     source_map: &mut SourceMap,
+    symbol_table: &mut SymbolTable,
+    debug_info: &mut DebugInfo,
 ) -> BytecodeCompilationResult {
-    let asm_res = compile_to_asm(input, initial_namespace, build_config);
+    let asm_res = compile_to_asm(
+        input,
+        initial_namespace,
+        build_config,
+        symbol_table,
+        debug_info,
+    );
     asm_to_bytecode(asm_res, source_map)
 }
 
@@ -525,6 +582,7 @@ pub fn asm_to_bytecode(
         CompilationResult::Success {
             mut asm,
             mut warnings,
+            size_report,
         } => {
             let mut asm_res = asm.to_bytecode_mut(source_map);
             warnings.append(&mut asm_res.warnings);
@@ -538,6 +596,7 @@ pub fn asm_to_bytecode(
                 BytecodeCompilationResult::Success {
                     bytes: asm_res.value.unwrap(),
                     warnings,
+                    size_report,
                 }
             }
         }
@@ -847,6 +906,113 @@ fn test_unary_ordering() {
     };
 }
 
+#[test]
+fn test_unknown_hot_abi_method() {
+    let build_config =
+        test_utils::build_config().hot_abi_methods(vec!["get".into(), "sett".into()]);
+    let result = test_utils::compile_to_asm(
+        r#"
+    contract;
+    abi Counter {
+        fn get() -> u64;
+        fn set(value: u64);
+    }
+    impl Counter for Contract {
+        fn get() -> u64 {
+            0
+        }
+        fn set(value: u64) {
+        }
+    }"#,
+        build_config,
+    );
+    let warnings = match result {
+        CompilationResult::Success { warnings, .. } => warnings,
+        _ => panic!("Contract failed to compile."),
+    };
+    let hot_method_warnings = warnings
+        .iter()
+        .filter_map(|warning| match &warning.warning_content {
+            Warning::UnknownHotAbiMethod { method_name } => Some(method_name.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(hot_method_warnings, ["sett"]);
+}
+
+#[test]
+fn test_skip_bounds_checks_without_ir() {
+    for use_ir in [false, true] {
+        let build_config = test_utils::build_config()
+            .use_ir(use_ir)
+            .skip_bounds_checks(true);
+        let result = test_utils::compile_to_asm(
+            r#"
+    script;
+    fn main() -> u64 {
+        let a = [1, 2, 3];
+        a[0]
+    }"#,
+            build_config,
+        );
+        let warnings = match result {
+            CompilationResult::Success { warnings, .. } => warnings,
+            _ => panic!("Script failed to compile."),
+        };
+        let warned = warnings
+            .iter()
+            .any(|warning| matches!(warning.warning_content, Warning::BoundsChecksNotSkipped));
+        assert_eq!(warned, !use_ir);
+    }
+}
+
+#[test]
+fn test_predicate_data_with_each_backend() {
+    // The arguments of main() are read from the predicate data, laid out like a struct.
+    let predicate = format!(
+        r#"
+    predicate;{}
+    fn main(a: u64, key: b256, b: u64) -> bool {{
+        let expected = 0x0101010101010101010101010101010101010101010101010101010101010101;
+        let key_matches = asm(r1: key, r2: expected, r3, r4: 32) {{
+            meq r3 r1 r2 r4;
+            r3: bool
+        }};
+        if key_matches {{
+            eq(add(a, b), 10)
+        }} else {{
+            false
+        }}
+    }}"#,
+        test_utils::ASM_OPERATORS
+    );
+    let predicate_data = |a: u64, key: u8, b: u64| {
+        [
+            a.to_be_bytes().to_vec(),
+            vec![key; 32],
+            b.to_be_bytes().to_vec(),
+        ]
+        .concat()
+    };
+    let bytecode = [false, true].map(|use_ir| {
+        test_utils::compile_to_bytes(&predicate, test_utils::build_config().use_ir(use_ir))
+    });
+    for (data, expected) in [
+        (predicate_data(3, 1, 7), true),
+        (predicate_data(3, 1, 8), false),
+        (predicate_data(3, 0, 7), false),
+    ] {
+        for (use_ir, bytecode) in [false, true].iter().zip(&bytecode) {
+            assert_eq!(
+                test_utils::predicate_holds(bytecode.clone(), data.clone()),
+                expected,
+                "use_ir: {}",
+                use_ir
+            );
+        }
+    }
+}
+
 /// We want compile errors and warnings to retain their ordering, since typically
 /// they are grouped by relevance. However, we want to deduplicate them.
 /// Stdlib dedup in Rust assumes sorted data for efficiency, but we don't want that.
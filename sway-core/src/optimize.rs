@@ -1,9 +1,15 @@
 use crate::constants;
 use fuel_crypto::Hasher;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
-    asm_generation::from_ir::ir_type_size_in_bytes,
+    asm_generation::{
+        contract_call_argument, from_ir::ir_type_size_in_bytes, int_intrinsic_asm,
+        vec_from_call_data_asm, vec_from_return_data_asm, vec_intrinsic_asm, vec_return_asm,
+        NamedAsmOp, VecIntrinsicAsm, INT_LOOP_REG, INT_STATE_REG, INT_VALID_REG, VEC_ELEM_SIZE_REG,
+        VEC_HAS_SPACE_REG, VEC_IN_BOUNDS_REG,
+    },
+    error::CompileError,
     parse_tree::{AsmOp, AsmRegister, LazyOp, Literal, Visibility},
     semantic_analysis::{ast_node::*, *},
     type_engine::*,
@@ -15,10 +21,11 @@ use sway_ir::*;
 
 // -------------------------------------------------------------------------------------------------
 // XXX This needs to return a CompileResult.  OTOH, retrofitting a CompileResult here would add
-// very little value and require a lot of work.  An alternative might be returning
-// Result<T, CompileError>.
+// very little value and require a lot of work.  Errors in the program, such as constants which
+// overflow, are returned as a CompileError, but most errors here are internal and are Strings
+// until they're converted with `internal_error()`.
 
-pub(crate) fn compile_ast(ast: TypedParseTree) -> Result<Context, String> {
+pub(crate) fn compile_ast(ast: TypedParseTree) -> Result<Context, CompileError> {
     let mut ctx = Context::default();
     match ast {
         TypedParseTree::Script {
@@ -28,23 +35,35 @@ pub(crate) fn compile_ast(ast: TypedParseTree) -> Result<Context, String> {
             all_nodes: _,
         } => compile_script(&mut ctx, main_function, namespace, declarations),
         TypedParseTree::Predicate {
-            namespace: _,
-            main_function: _,
-            declarations: _,
+            namespace,
+            main_function,
+            declarations,
             all_nodes: _,
-        } => unimplemented!("compile predicate to ir"),
+        } => compile_predicate(&mut ctx, main_function, namespace, declarations),
         TypedParseTree::Contract {
             abi_entries,
             namespace,
             declarations,
             all_nodes: _,
+            span: _,
         } => compile_contract(&mut ctx, abi_entries, namespace, declarations),
         TypedParseTree::Library {
             namespace: _,
             all_nodes: _,
         } => unimplemented!("compile library to ir"),
     }?;
-    ctx.verify().map_err(|ir_error| ir_error.to_string())
+    ctx.verify()
+        .map_err(|ir_error| internal_error(ir_error.to_string()))
+}
+
+fn internal_error(msg: String) -> CompileError {
+    CompileError::InternalOwned(
+        msg,
+        Span {
+            span: pest::Span::new(" ".into(), 0, 0).unwrap(),
+            path: None,
+        },
+    )
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -54,12 +73,27 @@ fn compile_script(
     main_function: TypedFunctionDeclaration,
     namespace: NamespaceRef,
     declarations: Vec<TypedDeclaration>,
-) -> Result<Module, String> {
+) -> Result<Module, CompileError> {
     let module = Module::new(context, Kind::Script);
 
     compile_constants(context, module, namespace, false)?;
     compile_declarations(context, module, declarations)?;
-    compile_function(context, module, main_function)?;
+    compile_function(context, module, main_function).map_err(internal_error)?;
+
+    Ok(module)
+}
+
+fn compile_predicate(
+    context: &mut Context,
+    main_function: TypedFunctionDeclaration,
+    namespace: NamespaceRef,
+    declarations: Vec<TypedDeclaration>,
+) -> Result<Module, CompileError> {
+    let module = Module::new(context, Kind::Predicate);
+
+    compile_constants(context, module, namespace, false)?;
+    compile_declarations(context, module, declarations)?;
+    compile_function(context, module, main_function).map_err(internal_error)?;
 
     Ok(module)
 }
@@ -69,13 +103,13 @@ fn compile_contract(
     abi_entries: Vec<TypedFunctionDeclaration>,
     namespace: NamespaceRef,
     declarations: Vec<TypedDeclaration>,
-) -> Result<Module, String> {
+) -> Result<Module, CompileError> {
     let module = Module::new(context, Kind::Contract);
 
     compile_constants(context, module, namespace, false)?;
     compile_declarations(context, module, declarations)?;
     for decl in abi_entries {
-        compile_abi_method(context, module, decl)?;
+        compile_abi_method(context, module, decl).map_err(internal_error)?;
     }
 
     Ok(module)
@@ -88,9 +122,9 @@ fn compile_constants(
     module: Module,
     namespace: NamespaceRef,
     public_only: bool,
-) -> Result<(), String> {
+) -> Result<(), CompileError> {
     read_module(
-        |ns| -> Result<(), String> {
+        |ns| -> Result<(), CompileError> {
             for decl in ns.get_all_declared_symbols() {
                 let decl_name_value = match decl {
                     TypedDeclaration::ConstantDeclaration(TypedConstantDeclaration {
@@ -117,7 +151,7 @@ fn compile_constants(
                 };
 
                 if let Some((name, value)) = decl_name_value {
-                    let const_val = compile_constant_expression(context, value)?;
+                    let const_val = compile_constant_expression(context, module, value)?;
                     module.add_global_constant(context, name.as_str().to_owned(), const_val);
                 }
             }
@@ -135,14 +169,123 @@ fn compile_constants(
 
 fn compile_constant_expression(
     context: &mut Context,
+    module: Module,
     const_expr: &TypedExpression,
-) -> Result<Value, String> {
+) -> Result<Value, CompileError> {
+    let span_md_idx = MetadataIndex::from_span(context, &const_expr.span);
     if let TypedExpressionVariant::Literal(literal) = &const_expr.expression {
-        let span_md_idx = MetadataIndex::from_span(context, &const_expr.span);
         Ok(convert_literal_to_value(context, literal, span_md_idx))
     } else {
-        Err("Unsupported constant expression type.".into())
+        evaluate_constant_expression(context, module, const_expr)
+            .map(|constant| Value::new_constant(context, constant, span_md_idx))
+    }
+}
+
+// Evaluate a non-literal constant expression by compiling it into a function in a temporary module
+// and running it in the IR interpreter.  The temporary module shares the global constants from
+// `module` and is removed again afterwards, along with everything created for it.
+fn evaluate_constant_expression(
+    context: &mut Context,
+    module: Module,
+    const_expr: &TypedExpression,
+) -> Result<Constant, CompileError> {
+    let old_entries = ContextEntries::of(context);
+    let eval_module = Module::new(context, module.get_kind(context));
+    context.modules[eval_module.0].globals = context.modules[module.0].globals.clone();
+
+    // The interpreter wraps like the VM, but a constant which overflows or divides by zero is
+    // rejected just as it would be if its arithmetic were folded.
+    let result = compile_constant_evaluator(context, eval_module, const_expr)
+        .and_then(|eval_fn| {
+            let mut interpreter = Interpreter::new(context).forbid_external_state();
+            let constant = interpreter.call(&eval_fn, &[]).ok()?;
+            Some(match interpreter.arithmetic_fault() {
+                Some(_) => Err(CompileError::ConstantOverflow {
+                    span: const_expr.span.clone(),
+                }),
+                None => Ok(constant),
+            })
+        })
+        .unwrap_or_else(|| {
+            Err(internal_error(
+                "Unsupported constant expression type.".into(),
+            ))
+        });
+
+    old_entries.remove_new_entries(context);
+    result
+}
+
+// The indices of the modules, functions, blocks, values, pointers, asm blocks and metadata in a
+// context.  Aggregates are kept as they may be shared by types outside the temporary module.
+struct ContextEntries {
+    modules: HashSet<generational_arena::Index>,
+    functions: HashSet<generational_arena::Index>,
+    blocks: HashSet<generational_arena::Index>,
+    values: HashSet<generational_arena::Index>,
+    pointers: HashSet<generational_arena::Index>,
+    asm_blocks: HashSet<generational_arena::Index>,
+    metadata: HashSet<generational_arena::Index>,
+}
+
+impl ContextEntries {
+    fn of(context: &Context) -> Self {
+        fn indices<T>(arena: &generational_arena::Arena<T>) -> HashSet<generational_arena::Index> {
+            arena.iter().map(|(idx, _)| idx).collect()
+        }
+        ContextEntries {
+            modules: indices(&context.modules),
+            functions: indices(&context.functions),
+            blocks: indices(&context.blocks),
+            values: indices(&context.values),
+            pointers: indices(&context.pointers),
+            asm_blocks: indices(&context.asm_blocks),
+            metadata: indices(&context.metadata),
+        }
     }
+
+    // Remove every entry which has been added to `context` since these entries were gathered.
+    fn remove_new_entries(&self, context: &mut Context) {
+        fn retain<T>(
+            arena: &mut generational_arena::Arena<T>,
+            old: &HashSet<generational_arena::Index>,
+        ) {
+            arena.retain(|idx, _| old.contains(&idx));
+        }
+        retain(&mut context.modules, &self.modules);
+        retain(&mut context.functions, &self.functions);
+        retain(&mut context.blocks, &self.blocks);
+        retain(&mut context.values, &self.values);
+        retain(&mut context.pointers, &self.pointers);
+        retain(&mut context.asm_blocks, &self.asm_blocks);
+        retain(&mut context.metadata, &self.metadata);
+    }
+}
+
+fn compile_constant_evaluator(
+    context: &mut Context,
+    eval_module: Module,
+    const_expr: &TypedExpression,
+) -> Option<Function> {
+    let ret_type =
+        convert_resolved_typeid(context, &const_expr.return_type, &const_expr.span).ok()?;
+    let eval_fn = Function::new(
+        context,
+        eval_module,
+        "__const_eval".to_owned(),
+        Vec::new(),
+        ret_type,
+        None,
+        false,
+    );
+    let mut compiler = FnCompiler::new(context, eval_module, eval_fn);
+    let ret_val = compiler
+        .compile_expression(context, const_expr.clone())
+        .ok()?;
+    compiler
+        .compile_ret(context, ret_val, ret_type, None)
+        .ok()?;
+    Some(eval_fn)
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -160,12 +303,12 @@ fn compile_declarations(
     context: &mut Context,
     module: Module,
     declarations: Vec<TypedDeclaration>,
-) -> Result<(), String> {
+) -> Result<(), CompileError> {
     for declaration in declarations {
         match declaration {
             TypedDeclaration::ConstantDeclaration(decl) => {
                 // These are in the global scope for the module, so they can be added there.
-                let const_val = compile_constant_expression(context, &decl.value)?;
+                let const_val = compile_constant_expression(context, module, &decl.value)?;
                 module.add_global_constant(context, decl.name.as_str().to_owned(), const_val);
             }
 
@@ -288,6 +431,7 @@ fn compile_fn_with_args(
     let TypedFunctionDeclaration {
         name,
         body,
+        parameters,
         return_type,
         return_type_span,
         visibility,
@@ -312,6 +456,21 @@ fn compile_fn_with_args(
     // We clone the struct symbols here, as they contain the globals; any new local declarations
     // may remain within the function scope.
     let mut compiler = FnCompiler::new(context, module, func);
+    if selector.is_some() {
+        if let TypeInfo::Vec(elem_type) = look_up_type_id(return_type) {
+            compiler.returned_vec_elem_type = Some((elem_type, return_type_span));
+        }
+        for param in &parameters {
+            if let TypeInfo::Vec(elem_type) = look_up_type_id(param.r#type) {
+                compiler.compile_vec_from_call_data(
+                    context,
+                    param.name.as_str(),
+                    elem_type,
+                    param.name.span().clone(),
+                )?;
+            }
+        }
+    }
 
     let mut ret_val = compiler.compile_code_block(context, body)?;
 
@@ -337,10 +496,7 @@ fn compile_fn_with_args(
         || compiler.current_block == compiler.function.get_entry_block(context)
         || compiler.current_block.num_predecessors(context) > 0
     {
-        compiler
-            .current_block
-            .ins(context)
-            .ret(ret_val, ret_type, None);
+        compiler.compile_ret(context, ret_val, ret_type, None)?;
     }
     Ok(func)
 }
@@ -406,6 +562,9 @@ struct FnCompiler {
     function: Function,
     current_block: Block,
     lexical_map: LexicalMap,
+    /// Set for ABI methods which return a `Vec`, which must be returned as its elements rather
+    /// than as a pointer into this contract's heap.
+    returned_vec_elem_type: Option<(TypeId, Span)>,
 }
 
 pub enum StateAccessType {
@@ -425,6 +584,7 @@ impl FnCompiler {
             function,
             current_block: function.get_entry_block(context),
             lexical_map,
+            returned_vec_elem_type: None,
         }
     }
 
@@ -527,14 +687,32 @@ impl FnCompiler {
                 selector,
             } => {
                 if let Some(metadata) = selector {
-                    self.compile_contract_call(
+                    let call_val = self.compile_contract_call(
                         &metadata,
                         &contract_call_params,
                         context,
                         name.suffix.as_str(),
                         arguments,
                         span_md_idx,
-                    )
+                    )?;
+                    // A returned vector is passed back as its elements, and must be copied onto
+                    // our heap.
+                    match look_up_type_id(ast_expr.return_type) {
+                        TypeInfo::Vec(elem_type) => self.compile_vec_asm_block(
+                            context,
+                            &vec_from_return_data_asm(),
+                            vec![],
+                            elem_type,
+                            Type::Uint(64),
+                            ast_expr.span,
+                            span_md_idx,
+                        ),
+                        _ => Ok(call_val),
+                    }
+                } else if let Some(simple_op) =
+                    match_simple_op(context, &arguments, &function_body, ast_expr.return_type)
+                {
+                    self.compile_simple_op(context, simple_op, arguments, span_md_idx)
                 } else {
                     self.compile_fn_call(
                         context,
@@ -567,6 +745,11 @@ impl FnCompiler {
                 then,
                 r#else,
             } => self.compile_if(context, *condition, *then, r#else),
+            TypedExpressionVariant::IntegerSwitch {
+                scrutinee,
+                cases,
+                default,
+            } => self.compile_integer_switch(context, *scrutinee, cases, *default),
             TypedExpressionVariant::IfLet {
                 enum_type,
                 expr,
@@ -663,6 +846,29 @@ impl FnCompiler {
                     }
                 }
             }
+            TypedExpressionVariant::VecIntrinsic { kind, elem_type } => self.compile_vec_intrinsic(
+                context,
+                kind,
+                elem_type,
+                ast_expr.return_type,
+                ast_expr.span,
+                span_md_idx,
+            ),
+            TypedExpressionVariant::IntIntrinsic {
+                op,
+                lhs,
+                rhs,
+                int_type,
+            } => self.compile_int_intrinsic(
+                context,
+                op,
+                *lhs,
+                *rhs,
+                int_type,
+                ast_expr.return_type,
+                ast_expr.span,
+                span_md_idx,
+            ),
         }
     }
 
@@ -678,9 +884,7 @@ impl FnCompiler {
         match ret_value.get_type(context) {
             None => Err("Unable to determine type for return statement expression.".into()),
             Some(ret_ty) => {
-                self.current_block
-                    .ins(context)
-                    .ret(ret_value, ret_ty, span_md_idx);
+                self.compile_ret(context, ret_value, ret_ty, span_md_idx)?;
                 // RET is a terminator so we must create a new block here.  If anything is added to
                 // it then it'll almost certainly be dead code.
                 self.current_block = self.function.create_block(context, None);
@@ -691,6 +895,35 @@ impl FnCompiler {
 
     // ---------------------------------------------------------------------------------------------
 
+    fn compile_ret(
+        &mut self,
+        context: &mut Context,
+        ret_value: Value,
+        ret_ty: Type,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<(), String> {
+        // A returned vector is copied out with RETD, which terminates the call before the RET.
+        // An implicit return has no span of its own so the return type's is used instead.
+        if let Some((elem_type, span)) = self.returned_vec_elem_type.clone() {
+            let vec_span_md_idx = span_md_idx.or_else(|| MetadataIndex::from_span(context, &span));
+            self.compile_vec_asm_block(
+                context,
+                &vec_return_asm(),
+                vec![ret_value],
+                elem_type,
+                Type::Unit,
+                span,
+                vec_span_md_idx,
+            )?;
+        }
+        self.current_block
+            .ins(context)
+            .ret(ret_value, ret_ty, span_md_idx);
+        Ok(())
+    }
+
+    // ---------------------------------------------------------------------------------------------
+
     fn compile_lazy_op(
         &mut self,
         context: &mut Context,
@@ -746,7 +979,7 @@ impl FnCompiler {
         // Compile each user argument
         let compiled_args = ast_args
             .into_iter()
-            .map(|(_, expr)| self.compile_expression(context, expr))
+            .map(|(_, expr)| self.compile_expression(context, contract_call_argument(expr)))
             .collect::<Result<Vec<Value>, String>>()?;
 
         // New struct type to hold the user arguments
@@ -889,6 +1122,41 @@ impl FnCompiler {
 
     // ---------------------------------------------------------------------------------------------
 
+    fn compile_simple_op(
+        &mut self,
+        context: &mut Context,
+        simple_op: SimpleOp,
+        ast_args: Vec<(Ident, TypedExpression)>,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        // All the arguments are still evaluated, in order, even though the operation itself
+        // can't have any side effects.
+        let mut arg_vals = Vec::with_capacity(ast_args.len());
+        for (_, arg_expr) in ast_args {
+            arg_vals.push(self.compile_expression(context, arg_expr)?);
+        }
+        Ok(match simple_op {
+            SimpleOp::Binary(op, lhs_idx, rhs_idx) => self.current_block.ins(context).binary_op(
+                op,
+                arg_vals[lhs_idx],
+                arg_vals[rhs_idx],
+                span_md_idx,
+            ),
+            SimpleOp::Cmp(pred, lhs_idx, rhs_idx) => self.current_block.ins(context).cmp(
+                pred,
+                arg_vals[lhs_idx],
+                arg_vals[rhs_idx],
+                span_md_idx,
+            ),
+            SimpleOp::Not(val_idx) => self
+                .current_block
+                .ins(context)
+                .not(arg_vals[val_idx], span_md_idx),
+        })
+    }
+
+    // ---------------------------------------------------------------------------------------------
+
     fn compile_fn_call(
         &mut self,
         context: &mut Context,
@@ -1053,6 +1321,269 @@ impl FnCompiler {
 
     // ---------------------------------------------------------------------------------------------
 
+    fn compile_integer_switch(
+        &mut self,
+        context: &mut Context,
+        ast_scrutinee: TypedExpression,
+        ast_cases: Vec<(u64, TypedExpression)>,
+        ast_default: TypedExpression,
+    ) -> Result<Value, String> {
+        // Compile the scrutinee in the entry block and then each of the arms in turn, like the
+        // branches in compile_if().  Only once the arm blocks exist can we build the search for
+        // the right one, which goes between the entry block and the first arm.
+        let scrutinee_span_md_idx = MetadataIndex::from_span(context, &ast_scrutinee.span);
+        let scrutinee_value = self.compile_expression(context, ast_scrutinee)?;
+
+        if let Some(table) = integer_switch_table(&ast_cases, &ast_default) {
+            let result_type =
+                convert_resolved_typeid(context, &ast_default.return_type, &ast_default.span)?;
+            if matches!(result_type, Type::Bool | Type::Uint(_)) {
+                return self.compile_switch_table(
+                    context,
+                    scrutinee_value,
+                    &table,
+                    result_type,
+                    ast_default,
+                    scrutinee_span_md_idx,
+                );
+            }
+        }
+
+        let entry_block = self.current_block;
+
+        let mut case_blocks = Vec::with_capacity(ast_cases.len());
+        let mut arm_ends = Vec::with_capacity(ast_cases.len() + 1);
+        for (case_value, ast_body) in ast_cases {
+            let arm_block_begin = self.function.create_block(context, None);
+            self.current_block = arm_block_begin;
+            let arm_value = self.compile_expression(context, ast_body)?;
+            case_blocks.push((case_value, arm_block_begin));
+            arm_ends.push((self.current_block, arm_value));
+        }
+
+        let default_block_begin = self.function.create_block(context, None);
+        self.current_block = default_block_begin;
+        let default_value = self.compile_expression(context, ast_default)?;
+        arm_ends.push((self.current_block, default_value));
+
+        let first_arm_block = case_blocks
+            .first()
+            .map(|(_, block)| *block)
+            .unwrap_or(default_block_begin);
+        self.compile_switch_search(
+            context,
+            entry_block,
+            first_arm_block,
+            scrutinee_value,
+            &case_blocks,
+            default_block_begin,
+            scrutinee_span_md_idx,
+        )?;
+
+        let merge_block = self.function.create_block(context, None);
+        for (arm_block_end, arm_value) in arm_ends {
+            arm_block_end
+                .ins(context)
+                .branch(merge_block, Some(arm_value), None);
+        }
+
+        self.current_block = merge_block;
+        Ok(merge_block.get_phi(context))
+    }
+
+    // Look up the result for the scrutinee in a constant array of the `table` results, or use
+    // the default arm if it's outside the table.
+    fn compile_switch_table(
+        &mut self,
+        context: &mut Context,
+        scrutinee_value: Value,
+        table: &IntegerSwitchTable,
+        result_type: Type,
+        ast_default: TypedExpression,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        let default_block = self.function.create_block(context, None);
+
+        let mut block = self.current_block;
+        let index_value = if table.first_value == 0 {
+            scrutinee_value
+        } else {
+            let first_value = Constant::get_uint(context, 64, table.first_value, None);
+            let cond_value = block.ins(context).cmp(
+                Predicate::LessThan,
+                scrutinee_value,
+                first_value,
+                span_md_idx,
+            );
+            let above_first_block = self
+                .function
+                .create_block_before(context, &default_block, None)
+                .map_err(|ir_error| ir_error.to_string())?;
+            block.ins(context).conditional_branch(
+                cond_value,
+                default_block,
+                above_first_block,
+                None,
+                span_md_idx,
+            );
+            block = above_first_block;
+            let first_value = Constant::get_uint(context, 64, table.first_value, None);
+            block.ins(context).binary_op(
+                BinaryOpKind::Sub,
+                scrutinee_value,
+                first_value,
+                span_md_idx,
+            )
+        };
+
+        let len = table.results.len() as u64;
+        let len_value = Constant::get_uint(context, 64, len, None);
+        let cond_value =
+            block
+                .ins(context)
+                .cmp(Predicate::LessThan, index_value, len_value, span_md_idx);
+        let lookup_block = self
+            .function
+            .create_block_before(context, &default_block, None)
+            .map_err(|ir_error| ir_error.to_string())?;
+        block.ins(context).conditional_branch(
+            cond_value,
+            lookup_block,
+            default_block,
+            None,
+            span_md_idx,
+        );
+
+        let results = table
+            .results
+            .iter()
+            .map(|result| match result_type {
+                Type::Bool => Constant::new_bool(*result != 0),
+                Type::Uint(nbits) => Constant::new_uint(nbits, *result),
+                _ => unreachable!("Switch tables only hold integers and bools."),
+            })
+            .collect();
+        let table_aggregate = Aggregate::new_array(context, result_type, len);
+        let table_value = Constant::get_array(
+            context,
+            Constant::new_array(&table_aggregate, results),
+            None,
+        );
+        let lookup_value = lookup_block.ins(context).extract_element(
+            table_value,
+            table_aggregate,
+            index_value,
+            span_md_idx,
+        );
+
+        self.current_block = default_block;
+        let default_value = self.compile_expression(context, ast_default)?;
+        let default_block_end = self.current_block;
+
+        let merge_block = self.function.create_block(context, None);
+        lookup_block
+            .ins(context)
+            .branch(merge_block, Some(lookup_value), None);
+        default_block_end
+            .ins(context)
+            .branch(merge_block, Some(default_value), None);
+
+        self.current_block = merge_block;
+        Ok(merge_block.get_phi(context))
+    }
+
+    // Branch from `block` to whichever of the sorted `cases` matches the scrutinee, or to the
+    // default block.  Large sets of cases are halved with an unsigned less-than until only a few
+    // remain, which are then compared one at a time.  Any new blocks are placed before
+    // `arms_begin`.
+    #[allow(clippy::too_many_arguments)]
+    fn compile_switch_search(
+        &mut self,
+        context: &mut Context,
+        block: Block,
+        arms_begin: Block,
+        scrutinee_value: Value,
+        cases: &[(u64, Block)],
+        default_block: Block,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<(), String> {
+        const MAX_LINEAR_CASES: usize = 3;
+
+        if cases.len() > MAX_LINEAR_CASES {
+            let (lower_cases, upper_cases) = cases.split_at(cases.len() / 2);
+            let pivot_value = Constant::get_uint(context, 64, upper_cases[0].0, None);
+            let cond_value = block.ins(context).cmp(
+                Predicate::LessThan,
+                scrutinee_value,
+                pivot_value,
+                span_md_idx,
+            );
+            let lower_block = self
+                .function
+                .create_block_before(context, &arms_begin, None)
+                .map_err(|ir_error| ir_error.to_string())?;
+            let upper_block = self
+                .function
+                .create_block_before(context, &arms_begin, None)
+                .map_err(|ir_error| ir_error.to_string())?;
+            block.ins(context).conditional_branch(
+                cond_value,
+                lower_block,
+                upper_block,
+                None,
+                span_md_idx,
+            );
+            self.compile_switch_search(
+                context,
+                lower_block,
+                arms_begin,
+                scrutinee_value,
+                lower_cases,
+                default_block,
+                span_md_idx,
+            )?;
+            return self.compile_switch_search(
+                context,
+                upper_block,
+                arms_begin,
+                scrutinee_value,
+                upper_cases,
+                default_block,
+                span_md_idx,
+            );
+        }
+
+        let mut block = block;
+        for (idx, (case_value, case_block)) in cases.iter().enumerate() {
+            let case_value = Constant::get_uint(context, 64, *case_value, None);
+            let cond_value =
+                block
+                    .ins(context)
+                    .cmp(Predicate::Equal, scrutinee_value, case_value, span_md_idx);
+            let next_block = if idx + 1 == cases.len() {
+                default_block
+            } else {
+                self.function
+                    .create_block_before(context, &arms_begin, None)
+                    .map_err(|ir_error| ir_error.to_string())?
+            };
+            block.ins(context).conditional_branch(
+                cond_value,
+                *case_block,
+                next_block,
+                None,
+                span_md_idx,
+            );
+            block = next_block;
+        }
+        if cases.is_empty() {
+            block.ins(context).branch(default_block, None, None);
+        }
+        Ok(())
+    }
+
+    // ---------------------------------------------------------------------------------------------
+
     #[allow(clippy::too_many_arguments)]
     fn compile_if_let(
         &mut self,
@@ -1307,7 +1838,7 @@ impl FnCompiler {
         let TypedConstantDeclaration { name, value, .. } = ast_const_decl;
 
         if let TypedExpressionVariant::Literal(literal) = &value.expression {
-            let initialiser = convert_literal_to_constant(literal);
+            let initialiser = convert_literal_to_constant(context, literal);
             let return_type = convert_resolved_typeid(context, &value.return_type, &value.span)?;
             let name = name.as_str().to_owned();
             self.function
@@ -1809,6 +2340,358 @@ impl FnCompiler {
         ))
     }
 
+    // ---------------------------------------------------------------------------------------------
+
+    fn compile_vec_intrinsic(
+        &mut self,
+        context: &mut Context,
+        kind: VecIntrinsicKind,
+        elem_type: TypeId,
+        return_type: TypeId,
+        span: Span,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        // The instruction sequences are shared with the AST code generator.  Each is compiled to
+        // an asm block, with any bounds check in its own block followed by a branch to a revert,
+        // and any space check followed by a branch to the reallocation.
+        let vec_asm = vec_intrinsic_asm(&kind, look_up_type_id(elem_type).is_copy_type());
+        let arg_values = kind
+            .arguments()
+            .into_iter()
+            .map(|arg| self.compile_expression(context, arg.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let return_type = convert_resolved_typeid(context, &return_type, &span)?;
+        self.compile_vec_asm_block(
+            context,
+            &vec_asm,
+            arg_values,
+            elem_type,
+            return_type,
+            span,
+            span_md_idx,
+        )
+    }
+
+    /// Rebuild the vector passed to a contract method as the argument `name`, on our heap, and
+    /// shadow the argument with it.
+    fn compile_vec_from_call_data(
+        &mut self,
+        context: &mut Context,
+        name: &str,
+        elem_type: TypeId,
+        span: Span,
+    ) -> Result<(), String> {
+        let span_md_idx = MetadataIndex::from_span(context, &span);
+        let call_data = self
+            .function
+            .get_arg(context, name)
+            .ok_or_else(|| format!("Unable to resolve argument '{}'.", name))?;
+        let vec_val = self.compile_vec_asm_block(
+            context,
+            &vec_from_call_data_asm(),
+            vec![call_data],
+            elem_type,
+            Type::Uint(64),
+            span,
+            span_md_idx,
+        )?;
+        let local_name = self.lexical_map.insert(name.to_owned());
+        let ptr = self
+            .function
+            .new_local_ptr(context, local_name, Type::Uint(64), false, None)
+            .map_err(|ir_error| ir_error.to_string())?;
+        let ptr_val = self
+            .current_block
+            .ins(context)
+            .get_ptr(ptr, Type::Uint(64), 0, span_md_idx);
+        self.current_block
+            .ins(context)
+            .store(ptr_val, vec_val, span_md_idx);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn compile_vec_asm_block(
+        &mut self,
+        context: &mut Context,
+        vec_asm: &VecIntrinsicAsm,
+        arg_values: Vec<Value>,
+        elem_type: TypeId,
+        return_type: Type,
+        span: Span,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        let ir_elem_type = convert_resolved_typeid(context, &elem_type, &span)?;
+        let elem_size_in_bytes = ir_type_size_in_bytes(context, &ir_elem_type).max(8);
+
+        let ident = |name: &'static str| Ident::new_with_override(name, span.clone());
+        let asm_args = |context: &mut Context| {
+            let mut args = vec_asm
+                .arg_names
+                .iter()
+                .zip(arg_values.iter())
+                .map(|(name, value)| AsmArg {
+                    name: ident(name),
+                    initializer: Some(asm_arg_initializer(context, *value)),
+                })
+                .collect::<Vec<_>>();
+            args.push(AsmArg {
+                name: ident(VEC_ELEM_SIZE_REG),
+                initializer: Some(Constant::get_uint(context, 64, elem_size_in_bytes, None)),
+            });
+            args.extend(vec_asm.scratch_names().into_iter().map(|name| AsmArg {
+                name: ident(name),
+                initializer: None,
+            }));
+            args
+        };
+
+        let to_instructions = |ops: &[NamedAsmOp]| named_asm_instructions(ops, &span, span_md_idx);
+
+        if !vec_asm.bounds_check.is_empty() {
+            let args = asm_args(context);
+            let in_bounds = self.current_block.ins(context).asm_block(
+                args,
+                to_instructions(&vec_asm.bounds_check),
+                Type::Bool,
+                Some(ident(VEC_IN_BOUNDS_REG)),
+                span_md_idx,
+            );
+            self.compile_revert_unless(
+                context,
+                in_bounds,
+                crate::constants::INDEX_OUT_OF_BOUNDS_REVERT_CODE,
+                &span,
+                span_md_idx,
+            );
+        }
+
+        if !vec_asm.space_check.is_empty() {
+            let args = asm_args(context);
+            let has_space = self.current_block.ins(context).asm_block(
+                args,
+                to_instructions(&vec_asm.space_check),
+                Type::Bool,
+                Some(ident(VEC_HAS_SPACE_REG)),
+                span_md_idx,
+            );
+            let grow_block = self.function.create_block(context, None);
+            let body_block = self.function.create_block(context, None);
+            self.current_block.ins(context).conditional_branch(
+                has_space,
+                body_block,
+                grow_block,
+                None,
+                span_md_idx,
+            );
+            let args = asm_args(context);
+            grow_block.ins(context).asm_block(
+                args,
+                to_instructions(&vec_asm.grow),
+                Type::Unit,
+                None,
+                span_md_idx,
+            );
+            grow_block.ins(context).branch(body_block, None, None);
+            self.current_block = body_block;
+        }
+
+        let args = asm_args(context);
+        Ok(self.current_block.ins(context).asm_block(
+            args,
+            to_instructions(&vec_asm.body),
+            return_type,
+            vec_asm.return_name.map(ident),
+            span_md_idx,
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn compile_int_intrinsic(
+        &mut self,
+        context: &mut Context,
+        op: IntIntrinsicOp,
+        lhs: TypedExpression,
+        rhs: TypedExpression,
+        int_type: TypeId,
+        return_type: TypeId,
+        span: Span,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Result<Value, String> {
+        // The instruction sequences are shared with the AST code generator.  Registers aren't
+        // preserved between asm blocks so each is given the operands, and the state once it has
+        // been set up.
+        let int_asm = int_intrinsic_asm(op, &look_up_type_id(int_type));
+        let lhs = self.compile_expression(context, lhs)?;
+        let rhs = self.compile_expression(context, rhs)?;
+        let return_type = convert_resolved_typeid(context, &return_type, &span)?;
+
+        let ident = |name: &'static str| Ident::new_with_override(name, span.clone());
+        let asm_args = |context: &mut Context, ops: &[NamedAsmOp], state: Option<Value>| {
+            let mut args = int_asm
+                .arg_names
+                .iter()
+                .zip([lhs, rhs])
+                .map(|(name, value)| AsmArg {
+                    name: ident(name),
+                    initializer: Some(asm_arg_initializer(context, value)),
+                })
+                .collect::<Vec<_>>();
+            if int_asm.has_state() {
+                args.push(AsmArg {
+                    name: ident(INT_STATE_REG),
+                    initializer: state,
+                });
+            }
+            args.extend(int_asm.scratch_names(ops).into_iter().map(|name| AsmArg {
+                name: ident(name),
+                initializer: None,
+            }));
+            args
+        };
+        let compile_asm_block =
+            |this: &mut Self,
+             context: &mut Context,
+             ops: &[NamedAsmOp],
+             state: Option<Value>,
+             return_type: Type,
+             return_name: Option<&'static str>| {
+                let args = asm_args(context, ops, state);
+                this.current_block.ins(context).asm_block(
+                    args,
+                    named_asm_instructions(ops, &span, span_md_idx),
+                    return_type,
+                    return_name.map(ident),
+                    span_md_idx,
+                )
+            };
+
+        if !int_asm.divisor_check.is_empty() {
+            let valid = compile_asm_block(
+                self,
+                context,
+                &int_asm.divisor_check,
+                None,
+                Type::Bool,
+                Some(INT_VALID_REG),
+            );
+            self.compile_revert_unless(
+                context,
+                valid,
+                constants::DIVIDE_BY_ZERO_REVERT_CODE,
+                &span,
+                span_md_idx,
+            );
+        }
+        let state = int_asm.has_state().then(|| {
+            compile_asm_block(
+                self,
+                context,
+                &int_asm.setup,
+                None,
+                Type::Uint(64),
+                Some(INT_STATE_REG),
+            )
+        });
+        if !int_asm.loop_body.is_empty() {
+            let check_block = self.function.create_block(context, None);
+            let body_block = self.function.create_block(context, None);
+            let exit_block = self.function.create_block(context, None);
+            self.current_block
+                .ins(context)
+                .branch(check_block, None, span_md_idx);
+
+            self.current_block = check_block;
+            let more = compile_asm_block(
+                self,
+                context,
+                &int_asm.loop_check,
+                state,
+                Type::Bool,
+                Some(INT_LOOP_REG),
+            );
+            self.current_block.ins(context).conditional_branch(
+                more,
+                body_block,
+                exit_block,
+                None,
+                span_md_idx,
+            );
+
+            self.current_block = body_block;
+            compile_asm_block(self, context, &int_asm.loop_body, state, Type::Unit, None);
+            self.current_block
+                .ins(context)
+                .branch(check_block, None, span_md_idx);
+
+            self.current_block = exit_block;
+        }
+        if !int_asm.overflow_check.is_empty() {
+            let valid = compile_asm_block(
+                self,
+                context,
+                &int_asm.overflow_check,
+                state,
+                Type::Bool,
+                Some(INT_VALID_REG),
+            );
+            self.compile_revert_unless(
+                context,
+                valid,
+                constants::INTEGER_OVERFLOW_REVERT_CODE,
+                &span,
+                span_md_idx,
+            );
+        }
+        Ok(compile_asm_block(
+            self,
+            context,
+            &int_asm.body,
+            state,
+            return_type,
+            Some(int_asm.return_name),
+        ))
+    }
+
+    // Revert with `revert_code` unless `flag` is set, and continue in a new block.
+    fn compile_revert_unless(
+        &mut self,
+        context: &mut Context,
+        flag: Value,
+        revert_code: u64,
+        span: &Span,
+        span_md_idx: Option<MetadataIndex>,
+    ) {
+        let ident = |name: &'static str| Ident::new_with_override(name, span.clone());
+        let revert_block = self.function.create_block(context, None);
+        let body_block = self.function.create_block(context, None);
+        self.current_block.ins(context).conditional_branch(
+            flag,
+            body_block,
+            revert_block,
+            None,
+            span_md_idx,
+        );
+        let revert_code = Constant::get_uint(context, 64, revert_code, None);
+        revert_block.ins(context).asm_block(
+            vec![AsmArg {
+                name: ident("code"),
+                initializer: Some(revert_code),
+            }],
+            vec![AsmInstruction {
+                name: ident("rvrt"),
+                args: vec![ident("code")],
+                immediate: None,
+                span_md_idx,
+            }],
+            Type::Unit,
+            None,
+            span_md_idx,
+        );
+        revert_block.ins(context).branch(body_block, None, None);
+        self.current_block = body_block;
+    }
+
     // -------------------------------------------------------------------------------------------------
     // Utils
 
@@ -2074,6 +2957,180 @@ impl LexicalMap {
     }
 }
 
+// -------------------------------------------------------------------------------------------------
+// Recognise the core library operators which may be compiled to native IR instructions.
+
+/// A native IR operation equivalent to a call to a function whose body is a single ASM
+/// instruction.  The indices refer to the arguments of the call.
+enum SimpleOp {
+    Binary(BinaryOpKind, usize, usize),
+    Cmp(Predicate, usize, usize),
+    Not(usize),
+}
+
+/// The arithmetic, bitwise and comparison operators for the built-in integer types are
+/// implemented in the core library as a single ASM instruction applied to the function
+/// parameters, e.g., `asm(r1: self, r2: other, r3) { add r3 r1 r2; r3: u64 }`.  Recognise these
+/// function bodies so they may be compiled to native IR instructions rather than opaque ASM
+/// blocks.
+fn match_simple_op(
+    context: &mut Context,
+    ast_args: &[(Ident, TypedExpression)],
+    callee_body: &TypedCodeBlock,
+    return_type: TypeId,
+) -> Option<SimpleOp> {
+    let (registers, op, returns) = match callee_body.contents.as_slice() {
+        [TypedAstNode {
+            content:
+                TypedAstNodeContent::ImplicitReturnExpression(TypedExpression {
+                    expression:
+                        TypedExpressionVariant::AsmExpression {
+                            registers,
+                            body,
+                            returns: Some((returns, _)),
+                            ..
+                        },
+                    ..
+                }),
+            ..
+        }] if body.len() == 1 && body[0].immediate.is_none() => (registers, &body[0], returns),
+        _otherwise => return None,
+    };
+
+    // Each of the instruction arguments must be either the returned register, a register
+    // initialised directly with a parameter, or the `zero` register.
+    enum OpArg {
+        Ret,
+        Param(usize),
+        Zero,
+    }
+    let op_args = op
+        .op_args
+        .iter()
+        .map(|op_arg| {
+            if op_arg.as_str() == returns.name {
+                return Some(OpArg::Ret);
+            }
+            if op_arg.as_str() == "zero" {
+                return Some(OpArg::Zero);
+            }
+            let reg = registers
+                .iter()
+                .find(|reg| reg.name.as_str() == op_arg.as_str())?;
+            match &reg.initializer {
+                Some(TypedExpression {
+                    expression: TypedExpressionVariant::VariableExpression { name },
+                    ..
+                }) => ast_args
+                    .iter()
+                    .position(|(param_name, _)| param_name.as_str() == name.as_str())
+                    .map(OpArg::Param),
+                _otherwise => None,
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    // The returned register must be a fresh one, not initialised to some other value.
+    if registers
+        .iter()
+        .any(|reg| reg.name.as_str() == returns.name && reg.initializer.is_some())
+    {
+        return None;
+    }
+
+    let mut ir_type = |ty: TypeId| convert_resolved_typeid_no_span(context, &ty).ok();
+    let ret_ty = ir_type(return_type)?;
+    let arg_tys = ast_args
+        .iter()
+        .map(|(_, arg)| ir_type(arg.return_type))
+        .collect::<Option<Vec<_>>>()?;
+    let is_int = |idx: &usize, ty: &Type| match (&arg_tys[*idx], ty) {
+        (Type::Uint(arg_nbits), Type::Uint(nbits)) => arg_nbits == nbits,
+        _otherwise => false,
+    };
+
+    match (op.op_name.as_str(), op_args.as_slice()) {
+        (
+            "add" | "sub" | "mul" | "div" | "mod" | "and" | "or" | "xor" | "sll" | "srl",
+            [OpArg::Ret, OpArg::Param(lhs_idx), OpArg::Param(rhs_idx)],
+        ) if is_int(lhs_idx, &ret_ty) && is_int(rhs_idx, &ret_ty) => {
+            let op = match op.op_name.as_str() {
+                "add" => BinaryOpKind::Add,
+                "sub" => BinaryOpKind::Sub,
+                "mul" => BinaryOpKind::Mul,
+                "div" => BinaryOpKind::Div,
+                "mod" => BinaryOpKind::Mod,
+                "and" => BinaryOpKind::And,
+                "or" => BinaryOpKind::Or,
+                "xor" => BinaryOpKind::Xor,
+                "sll" => BinaryOpKind::Shl,
+                "srl" => BinaryOpKind::Shr,
+                _ => unreachable!("op name was matched above"),
+            };
+            Some(SimpleOp::Binary(op, *lhs_idx, *rhs_idx))
+        }
+        ("eq" | "lt" | "gt", [OpArg::Ret, OpArg::Param(lhs_idx), OpArg::Param(rhs_idx)])
+            if matches!(ret_ty, Type::Bool) && is_int(rhs_idx, &arg_tys[*lhs_idx]) =>
+        {
+            let pred = match op.op_name.as_str() {
+                "eq" => Predicate::Equal,
+                "lt" => Predicate::LessThan,
+                "gt" => Predicate::GreaterThan,
+                _ => unreachable!("op name was matched above"),
+            };
+            Some(SimpleOp::Cmp(pred, *lhs_idx, *rhs_idx))
+        }
+        ("not", [OpArg::Ret, OpArg::Param(val_idx)])
+            if matches!(ret_ty, Type::Uint(64)) && is_int(val_idx, &ret_ty) =>
+        {
+            Some(SimpleOp::Not(*val_idx))
+        }
+        (
+            "eq",
+            [OpArg::Ret, OpArg::Param(val_idx), OpArg::Zero]
+            | [OpArg::Ret, OpArg::Zero, OpArg::Param(val_idx)],
+        ) if matches!((&ret_ty, &arg_tys[*val_idx]), (Type::Bool, Type::Bool)) => {
+            Some(SimpleOp::Not(*val_idx))
+        }
+        _otherwise => None,
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+// Convert the instruction sequences shared with the AST code generator to asm block instructions.
+
+// Each asm block is given its own copy of any constant argument, as constants are printed where
+// they're used and so mustn't be shared between blocks.
+fn asm_arg_initializer(context: &mut Context, value: Value) -> Value {
+    let ValueContent {
+        value: datum,
+        span_md_idx,
+    } = &context.values[value.0];
+    match datum {
+        ValueDatum::Constant(constant) => {
+            let (constant, span_md_idx) = (constant.clone(), *span_md_idx);
+            Value::new_constant(context, constant, span_md_idx)
+        }
+        _otherwise => value,
+    }
+}
+
+fn named_asm_instructions(
+    ops: &[NamedAsmOp],
+    span: &Span,
+    span_md_idx: Option<MetadataIndex>,
+) -> Vec<AsmInstruction> {
+    let ident = |name: &'static str| Ident::new_with_override(name, span.clone());
+    ops.iter()
+        .map(|op| AsmInstruction {
+            name: ident(op.name),
+            args: op.args.iter().copied().map(ident).collect(),
+            immediate: op.immediate.map(ident),
+            span_md_idx,
+        })
+        .collect()
+}
+
 // -------------------------------------------------------------------------------------------------
 // Get the name of a struct and the index to a particular named field from a TypeId.
 
@@ -2170,13 +3227,25 @@ fn convert_literal_to_value(
         Literal::U32(n) => Constant::get_uint(context, 64, *n as u64, span_id_idx),
         Literal::U64(n) => Constant::get_uint(context, 64, *n, span_id_idx),
         Literal::Numeric(n) => Constant::get_uint(context, 64, *n, span_id_idx),
+        Literal::U128(_) | Literal::U256(_) => {
+            let constant = convert_literal_to_constant(context, ast_literal);
+            Constant::get_array(context, constant, span_id_idx)
+        }
+        // Signed integers are also `u64` to the IR, holding their sign extended two's complement
+        // representation.
+        Literal::I8(n) => Constant::get_uint(context, 64, *n as i64 as u64, span_id_idx),
+        Literal::I16(n) => Constant::get_uint(context, 64, *n as i64 as u64, span_id_idx),
+        Literal::I32(n) => Constant::get_uint(context, 64, *n as i64 as u64, span_id_idx),
+        Literal::I64(n) | Literal::NegativeNumeric(n) => {
+            Constant::get_uint(context, 64, *n as u64, span_id_idx)
+        }
         Literal::String(s) => Constant::get_string(context, s.as_str().to_owned(), span_id_idx),
         Literal::Boolean(b) => Constant::get_bool(context, *b, span_id_idx),
         Literal::B256(bs) => Constant::get_b256(context, *bs, span_id_idx),
     }
 }
 
-fn convert_literal_to_constant(ast_literal: &Literal) -> Constant {
+fn convert_literal_to_constant(context: &mut Context, ast_literal: &Literal) -> Constant {
     match ast_literal {
         // All integers are `u64`.  See comment above.
         Literal::U8(n) | Literal::Byte(n) => Constant::new_uint(64, *n as u64),
@@ -2184,12 +3253,29 @@ fn convert_literal_to_constant(ast_literal: &Literal) -> Constant {
         Literal::U32(n) => Constant::new_uint(64, *n as u64),
         Literal::U64(n) => Constant::new_uint(64, *n),
         Literal::Numeric(n) => Constant::new_uint(64, *n),
+        Literal::U128(n) => convert_wide_uint_to_constant(context, &n.to_be_bytes()),
+        Literal::U256(bs) => convert_wide_uint_to_constant(context, bs),
+        Literal::I8(n) => Constant::new_uint(64, *n as i64 as u64),
+        Literal::I16(n) => Constant::new_uint(64, *n as i64 as u64),
+        Literal::I32(n) => Constant::new_uint(64, *n as i64 as u64),
+        Literal::I64(n) | Literal::NegativeNumeric(n) => Constant::new_uint(64, *n as u64),
         Literal::String(s) => Constant::new_string(s.as_str().to_owned()),
         Literal::Boolean(b) => Constant::new_bool(*b),
         Literal::B256(bs) => Constant::new_b256(*bs),
     }
 }
 
+// Integers wider than a word are arrays of `u64` to the IR, most significant word first, which
+// matches their big-endian layout in memory.
+fn convert_wide_uint_to_constant(context: &mut Context, bytes: &[u8]) -> Constant {
+    let aggregate = Aggregate::new_array(context, Type::Uint(64), bytes.len() as u64 / 8);
+    let words = bytes
+        .chunks(8)
+        .map(|word| Constant::new_uint(64, u64::from_be_bytes(word.try_into().unwrap())))
+        .collect();
+    Constant::new_array(&aggregate, words)
+}
+
 fn convert_resolved_typeid(
     context: &mut Context,
     ast_type: &TypeId,
@@ -2217,8 +3303,15 @@ fn convert_resolved_typeid_no_span(
 
 fn convert_resolved_type(context: &mut Context, ast_type: &TypeInfo) -> Result<Type, String> {
     Ok(match ast_type {
-        // All integers are `u64`, see comment in convert_literal_to_value() above.
+        // All integers are `u64`, see comment in convert_literal_to_value() above, except for the
+        // wide integers which are arrays of `u64`.
+        TypeInfo::UnsignedInteger(bits) if bits.is_wide() => Type::Array(Aggregate::new_array(
+            context,
+            Type::Uint(64),
+            bits.size_in_words(),
+        )),
         TypeInfo::UnsignedInteger(_) => Type::Uint(64),
+        TypeInfo::SignedInteger(_) => Type::Uint(64),
         TypeInfo::Numeric => Type::Uint(64),
         TypeInfo::Boolean => Type::Bool,
         TypeInfo::Byte => Type::Uint(64), // XXX?
@@ -2240,6 +3333,8 @@ fn convert_resolved_type(context: &mut Context, ast_type: &TypeInfo) -> Result<T
             let elem_type = convert_resolved_typeid_no_span(context, elem_type_id)?;
             Type::Array(Aggregate::new_array(context, elem_type, *count as u64))
         }
+        // A vector is a pointer to its header on the heap.
+        TypeInfo::Vec(_) => Type::Uint(64),
         TypeInfo::Tuple(fields) => {
             if fields.is_empty() {
                 // XXX We've removed Unit from the core compiler, replaced with an empty Tuple.
@@ -2273,10 +3368,12 @@ mod tests {
 
     use crate::{
         control_flow_analysis::{ControlFlowGraph, Graph},
+        error::CompileError,
         parser::{Rule, SwayParser},
-        semantic_analysis::{TreeType, TypedParseTree},
+        semantic_analysis::{TreeType, TypedAstNodeContent, TypedParseTree},
     };
     use pest::Parser;
+    use sway_ir::{Context, Kind, Module};
 
     // -------------------------------------------------------------------------------------------------
 
@@ -2315,7 +3412,23 @@ mod tests {
         let expected = String::from_utf8_lossy(&expected_bytes);
 
         let typed_ast = parse_to_typed_ast(sw_path, &input);
-        let ir = super::compile_ast(typed_ast).unwrap();
+        let mut ir = super::compile_ast(typed_ast).unwrap();
+
+        // A test may run some passes over the IR first, listed in a leading `// passes:` comment.
+        if let Some(pass_names) = input.lines().next().unwrap().strip_prefix("// passes: ") {
+            let functions = ir
+                .functions
+                .iter()
+                .map(|(idx, _)| sway_ir::function::Function(idx))
+                .collect::<Vec<_>>();
+            sway_ir::PassManager::default()
+                .run(
+                    &mut ir,
+                    &pass_names.split_whitespace().collect::<Vec<_>>(),
+                    &functions,
+                )
+                .unwrap();
+        }
         let output = sway_ir::printer::to_string(&ir);
 
         // Use a tricky regex to replace the local path in the metadata with something generic.  It
@@ -2385,6 +3498,44 @@ mod tests {
 
     // -------------------------------------------------------------------------------------------------
 
+    #[test]
+    fn constant_overflow() {
+        // A `const` may only be initialised with a literal, so evaluate the value returned by
+        // `main()` instead.
+        for (expr, overflows) in [
+            ("add(18446744073709551614, 1)", false),
+            ("add(18446744073709551615, 1)", true),
+        ] {
+            let input = format!(
+                "script;{}\nfn main() -> u64 {{\n    {}\n}}\n",
+                crate::test_utils::ASM_OPERATORS,
+                expr
+            );
+            let main_expr = match parse_to_typed_ast("main.sw".into(), &input) {
+                TypedParseTree::Script { main_function, .. } => {
+                    match main_function.body.contents.last().map(|node| &node.content) {
+                        Some(TypedAstNodeContent::ImplicitReturnExpression(expr)) => expr.clone(),
+                        _ => panic!("main() should end with an implicit return."),
+                    }
+                }
+                _ => unreachable!(),
+            };
+
+            let mut context = Context::default();
+            let module = Module::new(&mut context, Kind::Script);
+            let result = super::evaluate_constant_expression(&mut context, module, &main_expr);
+            assert_eq!(
+                matches!(result, Err(CompileError::ConstantOverflow { .. })),
+                overflows,
+                "{}",
+                expr
+            );
+            assert!(overflows || result.is_ok(), "{}", expr);
+        }
+    }
+
+    // -------------------------------------------------------------------------------------------------
+
     fn parse_to_typed_ast(path: PathBuf, input: &str) -> TypedParseTree {
         let mut parsed =
             SwayParser::parse(Rule::program, std::sync::Arc::from(input)).expect("parse_tree");
@@ -2415,6 +3566,11 @@ mod tests {
             print_intermediate_asm: false,
             print_finalized_asm: false,
             print_ir: false,
+            skip_bounds_checks: false,
+            optimization_level: sway_ir::OptLevel::O1,
+            hot_abi_methods: Vec::new(),
+            size_report: false,
+            contract_size_limit: None,
             generated_names: Default::default(),
         };
 
@@ -2429,10 +3585,11 @@ mod tests {
             entry_points: vec![],
             namespace: Default::default(),
         };
+        let namespace = crate::test_utils::root_module();
         TypedParseTree::type_check(
             parse_tree.tree,
-            crate::create_module(),
-            crate::create_module(),
+            namespace,
+            namespace,
             &program_type,
             &build_config,
             &mut dead_code_graph,
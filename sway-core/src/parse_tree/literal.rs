@@ -1,6 +1,10 @@
 use crate::{
-    build_config::BuildConfig, error::*, parser::Rule, type_engine::IntegerBits,
-    types::ResolvedType, CompileError, TypeInfo,
+    build_config::BuildConfig,
+    error::*,
+    parser::Rule,
+    type_engine::{IntegerBits, SignedIntegerBits},
+    types::ResolvedType,
+    CompileError, TypeInfo,
 };
 
 use sway_types::span;
@@ -21,8 +25,17 @@ pub enum Literal {
     U16(u16),
     U32(u32),
     U64(u64),
+    U128(u128),
+    /// Big-endian bytes, the same representation as a `b256`.
+    U256([u8; 32]),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
     String(span::Span),
     Numeric(u64),
+    /// An untyped negative integer, which may only ever resolve to a signed integer type.
+    NegativeNumeric(i64),
     Boolean(bool),
     Byte(u8),
     B256([u8; 32]),
@@ -37,7 +50,14 @@ impl Literal {
             U16(_) => ResolvedType::UnsignedInteger(IntegerBits::Sixteen),
             U32(_) => ResolvedType::UnsignedInteger(IntegerBits::ThirtyTwo),
             U64(_) => ResolvedType::UnsignedInteger(IntegerBits::SixtyFour),
+            U128(_) => ResolvedType::UnsignedInteger(IntegerBits::OneTwentyEight),
+            U256(_) => ResolvedType::UnsignedInteger(IntegerBits::TwoFiftySix),
+            I8(_) => ResolvedType::SignedInteger(SignedIntegerBits::Eight),
+            I16(_) => ResolvedType::SignedInteger(SignedIntegerBits::Sixteen),
+            I32(_) => ResolvedType::SignedInteger(SignedIntegerBits::ThirtyTwo),
+            I64(_) => ResolvedType::SignedInteger(SignedIntegerBits::SixtyFour),
             Numeric(_) => ResolvedType::UnsignedInteger(IntegerBits::SixtyFour),
+            NegativeNumeric(_) => ResolvedType::SignedInteger(SignedIntegerBits::SixtyFour),
             String(inner) => ResolvedType::Str(inner.as_str().len() as u64),
             Boolean(_) => ResolvedType::Boolean,
             Byte(_) => ResolvedType::Byte,
@@ -74,6 +94,29 @@ impl Literal {
                     span,
                 )
             }
+            Rule::negative_integer => {
+                let span = span::Span {
+                    span: lit_inner.as_span(),
+                    path: path.clone(),
+                };
+                (
+                    lit_inner
+                        .as_str()
+                        .trim()
+                        .replace('_', "")
+                        .parse()
+                        .map(Literal::NegativeNumeric)
+                        .map_err(|e| {
+                            Literal::handle_parse_int_error(
+                                e,
+                                TypeInfo::SignedInteger(SignedIntegerBits::SixtyFour),
+                                lit_inner.as_span(),
+                                path.clone(),
+                            )
+                        }),
+                    span,
+                )
+            }
             Rule::typed_integer => {
                 let mut int_inner = lit_inner.into_inner().next().unwrap();
                 let rule = int_inner.as_rule();
@@ -142,6 +185,88 @@ impl Literal {
                                     path.clone(),
                                 )
                             }),
+                        Rule::u128_integer => int_inner
+                            .as_str()
+                            .trim()
+                            .replace('_', "")
+                            .parse()
+                            .map(Literal::U128)
+                            .map_err(|e| {
+                                Literal::handle_parse_int_error(
+                                    e,
+                                    TypeInfo::UnsignedInteger(IntegerBits::OneTwentyEight),
+                                    int_inner.as_span(),
+                                    path.clone(),
+                                )
+                            }),
+                        Rule::u256_integer => {
+                            parse_u256_from_decimal(int_inner.as_str().trim().replace('_', ""))
+                                .map(Literal::U256)
+                                .ok_or_else(|| CompileError::IntegerTooLarge {
+                                    ty: TypeInfo::UnsignedInteger(IntegerBits::TwoFiftySix)
+                                        .friendly_type_str(),
+                                    span: span::Span {
+                                        span: int_inner.as_span(),
+                                        path: path.clone(),
+                                    },
+                                })
+                        }
+                        Rule::i8_integer => int_inner
+                            .as_str()
+                            .trim()
+                            .replace('_', "")
+                            .parse()
+                            .map(Literal::I8)
+                            .map_err(|e| {
+                                Literal::handle_parse_int_error(
+                                    e,
+                                    TypeInfo::SignedInteger(SignedIntegerBits::Eight),
+                                    int_inner.as_span(),
+                                    path.clone(),
+                                )
+                            }),
+                        Rule::i16_integer => int_inner
+                            .as_str()
+                            .trim()
+                            .replace('_', "")
+                            .parse()
+                            .map(Literal::I16)
+                            .map_err(|e| {
+                                Literal::handle_parse_int_error(
+                                    e,
+                                    TypeInfo::SignedInteger(SignedIntegerBits::Sixteen),
+                                    int_inner.as_span(),
+                                    path.clone(),
+                                )
+                            }),
+                        Rule::i32_integer => int_inner
+                            .as_str()
+                            .trim()
+                            .replace('_', "")
+                            .parse()
+                            .map(Literal::I32)
+                            .map_err(|e| {
+                                Literal::handle_parse_int_error(
+                                    e,
+                                    TypeInfo::SignedInteger(SignedIntegerBits::ThirtyTwo),
+                                    int_inner.as_span(),
+                                    path.clone(),
+                                )
+                            }),
+                        Rule::i64_integer => int_inner
+                            .as_str()
+                            .trim()
+                            .replace('_', "")
+                            .parse()
+                            .map(Literal::I64)
+                            .map_err(|e| {
+                                Literal::handle_parse_int_error(
+                                    e,
+                                    TypeInfo::SignedInteger(SignedIntegerBits::SixtyFour),
+                                    int_inner.as_span(),
+                                    path.clone(),
+                                )
+                            }),
                         _ => unreachable!(),
                     },
                     span,
@@ -235,7 +360,16 @@ impl Literal {
                 vec![0, 0, 0, 0, bytes[0], bytes[1], bytes[2], bytes[3]]
             }
             U64(val) => val.to_be_bytes().to_vec(),
+            U128(val) => val.to_be_bytes().to_vec(),
+            U256(bytes) => bytes.to_vec(),
+            // Signed integers are sign extended to a full word, so the two's complement
+            // representation is preserved when they're loaded into a register.
+            I8(val) => (*val as i64).to_be_bytes().to_vec(),
+            I16(val) => (*val as i64).to_be_bytes().to_vec(),
+            I32(val) => (*val as i64).to_be_bytes().to_vec(),
+            I64(val) => val.to_be_bytes().to_vec(),
             Numeric(val) => val.to_be_bytes().to_vec(),
+            NegativeNumeric(val) => val.to_be_bytes().to_vec(),
             Boolean(b) => {
                 vec![
                     0,
@@ -262,6 +396,13 @@ impl Literal {
         }
     }
 
+    /// Widens a `u64` to the big-endian byte representation of a `u256`.
+    pub(crate) fn u256_from_u64(val: u64) -> Literal {
+        let mut bytes = [0; 32];
+        bytes[24..].copy_from_slice(&val.to_be_bytes());
+        Literal::U256(bytes)
+    }
+
     /// Used when creating a pointer literal value, typically during code generation for
     /// values that wouldn't fit in a register.
     pub(crate) fn new_pointer_literal(offset_bytes: u64) -> Literal {
@@ -296,6 +437,26 @@ impl Literal {
     }
 }
 
+/// Parses a string of decimal digits into big-endian bytes, returning `None` if the value doesn't
+/// fit in 256 bits.
+fn parse_u256_from_decimal(digits: String) -> Option<[u8; 32]> {
+    let mut bytes = [0u8; 32];
+    for digit in digits.chars() {
+        // Multiply the accumulated value by 10 and add the new digit, propagating the carry from
+        // the least significant byte up.
+        let mut carry = digit.to_digit(10)?;
+        for byte in bytes.iter_mut().rev() {
+            let wide = *byte as u32 * 10 + carry;
+            *byte = wide as u8;
+            carry = wide >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(bytes)
+}
+
 fn parse_hex_from_pair(
     pair: Pair<Rule>,
     config: Option<&BuildConfig>,
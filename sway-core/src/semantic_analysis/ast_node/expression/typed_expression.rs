@@ -7,9 +7,13 @@ use crate::{
     type_engine::{insert_type, IntegerBits},
 };
 
+mod int_intrinsic;
 mod method_application;
+mod vec_intrinsic;
 use crate::type_engine::TypeId;
+use int_intrinsic::*;
 use method_application::type_check_method_application;
+use vec_intrinsic::*;
 
 #[derive(Clone, Debug)]
 pub struct TypedExpression {
@@ -58,6 +62,13 @@ impl TypedExpression {
             SizeOf {
                 variant: SizeOfVariant::Val(v),
             } => v.deterministically_aborts(),
+            VecIntrinsic { kind, .. } => kind
+                .arguments()
+                .iter()
+                .any(|x| x.deterministically_aborts()),
+            IntIntrinsic { lhs, rhs, .. } => {
+                lhs.deterministically_aborts() || rhs.deterministically_aborts()
+            }
             StructFieldAccess { .. }
             | Literal(_)
             | StorageAccess { .. }
@@ -98,6 +109,15 @@ impl TypedExpression {
                             .map(|x| x.deterministically_aborts())
                             .unwrap_or(false))
             }
+            IntegerSwitch {
+                scrutinee,
+                cases,
+                default,
+            } => {
+                scrutinee.deterministically_aborts()
+                    || (cases.iter().all(|(_, x)| x.deterministically_aborts())
+                        && default.deterministically_aborts())
+            }
             IfLet {
                 expr, then, r#else, ..
             } => {
@@ -128,6 +148,18 @@ impl TypedExpression {
                 }
                 buf
             }
+            TypedExpressionVariant::IntegerSwitch {
+                scrutinee,
+                cases,
+                default,
+            } => {
+                let mut buf = scrutinee.gather_return_statements();
+                for (_, body) in cases {
+                    buf.append(&mut body.gather_return_statements());
+                }
+                buf.append(&mut default.gather_return_statements());
+                buf
+            }
             TypedExpressionVariant::IfLet {
                 expr, then, r#else, ..
             } => {
@@ -161,6 +193,8 @@ impl TypedExpression {
             | TypedExpressionVariant::EnumInstantiation { .. }
             | TypedExpressionVariant::AbiCast { .. }
             | TypedExpressionVariant::SizeOf { .. }
+            | TypedExpressionVariant::VecIntrinsic { .. }
+            | TypedExpressionVariant::IntIntrinsic { .. }
             | TypedExpressionVariant::StructExpression { .. }
             | TypedExpressionVariant::VariableExpression { .. }
             | TypedExpressionVariant::StorageAccess { .. }
@@ -551,11 +585,13 @@ impl TypedExpression {
             });
 
         // Literals of type Numeric can now be resolved if typed_expression.return_type is
-        // an UnsignedInteger or a Numeric
+        // an UnsignedInteger, a SignedInteger or a Numeric
         if let TypedExpressionVariant::Literal(lit) = typed_expression.clone().expression {
-            if let Literal::Numeric(_) = lit {
+            if let Literal::Numeric(_) | Literal::NegativeNumeric(_) = lit {
                 match look_up_type_id(typed_expression.return_type) {
-                    TypeInfo::UnsignedInteger(_) | TypeInfo::Numeric => {
+                    TypeInfo::UnsignedInteger(_)
+                    | TypeInfo::SignedInteger(_)
+                    | TypeInfo::Numeric => {
                         typed_expression = check!(
                             Self::resolve_numeric_literal(
                                 lit,
@@ -596,6 +632,13 @@ impl TypedExpression {
             Literal::U16(_) => TypeInfo::UnsignedInteger(IntegerBits::Sixteen),
             Literal::U32(_) => TypeInfo::UnsignedInteger(IntegerBits::ThirtyTwo),
             Literal::U64(_) => TypeInfo::UnsignedInteger(IntegerBits::SixtyFour),
+            Literal::U128(_) => TypeInfo::UnsignedInteger(IntegerBits::OneTwentyEight),
+            Literal::U256(_) => TypeInfo::UnsignedInteger(IntegerBits::TwoFiftySix),
+            Literal::I8(_) => TypeInfo::SignedInteger(SignedIntegerBits::Eight),
+            Literal::I16(_) => TypeInfo::SignedInteger(SignedIntegerBits::Sixteen),
+            Literal::I32(_) => TypeInfo::SignedInteger(SignedIntegerBits::ThirtyTwo),
+            Literal::I64(_) => TypeInfo::SignedInteger(SignedIntegerBits::SixtyFour),
+            Literal::NegativeNumeric(_) => TypeInfo::Numeric,
             Literal::Boolean(_) => TypeInfo::Boolean,
             Literal::Byte(_) => TypeInfo::Byte,
             Literal::B256(_) => TypeInfo::B256,
@@ -1242,6 +1285,7 @@ impl TypedExpression {
             warnings,
             errors
         );
+        let switch_arms = integer_switch_arms(&cases_covered);
         let (witness_report, arms_reachability) = check!(
             check_match_expression_usefulness(cases_covered, span.clone()),
             return err(warnings, errors),
@@ -1263,7 +1307,16 @@ impl TypedExpression {
             });
             return err(warnings, errors);
         }
-        ok(typed_if_exp, warnings, errors)
+        let typed_exp = match switch_arms.and_then(|(values, has_catch_all)| {
+            integer_switch_from_if_exp(&typed_if_exp, &values, has_catch_all)
+        }) {
+            Some(switch) => TypedExpression {
+                expression: switch,
+                ..typed_if_exp
+            },
+            None => typed_if_exp,
+        };
+        ok(typed_exp, warnings, errors)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -1722,6 +1775,38 @@ impl TypedExpression {
     ) -> CompileResult<TypedExpression> {
         let mut warnings = vec![];
         let mut errors = vec![];
+
+        // Constructors for the built-in `Vec` type, e.g., `Vec::new()`, have no declaration.
+        if let Some(vec_type) = vec_type_from_call_path(&call_path) {
+            let mut typed_args = vec![];
+            for arg in args {
+                typed_args.push(check!(
+                    TypedExpression::type_check(TypeCheckArguments {
+                        checkee: arg,
+                        namespace,
+                        crate_namespace,
+                        return_type_annotation: insert_type(TypeInfo::Unknown),
+                        help_text: Default::default(),
+                        self_type,
+                        build_config,
+                        dead_code_graph,
+                        mode: Mode::NonAbi,
+                        opts,
+                    }),
+                    return err(warnings, errors),
+                    warnings,
+                    errors
+                ));
+            }
+            let exp = check!(
+                type_check_vec_intrinsic(vec_type, call_path.suffix, typed_args, self_type, span),
+                return err(warnings, errors),
+                warnings,
+                errors
+            );
+            return ok(exp, warnings, errors);
+        }
+
         // The first step is to determine if the call path refers to a module, enum, or function.
         // We could rely on the capitalization convention, where modules are lowercase
         // and enums are uppercase, but this is not robust in the long term.
@@ -2067,8 +2152,10 @@ impl TypedExpression {
             errors
         );
 
-        // If the return type is a static array then create a TypedArrayIndex.
-        if let TypeInfo::Array(elem_type_id, _) = look_up_type_id(prefix_te.return_type) {
+        // If the return type is a static array then create a TypedArrayIndex, or if it's a vector
+        // then use the vector indexing intrinsic.
+        let prefix_type = look_up_type_id(prefix_te.return_type);
+        if matches!(prefix_type, TypeInfo::Array(..) | TypeInfo::Vec(_)) {
             let index_te = check!(
                 TypedExpression::type_check(TypeCheckArguments {
                     checkee: index,
@@ -2089,8 +2176,8 @@ impl TypedExpression {
                 errors
             );
 
-            ok(
-                TypedExpression {
+            let exp = match prefix_type {
+                TypeInfo::Array(elem_type_id, _) => TypedExpression {
                     expression: TypedExpressionVariant::ArrayIndex {
                         prefix: Box::new(prefix_te),
                         index: Box::new(index_te),
@@ -2099,9 +2186,9 @@ impl TypedExpression {
                     is_constant: IsConstant::No,
                     span,
                 },
-                warnings,
-                errors,
-            )
+                _ => type_check_vec_index(prefix_te, index_te, span),
+            };
+            ok(exp, warnings, errors)
         } else {
             // Otherwise convert into a method call 'index(self, index)' via the std::ops::Index trait.
             let method_name = MethodName::FromType {
@@ -2410,6 +2497,54 @@ impl TypedExpression {
                         }),
                         new_type,
                     ),
+                    IntegerBits::OneTwentyEight => (Ok(Literal::U128(num as u128)), new_type),
+                    IntegerBits::TwoFiftySix => (Ok(Literal::u256_from_u64(num)), new_type),
+                },
+                TypeInfo::SignedInteger(n) => match n {
+                    SignedIntegerBits::Eight => (
+                        num.to_string().parse().map(Literal::I8).map_err(|e| {
+                            Literal::handle_parse_int_error(
+                                e,
+                                TypeInfo::SignedInteger(SignedIntegerBits::Eight),
+                                pest_span,
+                                path,
+                            )
+                        }),
+                        new_type,
+                    ),
+                    SignedIntegerBits::Sixteen => (
+                        num.to_string().parse().map(Literal::I16).map_err(|e| {
+                            Literal::handle_parse_int_error(
+                                e,
+                                TypeInfo::SignedInteger(SignedIntegerBits::Sixteen),
+                                pest_span,
+                                path,
+                            )
+                        }),
+                        new_type,
+                    ),
+                    SignedIntegerBits::ThirtyTwo => (
+                        num.to_string().parse().map(Literal::I32).map_err(|e| {
+                            Literal::handle_parse_int_error(
+                                e,
+                                TypeInfo::SignedInteger(SignedIntegerBits::ThirtyTwo),
+                                pest_span,
+                                path,
+                            )
+                        }),
+                        new_type,
+                    ),
+                    SignedIntegerBits::SixtyFour => (
+                        num.to_string().parse().map(Literal::I64).map_err(|e| {
+                            Literal::handle_parse_int_error(
+                                e,
+                                TypeInfo::SignedInteger(SignedIntegerBits::SixtyFour),
+                                pest_span,
+                                path,
+                            )
+                        }),
+                        new_type,
+                    ),
                 },
                 TypeInfo::Numeric => (
                     num.to_string().parse().map(Literal::U64).map_err(|e| {
@@ -2424,6 +2559,67 @@ impl TypedExpression {
                 ),
                 _ => unreachable!("Unexpected type for integer literals"),
             },
+            Literal::NegativeNumeric(num) => match look_up_type_id(new_type) {
+                TypeInfo::SignedInteger(n) => match n {
+                    SignedIntegerBits::Eight => (
+                        num.to_string().parse().map(Literal::I8).map_err(|e| {
+                            Literal::handle_parse_int_error(
+                                e,
+                                TypeInfo::SignedInteger(SignedIntegerBits::Eight),
+                                pest_span,
+                                path,
+                            )
+                        }),
+                        new_type,
+                    ),
+                    SignedIntegerBits::Sixteen => (
+                        num.to_string().parse().map(Literal::I16).map_err(|e| {
+                            Literal::handle_parse_int_error(
+                                e,
+                                TypeInfo::SignedInteger(SignedIntegerBits::Sixteen),
+                                pest_span,
+                                path,
+                            )
+                        }),
+                        new_type,
+                    ),
+                    SignedIntegerBits::ThirtyTwo => (
+                        num.to_string().parse().map(Literal::I32).map_err(|e| {
+                            Literal::handle_parse_int_error(
+                                e,
+                                TypeInfo::SignedInteger(SignedIntegerBits::ThirtyTwo),
+                                pest_span,
+                                path,
+                            )
+                        }),
+                        new_type,
+                    ),
+                    SignedIntegerBits::SixtyFour => (
+                        num.to_string().parse().map(Literal::I64).map_err(|e| {
+                            Literal::handle_parse_int_error(
+                                e,
+                                TypeInfo::SignedInteger(SignedIntegerBits::SixtyFour),
+                                pest_span,
+                                path,
+                            )
+                        }),
+                        new_type,
+                    ),
+                },
+                // A negative value can never be represented by an unsigned integer.
+                ty @ TypeInfo::UnsignedInteger(_) => (
+                    Err(CompileError::IntegerTooSmall {
+                        ty: ty.friendly_type_str(),
+                        span: span.clone(),
+                    }),
+                    new_type,
+                ),
+                TypeInfo::Numeric => (
+                    Ok(Literal::I64(num)),
+                    insert_type(TypeInfo::SignedInteger(SignedIntegerBits::SixtyFour)),
+                ),
+                _ => unreachable!("Unexpected type for integer literals"),
+            },
             _ => unreachable!("Unexpected non-integer literals"),
         };
 
@@ -2454,6 +2650,107 @@ impl TypedExpression {
     }
 }
 
+/// The fewest distinct literal arms for which a `match` is compiled to an
+/// [TypedExpressionVariant::IntegerSwitch] rather than left as a chain of `if`s.
+const MIN_INTEGER_SWITCH_CASES: usize = 4;
+
+/// If every arm of a `match` is an unsigned integer literal, apart from perhaps a catch-all or
+/// variable binding as the final arm, returns the literal values in order and whether there is
+/// such a final arm.
+fn integer_switch_arms(cases_covered: &[MatchCondition]) -> Option<(Vec<u64>, bool)> {
+    let (last, leading) = cases_covered.split_last()?;
+    let has_catch_all = matches!(
+        last,
+        MatchCondition::CatchAll(_) | MatchCondition::Scrutinee(Scrutinee::Variable { .. })
+    );
+    let literal_arms = if has_catch_all {
+        leading
+    } else {
+        cases_covered
+    };
+    let values = literal_arms
+        .iter()
+        .map(|condition| match condition {
+            MatchCondition::Scrutinee(Scrutinee::Literal { value, .. }) => match value {
+                Literal::U8(n) | Literal::Byte(n) => Some(*n as u64),
+                Literal::U16(n) => Some(*n as u64),
+                Literal::U32(n) => Some(*n as u64),
+                Literal::U64(n) | Literal::Numeric(n) => Some(*n),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some((values, has_catch_all))
+}
+
+/// Rebuilds the `if` chain which a `match` with the arms found by [integer_switch_arms] is
+/// desugared into as an [TypedExpressionVariant::IntegerSwitch], provided the scrutinee is an
+/// unsigned integer of at most 64 bits and there are enough distinct arms to be worth it.
+fn integer_switch_from_if_exp(
+    if_exp: &TypedExpression,
+    values: &[u64],
+    has_catch_all: bool,
+) -> Option<TypedExpressionVariant> {
+    let mut scrutinee = None;
+    let mut cases = Vec::with_capacity(values.len());
+    let mut next = Some(if_exp);
+    for value in values {
+        let (condition, then, r#else) = match &next?.expression {
+            TypedExpressionVariant::IfExp {
+                condition,
+                then,
+                r#else,
+            } => (condition, then, r#else),
+            _ => return None,
+        };
+        // Each condition is `eq(scrutinee, literal)`, so the first one tells us the scrutinee.
+        if scrutinee.is_none() {
+            scrutinee = match &condition.expression {
+                TypedExpressionVariant::FunctionApplication { arguments, .. } => {
+                    arguments.first().map(|(_, arg)| arg.clone())
+                }
+                _ => return None,
+            };
+        }
+        cases.push((*value, (**then).clone()));
+        next = r#else.as_deref();
+    }
+
+    let scrutinee = scrutinee?;
+    match look_up_type_id(scrutinee.return_type) {
+        TypeInfo::Byte
+        | TypeInfo::UnsignedInteger(
+            IntegerBits::Eight
+            | IntegerBits::Sixteen
+            | IntegerBits::ThirtyTwo
+            | IntegerBits::SixtyFour,
+        ) => (),
+        _ => return None,
+    }
+
+    // Without a catch-all the match is only exhaustive if the literals cover every value, so the
+    // last of them may as well be the default.
+    let default = match (has_catch_all, next) {
+        (true, Some(default)) => default.clone(),
+        (false, None) => cases.pop()?.1,
+        _ => return None,
+    };
+
+    // Only the first arm for any value is reachable.  The sort is stable so it's the one kept.
+    cases.sort_by_key(|(value, _)| *value);
+    cases.dedup_by_key(|(value, _)| *value);
+    if cases.len() < MIN_INTEGER_SWITCH_CASES {
+        return None;
+    }
+
+    Some(TypedExpressionVariant::IntegerSwitch {
+        scrutinee: Box::new(scrutinee),
+        cases,
+        default: Box::new(default),
+    })
+}
+
 fn check_scrutinee_type(
     scrutinee: &Scrutinee,
     namespace: NamespaceRef,
@@ -2549,6 +2846,11 @@ mod tests {
             print_intermediate_asm: false,
             print_finalized_asm: false,
             print_ir: false,
+            skip_bounds_checks: false,
+            optimization_level: sway_ir::OptLevel::O1,
+            hot_abi_methods: Vec::new(),
+            size_report: false,
+            contract_size_limit: None,
             generated_names: Arc::new(Mutex::new(vec![])),
         };
         let mut dead_code_graph: ControlFlowGraph = Default::default();
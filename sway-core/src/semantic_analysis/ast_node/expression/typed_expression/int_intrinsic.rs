@@ -0,0 +1,117 @@
+use super::*;
+use std::collections::VecDeque;
+
+/// If `method_name` is one of the `core::ops` operators then return it.
+fn core_ops_operator(method_name: &MethodName) -> Option<IntIntrinsicOp> {
+    match method_name {
+        MethodName::FromType {
+            call_path,
+            type_name: None,
+        } => match call_path.prefixes.as_slice() {
+            [core, ops] if core.as_str() == "core" && ops.as_str() == "ops" => {
+                IntIntrinsicOp::from_method_name(call_path.suffix.as_str())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// If `method_name` is an operator with an untyped numeric literal operand then return the
+/// index of the operand.  It should be type checked after the other operand, so that it may be
+/// given the type of a signed or wide integer, as there's no implicit conversion from a `u64` to
+/// either.
+pub(crate) fn numeric_literal_operand(
+    method_name: &MethodName,
+    arguments: &[Expression],
+) -> Option<usize> {
+    core_ops_operator(method_name)?;
+    if arguments.len() != 2 {
+        return None;
+    }
+    arguments.iter().position(|arg| {
+        matches!(
+            arg,
+            Expression::Literal {
+                value: Literal::Numeric(_) | Literal::NegativeNumeric(_),
+                ..
+            }
+        )
+    })
+}
+
+/// Return the type of the first of `args_buf` which is an integer the VM has no instructions for,
+/// if any.  These are the signed integers and the unsigned integers wider than a word.
+pub(crate) fn intrinsic_operand_type(args_buf: &VecDeque<TypedExpression>) -> Option<TypeId> {
+    args_buf
+        .iter()
+        .map(|arg| arg.return_type)
+        .find(|ty| match look_up_type_id(*ty) {
+            TypeInfo::SignedInteger(_) => true,
+            TypeInfo::UnsignedInteger(bits) => bits.is_wide(),
+            _ => false,
+        })
+}
+
+/// If `method_name` is one of the `core::ops` operators applied to a signed or wide integer then
+/// return the operator and the type of its operands.
+pub(crate) fn int_intrinsic_from_method_name(
+    method_name: &MethodName,
+    args_buf: &VecDeque<TypedExpression>,
+) -> Option<(IntIntrinsicOp, TypeId)> {
+    let op = core_ops_operator(method_name)?;
+    if args_buf.len() != 2 {
+        return None;
+    }
+    intrinsic_operand_type(args_buf).map(|int_type| (op, int_type))
+}
+
+/// Type check an operator applied to integers which the VM has no instructions for.  These have
+/// no declaration and are instead compiled directly to sequences of VM instructions.
+pub(crate) fn type_check_int_intrinsic(
+    op: IntIntrinsicOp,
+    int_type: TypeId,
+    arguments: Vec<TypedExpression>,
+    self_type: TypeId,
+    span: Span,
+) -> CompileResult<TypedExpression> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+
+    let mut operands = Vec::new();
+    for arg in arguments {
+        unify_or_error(
+            arg.return_type,
+            int_type,
+            self_type,
+            &arg.span,
+            "Both operands must have the same integer type.",
+            &mut warnings,
+            &mut errors,
+        );
+        operands.push(Box::new(arg));
+    }
+    let rhs = operands.pop().expect("operators have two operands");
+    let lhs = operands.pop().expect("operators have two operands");
+
+    let return_type = if op.is_comparison() {
+        insert_type(TypeInfo::Boolean)
+    } else {
+        int_type
+    };
+    ok(
+        TypedExpression {
+            expression: TypedExpressionVariant::IntIntrinsic {
+                op,
+                lhs,
+                rhs,
+                int_type,
+            },
+            return_type,
+            is_constant: IsConstant::No,
+            span,
+        },
+        warnings,
+        errors,
+    )
+}
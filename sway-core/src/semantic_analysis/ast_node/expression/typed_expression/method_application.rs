@@ -26,13 +26,13 @@ pub(crate) fn type_check_method_application(
     let mut errors = vec![];
     let mut args_buf = VecDeque::new();
     let mut contract_call_params_map = HashMap::new();
-    for arg in arguments {
-        args_buf.push_back(check!(
+    let mut type_check_arg = |arg, return_type_annotation, dead_code_graph: &mut _| {
+        check!(
             TypedExpression::type_check(TypeCheckArguments {
                 checkee: arg,
                 namespace,
                 crate_namespace,
-                return_type_annotation: insert_type(TypeInfo::Unknown),
+                return_type_annotation,
                 help_text: Default::default(),
                 self_type,
                 build_config,
@@ -43,7 +43,69 @@ pub(crate) fn type_check_method_application(
             error_recovery_expr(span.clone()),
             warnings,
             errors
-        ));
+        )
+    };
+    match numeric_literal_operand(&method_name, &arguments) {
+        Some(literal_idx) => {
+            // Check the literal last so that it can take the type of a signed or wide operand.
+            let mut arguments = arguments;
+            let literal = arguments.remove(literal_idx);
+            for arg in arguments {
+                args_buf.push_back(type_check_arg(
+                    arg,
+                    insert_type(TypeInfo::Unknown),
+                    dead_code_graph,
+                ));
+            }
+            let annotation =
+                intrinsic_operand_type(&args_buf).unwrap_or_else(|| insert_type(TypeInfo::Unknown));
+            args_buf.insert(
+                literal_idx,
+                type_check_arg(literal, annotation, dead_code_graph),
+            );
+        }
+        None => {
+            for arg in arguments {
+                args_buf.push_back(type_check_arg(
+                    arg,
+                    insert_type(TypeInfo::Unknown),
+                    dead_code_graph,
+                ));
+            }
+        }
+    }
+
+    // Methods of the built-in `Vec` type are compiler intrinsics rather than declarations.
+    if let Some(vec_type) = vec_type_from_method_name(&method_name, &args_buf) {
+        if !contract_call_params.is_empty() {
+            errors.push(CompileError::CallParamForNonContractCallMethod {
+                span: contract_call_params[0].name.span().clone(),
+            });
+        }
+        let exp = check!(
+            type_check_vec_intrinsic(
+                vec_type,
+                method_name.easy_name(),
+                args_buf.into(),
+                self_type,
+                span
+            ),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        return ok(exp, warnings, errors);
+    }
+
+    // Operators on signed and wide integers are compiler intrinsics rather than trait methods.
+    if let Some((op, int_type)) = int_intrinsic_from_method_name(&method_name, &args_buf) {
+        let exp = check!(
+            type_check_int_intrinsic(op, int_type, args_buf.into(), self_type, span),
+            return err(warnings, errors),
+            warnings,
+            errors
+        );
+        return ok(exp, warnings, errors);
     }
 
     let method = match method_name {
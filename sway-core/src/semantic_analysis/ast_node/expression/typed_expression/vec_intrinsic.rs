@@ -0,0 +1,209 @@
+use super::*;
+use std::collections::VecDeque;
+
+/// Map a built-in type name to a new `Vec` type.  `Bytes` is shorthand for `Vec<u8>`, while the
+/// element type of a plain `Vec` is inferred.
+fn vec_type_from_name(name: &str) -> Option<TypeId> {
+    let elem_type = match name {
+        "Vec" => TypeInfo::Unknown,
+        "Bytes" => TypeInfo::UnsignedInteger(IntegerBits::Eight),
+        _ => return None,
+    };
+    Some(insert_type(TypeInfo::Vec(insert_type(elem_type))))
+}
+
+/// If `call_path` is of the form `Vec::method` then return the `Vec` type it refers to.
+pub(crate) fn vec_type_from_call_path(call_path: &CallPath) -> Option<TypeId> {
+    match call_path.prefixes.as_slice() {
+        [type_name] if !call_path.is_absolute => vec_type_from_name(type_name.as_str()),
+        _ => None,
+    }
+}
+
+/// If `method_name` refers to a method of the built-in `Vec` type, either explicitly via the type
+/// name or implicitly via the type of the first argument, then return the `Vec` type.
+pub(crate) fn vec_type_from_method_name(
+    method_name: &MethodName,
+    args_buf: &VecDeque<TypedExpression>,
+) -> Option<TypeId> {
+    match method_name {
+        MethodName::FromType {
+            type_name: Some(TypeInfo::Vec(elem_type)),
+            ..
+        } => Some(insert_type(TypeInfo::Vec(*elem_type))),
+        MethodName::FromType {
+            type_name: Some(TypeInfo::Custom { name }),
+            ..
+        } => vec_type_from_name(name.as_str()),
+        MethodName::FromType {
+            type_name: None, ..
+        }
+        | MethodName::FromModule { .. } => args_buf
+            .front()
+            .map(|arg| arg.return_type)
+            .filter(|ty| matches!(look_up_type_id(*ty), TypeInfo::Vec(_))),
+        MethodName::FromType { .. } => None,
+    }
+}
+
+/// Type check a call to one of the methods of the built-in `Vec` type.  These have no declaration
+/// and are instead compiled directly to VM instructions which manage the heap allocation.
+pub(crate) fn type_check_vec_intrinsic(
+    vec_type: TypeId,
+    method_name: Ident,
+    arguments: Vec<TypedExpression>,
+    self_type: TypeId,
+    span: Span,
+) -> CompileResult<TypedExpression> {
+    let mut warnings = vec![];
+    let mut errors = vec![];
+
+    let expected_arg_count = match method_name.as_str() {
+        "new" => 0,
+        "with_capacity" | "len" | "pop" => 1,
+        "push" => 2,
+        _ => {
+            errors.push(CompileError::MethodNotFound {
+                span: method_name.span().clone(),
+                method_name: method_name.as_str().to_string(),
+                type_name: vec_type.friendly_type_str(),
+            });
+            return err(warnings, errors);
+        }
+    };
+    if arguments.len() > expected_arg_count {
+        errors.push(CompileError::TooManyArgumentsForFunction {
+            span,
+            method_name,
+            expected: expected_arg_count,
+            received: arguments.len(),
+        });
+        return err(warnings, errors);
+    }
+    if arguments.len() < expected_arg_count {
+        errors.push(CompileError::TooFewArgumentsForFunction {
+            span,
+            method_name,
+            expected: expected_arg_count,
+            received: arguments.len(),
+        });
+        return err(warnings, errors);
+    }
+
+    let u64_type = insert_type(TypeInfo::UnsignedInteger(IntegerBits::SixtyFour));
+    let mut arguments = arguments.into_iter().map(Box::new).collect::<VecDeque<_>>();
+
+    // The methods which take a vector as `self` must agree with the vector type, which also
+    // determines the element type.
+    if !matches!(method_name.as_str(), "new" | "with_capacity") {
+        unify_or_error(
+            arguments[0].return_type,
+            vec_type,
+            self_type,
+            &arguments[0].span,
+            "Method receiver must be a Vec.",
+            &mut warnings,
+            &mut errors,
+        );
+    }
+
+    let mut next_arg = || {
+        arguments
+            .pop_front()
+            .expect("argument count was checked above")
+    };
+    let (kind, return_type) = match method_name.as_str() {
+        "new" => (VecIntrinsicKind::New, vec_type),
+        "with_capacity" => {
+            let capacity = next_arg();
+            unify_or_error(
+                capacity.return_type,
+                u64_type,
+                self_type,
+                &capacity.span,
+                "Vec capacity must be a u64.",
+                &mut warnings,
+                &mut errors,
+            );
+            (VecIntrinsicKind::WithCapacity(capacity), vec_type)
+        }
+        "len" => (VecIntrinsicKind::Len(next_arg()), u64_type),
+        "push" => {
+            let vec = next_arg();
+            let value = next_arg();
+            unify_or_error(
+                value.return_type,
+                vec_elem_type(vec_type),
+                self_type,
+                &value.span,
+                "Pushed value must match the Vec element type.",
+                &mut warnings,
+                &mut errors,
+            );
+            (
+                VecIntrinsicKind::Push { vec, value },
+                insert_type(TypeInfo::Tuple(Vec::new())),
+            )
+        }
+        "pop" => (VecIntrinsicKind::Pop(next_arg()), vec_elem_type(vec_type)),
+        _ => unreachable!("method name was checked above"),
+    };
+
+    ok(
+        TypedExpression {
+            expression: TypedExpressionVariant::VecIntrinsic {
+                kind,
+                elem_type: vec_elem_type(vec_type),
+            },
+            return_type,
+            is_constant: IsConstant::No,
+            span,
+        },
+        warnings,
+        errors,
+    )
+}
+
+/// Build an indexing expression into a vector, `prefix[index]`, where the vector has already
+/// been type checked.
+pub(crate) fn type_check_vec_index(
+    prefix: TypedExpression,
+    index: TypedExpression,
+    span: Span,
+) -> TypedExpression {
+    let elem_type = vec_elem_type(prefix.return_type);
+    TypedExpression {
+        expression: TypedExpressionVariant::VecIntrinsic {
+            kind: VecIntrinsicKind::Index {
+                vec: Box::new(prefix),
+                index: Box::new(index),
+            },
+            elem_type,
+        },
+        return_type: elem_type,
+        is_constant: IsConstant::No,
+        span,
+    }
+}
+
+fn vec_elem_type(vec_type: TypeId) -> TypeId {
+    match look_up_type_id(vec_type) {
+        TypeInfo::Vec(elem_type) => elem_type,
+        _ => insert_type(TypeInfo::ErrorRecovery),
+    }
+}
+
+pub(crate) fn unify_or_error(
+    received: TypeId,
+    expected: TypeId,
+    self_type: TypeId,
+    span: &Span,
+    help_text: &str,
+    warnings: &mut Vec<CompileWarning>,
+    errors: &mut Vec<CompileError>,
+) {
+    match unify_with_self(received, expected, self_type, span, help_text) {
+        Ok(mut ws) => warnings.append(&mut ws),
+        Err(e) => errors.push(e.into()),
+    }
+}
@@ -52,6 +52,15 @@ pub(crate) enum TypedExpressionVariant {
         then: Box<TypedExpression>,
         r#else: Option<Box<TypedExpression>>,
     },
+    /// A `match` on an unsigned integer whose arms are all literals, apart from perhaps a final
+    /// catch-all.  Rather than testing each arm in turn the arm is found with a binary search.
+    IntegerSwitch {
+        scrutinee: Box<TypedExpression>,
+        /// The arm values, sorted and without duplicates, with the body for each.
+        cases: Vec<(u64, TypedExpression)>,
+        /// The body to evaluate when none of the `cases` match.
+        default: Box<TypedExpression>,
+    },
     AsmExpression {
         registers: Vec<TypedAsmRegisterDeclaration>,
         body: Vec<AsmOp>,
@@ -102,6 +111,20 @@ pub(crate) enum TypedExpressionVariant {
     SizeOf {
         variant: SizeOfVariant,
     },
+    /// A built-in operation on a heap allocated `Vec`.
+    VecIntrinsic {
+        kind: VecIntrinsicKind,
+        /// The type of the elements held by the vector.
+        elem_type: TypeId,
+    },
+    /// A built-in operator on signed or wide integers, which the VM has no instructions for.
+    IntIntrinsic {
+        op: IntIntrinsicOp,
+        lhs: Box<TypedExpression>,
+        rhs: Box<TypedExpression>,
+        /// The type of both operands.
+        int_type: TypeId,
+    },
 }
 
 /// Describes the full storage access including all the subfields
@@ -138,6 +161,138 @@ pub(crate) enum SizeOfVariant {
     Val(Box<TypedExpression>),
 }
 
+#[derive(Clone, Debug)]
+pub(crate) enum VecIntrinsicKind {
+    New,
+    WithCapacity(Box<TypedExpression>),
+    Len(Box<TypedExpression>),
+    Push {
+        vec: Box<TypedExpression>,
+        value: Box<TypedExpression>,
+    },
+    Pop(Box<TypedExpression>),
+    Index {
+        vec: Box<TypedExpression>,
+        index: Box<TypedExpression>,
+    },
+    /// The encoding of a vector passed to a contract call, a pointer to its element pointer and
+    /// length.  This is generated by the compiler and has no method name.
+    ToCallData(Box<TypedExpression>),
+}
+
+impl VecIntrinsicKind {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            VecIntrinsicKind::New => "new",
+            VecIntrinsicKind::WithCapacity(_) => "with_capacity",
+            VecIntrinsicKind::Len(_) => "len",
+            VecIntrinsicKind::Push { .. } => "push",
+            VecIntrinsicKind::Pop(_) => "pop",
+            VecIntrinsicKind::Index { .. } => "index",
+            VecIntrinsicKind::ToCallData(_) => "to call data",
+        }
+    }
+
+    /// The operands of the intrinsic, in evaluation order.
+    pub(crate) fn arguments(&self) -> Vec<&TypedExpression> {
+        match self {
+            VecIntrinsicKind::New => vec![],
+            VecIntrinsicKind::WithCapacity(exp)
+            | VecIntrinsicKind::Len(exp)
+            | VecIntrinsicKind::Pop(exp)
+            | VecIntrinsicKind::ToCallData(exp) => {
+                vec![exp]
+            }
+            VecIntrinsicKind::Push { vec, value } => vec![vec, value],
+            VecIntrinsicKind::Index { vec, index } => vec![vec, index],
+        }
+    }
+
+    pub(crate) fn arguments_mut(&mut self) -> Vec<&mut TypedExpression> {
+        match self {
+            VecIntrinsicKind::New => vec![],
+            VecIntrinsicKind::WithCapacity(exp)
+            | VecIntrinsicKind::Len(exp)
+            | VecIntrinsicKind::Pop(exp)
+            | VecIntrinsicKind::ToCallData(exp) => {
+                vec![exp]
+            }
+            VecIntrinsicKind::Push { vec, value } => vec![vec, value],
+            VecIntrinsicKind::Index { vec, index } => vec![vec, index],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum IntIntrinsicOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    BinaryAnd,
+    BinaryOr,
+    Xor,
+    Equals,
+    NotEquals,
+    LessThan,
+    GreaterThan,
+    LessThanOrEqualTo,
+    GreaterThanOrEqualTo,
+}
+
+impl IntIntrinsicOp {
+    /// Map the name of a `core::ops` method to the operator it implements.
+    pub(crate) fn from_method_name(name: &str) -> Option<IntIntrinsicOp> {
+        use IntIntrinsicOp::*;
+        Some(match name {
+            "add" => Add,
+            "subtract" => Subtract,
+            "multiply" => Multiply,
+            "divide" => Divide,
+            "modulo" => Modulo,
+            "binary_and" => BinaryAnd,
+            "binary_or" => BinaryOr,
+            "xor" => Xor,
+            "eq" => Equals,
+            "neq" => NotEquals,
+            "lt" => LessThan,
+            "gt" => GreaterThan,
+            "le" => LessThanOrEqualTo,
+            "ge" => GreaterThanOrEqualTo,
+            _ => return None,
+        })
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        use IntIntrinsicOp::*;
+        match self {
+            Add => "add",
+            Subtract => "subtract",
+            Multiply => "multiply",
+            Divide => "divide",
+            Modulo => "modulo",
+            BinaryAnd => "binary_and",
+            BinaryOr => "binary_or",
+            Xor => "xor",
+            Equals => "eq",
+            NotEquals => "neq",
+            LessThan => "lt",
+            GreaterThan => "gt",
+            LessThanOrEqualTo => "le",
+            GreaterThanOrEqualTo => "ge",
+        }
+    }
+
+    pub(crate) fn is_comparison(&self) -> bool {
+        use IntIntrinsicOp::*;
+        matches!(
+            self,
+            Equals | NotEquals | LessThan | GreaterThan | LessThanOrEqualTo | GreaterThanOrEqualTo
+        )
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct TypedAsmRegisterDeclaration {
     pub(crate) initializer: Option<TypedExpression>,
@@ -162,7 +317,21 @@ impl TypedExpressionVariant {
                     Literal::U16(content) => content.to_string(),
                     Literal::U32(content) => content.to_string(),
                     Literal::U64(content) => content.to_string(),
+                    Literal::U128(content) => content.to_string(),
+                    Literal::U256(content) => format!(
+                        "0x{}",
+                        content
+                            .iter()
+                            .map(|x| format!("{:02x}", x))
+                            .collect::<Vec<_>>()
+                            .join("")
+                    ),
+                    Literal::I8(content) => content.to_string(),
+                    Literal::I16(content) => content.to_string(),
+                    Literal::I32(content) => content.to_string(),
+                    Literal::I64(content) => content.to_string(),
                     Literal::Numeric(content) => content.to_string(),
+                    Literal::NegativeNumeric(content) => content.to_string(),
                     Literal::String(content) => content.as_str().to_string(),
                     Literal::Boolean(content) => content.to_string(),
                     Literal::Byte(content) => content.to_string(),
@@ -196,6 +365,9 @@ impl TypedExpressionVariant {
             TypedExpressionVariant::CodeBlock(_) => "code block entry".into(),
             TypedExpressionVariant::FunctionParameter => "fn param access".into(),
             TypedExpressionVariant::IfExp { .. } => "if exp".into(),
+            TypedExpressionVariant::IntegerSwitch { cases, .. } => {
+                format!("integer switch with {} cases", cases.len())
+            }
             TypedExpressionVariant::AsmExpression { .. } => "inline asm".into(),
             TypedExpressionVariant::AbiCast { abi_name, .. } => {
                 format!("abi cast {}", abi_name.suffix.as_str())
@@ -256,6 +428,12 @@ impl TypedExpressionVariant {
                     format!("size_of({:?})", type_name.friendly_type_str())
                 }
             },
+            TypedExpressionVariant::VecIntrinsic { kind, elem_type } => {
+                format!("Vec<{}>::{}", elem_type.friendly_type_str(), kind.name())
+            }
+            TypedExpressionVariant::IntIntrinsic { op, int_type, .. } => {
+                format!("{}::{}", int_type.friendly_type_str(), op.name())
+            }
         }
     }
     /// Makes a fresh copy of all type ids in this expression. Used when monomorphizing.
@@ -302,6 +480,17 @@ impl TypedExpressionVariant {
                     r#else.copy_types(type_mapping);
                 }
             }
+            IntegerSwitch {
+                scrutinee,
+                cases,
+                default,
+            } => {
+                scrutinee.copy_types(type_mapping);
+                cases
+                    .iter_mut()
+                    .for_each(|(_, body)| body.copy_types(type_mapping));
+                default.copy_types(type_mapping);
+            }
             AsmExpression {
                 registers, //: Vec<TypedAsmRegisterDeclaration>,
                 ..
@@ -375,6 +564,88 @@ impl TypedExpressionVariant {
                 SizeOfVariant::Type(_) => (),
                 SizeOfVariant::Val(exp) => exp.copy_types(type_mapping),
             },
+            VecIntrinsic { kind, elem_type } => {
+                *elem_type = if let Some(matching_id) =
+                    look_up_type_id(*elem_type).matches_type_parameter(type_mapping)
+                {
+                    insert_type(TypeInfo::Ref(matching_id))
+                } else {
+                    insert_type(look_up_type_id_raw(*elem_type))
+                };
+                kind.arguments_mut()
+                    .into_iter()
+                    .for_each(|exp| exp.copy_types(type_mapping));
+            }
+            IntIntrinsic {
+                lhs, rhs, int_type, ..
+            } => {
+                *int_type = insert_type(look_up_type_id_raw(*int_type));
+                lhs.copy_types(type_mapping);
+                rhs.copy_types(type_mapping);
+            }
+        }
+    }
+}
+
+/// The result of every value of a dense [TypedExpressionVariant::IntegerSwitch] whose arms are
+/// all word sized literals, which may be looked up in a table indexed by the scrutinee rather than
+/// searched for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct IntegerSwitchTable {
+    /// The scrutinee value of the first entry.
+    pub(crate) first_value: u64,
+    /// The result for each scrutinee value from `first_value` on.  Values between the cases have
+    /// the result of the default arm.
+    pub(crate) results: Vec<u64>,
+}
+
+/// If the cases of an [TypedExpressionVariant::IntegerSwitch] and its default are all word sized
+/// literals, and at least half of the values from the first case to the last have a case, then
+/// returns the table of results to look the scrutinee up in.
+pub(crate) fn integer_switch_table(
+    cases: &[(u64, TypedExpression)],
+    default: &TypedExpression,
+) -> Option<IntegerSwitchTable> {
+    let (first_value, last_value) = match (cases.first(), cases.last()) {
+        (Some((first_value, _)), Some((last_value, _))) => (*first_value, *last_value),
+        _ => return None,
+    };
+    let len = last_value.checked_sub(first_value)?.checked_add(1)?;
+    if len > 2 * cases.len() as u64 {
+        return None;
+    }
+    let default_result = word_literal(default)?;
+    let mut results = vec![default_result; len as usize];
+    for (value, body) in cases {
+        results[(value - first_value) as usize] = word_literal(body)?;
+    }
+    Some(IntegerSwitchTable {
+        first_value,
+        results,
+    })
+}
+
+/// The value of `exp` if it's a literal no wider than a word, or a code block which just returns
+/// one.
+fn word_literal(exp: &TypedExpression) -> Option<u64> {
+    match &exp.expression {
+        TypedExpressionVariant::Literal(literal) => match literal {
+            Literal::U8(n) | Literal::Byte(n) => Some(*n as u64),
+            Literal::U16(n) => Some(*n as u64),
+            Literal::U32(n) => Some(*n as u64),
+            Literal::U64(n) | Literal::Numeric(n) => Some(*n),
+            Literal::Boolean(b) => Some(*b as u64),
+            _ => None,
+        },
+        TypedExpressionVariant::CodeBlock(TypedCodeBlock { contents, .. }) => {
+            match contents.as_slice() {
+                [TypedAstNode {
+                    content: TypedAstNodeContent::ImplicitReturnExpression(exp),
+                    ..
+                }] => word_literal(exp),
+                _ => None,
+            }
         }
+        _ => None,
     }
 }
@@ -62,8 +62,14 @@ impl ConstructorFactory {
     ) -> CompileResult<Pattern> {
         let mut warnings = vec![];
         let mut errors = vec![];
+        let sigma = sigma.flatten().filter_out_wildcards();
+        // we will not present every signed or wide integer case, including when
+        // they are mixed with `Numeric` patterns
+        if sigma.iter().any(Pattern::is_untracked_integer) {
+            return ok(Pattern::Wildcard, warnings, errors);
+        }
         let (first, rest) = check!(
-            sigma.flatten().filter_out_wildcards().split_first(span),
+            sigma.split_first(span),
             return err(warnings, errors),
             warnings,
             errors
@@ -211,6 +217,7 @@ impl ConstructorFactory {
             }
             // we will not present every string case
             Pattern::String(_) => Pattern::Wildcard,
+            Pattern::Signed(_) | Pattern::U128(_) | Pattern::U256(_) => Pattern::Wildcard,
             Pattern::Wildcard => unreachable!(),
             // we will not present every b256 case
             Pattern::B256(_) => Pattern::Wildcard,
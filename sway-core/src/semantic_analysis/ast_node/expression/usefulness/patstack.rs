@@ -211,6 +211,12 @@ impl PatStack {
         if preprocessed.pats.is_empty() {
             return ok(false, warnings, errors);
         }
+        // signed and wide integer patterns may be mixed with `Numeric` patterns
+        // and their ranges aren't tracked, so its assumed that no one is ever
+        // going to list every value
+        if preprocessed.iter().any(Pattern::is_untracked_integer) {
+            return ok(false, warnings, errors);
+        }
         let (first, rest) = check!(
             preprocessed.split_first(span),
             return err(warnings, errors),
@@ -220,6 +226,8 @@ impl PatStack {
         match first {
             // its assumed that no one is ever going to list every string
             Pattern::String(_) => ok(false, warnings, errors),
+            // see above
+            Pattern::Signed(_) | Pattern::U128(_) | Pattern::U256(_) => ok(false, warnings, errors),
             // its assumed that no one is ever going to list every B256
             Pattern::B256(_) => ok(false, warnings, errors),
             Pattern::U8(range) => {
@@ -106,6 +106,12 @@ pub(crate) enum Pattern {
     Boolean(bool),
     Byte(Range<u8>),
     Numeric(Range<u64>),
+    /// Signed integer literals, sign extended to 64 bits.  These aren't described with ranges
+    /// and so, like strings, are never considered to be a complete signature.
+    Signed(i64),
+    /// Wide unsigned integer literals.  Like `Signed`, these aren't described with ranges.
+    U128(u128),
+    U256([u8; 32]),
     String(String),
     Struct(StructPattern),
     Tuple(PatStack),
@@ -138,6 +144,14 @@ impl Pattern {
                 Literal::Numeric(x) => {
                     ok(Pattern::Numeric(Range::from_single(x)), warnings, errors)
                 }
+                Literal::U128(x) => ok(Pattern::U128(x), warnings, errors),
+                Literal::U256(x) => ok(Pattern::U256(x), warnings, errors),
+                Literal::I8(x) => ok(Pattern::Signed(x as i64), warnings, errors),
+                Literal::I16(x) => ok(Pattern::Signed(x as i64), warnings, errors),
+                Literal::I32(x) => ok(Pattern::Signed(x as i64), warnings, errors),
+                Literal::I64(x) | Literal::NegativeNumeric(x) => {
+                    ok(Pattern::Signed(x), warnings, errors)
+                }
                 Literal::String(s) => ok(Pattern::String(s.as_str().to_string()), warnings, errors),
             },
             Scrutinee::StructScrutinee {
@@ -398,6 +412,36 @@ impl Pattern {
                 }
                 Pattern::Numeric(range.clone())
             }
+            Pattern::Signed(x) => {
+                if !args.is_empty() {
+                    errors.push(CompileError::Internal(
+                        "malformed constructor request",
+                        span.clone(),
+                    ));
+                    return err(warnings, errors);
+                }
+                Pattern::Signed(*x)
+            }
+            Pattern::U128(x) => {
+                if !args.is_empty() {
+                    errors.push(CompileError::Internal(
+                        "malformed constructor request",
+                        span.clone(),
+                    ));
+                    return err(warnings, errors);
+                }
+                Pattern::U128(*x)
+            }
+            Pattern::U256(x) => {
+                if !args.is_empty() {
+                    errors.push(CompileError::Internal(
+                        "malformed constructor request",
+                        span.clone(),
+                    ));
+                    return err(warnings, errors);
+                }
+                Pattern::U256(*x)
+            }
             Pattern::String(s) => {
                 if !args.is_empty() {
                     errors.push(CompileError::Internal(
@@ -478,6 +522,16 @@ impl Pattern {
         Pattern::Wildcard
     }
 
+    /// Checks to see if the `Pattern` is an integer whose values aren't
+    /// tracked with ranges. These may be mixed with `Numeric` patterns for
+    /// the same scrutinee, so the whole signature is treated as incomplete.
+    pub(crate) fn is_untracked_integer(&self) -> bool {
+        matches!(
+            self,
+            Pattern::Signed(_) | Pattern::U128(_) | Pattern::U256(_)
+        )
+    }
+
     /// Finds the "a value" of the `Pattern`, AKA the number of sub-patterns
     /// used in the pattern's constructor. For example, the pattern
     /// `Pattern::Tuple([.., ..])` would have an "a value" of 2.
@@ -491,6 +545,9 @@ impl Pattern {
             Pattern::Boolean(_) => 0,
             Pattern::Byte(_) => 0,
             Pattern::Numeric(_) => 0,
+            Pattern::Signed(_) => 0,
+            Pattern::U128(_) => 0,
+            Pattern::U256(_) => 0,
             Pattern::String(_) => 0,
             Pattern::Struct(StructPattern { fields, .. }) => fields.len(),
             Pattern::Tuple(elems) => elems.len(),
@@ -538,6 +595,9 @@ impl Pattern {
             (Pattern::Boolean(x), Pattern::Boolean(y)) => x == y,
             (Pattern::Byte(a), Pattern::Byte(b)) => a == b,
             (Pattern::Numeric(a), Pattern::Numeric(b)) => a == b,
+            (Pattern::Signed(x), Pattern::Signed(y)) => x == y,
+            (Pattern::U128(x), Pattern::U128(y)) => x == y,
+            (Pattern::U256(x), Pattern::U256(y)) => x == y,
             (Pattern::String(x), Pattern::String(y)) => x == y,
             (
                 Pattern::Struct(StructPattern {
@@ -622,6 +682,9 @@ impl fmt::Display for Pattern {
             Pattern::U32(range) => format!("{}", range),
             Pattern::U64(range) => format!("{}", range),
             Pattern::Numeric(range) => format!("{}", range),
+            Pattern::Signed(x) => format!("{}", x),
+            Pattern::U128(x) => format!("{}", x),
+            Pattern::U256(x) => format!("{:#?}", x),
             Pattern::B256(n) => format!("{:#?}", n),
             Pattern::Boolean(b) => format!("{}", b),
             Pattern::Byte(range) => format!("{}", range),
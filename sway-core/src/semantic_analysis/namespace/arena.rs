@@ -680,6 +680,10 @@ impl NamespaceWrapper for NamespaceRef {
             }
             TypeInfo::SelfType => self_type,
             TypeInfo::Ref(id) => id,
+            TypeInfo::Vec(elem_ty) => {
+                let elem_ty = self.resolve_type_with_self(look_up_type_id(elem_ty), self_type)?;
+                insert_type(TypeInfo::Vec(elem_ty))
+            }
             o => insert_type(o),
         })
     }
@@ -707,6 +711,10 @@ impl NamespaceWrapper for NamespaceRef {
                 }
             }
             TypeInfo::Ref(id) => id,
+            TypeInfo::Vec(elem_ty) => {
+                let elem_ty = self.resolve_type_without_self(&look_up_type_id(elem_ty));
+                insert_type(TypeInfo::Vec(elem_ty))
+            }
             o => insert_type(o),
         }
     }
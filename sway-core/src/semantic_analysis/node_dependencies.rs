@@ -2,8 +2,11 @@ use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 
 use crate::{
-    error::*, parse_tree::*, type_engine::IntegerBits, AstNode, AstNodeContent, CodeBlock,
-    Declaration, Expression, ReturnStatement, TypeInfo, WhileLoop,
+    error::*,
+    parse_tree::*,
+    type_engine::{IntegerBits, SignedIntegerBits},
+    AstNode, AstNodeContent, CodeBlock, Declaration, Expression, ReturnStatement, TypeInfo,
+    WhileLoop,
 };
 
 use sway_types::{ident::Ident, span::Span};
@@ -575,6 +578,14 @@ fn type_info_name(type_info: &TypeInfo) -> String {
             IntegerBits::Sixteen => "uint16",
             IntegerBits::ThirtyTwo => "uint32",
             IntegerBits::SixtyFour => "uint64",
+            IntegerBits::OneTwentyEight => "uint128",
+            IntegerBits::TwoFiftySix => "uint256",
+        },
+        TypeInfo::SignedInteger(n) => match n {
+            SignedIntegerBits::Eight => "int8",
+            SignedIntegerBits::Sixteen => "int16",
+            SignedIntegerBits::ThirtyTwo => "int32",
+            SignedIntegerBits::SixtyFour => "int64",
         },
         TypeInfo::Boolean => "bool",
         TypeInfo::Custom { name } => name.as_str(),
@@ -593,6 +604,7 @@ fn type_info_name(type_info: &TypeInfo) -> String {
         TypeInfo::Struct { .. } => "struct",
         TypeInfo::Enum { .. } => "enum",
         TypeInfo::Array(..) => "array",
+        TypeInfo::Vec(..) => "vec",
         TypeInfo::Storage { .. } => "contract storage",
     }
     .to_string()
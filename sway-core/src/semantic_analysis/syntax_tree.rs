@@ -46,6 +46,8 @@ pub enum TypedParseTree {
         namespace: NamespaceRef,
         declarations: Vec<TypedDeclaration>,
         all_nodes: Vec<TypedAstNode>,
+        /// The whole contract, at which problems with the contract as a whole are reported.
+        span: Span,
     },
     Library {
         namespace: NamespaceRef,
@@ -260,6 +262,7 @@ impl TypedParseTree {
                 namespace,
                 declarations,
                 all_nodes,
+                span,
             },
         };
 
@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+
+use crate::asm_generation::program_functions::ProgramLayout;
+
+/// Names for the instructions and data of a program's bytecode, for debuggers and profilers.
+/// Instruction indices count 4 byte instructions from the start of the bytecode, as the opcode
+/// indices of the [SourceMap](crate::source_map::SourceMap) do.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SymbolTable {
+    /// The range of instructions generated for each function.  As functions are inlined into
+    /// their callers, a function called from another has a scope nested within the scope of its
+    /// caller, and is listed after it.
+    pub functions: Vec<FunctionScope>,
+    /// The entry point of each contract ABI method.
+    pub abi_methods: Vec<AbiMethodSymbol>,
+    /// Each entry of the data section which follows the instructions.
+    pub data: Vec<DataSymbol>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionScope {
+    pub name: String,
+    pub range: InstructionRange,
+    /// The number of scopes this scope is nested within, which is 0 for the entry points.
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbiMethodSymbol {
+    pub name: String,
+    pub selector: u32,
+    /// The index of the first instruction of the method.
+    pub entry: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataSymbol {
+    /// The offset of the entry in bytes from the start of the bytecode.
+    pub offset: usize,
+    pub size: usize,
+    /// The name of the constant the entry holds the value of, if any.
+    pub name: Option<String>,
+    /// The index of the entry which this entry is a pointer to, for values larger than a word.
+    pub pointer_to: Option<usize>,
+}
+
+/// A half open range of instruction indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstructionRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl InstructionRange {
+    pub fn contains(&self, pc: usize) -> bool {
+        self.start <= pc && pc < self.end
+    }
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The function scopes containing an instruction, from the outermost to the innermost.
+    pub fn scopes_at(&self, pc: usize) -> Vec<&FunctionScope> {
+        let mut scopes = self
+            .functions
+            .iter()
+            .filter(|scope| scope.range.contains(pc))
+            .collect::<Vec<_>>();
+        scopes.sort_by_key(|scope| scope.depth);
+        scopes
+    }
+
+    /// The ABI method with its entry point at an instruction.
+    pub fn abi_method_at(&self, pc: usize) -> Option<&AbiMethodSymbol> {
+        self.abi_methods.iter().find(|method| method.entry == pc)
+    }
+
+    /// The index of the data section entry containing a byte offset into the bytecode.
+    pub fn data_at(&self, offset: usize) -> Option<(usize, &DataSymbol)> {
+        self.data
+            .iter()
+            .enumerate()
+            .find(|(_, entry)| entry.offset <= offset && offset < entry.offset + entry.size)
+    }
+
+    pub(crate) fn insert_program(&mut self, layout: &ProgramLayout) {
+        let ProgramLayout {
+            functions,
+            offsets,
+            abi_entries,
+            ..
+        } = layout;
+
+        self.functions = layout
+            .function_scopes()
+            .into_iter()
+            .map(|(body_ix, range, depth)| FunctionScope {
+                name: functions.bodies[body_ix].0.clone(),
+                range,
+                depth,
+            })
+            .collect();
+
+        self.abi_methods = abi_entries
+            .iter()
+            .filter_map(|(body_ix, entry)| {
+                let (selector, _) = functions
+                    .abi_selectors
+                    .iter()
+                    .find(|(_, selector_body_ix)| selector_body_ix == body_ix)?;
+                Some(AbiMethodSymbol {
+                    name: functions.bodies[*body_ix].0.clone(),
+                    selector: *selector,
+                    entry: offsets[*entry] as usize,
+                })
+            })
+            .collect();
+
+        let names = layout.data_entry_names();
+        let pointer_targets = layout.data_pointer_targets();
+        let mut offset = layout.program_bytes();
+        self.data = layout
+            .data_section
+            .value_pairs
+            .iter()
+            .enumerate()
+            .map(|(ix, value)| {
+                let size = value.to_bytes().len();
+                let entry = DataSymbol {
+                    offset,
+                    size,
+                    name: names.get(&ix).cloned(),
+                    pointer_to: pointer_targets.get(&ix).copied(),
+                };
+                offset += size;
+                entry
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{debug_info::DebugInfo, test_utils, CompilationResult};
+
+    // Compile `source` and return its symbol table, as read back from the JSON written by
+    // `forc build`.
+    fn symbol_table(source: &str) -> SymbolTable {
+        let mut symbol_table = SymbolTable::new();
+        let result = test_utils::compile_to_asm_with_tables(
+            source,
+            test_utils::build_config(),
+            &mut symbol_table,
+            &mut DebugInfo::new(),
+        );
+        assert!(
+            matches!(result, CompilationResult::Success { .. }),
+            "Program failed to compile."
+        );
+        let json = serde_json::to_string_pretty(&symbol_table).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn scope_names(scopes: &[&FunctionScope]) -> Vec<(String, usize)> {
+        scopes
+            .iter()
+            .map(|scope| (scope.name.clone(), scope.depth))
+            .collect()
+    }
+
+    #[test]
+    fn script_inlined_calls() {
+        let symbol_table = symbol_table(
+            r#"
+    script;
+    fn inner() -> b256 {
+        0x0000000000000000000000000000000000000000000000000000000000000001
+    }
+    fn outer() -> b256 {
+        inner()
+    }
+    fn main() -> b256 {
+        outer()
+    }"#,
+        );
+        // Each function is inlined into its caller, so its scope is nested within the caller's.
+        let scopes = symbol_table.functions.iter().collect::<Vec<_>>();
+        assert_eq!(
+            scope_names(&scopes),
+            [
+                ("main".to_owned(), 0),
+                ("outer".to_owned(), 1),
+                ("inner".to_owned(), 2)
+            ]
+        );
+        for pair in scopes.windows(2) {
+            assert!(pair[0].range.start <= pair[1].range.start);
+            assert!(pair[1].range.end <= pair[0].range.end);
+        }
+        let inner = scopes[2];
+        assert_eq!(
+            scope_names(&symbol_table.scopes_at(inner.range.start)),
+            scope_names(&scopes)
+        );
+        assert!(symbol_table.scopes_at(inner.range.end).is_empty());
+        assert!(symbol_table.abi_methods.is_empty());
+
+        // The b256 literal is in the data section after the instructions, along with a pointer
+        // to it.
+        let mut offset = symbol_table.data[0].offset;
+        assert_eq!(offset % 8, 0);
+        for (ix, entry) in symbol_table.data.iter().enumerate() {
+            assert_eq!(entry.offset, offset);
+            assert_eq!(symbol_table.data_at(offset + entry.size - 1).unwrap().0, ix);
+            offset += entry.size;
+        }
+        assert!(symbol_table.data_at(offset).is_none());
+        let b256_ix = symbol_table
+            .data
+            .iter()
+            .position(|entry| entry.size == 32)
+            .unwrap();
+        assert!(symbol_table
+            .data
+            .iter()
+            .any(|entry| entry.pointer_to == Some(b256_ix)));
+    }
+
+    #[test]
+    fn contract_inlined_calls() {
+        let symbol_table = symbol_table(
+            r#"
+    contract;
+    fn helper() -> u64 {
+        7
+    }
+    abi Counter {
+        fn get() -> u64;
+        fn reset() -> u64;
+    }
+    impl Counter for Contract {
+        fn get() -> u64 {
+            helper()
+        }
+        fn reset() -> u64 {
+            0
+        }
+    }"#,
+        );
+        // The helper is inlined into the ABI method which calls it.
+        let scopes = symbol_table.functions.iter().collect::<Vec<_>>();
+        assert_eq!(
+            scope_names(&scopes),
+            [
+                ("get".to_owned(), 0),
+                ("helper".to_owned(), 1),
+                ("reset".to_owned(), 0)
+            ]
+        );
+        let (get, helper, reset) = (scopes[0], scopes[1], scopes[2]);
+        assert!(get.range.start <= helper.range.start && helper.range.end <= get.range.end);
+        assert!(get.range.end <= reset.range.start);
+
+        // Each ABI method starts at the start of its scope.
+        let methods = symbol_table
+            .abi_methods
+            .iter()
+            .map(|method| (method.name.as_str(), method.entry))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            methods,
+            [("get", get.range.start), ("reset", reset.range.start)]
+        );
+        assert_ne!(
+            symbol_table.abi_methods[0].selector,
+            symbol_table.abi_methods[1].selector
+        );
+        assert_eq!(
+            symbol_table.abi_method_at(reset.range.start).unwrap().name,
+            "reset"
+        );
+        assert!(symbol_table.abi_method_at(reset.range.start + 1).is_none());
+    }
+}
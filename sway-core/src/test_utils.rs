@@ -0,0 +1,221 @@
+//! Helpers for the unit tests which compile Sway snippets and run them in the VM.
+
+use crate::{
+    asm_generation::{DataSection, InstructionSet},
+    asm_lang::allocated_ops::{AllocatedOp, AllocatedOpcode},
+    create_module,
+    debug_info::DebugInfo,
+    source_map::SourceMap,
+    symbol_table::SymbolTable,
+    BuildConfig, CompilationResult, FinalizedAsm, NamespaceRef, NamespaceWrapper,
+};
+use fuel_vm::{interpreter::Interpreter, prelude::*};
+
+/// Arithmetic and comparison functions for snippets, which have no core library operators.
+pub(crate) const ASM_OPERATORS: &str = r#"
+    fn add(a: u64, b: u64) -> u64 {
+        asm(a: a, b: b, r) {
+            add r a b;
+            r: u64
+        }
+    }
+    fn mul(a: u64, b: u64) -> u64 {
+        asm(a: a, b: b, r) {
+            mul r a b;
+            r: u64
+        }
+    }
+    fn lt(a: u64, b: u64) -> bool {
+        asm(a: a, b: b, r) {
+            lt r a b;
+            r: bool
+        }
+    }
+    fn gt(a: u64, b: u64) -> bool {
+        asm(a: a, b: b, r) {
+            gt r a b;
+            r: bool
+        }
+    }
+    fn eq(a: u64, b: u64) -> bool {
+        asm(a: a, b: b, r) {
+            eq r a b;
+            r: bool
+        }
+    }"#;
+
+/// The `core::ops` functions which `match` on an integer is desugared to calls of.
+const CORE_OPS: &str = r#"
+    library ops;
+
+    pub trait Eq {
+        fn eq(self, other: Self) -> bool;
+    }
+
+    impl Eq for u64 {
+        fn eq(self, other: Self) -> bool {
+            asm(r1: self, r2: other, r3) {
+                eq r3 r1 r2;
+                r3: bool
+            }
+        }
+    }"#;
+
+/// A root module for snippets, with just enough of `core` for them to `match` on a `u64`.
+pub(crate) fn root_module() -> NamespaceRef {
+    let ops = match compile_to_asm_in(
+        CORE_OPS,
+        create_module(),
+        build_config(),
+        &mut SymbolTable::new(),
+        &mut DebugInfo::new(),
+    ) {
+        CompilationResult::Library { namespace, .. } => namespace,
+        CompilationResult::Failure { errors, .. } => panic!("Failed to compile: {:?}", errors),
+        CompilationResult::Success { .. } => panic!("Compiled to a program."),
+    };
+    let core = create_module();
+    core.insert_module_ref("ops".into(), ops);
+    let root = create_module();
+    root.insert_module_ref("core".into(), core);
+    root
+}
+
+/// The build config for a snippet compiled as `main.sw` in the current directory.
+pub(crate) fn build_config() -> BuildConfig {
+    BuildConfig::root_from_file_name_and_manifest_path("main.sw".into(), ".".into())
+}
+
+/// Compile `source` to asm.
+pub(crate) fn compile_to_asm(source: &str, build_config: BuildConfig) -> CompilationResult {
+    compile_to_asm_with_tables(
+        source,
+        build_config,
+        &mut SymbolTable::new(),
+        &mut DebugInfo::new(),
+    )
+}
+
+/// Compile `source` to asm, filling in `symbol_table` and `debug_info`.
+pub(crate) fn compile_to_asm_with_tables(
+    source: &str,
+    build_config: BuildConfig,
+    symbol_table: &mut SymbolTable,
+    debug_info: &mut DebugInfo,
+) -> CompilationResult {
+    compile_to_asm_in(
+        source,
+        root_module(),
+        build_config,
+        symbol_table,
+        debug_info,
+    )
+}
+
+fn compile_to_asm_in(
+    source: &str,
+    namespace: NamespaceRef,
+    build_config: BuildConfig,
+    symbol_table: &mut SymbolTable,
+    debug_info: &mut DebugInfo,
+) -> CompilationResult {
+    crate::compile_to_asm(
+        source.into(),
+        namespace,
+        build_config,
+        symbol_table,
+        debug_info,
+    )
+}
+
+/// Compile `source` to bytecode, which it must compile to without errors.
+pub(crate) fn compile_to_bytes(source: &str, build_config: BuildConfig) -> Vec<u8> {
+    match compile_to_asm(source, build_config) {
+        CompilationResult::Success { asm, .. } => assemble(asm),
+        CompilationResult::Failure { errors, .. } => panic!("Failed to compile: {:?}", errors),
+        CompilationResult::Library { .. } => panic!("Compiled to a library."),
+    }
+}
+
+/// Assemble `asm` to bytecode.
+pub(crate) fn assemble(mut asm: FinalizedAsm) -> Vec<u8> {
+    asm.to_bytecode_mut(&mut SourceMap::new())
+        .value
+        .expect("valid bytecode")
+}
+
+/// Run `script` in the VM and return its final state.
+pub(crate) fn run_script(script: Vec<u8>) -> ProgramState {
+    let tx = Transaction::script(0, 1_000_000, 0, 0, script, vec![], vec![], vec![], vec![]);
+    *Interpreter::with_storage(MemoryStorage::default())
+        .transact(tx)
+        .unwrap()
+        .state()
+}
+
+/// Run `ops` as a script in the VM and return its final state.  A `NOOP` is appended to an odd
+/// number of ops, as the program section must be a whole number of words.
+pub(crate) fn run_ops(mut ops: Vec<AllocatedOp>) -> ProgramState {
+    if ops.len() & 1 != 0 {
+        ops.push(AllocatedOp {
+            opcode: AllocatedOpcode::NOOP,
+            comment: String::new(),
+            owning_span: None,
+            defines: Vec::new(),
+        });
+    }
+    run_script(assemble(FinalizedAsm::ScriptMain {
+        program_section: InstructionSet { ops },
+        data_section: DataSection::default(),
+    }))
+}
+
+/// Compile the script `source` with each code generator, run it and check its final state.
+pub(crate) fn check_with_each_backend(source: &str, expected: ProgramState) {
+    for use_ir in [false, true] {
+        let state = run_script(compile_to_bytes(source, build_config().use_ir(use_ir)));
+        assert_eq!(state, expected, "use_ir: {}\n{}", use_ir, source);
+    }
+}
+
+/// Verify `predicate` with `predicate_data` in the VM, returning whether it holds.  The VM only
+/// verifies the predicates of transactions which create a contract, so the predicate is for a coin
+/// spent by one.
+pub(crate) fn predicate_holds(predicate: Vec<u8>, predicate_data: Vec<u8>) -> bool {
+    let program: Witness = vec![0; 8].into();
+    let salt = Salt::default();
+    let code = Contract::from(program.as_ref());
+    let state_root = Contract::default_state_root();
+    let contract_id = code.id(&salt, &code.root(), &state_root);
+    let coin = Input::coin(
+        Default::default(),
+        Default::default(),
+        0,
+        Default::default(),
+        0,
+        0,
+        predicate,
+        predicate_data,
+    );
+    let tx = Transaction::create(
+        0,
+        1_000_000,
+        0,
+        0,
+        0,
+        salt,
+        vec![],
+        vec![],
+        vec![coin],
+        vec![Output::contract_created(contract_id, state_root)],
+        vec![program],
+    );
+    match Interpreter::with_storage(MemoryStorage::default()).transact(tx) {
+        Ok(state_transition) => match state_transition.state() {
+            ProgramState::Return(1) => true,
+            state => panic!("Unexpected predicate state: {:?}", state),
+        },
+        Err(InterpreterError::PredicateFailure) => false,
+        Err(error) => panic!("Predicate failed to run: {:?}", error),
+    }
+}
@@ -51,6 +51,12 @@ impl ToJsonAbi for TypeId {
                     .map(|x| x.generate_json_abi())
                     .collect(),
             ),
+            // Vectors are encoded as a pointer and a length, so describe the element type.
+            TypeInfo::Vec(elem_ty) => Some(vec![Property {
+                name: "__vec_element".into(),
+                type_field: elem_ty.json_abi_str(),
+                components: elem_ty.generate_json_abi(),
+            }]),
             _ => None,
         }
     }
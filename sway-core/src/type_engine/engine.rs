@@ -75,30 +75,31 @@ impl Engine {
                 Ok(warnings)
             }
 
+            // Integers which don't share a representation can't be implicitly cast, e.g., a `u64`
+            // is held in a register but a `u256` is a reference to four words.
             (
                 ref received_info @ UnsignedInteger(received_width),
                 ref expected_info @ UnsignedInteger(expected_width),
-            ) => {
-                // E.g., in a variable declaration `let a: u32 = 10u64` the 'expected' type will be
-                // the annotation `u32`, and the 'received' type is 'self' of the initialiser, or
-                // `u64`.  So we're casting received TO expected.
-                let warn = match numeric_cast_compat(expected_width, received_width) {
-                    NumericCastCompatResult::CastableWithWarning(warn) => {
-                        vec![CompileWarning {
-                            span: span.clone(),
-                            warning_content: warn,
-                        }]
-                    }
-                    NumericCastCompatResult::Compatible => {
-                        vec![]
-                    }
-                };
-
-                // Cast the expected type to the received type.
-                self.slab
-                    .replace(received, received_info, expected_info.clone());
-                Ok(warn)
-            }
+            ) if received_width.size_in_words() == expected_width.size_in_words() => Ok(self
+                .cast_integer(
+                    received,
+                    received_info,
+                    expected_info,
+                    received_width,
+                    expected_width,
+                    span,
+                )),
+            (
+                ref received_info @ SignedInteger(received_width),
+                ref expected_info @ SignedInteger(expected_width),
+            ) => Ok(self.cast_integer(
+                received,
+                received_info,
+                expected_info,
+                received_width.into(),
+                expected_width.into(),
+                span,
+            )),
 
             (ref received_info @ UnknownGeneric { .. }, _) => {
                 self.slab
@@ -150,13 +151,13 @@ impl Engine {
                 Ok(vec![])
             }
 
-            (Numeric, expected_info @ UnsignedInteger(_)) => {
+            (Numeric, expected_info @ (UnsignedInteger(_) | SignedInteger(_))) => {
                 match self.slab.replace(received, &Numeric, expected_info) {
                     None => Ok(vec![]),
                     Some(_) => self.unify(received, expected, span, help_text),
                 }
             }
-            (received_info @ UnsignedInteger(_), Numeric) => {
+            (received_info @ (UnsignedInteger(_) | SignedInteger(_)), Numeric) => {
                 match self.slab.replace(expected, &Numeric, received_info) {
                     None => Ok(vec![]),
                     Some(_) => self.unify(received, expected, span, help_text),
@@ -174,6 +175,15 @@ impl Engine {
                     span: span.clone(),
                 }),
 
+            (Vec(a_elem), Vec(b_elem)) => self
+                .unify(a_elem, b_elem, span, help_text.clone())
+                .map_err(|_| TypeError::MismatchedType {
+                    expected,
+                    received,
+                    help_text,
+                    span: span.clone(),
+                }),
+
             // When unifying complex types, we must check their sub-types. This
             // can be trivially implemented for tuples, sum types, etc.
             // (List(a_item), List(b_item)) => self.unify(a_item, b_item),
@@ -196,6 +206,37 @@ impl Engine {
         }
     }
 
+    /// Cast the integer type `received` to `expected`, warning if this may lose precision.
+    fn cast_integer(
+        &self,
+        received: TypeId,
+        received_info: &TypeInfo,
+        expected_info: &TypeInfo,
+        received_width: IntegerBits,
+        expected_width: IntegerBits,
+        span: &Span,
+    ) -> Vec<CompileWarning> {
+        // E.g., in a variable declaration `let a: u32 = 10u64` the 'expected' type will be the
+        // annotation `u32`, and the 'received' type is 'self' of the initialiser, or `u64`.  So
+        // we're casting received TO expected.
+        let warn = match numeric_cast_compat(expected_width, received_width) {
+            NumericCastCompatResult::CastableWithWarning(warn) => {
+                vec![CompileWarning {
+                    span: span.clone(),
+                    warning_content: warn,
+                }]
+            }
+            NumericCastCompatResult::Compatible => {
+                vec![]
+            }
+        };
+
+        // Cast the expected type to the received type.
+        self.slab
+            .replace(received, received_info, expected_info.clone());
+        warn
+    }
+
     pub fn unify_with_self(
         &self,
         received: TypeId,
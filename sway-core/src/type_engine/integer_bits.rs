@@ -4,6 +4,8 @@ pub enum IntegerBits {
     Sixteen,
     ThirtyTwo,
     SixtyFour,
+    OneTwentyEight,
+    TwoFiftySix,
 }
 
 impl IntegerBits {
@@ -14,6 +16,44 @@ impl IntegerBits {
             Sixteen => "sixteen",
             ThirtyTwo => "thirty two",
             SixtyFour => "sixty four",
+            OneTwentyEight => "one hundred twenty eight",
+            TwoFiftySix => "two hundred fifty six",
+        }
+    }
+
+    /// Integers up to 64 bits are held in a single register.  The wider integers don't fit and
+    /// are instead passed by reference, like `b256`, with their words stored big-endian.
+    pub(crate) fn size_in_words(&self) -> u64 {
+        use IntegerBits::*;
+        match self {
+            Eight | Sixteen | ThirtyTwo | SixtyFour => 1,
+            OneTwentyEight => 2,
+            TwoFiftySix => 4,
+        }
+    }
+
+    pub(crate) fn is_wide(&self) -> bool {
+        self.size_in_words() > 1
+    }
+}
+
+/// The widths of the signed integers.  These are all held in a single register, sign extended to
+/// a full word, as there are no wide signed integers.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy)]
+pub enum SignedIntegerBits {
+    Eight,
+    Sixteen,
+    ThirtyTwo,
+    SixtyFour,
+}
+
+impl From<SignedIntegerBits> for IntegerBits {
+    fn from(bits: SignedIntegerBits) -> Self {
+        match bits {
+            SignedIntegerBits::Eight => IntegerBits::Eight,
+            SignedIntegerBits::Sixteen => IntegerBits::Sixteen,
+            SignedIntegerBits::ThirtyTwo => IntegerBits::ThirtyTwo,
+            SignedIntegerBits::SixtyFour => IntegerBits::SixtyFour,
         }
     }
 }
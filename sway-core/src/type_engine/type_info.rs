@@ -23,6 +23,7 @@ pub enum TypeInfo {
     },
     Str(u64),
     UnsignedInteger(IntegerBits),
+    SignedInteger(SignedIntegerBits),
     Enum {
         name: Ident,
         variant_types: Vec<TypedEnumVariant>,
@@ -64,6 +65,9 @@ pub enum TypeInfo {
     ErrorRecovery,
     // Static, constant size arrays.
     Array(TypeId, usize),
+    /// A growable vector allocated on the heap.  A value of this type is a single word pointing
+    /// at a header of three words: the element buffer pointer, its capacity and its length.
+    Vec(TypeId),
     /// Represents the entire storage declaration struct
     /// Stored without initializers here, as typed struct fields,
     /// so type checking is able to treat it as a struct with fields.
@@ -124,12 +128,21 @@ impl TypeInfo {
                 "u16" => TypeInfo::UnsignedInteger(IntegerBits::Sixteen),
                 "u32" => TypeInfo::UnsignedInteger(IntegerBits::ThirtyTwo),
                 "u64" => TypeInfo::UnsignedInteger(IntegerBits::SixtyFour),
+                "u128" => TypeInfo::UnsignedInteger(IntegerBits::OneTwentyEight),
+                "u256" => TypeInfo::UnsignedInteger(IntegerBits::TwoFiftySix),
+                "i8" => TypeInfo::SignedInteger(SignedIntegerBits::Eight),
+                "i16" => TypeInfo::SignedInteger(SignedIntegerBits::Sixteen),
+                "i32" => TypeInfo::SignedInteger(SignedIntegerBits::ThirtyTwo),
+                "i64" => TypeInfo::SignedInteger(SignedIntegerBits::SixtyFour),
                 "bool" => TypeInfo::Boolean,
                 "unit" => TypeInfo::Tuple(Vec::new()),
                 "byte" => TypeInfo::Byte,
                 "b256" => TypeInfo::B256,
                 "Self" | "self" => TypeInfo::SelfType,
                 "Contract" => TypeInfo::Contract,
+                "Bytes" => {
+                    TypeInfo::Vec(insert_type(TypeInfo::UnsignedInteger(IntegerBits::Eight)))
+                }
                 _other => TypeInfo::Custom {
                     name: Ident::new(span),
                 },
@@ -203,6 +216,26 @@ impl TypeInfo {
                 };
                 TypeInfo::Array(insert_type(elem_type_info), elem_count)
             }
+            Rule::vec_type => {
+                let elem_type_info = match input.into_inner().next() {
+                    None => {
+                        errors.push(CompileError::Internal(
+                            "Missing vector element type while parsing vector type.",
+                            span,
+                        ));
+                        return err(warnings, errors);
+                    }
+                    Some(vec_elem_type_pair) => {
+                        check!(
+                            Self::parse_from_pair(vec_elem_type_pair, config),
+                            return err(warnings, errors),
+                            warnings,
+                            errors
+                        )
+                    }
+                };
+                TypeInfo::Vec(insert_type(elem_type_info))
+            }
             Rule::tuple_type => {
                 let mut field_type_ids = vec![];
                 for field in input.into_inner() {
@@ -239,6 +272,15 @@ impl TypeInfo {
                 IntegerBits::Sixteen => "u16",
                 IntegerBits::ThirtyTwo => "u32",
                 IntegerBits::SixtyFour => "u64",
+                IntegerBits::OneTwentyEight => "u128",
+                IntegerBits::TwoFiftySix => "u256",
+            }
+            .into(),
+            SignedInteger(x) => match x {
+                SignedIntegerBits::Eight => "i8",
+                SignedIntegerBits::Sixteen => "i16",
+                SignedIntegerBits::ThirtyTwo => "i32",
+                SignedIntegerBits::SixtyFour => "i64",
             }
             .into(),
             Boolean => "bool".into(),
@@ -248,7 +290,7 @@ impl TypeInfo {
                 let field_strs = fields
                     .iter()
                     .map(|field| field.friendly_type_str())
-                    .collect::<Vec<String>>();
+                    .collect::<std::vec::Vec<String>>();
                 format!("({})", field_strs.join(", "))
             }
             SelfType => "Self".into(),
@@ -271,6 +313,7 @@ impl TypeInfo {
                 format!("contract caller {}", abi_name.suffix)
             }
             Array(elem_ty, count) => format!("[{}; {}]", elem_ty.friendly_type_str(), count),
+            Vec(elem_ty) => format!("Vec<{}>", elem_ty.friendly_type_str()),
             Storage { .. } => "contract storage".into(),
         }
     }
@@ -286,6 +329,15 @@ impl TypeInfo {
                 IntegerBits::Sixteen => "u16",
                 IntegerBits::ThirtyTwo => "u32",
                 IntegerBits::SixtyFour => "u64",
+                IntegerBits::OneTwentyEight => "u128",
+                IntegerBits::TwoFiftySix => "u256",
+            }
+            .into(),
+            SignedInteger(x) => match x {
+                SignedIntegerBits::Eight => "i8",
+                SignedIntegerBits::Sixteen => "i16",
+                SignedIntegerBits::ThirtyTwo => "i32",
+                SignedIntegerBits::SixtyFour => "i64",
             }
             .into(),
             Boolean => "bool".into(),
@@ -295,7 +347,7 @@ impl TypeInfo {
                 let field_strs = fields
                     .iter()
                     .map(|field| field.json_abi_str())
-                    .collect::<Vec<String>>();
+                    .collect::<std::vec::Vec<String>>();
                 format!("({})", field_strs.join(", "))
             }
             SelfType => "Self".into(),
@@ -314,6 +366,7 @@ impl TypeInfo {
                 format!("contract caller {}", abi_name.suffix)
             }
             Array(elem_ty, count) => format!("[{}; {}]", elem_ty.json_abi_str(), count),
+            Vec(elem_ty) => format!("Vec<{}>", elem_ty.json_abi_str()),
             Storage { .. } => "contract storage".into(),
         }
     }
@@ -330,6 +383,18 @@ impl TypeInfo {
                     Sixteen => "u16",
                     ThirtyTwo => "u32",
                     SixtyFour => "u64",
+                    OneTwentyEight => "u128",
+                    TwoFiftySix => "u256",
+                }
+                .into()
+            }
+            SignedInteger(bits) => {
+                use SignedIntegerBits::*;
+                match bits {
+                    Eight => "i8",
+                    Sixteen => "i16",
+                    ThirtyTwo => "i32",
+                    SixtyFour => "i64",
                 }
                 .into()
             }
@@ -344,7 +409,7 @@ impl TypeInfo {
                                 .expect("unreachable?")
                                 .to_selector_name(error_msg_span)
                         })
-                        .collect::<Vec<CompileResult<String>>>();
+                        .collect::<std::vec::Vec<CompileResult<String>>>();
                     let mut buf = vec![];
                     for name in names {
                         match name.value {
@@ -368,7 +433,7 @@ impl TypeInfo {
                                 .expect("unreachable?")
                                 .to_selector_name(error_msg_span)
                         })
-                        .collect::<Vec<CompileResult<String>>>();
+                        .collect::<std::vec::Vec<CompileResult<String>>>();
                     let mut buf = vec![];
                     for name in names {
                         match name.value {
@@ -392,7 +457,7 @@ impl TypeInfo {
                             };
                             ty.to_selector_name(error_msg_span)
                         })
-                        .collect::<Vec<CompileResult<String>>>();
+                        .collect::<std::vec::Vec<CompileResult<String>>>();
                     let mut buf = vec![];
                     for name in names {
                         match name.value {
@@ -405,6 +470,17 @@ impl TypeInfo {
 
                 format!("e({})", variant_names.join(","))
             }
+            Vec(elem_ty) => {
+                let elem_ty = match resolve_type(*elem_ty, error_msg_span) {
+                    Err(e) => return err(vec![], vec![e.into()]),
+                    Ok(ty) => ty,
+                };
+                let elem_name = elem_ty.to_selector_name(error_msg_span);
+                match elem_name.value {
+                    Some(ref value) => format!("v({})", value),
+                    None => return elem_name,
+                }
+            }
             _ => {
                 return err(
                     vec![],
@@ -425,8 +501,10 @@ impl TypeInfo {
             // Each char is a byte, so the size is the num of characters / 8
             // rounded up to the nearest word
             TypeInfo::Str(len) => Ok((len + 7) / 8),
-            // Since things are unpacked, all unsigned integers are 64 bits.....for now
-            TypeInfo::UnsignedInteger(_) | TypeInfo::Numeric => Ok(1),
+            // Since things are unpacked, all integers up to 64 bits take a full word.  Signed
+            // integers are sign extended to the full word.
+            TypeInfo::UnsignedInteger(bits) => Ok(bits.size_in_words()),
+            TypeInfo::SignedInteger(_) | TypeInfo::Numeric => Ok(1),
             TypeInfo::Boolean => Ok(1),
             TypeInfo::Tuple(fields) => Ok(fields
                 .iter()
@@ -477,6 +555,8 @@ impl TypeInfo {
             TypeInfo::Array(elem_ty, count) => {
                 Ok(look_up_type_id(*elem_ty).size_in_words(err_span)? * *count as u64)
             }
+            // The vector itself is just a pointer to its header on the heap.
+            TypeInfo::Vec(_) => Ok(1),
             TypeInfo::Storage { .. } => Ok(0),
         }
     }
@@ -484,8 +564,20 @@ impl TypeInfo {
     pub(crate) fn is_copy_type(&self) -> bool {
         matches!(
             self,
-            TypeInfo::UnsignedInteger(_) | TypeInfo::Boolean | TypeInfo::Byte
-        )
+            TypeInfo::UnsignedInteger(_)
+                | TypeInfo::SignedInteger(_)
+                | TypeInfo::Boolean
+                | TypeInfo::Byte
+                | TypeInfo::Vec(_)
+        ) && !self.is_wide_integer()
+    }
+
+    /// Wide integers, such as `u256`, are larger than a word and so are passed by reference.
+    pub(crate) fn is_wide_integer(&self) -> bool {
+        match self {
+            TypeInfo::UnsignedInteger(bits) => bits.is_wide(),
+            _ => false,
+        }
     }
 
     pub fn is_uninhabited(&self) -> bool {
@@ -614,8 +706,11 @@ impl TypeInfo {
             TypeInfo::Array(ary_ty_id, count) => look_up_type_id(*ary_ty_id)
                 .matches_type_parameter(mapping)
                 .map(|matching_id| insert_type(TypeInfo::Array(matching_id, *count))),
+            TypeInfo::Vec(elem_ty_id) => look_up_type_id(*elem_ty_id)
+                .matches_type_parameter(mapping)
+                .map(|matching_id| insert_type(TypeInfo::Vec(matching_id))),
             TypeInfo::Tuple(fields) => {
-                let mut new_fields = Vec::new();
+                let mut new_fields = std::vec::Vec::new();
                 let mut index = 0;
                 while index < fields.len() {
                     let new_field_id_opt =
@@ -646,6 +741,7 @@ impl TypeInfo {
             Unknown
             | Str(..)
             | UnsignedInteger(..)
+            | SignedInteger(..)
             | Boolean
             | Ref(..)
             | ContractCaller { .. }
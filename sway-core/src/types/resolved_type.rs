@@ -9,6 +9,7 @@ pub enum ResolvedType {
     /// The number in a `Str` represents its size, which must be known at compile time
     Str(u64),
     UnsignedInteger(IntegerBits),
+    SignedInteger(SignedIntegerBits),
     Boolean,
     Unit,
     Byte,
@@ -58,8 +59,9 @@ impl ResolvedType {
             // Each char is a byte, so the size is the num of characters / 8
             // rounded up to the nearest word
             ResolvedType::Str(len) => (len + 7) / 8,
-            // Since things are unpacked, all unsigned integers are 64 bits.....for now
-            ResolvedType::UnsignedInteger(_) => 1,
+            // Since things are unpacked, all integers up to 64 bits take a full word
+            ResolvedType::UnsignedInteger(bits) => bits.size_in_words(),
+            ResolvedType::SignedInteger(_) => 1,
             ResolvedType::Boolean => 1,
             ResolvedType::Unit => 0,
             ResolvedType::Byte => 1,
@@ -91,6 +93,9 @@ impl ResolvedType {
     }
 
     pub fn is_numeric(&self) -> bool {
-        matches!(self, ResolvedType::UnsignedInteger(_))
+        matches!(
+            self,
+            ResolvedType::UnsignedInteger(_) | ResolvedType::SignedInteger(_)
+        )
     }
 }
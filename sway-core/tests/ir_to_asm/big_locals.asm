@@ -0,0 +1,39 @@
+.program:
+ji   i4
+noop
+DATA_SECTION_OFFSET[0..32]
+DATA_SECTION_OFFSET[32..64]
+lw   $ds $is 1
+add  $$ds $$ds $is
+move $r4 $sp                  ; save locals base register
+cfei i16777208                ; allocate 16809608 bytes for all locals
+cfei i32400                   ; allocate 16809608 bytes for all locals
+lw   $r1 data_0               ; get extract offset
+add  $r1 $r4 $r1              ; get_ptr
+lw   $r0 data_1               ; literal instantiation
+sw   $r1 $r0 i0               ; insert_value @ 0
+lw   $r3 data_2               ; get extract offset
+add  $r3 $r4 $r3              ; get_ptr
+lw   $r1 data_3               ; literal instantiation
+lw   $r0 data_4               ; loading element size for relative offset
+mul  $r2 $r1 $r0              ; extract_element relative offset
+add  $r2 $r3 $r2              ; extract_element absolute offset
+lw   $r0 data_5               ; literal instantiation
+ori  $r1 $zero i1             ; get extract offset high bits
+slli $r1 $r1 i12              ; shift extract offset high bits
+ori  $r1 $r1 i704             ; get extract offset low bits
+mul  $r0 $r0 $r1              ; insert_element relative offset
+add  $r0 $r3 $r0              ; insert_element absolute offset
+mcp  $r0 $r2 $r1              ; insert_element store value
+lw   $r0 data_0               ; get extract offset
+add  $r0 $r4 $r0              ; get_ptr
+lw   $r0 $r0 i0               ; extract_value @ 0
+ret  $r0
+noop                          ; word-alignment of data section
+.data:
+data_0 .u64 0x1007e80
+data_1 .u64 0x2a
+data_2 .u64 0x1005900
+data_3 .u64 0x01
+data_4 .u64 0x12c0
+data_5 .u64 0x00
@@ -0,0 +1,30 @@
+.program:
+ji   i4
+noop
+DATA_SECTION_OFFSET[0..32]
+DATA_SECTION_OFFSET[32..64]
+lw   $ds $is 1
+add  $$ds $$ds $is
+move $r2 $sp                  ; save locals base register
+cfei i16                      ; allocate 16 bytes for all locals
+lw   $r0 data_2               ; literal instantiation for aggregate field
+sw   $r2 $r0 i0               ; initialise aggregate field
+lw   $r0 data_3               ; literal instantiation for aggregate field
+sw   $r2 $r0 i1               ; initialise aggregate field
+lw   $r0 data_0               ; get_ptr to constant
+move $r1 $sp                  ; save register for temporary stack value
+cfei i32                      ; allocate 32 bytes for temporary constant
+mcpi $r1 $r0 i32              ; copy constant to temporary
+move $r0 $r2                  ; get_ptr
+lw   $r0 $r0 i1               ; extract_value @ 1
+sw   $r1 $r0 i0               ; asm block
+lw   $r1 data_1               ; get_ptr to constant
+lw   $r0 data_4               ; loading size for RETD
+retd  $r1 $r0
+noop                          ; word-alignment of data section
+.data:
+data_0 .b256 0x0202020202020202020202020202020202020202020202020202020202020202
+data_1 .b256 0x0303030303030303030303030303030303030303030303030303030303030303
+data_2 .u64 0x01
+data_3 .u64 0x02
+data_4 .u64 0x20
@@ -0,0 +1,38 @@
+.program:
+ji   i4
+noop
+DATA_SECTION_OFFSET[0..32]
+DATA_SECTION_OFFSET[32..64]
+lw   $ds $is 1
+add  $$ds $$ds $is
+move $r2 $sp                  ; save locals base register
+cfei i32                      ; allocate 32 bytes for all locals
+lw   $r0 data_2               ; literal instantiation
+jnei $r0 $one i17
+lw   $r1 data_0               ; get_ptr to constant
+move $r0 $sp                  ; save register for temporary stack value
+cfei i32                      ; allocate 32 bytes for temporary constant
+mcpi $r0 $r1 i32              ; copy constant to temporary
+addi $r1 $r0 i8               ; get offset
+ji   i23
+lw   $r1 data_0               ; get_ptr to constant
+move $r0 $sp                  ; save register for temporary stack value
+cfei i32                      ; allocate 32 bytes for temporary constant
+mcpi $r0 $r1 i32              ; copy constant to temporary
+addi $r1 $r0 i16              ; get offset
+lw   $r0 data_3               ; literal instantiation
+sw   $r1 $r0 i0               ; asm block
+lw   $r1 data_1               ; get_ptr to constant
+move $r0 $r2                  ; get_ptr
+mcpi $r0 $r1 i32              ; store value
+move $r1 $r2                  ; load address
+lw   $r0 data_3               ; literal instantiation
+sw   $r1 $r0 i0               ; asm block
+lw   $r0 data_4               ; loading size for RETD
+retd  $r1 $r0
+.data:
+data_0 .b256 0x0202020202020202020202020202020202020202020202020202020202020202
+data_1 .b256 0x0303030303030303030303030303030303030303030303030303030303030303
+data_2 .bool 0x01
+data_3 .u64 0x00
+data_4 .u64 0x20
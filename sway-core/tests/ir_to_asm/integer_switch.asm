@@ -0,0 +1,62 @@
+.program:
+ji   i4
+noop
+DATA_SECTION_OFFSET[0..32]
+DATA_SECTION_OFFSET[32..64]
+lw   $ds $is 1
+add  $$ds $$ds $is
+lw   $r1 data_0               ; literal instantiation
+lw   $r0 data_1               ; literal instantiation
+lt   $r0 $r1 $r0
+jnei $r0 $one i11
+ji   i27
+lw   $r1 data_0               ; literal instantiation
+lw   $r0 data_1               ; literal instantiation
+sub  $r2 $r1 $r0
+lw   $r0 data_2               ; literal instantiation
+lt   $r0 $r2 $r0
+jnei $r0 $one i27
+addi $r1 $$ds i24             ; get offset
+lw   $r0 data_9               ; load last valid array index
+gt   $r0 $r2 $r0              ; compare array index for out of bounds
+jnei $r0 $one i23
+lw   $r0 data_10              ; load out of bounds revert code
+rvrt $r0                      ; aborting due to out of bounds access
+muli $r2 $r2 i8               ; extract_element relative offset
+add  $r0 $r1 $r2              ; extract_element absolute offset
+lw   $r2 $r0 i0               ; extract_element
+ji   i28
+lw   $r2 data_11              ; literal instantiation
+lw   $r1 data_12              ; literal instantiation
+lw   $r0 data_11              ; literal instantiation
+eq   $r0 $r1 $r0
+jnei $r0 $one i33
+ji   i38
+lw   $r1 data_12              ; literal instantiation
+lw   $r0 data_13              ; literal instantiation
+eq   $r0 $r1 $r0
+jnei $r0 $one i42
+ji   i40
+lw   $r0 data_14              ; literal instantiation
+ji   i43
+lw   $r0 data_1               ; literal instantiation
+ji   i43
+lw   $r0 data_9               ; literal instantiation
+eq   $r0 $r2 $r0
+ret  $r0
+.data:
+data_0 .u64 0x02
+data_1 .u64 0x01
+data_2 .u64 0x06
+data_3 .u64 0x1f
+data_4 .u64 0x1c
+data_5 .u64 0x1f
+data_6 .u64 0x1e
+data_7 .u64 0x1f
+data_8 .u64 0x1e
+data_9 .u64 0x05
+data_10 .u64 0xffffffffffff0001
+data_11 .u64 0x00
+data_12 .u64 0x2a
+data_13 .u64 0xf4240
+data_14 .u64 0x04
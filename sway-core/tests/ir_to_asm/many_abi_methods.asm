@@ -0,0 +1,67 @@
+.program:
+ji   i4
+noop
+DATA_SECTION_OFFSET[0..32]
+DATA_SECTION_OFFSET[32..64]
+lw   $ds $is 1
+add  $$ds $$ds $is
+lw   $r1 $fp i73              ; load input function selector
+lw   $r0 data_7               ; load fn selector for binary search
+lt   $r0 $r1 $r0              ; function selector binary search
+jnei $one $r0 i20             ; search upper function selectors
+lw   $r0 data_8               ; load fn selector for comparison
+eq   $r0 $r1 $r0              ; function selector comparison
+jnei $zero $r0 i37            ; jump to selected function
+lw   $r0 data_9               ; load fn selector for comparison
+eq   $r0 $r1 $r0              ; function selector comparison
+jnei $zero $r0 i41            ; jump to selected function
+lw   $r0 data_10              ; load fn selector for comparison
+eq   $r0 $r1 $r0              ; function selector comparison
+jnei $zero $r0 i45            ; jump to selected function
+rvrt $zero                    ; revert if no selectors matched
+lw   $r0 data_11              ; load fn selector for binary search
+lt   $r0 $r1 $r0              ; function selector binary search
+jnei $one $r0 i30             ; search upper function selectors
+lw   $r0 data_7               ; load fn selector for comparison
+eq   $r0 $r1 $r0              ; function selector comparison
+jnei $zero $r0 i49            ; jump to selected function
+lw   $r0 data_12              ; load fn selector for comparison
+eq   $r0 $r1 $r0              ; function selector comparison
+jnei $zero $r0 i39            ; jump to selected function
+rvrt $zero                    ; revert if no selectors matched
+lw   $r0 data_11              ; load fn selector for comparison
+eq   $r0 $r1 $r0              ; function selector comparison
+jnei $zero $r0 i47            ; jump to selected function
+lw   $r0 data_13              ; load fn selector for comparison
+eq   $r0 $r1 $r0              ; function selector comparison
+jnei $zero $r0 i43            ; jump to selected function
+rvrt $zero                    ; revert if no selectors matched
+lw   $r0 data_0               ; literal instantiation
+ret  $r0
+lw   $r0 data_1               ; literal instantiation
+ret  $r0
+lw   $r0 data_2               ; literal instantiation
+ret  $r0
+lw   $r0 data_3               ; literal instantiation
+ret  $r0
+lw   $r0 data_4               ; literal instantiation
+ret  $r0
+lw   $r0 data_5               ; literal instantiation
+ret  $r0
+lw   $r0 data_6               ; literal instantiation
+ret  $r0
+.data:
+data_0 .u64 0x01
+data_1 .u64 0x02
+data_2 .u64 0x03
+data_3 .u64 0x04
+data_4 .u64 0x05
+data_5 .u64 0x06
+data_6 .u64 0x07
+data_7 .u32 0x70000007
+data_8 .u32 0x01
+data_9 .u32 0x30000003
+data_10 .u32 0x50000005
+data_11 .u32 0xc0000006
+data_12 .u32 0xa0000002
+data_13 .u32 0xf0000004
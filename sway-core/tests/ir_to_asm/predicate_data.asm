@@ -0,0 +1,17 @@
+.program:
+ji   i4
+noop
+DATA_SECTION_OFFSET[0..32]
+DATA_SECTION_OFFSET[32..64]
+lw   $ds $is 1
+add  $$ds $$ds $is
+subi $r0 $is i16              ; address of the predicate length
+lw   $r0 $r0 i0               ; load the predicate length
+add  $r0 $is $r0              ; address of the predicate data
+lw   $r1 $r0 i0               ; Get arg a
+addi $r0 $r0 i8               ; Get address for arg key
+lw   $r0 $r0 i0               ; asm block
+eq   $r0 $r1 $r0              ; asm block
+ret  $r0
+noop                          ; word-alignment of data section
+.data:
@@ -8,10 +8,10 @@ add  $$ds $$ds $is
 lw   $r1 $fp i73              ; load input function selector
 lw   $r0 data_2               ; load fn selector for comparison
 eq   $r0 $r1 $r0              ; function selector comparison
-jnei $zero $r0 i17            ; jump to selected function
+jnei $zero $r0 i20            ; jump to selected function
 lw   $r0 data_3               ; load fn selector for comparison
 eq   $r0 $r1 $r0              ; function selector comparison
-jnei $zero $r0 i20            ; jump to selected function
+jnei $zero $r0 i17            ; jump to selected function
 lw   $r0 data_4               ; load fn selector for comparison
 eq   $r0 $r1 $r0              ; function selector comparison
 jnei $zero $r0 i24            ; jump to selected function
@@ -20,7 +20,7 @@ lw   $r0 $fp i74              ; Base register for method parameter
 lw   $r0 $r0 i0               ; Get arg val
 ret  $r0
 lw   $r0 $fp i74              ; Base register for method parameter
-addi $r1 $r0 i0               ; Get address for arg val
+move $r1 $r0                  ; Get address for arg val
 lw   $r0 data_0               ; loading size for RETD
 retd  $r1 $r0
 lw   $r1 $fp i74              ; Base register for method parameter
@@ -37,6 +37,6 @@ noop                          ; word-alignment of data section
 .data:
 data_0 .u64 0x20
 data_1 .u64 0x28
-data_2 .u32 0x9890aef4
-data_3 .u32 0x42123b96
+data_2 .u32 0x42123b96
+data_3 .u32 0x9890aef4
 data_4 .u32 0xfc62d029
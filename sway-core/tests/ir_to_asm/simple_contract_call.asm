@@ -13,7 +13,7 @@ sw   $r1 $r0 i0               ; insert_value @ 0
 move $r2 $sp                  ; save register for temporary stack value
 cfei i48                      ; allocate 48 bytes for temporary struct
 lw   $r1 data_1               ; literal instantiation
-addi $r0 $r2 i0               ; get struct field(s) 0 offset
+move $r0 $r2                  ; get struct field(s) 0 offset
 mcpi $r0 $r1 i32              ; store struct field value
 lw   $r0 data_2               ; literal instantiation
 sw   $r2 $r0 i4               ; insert_value @ 1
@@ -23,25 +23,22 @@ lw   $r1 data_3               ; literal instantiation
 lw   $r0 data_4               ; literal instantiation
 lw   $r3 data_5               ; literal instantiation
 call $r2 $r1 $r0 $r3          ; call external contract
-move $r0 $ret
-addi $r0 $r4 i0               ; get_ptr
+move $r0 $r4                  ; get_ptr
 lw   $r1 data_6               ; literal instantiation
-addi $r0 $r0 i0               ; get struct field(s) 0 offset
 mcpi $r0 $r1 i32              ; store struct field value
 move $r3 $sp                  ; save register for temporary stack value
 cfei i48                      ; allocate 48 bytes for temporary struct
 lw   $r1 data_1               ; literal instantiation
-addi $r0 $r3 i0               ; get struct field(s) 0 offset
+move $r0 $r3                  ; get struct field(s) 0 offset
 mcpi $r0 $r1 i32              ; store struct field value
 lw   $r0 data_7               ; literal instantiation
 sw   $r3 $r0 i4               ; insert_value @ 1
-addi $r0 $r4 i0               ; get_ptr
+move $r0 $r4                  ; get_ptr
 sw   $r3 $r0 i5               ; insert_value @ 2
 lw   $r2 data_3               ; literal instantiation
 lw   $r1 data_4               ; literal instantiation
 lw   $r0 data_8               ; literal instantiation
 call $r3 $r2 $r1 $r0          ; call external contract
-move $r0 $ret
 addi $r2 $r4 i32              ; get_ptr
 lw   $r0 data_9               ; literal instantiation
 sw   $r2 $r0 i0               ; insert_value @ 0
@@ -51,7 +48,7 @@ mcpi $r0 $r1 i32              ; store struct field value
 move $r3 $sp                  ; save register for temporary stack value
 cfei i48                      ; allocate 48 bytes for temporary struct
 lw   $r1 data_1               ; literal instantiation
-addi $r0 $r3 i0               ; get struct field(s) 0 offset
+move $r0 $r3                  ; get struct field(s) 0 offset
 mcpi $r0 $r1 i32              ; store struct field value
 lw   $r0 data_11              ; literal instantiation
 sw   $r3 $r0 i4               ; insert_value @ 1
@@ -61,7 +58,6 @@ lw   $r2 $cgas i0             ; loading register into abi function
 lw   $r1 data_3               ; literal instantiation
 lw   $r0 data_4               ; literal instantiation
 call $r3 $r1 $r0 $r2          ; call external contract
-move $r0 $ret
 lw   $r0 data_3               ; literal instantiation
 ret  $r0
 .data:
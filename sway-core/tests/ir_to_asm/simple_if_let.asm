@@ -13,16 +13,14 @@ lw   $r0 data_0               ; literal instantiation
 sw   $r1 $r0 i0               ; insert_value @ 0
 lw   $r0 data_1               ; literal instantiation
 sw   $r1 $r0 i1               ; insert_value @ 1
-addi $r0 $r2 i0               ; get_ptr
-addi $r0 $r2 i0               ; get store offset
+move $r0 $r2                  ; get_ptr
 mcpi $r0 $r1 i16              ; store value
-addi $r2 $r2 i0               ; get_ptr
 lw   $r1 $r2 i0               ; extract_value @ 0
 lw   $r0 data_2               ; literal instantiation
 eq   $r0 $r1 $r0
-jnei $r0 $one i24
+jnei $r0 $one i22
 lw   $r0 $r2 i1               ; extract_value @ 1,1
-ji   i25
+ji   i23
 lw   $r0 data_0               ; literal instantiation
 ret  $r0
 noop                          ; word-alignment of data section
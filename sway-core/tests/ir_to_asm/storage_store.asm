@@ -11,17 +11,14 @@ eq   $r0 $r1 $r0              ; function selector comparison
 jnei $zero $r0 i14            ; jump to selected function
 lw   $r0 data_5               ; load fn selector for comparison
 eq   $r0 $r1 $r0              ; function selector comparison
-jnei $zero $r0 i26            ; jump to selected function
+jnei $zero $r0 i23            ; jump to selected function
 rvrt $zero                    ; revert if no selectors matched
 move $r2 $sp                  ; save locals base register
 cfei i32                      ; allocate 32 bytes for all locals
-lw   $r0 data_0               ; literal instantiation
-addi $r0 $r2 i0               ; get_ptr
+move $r0 $r2                  ; get_ptr
 lw   $r1 data_1               ; literal instantiation
-addi $r0 $r2 i0               ; get store offset
 mcpi $r0 $r1 i32              ; store value
 lw   $r1 data_0               ; literal instantiation
-addi $r0 $r2 i0               ; get offset
 sww  $r0 $r1                  ; single word state access
 ret  $zero                    ; returning unit as zero
 move $r1 $sp                  ; save locals base register
@@ -30,13 +27,12 @@ addi $r0 $r1 i32              ; get_ptr
 lw   $r2 data_2               ; literal instantiation
 addi $r0 $r1 i32              ; get store offset
 mcpi $r0 $r2 i32              ; store value
-addi $r0 $r1 i0               ; get_ptr
+move $r0 $r1                  ; get_ptr
 lw   $r2 data_3               ; literal instantiation
-addi $r0 $r1 i0               ; get store offset
 mcpi $r0 $r2 i32              ; store value
 addi $r0 $r1 i32              ; get_ptr
 addi $r2 $r1 i32              ; get offset
-addi $r0 $r1 i0               ; get offset
+move $r0 $r1                  ; get offset
 swwq $r0 $r2                  ; quad word state access
 ret  $zero                    ; returning unit as zero
 noop                          ; word-alignment of data section
@@ -0,0 +1,61 @@
+.program:
+ji   i4
+noop
+DATA_SECTION_OFFSET[0..32]
+DATA_SECTION_OFFSET[32..64]
+lw   $ds $is 1
+add  $$ds $$ds $is
+addi $r0 $zero i24            ; asm block
+aloc $r0                      ; asm block
+addi $r0 $hp i1               ; asm block
+sw   $r0 $zero i0             ; asm block
+sw   $r0 $zero i1             ; asm block
+sw   $r0 $zero i2             ; asm block
+lw   $r2 $r0 i1               ; asm block
+lw   $r1 $r0 i2               ; asm block
+lt   $r1 $r1 $r2              ; asm block
+jnei $r1 $one i17
+ji   i31
+lw   $r1 data_0               ; literal instantiation
+lw   $r6 $r0 i0               ; asm block
+lw   $r5 $r0 i1               ; asm block
+add  $r4 $r5 $r5              ; asm block
+addi $r4 $r4 i1               ; asm block
+mul  $r2 $r4 $r1              ; asm block
+aloc $r2                      ; asm block
+addi $r3 $hp i1               ; asm block
+eq   $r2 $r5 $zero            ; asm block
+add  $r2 $r5 $r2              ; asm block
+mul  $r2 $r2 $r1              ; asm block
+mcp  $r3 $r6 $r2              ; asm block
+sw   $r0 $r3 i0               ; asm block
+sw   $r0 $r4 i1               ; asm block
+lw   $r4 data_1               ; literal instantiation
+lw   $r1 data_0               ; literal instantiation
+lw   $r3 $r0 i0               ; asm block
+lw   $r2 $r0 i2               ; asm block
+mul  $r1 $r2 $r1              ; asm block
+add  $r1 $r3 $r1              ; asm block
+sw   $r1 $r4 i0               ; asm block
+addi $r2 $r2 i1               ; asm block
+sw   $r0 $r2 i2               ; asm block
+lw   $r2 data_2               ; literal instantiation
+lw   $r1 $r0 i2               ; asm block
+lt   $r1 $r2 $r1              ; asm block
+jnei $r1 $one i45
+ji   i47
+lw   $r0 data_3               ; literal instantiation
+rvrt $r0                      ; asm block
+lw   $r3 data_2               ; literal instantiation
+lw   $r2 data_0               ; literal instantiation
+lw   $r1 $r0 i0               ; asm block
+mul  $r0 $r3 $r2              ; asm block
+add  $r0 $r1 $r0              ; asm block
+lw   $r0 $r0 i0               ; asm block
+ret  $r0
+noop                          ; word-alignment of data section
+.data:
+data_0 .u64 0x08
+data_1 .u64 0x2a
+data_2 .u64 0x00
+data_3 .u64 0xffffffffffff0001
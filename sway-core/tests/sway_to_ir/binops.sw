@@ -0,0 +1,26 @@
+script;
+
+fn add(a: u64, b: u64) -> u64 {
+    asm(r1: a, r2: b, r3) {
+        add r3 r1 r2;
+        r3: u64
+    }
+}
+
+fn lt(a: u64, b: u64) -> bool {
+    asm(r1: a, r2: b, r3) {
+        lt r3 r1 r2;
+        r3: bool
+    }
+}
+
+fn not(a: bool) -> bool {
+    asm(r1: a, r2) {
+        eq r2 r1 zero;
+        r2: bool
+    }
+}
+
+fn main() -> bool {
+    not(lt(add(1, 2), 4))
+}
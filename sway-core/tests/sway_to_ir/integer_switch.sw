@@ -0,0 +1,30 @@
+// passes: mem2reg dce
+script;
+
+// Dense, with literal arms, so looked up in a table.
+fn days_in_month(month: u64) -> u64 {
+    match month {
+        1 => { 31 },
+        2 => { 28 },
+        3 => { 31 },
+        4 => { 30 },
+        5 => { 31 },
+        6 => { 30 },
+        _ => { 0 },
+    }
+}
+
+// Sparse, so searched for.
+fn sparse(x: u64) -> u64 {
+    match x {
+        1000000 => { 1 },
+        3 => { 2 },
+        42 => { 3 },
+        0 => { 4 },
+        _ => { 5 },
+    }
+}
+
+fn main() -> bool {
+    days_in_month(2) == sparse(42)
+}
@@ -0,0 +1,5 @@
+predicate;
+
+fn main() -> bool {
+    false
+}
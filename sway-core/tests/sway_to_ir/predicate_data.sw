@@ -0,0 +1,9 @@
+predicate;
+
+fn main(a: u64, key: b256) -> bool {
+    asm(r1: a, r2: key, r3) {
+        lw r3 r2 i0;
+        eq r3 r1 r3;
+        r3: bool
+    }
+}
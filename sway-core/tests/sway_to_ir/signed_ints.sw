@@ -0,0 +1,7 @@
+script;
+
+fn main() -> i64 {
+    let a: i32 = -5;
+    let b = -7i8;
+    -9
+}
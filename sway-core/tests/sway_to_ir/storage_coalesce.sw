@@ -0,0 +1,20 @@
+// passes: storage
+contract;
+
+storage {
+    x: u64,
+    y: u64,
+}
+
+abi Test {
+    fn set_and_get(val: u64) -> u64;
+}
+
+impl Test for Contract {
+    impure fn set_and_get(val: u64) -> u64 {
+        storage.x = 1;
+        storage.y = storage.x;
+        storage.x = val;
+        storage.x
+    }
+}
@@ -0,0 +1,10 @@
+script;
+
+fn main() -> u64 {
+    let v: Vec<u64> = Vec::with_capacity(1);
+    v.push(10);
+    v.push(20);
+    let last = v.pop();
+    let len = v.len();
+    v[0]
+}
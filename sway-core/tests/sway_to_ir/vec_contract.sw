@@ -0,0 +1,17 @@
+contract;
+
+abi Test {
+    fn append(vals: Vec<u64>, val: u64) -> Vec<u64>;
+    fn first(vals: Vec<u64>) -> u64;
+}
+
+impl Test for Contract {
+    fn append(vals: Vec<u64>, val: u64) -> Vec<u64> {
+        vals.push(val);
+        vals
+    }
+
+    fn first(vals: Vec<u64>) -> u64 {
+        vals[0]
+    }
+}
@@ -0,0 +1,18 @@
+script;
+
+abi Test {
+    fn append(vals: Vec<u64>, val: u64) -> Vec<u64>;
+    fn first(vals: Vec<u64>) -> u64;
+}
+
+fn main() -> u64 {
+    let caller = abi(Test, 0x0c1c50c2bf5ba4bb351b4249a2f5e7d86556fcb4a6ae90465ff6c86126eeb3c0);
+    let v: Vec<u64> = Vec::new();
+    v.push(1);
+    let w = caller.append(v, 2);
+
+    // We can't use the result of a contract call yet as its type isn't known, but a returned
+    // vector is rebuilt before it is used.
+    caller.first(w);
+    w[1]
+}
@@ -0,0 +1,6 @@
+script;
+
+fn main() -> u256 {
+    let a: u128 = 1;
+    340282366920938463463374607431768211456u256
+}
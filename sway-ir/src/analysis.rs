@@ -0,0 +1,12 @@
+//! A collection of analyses over the IR.
+//!
+//! These don't modify the IR, they gather information about it for use by the optimization passes
+//! in [`optimize`](crate::optimize).  Any modification to a function may invalidate a result, in
+//! which case it should be recomputed.
+
+pub mod cfg;
+pub use cfg::*;
+pub mod dominator;
+pub use dominator::*;
+pub mod use_def;
+pub use use_def::*;
@@ -0,0 +1,54 @@
+//! Control flow graph queries.
+//!
+//! Blocks only record their successors, via their terminator, so the predecessors and traversal
+//! orders of the blocks in a function are computed here.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{block::Block, context::Context, function::Function};
+
+/// Map every block in `function` to the blocks which branch to it.
+///
+/// A predecessor is listed once even if it has more than one branch to the block.
+pub fn compute_predecessors(context: &Context, function: &Function) -> HashMap<Block, Vec<Block>> {
+    let mut preds: HashMap<Block, Vec<Block>> = function
+        .block_iter(context)
+        .map(|block| (block, Vec::new()))
+        .collect();
+    for block in function.block_iter(context) {
+        for succ in block.successors(context) {
+            preds.entry(succ).or_default().push(block);
+        }
+    }
+    preds
+}
+
+/// Return the blocks reachable from the entry block of `function` in reverse post order.
+///
+/// Every block appears before its successors, except where the successor is reached by a loop
+/// back edge.
+pub fn reverse_post_order(context: &Context, function: &Function) -> Vec<Block> {
+    let entry_block = function.get_entry_block(context);
+    let mut visited = HashSet::from([entry_block]);
+    let mut post_order = Vec::new();
+
+    // Each stack entry is a block and its successors which are still to be visited.
+    let mut stack = vec![(entry_block, entry_block.successors(context))];
+    while let Some((block, succs)) = stack.last_mut() {
+        match succs.pop() {
+            Some(succ) => {
+                if visited.insert(succ) {
+                    let succ_succs = succ.successors(context);
+                    stack.push((succ, succ_succs));
+                }
+            }
+            None => {
+                post_order.push(*block);
+                stack.pop();
+            }
+        }
+    }
+
+    post_order.reverse();
+    post_order
+}
@@ -0,0 +1,158 @@
+//! Dominator tree and dominance frontiers.
+//!
+//! Block `A` dominates block `B` if every path from the entry block to `B` passes through `A`.
+//! The immediate dominator of `B` is its closest strict dominator, and these form a tree rooted at
+//! the entry block.
+//!
+//! The tree is built using the iterative algorithm from _A Simple, Fast Dominance Algorithm_ by
+//! Cooper, Harvey and Kennedy.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    analysis::cfg::{compute_predecessors, reverse_post_order},
+    block::Block,
+    context::Context,
+    function::Function,
+};
+
+/// The dominator tree for the blocks in a function which are reachable from its entry block.
+pub struct DominatorTree {
+    entry_block: Block,
+    rpo: Vec<Block>,
+    idoms: HashMap<Block, Block>,
+    children: HashMap<Block, Vec<Block>>,
+    preds: HashMap<Block, Vec<Block>>,
+}
+
+impl DominatorTree {
+    /// Compute the dominator tree for `function`.
+    pub fn compute(context: &Context, function: &Function) -> Self {
+        let entry_block = function.get_entry_block(context);
+        let rpo = reverse_post_order(context, function);
+        let preds = compute_predecessors(context, function);
+
+        // The position of each block in the post order, used to walk up the tree.
+        let po_idcs: HashMap<Block, usize> = rpo
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(idx, block)| (*block, idx))
+            .collect();
+
+        let mut idoms = HashMap::from([(entry_block, entry_block)]);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for block in rpo.iter().skip(1) {
+                // Intersect the dominators of every predecessor already processed.  Unreachable
+                // predecessors have no post order index and are ignored.
+                let new_idom = preds[block]
+                    .iter()
+                    .filter(|pred| idoms.contains_key(*pred))
+                    .copied()
+                    .reduce(|mut lhs, mut rhs| {
+                        while lhs != rhs {
+                            while po_idcs[&lhs] < po_idcs[&rhs] {
+                                lhs = idoms[&lhs];
+                            }
+                            while po_idcs[&rhs] < po_idcs[&lhs] {
+                                rhs = idoms[&rhs];
+                            }
+                        }
+                        lhs
+                    })
+                    .expect("Reachable block must have a processed predecessor.");
+                if idoms.insert(*block, new_idom) != Some(new_idom) {
+                    changed = true;
+                }
+            }
+        }
+
+        let mut children: HashMap<Block, Vec<Block>> = HashMap::new();
+        for block in rpo.iter().skip(1) {
+            children.entry(idoms[block]).or_default().push(*block);
+        }
+
+        DominatorTree {
+            entry_block,
+            rpo,
+            idoms,
+            children,
+            preds,
+        }
+    }
+
+    /// Return the immediate dominator of `block`, or `None` for the entry block or an unreachable
+    /// block.
+    pub fn immediate_dominator(&self, block: &Block) -> Option<Block> {
+        if *block == self.entry_block {
+            None
+        } else {
+            self.idoms.get(block).copied()
+        }
+    }
+
+    /// Return the blocks immediately dominated by `block`.
+    pub fn children(&self, block: &Block) -> &[Block] {
+        self.children
+            .get(block)
+            .map(|children| children.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Return whether `dominator` dominates `block`.  Every block dominates itself.
+    pub fn dominates(&self, dominator: &Block, block: &Block) -> bool {
+        let mut block = *block;
+        loop {
+            if block == *dominator {
+                return true;
+            }
+            match self.immediate_dominator(&block) {
+                Some(idom) => block = idom,
+                None => return false,
+            }
+        }
+    }
+
+    /// Return whether `block` is reachable from the entry block.
+    pub fn is_reachable(&self, block: &Block) -> bool {
+        self.idoms.contains_key(block)
+    }
+
+    /// Return the reachable blocks in reverse post order, where every block follows its
+    /// dominators.
+    pub fn reverse_post_order(&self) -> &[Block] {
+        &self.rpo
+    }
+
+    /// Compute the dominance frontier of each reachable block.
+    ///
+    /// The frontier of `A` is the set of blocks where `A`'s dominance ends, i.e., those which `A`
+    /// doesn't strictly dominate but which have a predecessor that `A` does dominate.
+    pub fn dominance_frontiers(&self) -> HashMap<Block, HashSet<Block>> {
+        let mut frontiers: HashMap<Block, HashSet<Block>> = self
+            .rpo
+            .iter()
+            .map(|block| (*block, HashSet::new()))
+            .collect();
+        for block in &self.rpo {
+            let preds = self.preds[block]
+                .iter()
+                .filter(|pred| self.is_reachable(pred))
+                .collect::<Vec<_>>();
+            if preds.len() < 2 {
+                continue;
+            }
+            let idom = self.idoms[block];
+            for pred in preds {
+                let mut runner = *pred;
+                while runner != idom {
+                    frontiers.get_mut(&runner).unwrap().insert(*block);
+                    runner = self.idoms[&runner];
+                }
+            }
+        }
+        frontiers
+    }
+}
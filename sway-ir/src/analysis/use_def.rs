@@ -0,0 +1,27 @@
+//! Use-def chains.
+//!
+//! Each instruction records the values it uses, but not the reverse, so the users of each value
+//! within a function are computed here.
+
+use std::collections::HashMap;
+
+use crate::{
+    context::Context,
+    function::Function,
+    value::{Value, ValueDatum},
+};
+
+/// Map every value used within `function` to the instructions which use it.
+///
+/// An instruction which uses a value more than once is listed once for each use.
+pub fn compute_users(context: &Context, function: &Function) -> HashMap<Value, Vec<Value>> {
+    let mut users: HashMap<Value, Vec<Value>> = HashMap::new();
+    for (_, ins_val) in function.instruction_iter(context) {
+        if let ValueDatum::Instruction(ins) = &context.values[ins_val.0].value {
+            for operand in ins.get_operands() {
+                users.entry(operand).or_default().push(ins_val);
+            }
+        }
+    }
+    users
+}
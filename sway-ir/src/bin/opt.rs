@@ -3,7 +3,11 @@ use std::{
     io::{BufReader, BufWriter, Error, ErrorKind, Read, Write},
 };
 
-use sway_ir::{error::IrError, function::Function, optimize, Context};
+use sway_ir::{
+    function::Function,
+    optimize::{inline_function_calls_by_cost_with_decisions, InlineCostModel},
+    OptLevel, PassManager,
+};
 
 // -------------------------------------------------------------------------------------------------
 
@@ -12,8 +16,45 @@ fn main() -> std::io::Result<()> {
         Error::new(ErrorKind::Other, err.to_string())
     }
 
+    let mut pass_mgr = PassManager::default();
+
     // Build the config from the command line.
-    let config = ConfigBuilder::build(std::env::args()).map_err(&to_err)?;
+    let config = ConfigBuilder::build(&pass_mgr, std::env::args()).map_err(&to_err)?;
+
+    if config.list_passes {
+        for pass in pass_mgr.passes() {
+            println!("{:16}{}", pass.name, pass.descr);
+        }
+        return Ok(());
+    }
+
+    if config.print_inline_decisions {
+        let mut pass = *pass_mgr.lookup("inlinecost").unwrap();
+        pass.run = |context, _, function| {
+            let mut decisions = Vec::new();
+            let modified = inline_function_calls_by_cost_with_decisions(
+                context,
+                function,
+                &InlineCostModel::default(),
+                &mut decisions,
+            )?;
+            for (inlined_function, decision) in decisions {
+                println!(
+                    "inline {} into {}: {}",
+                    inlined_function.get_name(context),
+                    function.get_name(context),
+                    decision
+                );
+            }
+            Ok(modified)
+        };
+        pass_mgr.register(pass);
+    }
+
+    for pass_name in &config.print_after {
+        pass_mgr.print_after(pass_name);
+    }
+    pass_mgr.verify_each(config.verify_each);
 
     // Read the input file, or standard in.
     let input_str = read_from_input(&config.input_path)?;
@@ -21,13 +62,18 @@ fn main() -> std::io::Result<()> {
     // Parse it. XXX Improve this error message too.
     let mut ir = sway_ir::parser::parse(&input_str).map_err(&to_err)?;
 
-    // Perform optimisation passes in order.
-    for pass in config.passes {
-        match pass.name.as_ref() {
-            "inline" => perform_inline(&mut ir).map_err(&to_err)?,
-            "constcombine" => perform_combine_constants(&mut ir).map_err(&to_err)?,
-            _otherwise => unreachable!("Unknown pass name: {}", pass.name),
-        };
+    // Perform optimisation passes in order, over every function.
+    let functions = ir
+        .functions
+        .iter()
+        .map(|(idx, _)| Function(idx))
+        .collect::<Vec<_>>();
+    for step in config.steps {
+        match step {
+            Step::Pass(pass) => pass_mgr.run(&mut ir, &[pass.name.as_str()], &functions),
+            Step::Pipeline(opt_level) => pass_mgr.run_pipeline(&mut ir, opt_level, &functions),
+        }
+        .map_err(&to_err)?;
     }
 
     // Write the output file or standard out.
@@ -63,31 +109,6 @@ fn write_to_output<S: Into<String>>(ir_str: S, path_str: &Option<String>) -> std
     }
 }
 
-// -------------------------------------------------------------------------------------------------
-
-fn perform_inline(ir: &mut Context) -> Result<bool, IrError> {
-    // For now we inline everything into `main()`.  Eventually we can be more selective.
-    let main_fn = ir
-        .functions
-        .iter()
-        .find_map(|(idx, fc)| if fc.name == "main" { Some(idx) } else { None })
-        .unwrap();
-    optimize::inline_all_function_calls(ir, &Function(main_fn))
-}
-
-// -------------------------------------------------------------------------------------------------
-
-fn perform_combine_constants(ir: &mut Context) -> Result<bool, IrError> {
-    let funcs = ir.functions.iter().map(|(idx, _)| idx).collect::<Vec<_>>();
-    let mut modified = false;
-    for idx in funcs {
-        if optimize::combine_constants(ir, &Function(idx))? {
-            modified = true;
-        }
-    }
-    Ok(modified)
-}
-
 // -------------------------------------------------------------------------------------------------
 // Using a bespoke CLI parser since the order in which passes are specified is important.
 
@@ -96,11 +117,19 @@ struct Config {
     input_path: Option<String>,
     output_path: Option<String>,
 
-    _verify_each: bool,
+    verify_each: bool,
+    print_after: Vec<String>,
+    list_passes: bool,
+    print_inline_decisions: bool,
     _time_passes: bool,
     _stats: bool,
 
-    passes: Vec<Pass>,
+    steps: Vec<Step>,
+}
+
+enum Step {
+    Pass(Pass),
+    Pipeline(OptLevel),
 }
 
 #[derive(Default)]
@@ -121,20 +150,22 @@ impl From<&str> for Pass {
 
 // This is a little clumsy in that it needs to consume items from the iterator carefully in each
 // method to ensure we don't enter a weird state.
-struct ConfigBuilder<I: Iterator<Item = String>> {
+struct ConfigBuilder<'a, I: Iterator<Item = String>> {
     next: Option<String>,
     rest: I,
     cfg: Config,
+    pass_mgr: &'a PassManager,
 }
 
-impl<I: Iterator<Item = String>> ConfigBuilder<I> {
-    fn build(mut rest: I) -> Result<Config, String> {
+impl<'a, I: Iterator<Item = String>> ConfigBuilder<'a, I> {
+    fn build(pass_mgr: &'a PassManager, mut rest: I) -> Result<Config, String> {
         rest.next(); // Skip the first arg which is the binary name.
         let next = rest.next();
         ConfigBuilder {
             next,
             rest,
             cfg: Config::default(),
+            pass_mgr,
         }
         .build_root()
     }
@@ -148,10 +179,24 @@ impl<I: Iterator<Item = String>> ConfigBuilder<I> {
                     "-i" => self.build_input(),
                     "-o" => self.build_output(),
 
-                    "inline" => self.build_inline_pass(),
-                    "constcombine" => self.build_const_combine_pass(),
+                    "--verify-each" => {
+                        self.cfg.verify_each = true;
+                        self.build_root()
+                    }
+                    "--print-after" => self.build_print_after(),
+                    "--list-passes" => {
+                        self.cfg.list_passes = true;
+                        self.build_root()
+                    }
+                    "--print-inline-decisions" => {
+                        self.cfg.print_inline_decisions = true;
+                        self.build_root()
+                    }
 
-                    _otherwise => Err(format!("Unrecognised option '{}'.", opt)),
+                    _otherwise => match opt.strip_prefix("-O") {
+                        Some(level) => self.build_pipeline(level),
+                        None => self.build_pass(opt),
+                    },
                 }
             }
         }
@@ -179,19 +224,32 @@ impl<I: Iterator<Item = String>> ConfigBuilder<I> {
         }
     }
 
-    fn build_inline_pass(mut self) -> Result<Config, String> {
-        // No args yet.  Eventually we should allow specifying which functions are to be inlined
-        // or which functions are to have all embedded calls inlined.
-        self.cfg.passes.push("inline".into());
-        self.next = self.rest.next();
+    fn build_print_after(mut self) -> Result<Config, String> {
+        match self.next {
+            None => Err("--print-after option requires a pass name.".to_owned()),
+            Some(name) => {
+                if self.pass_mgr.lookup(&name).is_none() {
+                    return Err(format!("Unrecognised pass '{}'.", name));
+                }
+                self.cfg.print_after.push(name);
+                self.next = self.rest.next();
+                self.build_root()
+            }
+        }
+    }
+
+    fn build_pipeline(mut self, level: &str) -> Result<Config, String> {
+        let opt_level = level.parse::<OptLevel>()?;
+        self.cfg.steps.push(Step::Pipeline(opt_level));
         self.build_root()
     }
 
-    fn build_const_combine_pass(mut self) -> Result<Config, String> {
-        // No args yet.  Eventually we should allow specifying which functions should have consts
-        // combined.
-        self.cfg.passes.push("constcombine".into());
-        self.next = self.rest.next();
+    fn build_pass(mut self, name: String) -> Result<Config, String> {
+        // No args yet.  Eventually we should allow specifying which functions a pass applies to.
+        if self.pass_mgr.lookup(&name).is_none() {
+            return Err(format!("Unrecognised option '{}'.", name));
+        }
+        self.cfg.steps.push(Step::Pass(name.as_str().into()));
         self.build_root()
     }
 }
@@ -6,7 +6,8 @@
 //!
 //! Blocks also contain a single 'phi' instruction at its start.  In
 //! [SSA](https://en.wikipedia.org/wiki/Static_single_assignment_form) form 'phi' instructions are
-//! used to merge values from preceding blocks.
+//! used to merge values from preceding blocks.  Optimisation passes such as
+//! [`mem2reg`](crate::optimize::mem2reg) may add further phi instructions directly after it.
 //!
 //! Every [`Function`] has at least one block, the first of which is usually labeled `entry`.
 
@@ -75,6 +76,30 @@ impl Block {
         context.blocks[self.0].instructions[0]
     }
 
+    /// Get every phi instruction at the start of this block, the first always being the block's own
+    /// phi as returned by [`Block::get_phi`].
+    pub fn get_phis(&self, context: &Context) -> Vec<Value> {
+        context.blocks[self.0]
+            .instructions
+            .iter()
+            .take_while(|ins_val| {
+                matches!(
+                    context.values[ins_val.0].value,
+                    ValueDatum::Instruction(Instruction::Phi(_))
+                )
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Create a new empty phi instruction, placed after any existing phis in this block.
+    pub fn new_phi(&self, context: &mut Context) -> Value {
+        let num_phis = self.get_phis(context).len();
+        let phi = Value::new_instruction(context, Instruction::Phi(Vec::new()), None);
+        context.blocks[self.0].instructions.insert(num_phis, phi);
+        phi
+    }
+
     /// Get the number of predecessor blocks, i.e., blocks which branch to this one.
     pub fn num_predecessors(&self, context: &Context) -> usize {
         context.blocks[self.0].num_predecessors(context)
@@ -86,11 +111,23 @@ impl Block {
     /// use `phi_value`.
     pub fn add_phi(&self, context: &mut Context, from_block: Block, phi_value: Value) {
         let phi_val = self.get_phi(context);
+        self.add_phi_to(context, phi_val, from_block, phi_value);
+    }
+
+    /// Add a new entry to a particular phi instruction in this block, which may be any of those
+    /// returned by [`Block::get_phis`].
+    pub fn add_phi_to(
+        &self,
+        context: &mut Context,
+        phi_val: Value,
+        from_block: Block,
+        phi_value: Value,
+    ) {
         match &mut context.values[phi_val.0].value {
             ValueDatum::Instruction(Instruction::Phi(list)) => {
                 list.push((from_block, phi_value));
             }
-            _ => unreachable!("First value in block instructions is not a phi."),
+            _ => unreachable!("Phi value must be a PHI instruction."),
         }
     }
 
@@ -112,26 +149,61 @@ impl Block {
         }
     }
 
-    /// Replace a block reference in the phi instruction.
+    /// Get the value from every phi instruction in this block which correlates to `from_block`,
+    /// paired with the phi itself.
+    pub fn get_phi_vals_coming_from(
+        &self,
+        context: &Context,
+        from_block: &Block,
+    ) -> Vec<(Value, Value)> {
+        self.get_phis(context)
+            .into_iter()
+            .filter_map(|phi_val| match &context.values[phi_val.0].value {
+                ValueDatum::Instruction(Instruction::Phi(pairs)) => pairs
+                    .iter()
+                    .find(|(block, _)| block == from_block)
+                    .map(|(_, value)| (phi_val, *value)),
+                _ => unreachable!("Phi value must be a PHI instruction."),
+            })
+            .collect()
+    }
+
+    /// Remove the values from the phi instructions which correlate to `from_block`.
+    ///
+    /// This is required when `from_block` is no longer a predecessor of this block.
+    pub fn remove_phi_val_coming_from(&self, context: &mut Context, from_block: &Block) {
+        for phi_val in self.get_phis(context) {
+            if let ValueDatum::Instruction(Instruction::Phi(ref mut pairs)) =
+                &mut context.values[phi_val.0].value
+            {
+                pairs.retain(|(block, _)| block != from_block);
+            } else {
+                unreachable!("Phi value must be a PHI instruction.");
+            }
+        }
+    }
+
+    /// Replace a block reference in the phi instructions.
     ///
-    /// Any reference to `old_source` will be replace with `new_source` in the list of phi values.
+    /// Any reference to `old_source` will be replace with `new_source` in the lists of phi values.
     pub fn update_phi_source_block(
         &self,
         context: &mut Context,
         old_source: Block,
         new_source: Block,
     ) {
-        let phi_val = self.get_phi(context);
-        if let ValueDatum::Instruction(Instruction::Phi(ref mut pairs)) =
-            &mut context.values[phi_val.0].value
-        {
-            for (block, _) in pairs {
-                if *block == old_source {
-                    *block = new_source;
+        for phi_val in self.get_phis(context) {
+            if let ValueDatum::Instruction(Instruction::Phi(ref mut pairs)) =
+                &mut context.values[phi_val.0].value
+            {
+                for (block, _) in pairs {
+                    if *block == old_source {
+                        *block = new_source;
+                    }
                 }
+            } else {
+                unreachable!("Phi value must be a PHI instruction.");
             }
-        } else {
-            unreachable!("Phi value must be a PHI instruction.");
         }
     }
 
@@ -149,6 +221,24 @@ impl Block {
         })
     }
 
+    /// Get the blocks which this block may branch to, according to its terminator.
+    pub fn successors(&self, context: &Context) -> Vec<Block> {
+        match self.get_term_inst(context) {
+            Some(Instruction::Branch(to_block)) => vec![*to_block],
+            Some(Instruction::ConditionalBranch {
+                true_block,
+                false_block,
+                ..
+            }) if true_block == false_block => vec![*true_block],
+            Some(Instruction::ConditionalBranch {
+                true_block,
+                false_block,
+                ..
+            }) => vec![*true_block, *false_block],
+            _otherwise => Vec::new(),
+        }
+    }
+
     /// Replace a value within this block.
     ///
     /// For every instruction within the block, any reference to `old_val` is replaced with
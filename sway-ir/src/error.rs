@@ -5,12 +5,17 @@
 
 #[derive(Debug)]
 pub enum IrError {
+    ExecutionPanic(String),
+    ExecutionReverted(u64),
+    ExecutionStepLimit(u64),
+    ExecutionUnsupported(String),
     FunctionLocalClobbered(String, String),
     InvalidMetadatum,
     MisplacedTerminator(String),
     MissingBlock(String),
     MissingTerminator(String),
     ParseFailure(String, String),
+    UnknownPass(String),
     ValueNotFound(String),
 
     VerifyAccessElementInconsistentTypes,
@@ -20,6 +25,8 @@ pub enum IrError {
     VerifyAccessValueInvalidIndices,
     VerifyAccessValueOnNonStruct,
     VerifyArgumentValueIsNotArgument(String),
+    VerifyBinaryOpBadTypes(String, String),
+    VerifyBinaryOpUnknownTypes,
     VerifyBranchToMissingBlock(String),
     VerifyCallArgTypeMismatch(String),
     VerifyCallToMissingFunction(String),
@@ -34,6 +41,8 @@ pub enum IrError {
     VerifyLoadFromNonPointer,
     VerifyLoadNonExistentPointer,
     VerifyMismatchedReturnTypes(String),
+    VerifyNotBadType(String),
+    VerifyNotUnknownType,
     VerifyPhiFromMissingBlock(String),
     VerifyPhiInconsistentTypes,
     VerifyPhiNonUniqueLabels,
@@ -51,6 +60,12 @@ use std::fmt;
 impl fmt::Display for IrError {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match self {
+            IrError::ExecutionPanic(reason) => write!(f, "Execution panicked: {reason}"),
+            IrError::ExecutionReverted(code) => write!(f, "Execution reverted with {code}."),
+            IrError::ExecutionStepLimit(limit) => {
+                write!(f, "Execution exceeded the limit of {limit} steps.")
+            }
+            IrError::ExecutionUnsupported(reason) => write!(f, "Unable to execute: {reason}"),
             IrError::FunctionLocalClobbered(fn_str, var_str) => write!(
                 f,
                 "Local storage for function {fn_str} already has an entry for variable {var_str}"
@@ -66,6 +81,7 @@ impl fmt::Display for IrError {
             IrError::ParseFailure(expecting, found) => {
                 write!(f, "Parse failure: expecting '{expecting}', found '{found}'")
             }
+            IrError::UnknownPass(name) => write!(f, "Unknown optimization pass '{name}'."),
             IrError::ValueNotFound(reason) => {
                 write!(f, "Invalid value: {reason}")
             }
@@ -108,6 +124,18 @@ impl fmt::Display for IrError {
                 f,
                 "Verification failed: Argument specifier for function '{callee}' is not an argument value."
             ),
+            IrError::VerifyBinaryOpBadTypes(lhs_ty, rhs_ty) => {
+                write!(
+                    f,
+                    "Verification failed: Binary operation must be between integers of the same width, found {lhs_ty} and {rhs_ty}."
+                )
+            }
+            IrError::VerifyBinaryOpUnknownTypes => {
+                write!(
+                    f,
+                    "Verification failed: Unable to determine type(s) of binary operation argument(s)."
+                )
+            }
             IrError::VerifyBranchToMissingBlock(label) => {
                 write!(
                     f,
@@ -185,6 +213,18 @@ impl fmt::Display for IrError {
                 f,
                 "Verification failed: Function {fn_str} return type must match its RET instructions."
             ),
+            IrError::VerifyNotBadType(ty) => {
+                write!(
+                    f,
+                    "Verification failed: Not must be applied to an integer or boolean, found {ty}."
+                )
+            }
+            IrError::VerifyNotUnknownType => {
+                write!(
+                    f,
+                    "Verification failed: Unable to determine type of not argument."
+                )
+            }
             IrError::VerifyPhiFromMissingBlock(label) => {
                 write!(
                     f,
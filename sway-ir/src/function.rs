@@ -25,6 +25,16 @@ use crate::{
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
 pub struct Function(pub generational_arena::Index);
 
+/// A hint to the inliner overriding its usual choice of whether to inline calls to a function.
+///
+/// Hints are only written in textual IR for now.  Sway has no attribute to set them, and the
+/// compiler inlines every call regardless, since the backend can't compile calls.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InlineHint {
+    Always,
+    Never,
+}
+
 #[doc(hidden)]
 pub struct FunctionContent {
     pub name: String,
@@ -33,6 +43,7 @@ pub struct FunctionContent {
     pub blocks: Vec<Block>,
     pub is_public: bool,
     pub selector: Option<[u8; 4]>,
+    pub inline_hint: Option<InlineHint>,
 
     pub local_storage: BTreeMap<String, Pointer>, // BTree rather than Hash for deterministic ordering.
 
@@ -67,6 +78,7 @@ impl Function {
             blocks: Vec::new(),
             is_public,
             selector,
+            inline_hint: None,
             local_storage: BTreeMap::new(),
             next_label_idx: 0,
         };
@@ -190,6 +202,16 @@ impl Function {
         context.functions[self.0].blocks[0]
     }
 
+    /// Return the inlining hint for this function, if it has one.
+    pub fn get_inline_hint(&self, context: &Context) -> Option<InlineHint> {
+        context.functions[self.0].inline_hint
+    }
+
+    /// Set or clear the inlining hint for this function.
+    pub fn set_inline_hint(&self, context: &mut Context, inline_hint: Option<InlineHint>) {
+        context.functions[self.0].inline_hint = inline_hint;
+    }
+
     /// Whether this function has a valid selector.
     pub fn has_selector(&self, context: &Context) -> bool {
         context.functions[self.0].selector.is_some()
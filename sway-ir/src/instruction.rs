@@ -1,12 +1,12 @@
 //! Instructions for data manipulation, but mostly control flow.
 //!
 //! Since Sway abstracts most low level operations behind traits they are translated into function
-//! calls which contain ASM blocks.  Therefore _at this stage_ Sway-IR doesn't need low level
-//! operations such as binary arithmetic and logic operators.
-//!
-//! Unfortuntely, using opaque ASM blocks limits the effectiveness of certain optimizations and
-//! this should be addressed in the future, perhaps by using compiler intrinsic calls instead of
-//! the ASM blocks where possible.
+//! calls which contain ASM blocks.  The simple integer arithmetic, bitwise and comparison
+//! operators are recognised and converted to native [`Instruction::BinaryOp`],
+//! [`Instruction::Not`] and [`Instruction::Cmp`] instructions though, as opaque ASM blocks limit
+//! the effectiveness of most optimizations.
+
+use std::collections::HashSet;
 
 use sway_types::ident::Ident;
 
@@ -25,6 +25,12 @@ use crate::{
 pub enum Instruction {
     /// An opaque list of ASM instructions passed directly to codegen.
     AsmBlock(AsmBlock, Vec<AsmArg>),
+    /// A binary arithmetic or bitwise operation on two integers of the same type.
+    BinaryOp {
+        op: BinaryOpKind,
+        lhs: Value,
+        rhs: Value,
+    },
     /// An unconditional jump.
     Branch(Block),
     /// A function call with a list of arguments.
@@ -80,6 +86,8 @@ pub enum Instruction {
     Load(Value),
     /// No-op, handy as a placeholder instruction.
     Nop,
+    /// Bitwise inversion of an integer or logical negation of a boolean.
+    Not(Value),
     /// Choose a value from a list depending on the preceding block.
     Phi(Vec<(Block, Value)>),
     /// Reads a special register in the VM.
@@ -100,11 +108,29 @@ pub enum Instruction {
     Store { dst_val: Value, stored_val: Value },
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinaryOpKind {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    And,
+    Or,
+    Xor,
+    Shl,
+    Shr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Predicate {
     /// Equivalence.
     Equal,
-    // More soon.  NotEqual, LessThan, LessThanOrEqual, GreaterThan, GreaterThanOrEqual.
+    /// Unsigned integer less than.
+    LessThan,
+    /// Unsigned integer greater than.
+    GreaterThan,
+    // More soon.  NotEqual, LessThanOrEqual, GreaterThanOrEqual.
 }
 
 /// Special registers in the Fuel Virtual Machine.
@@ -148,6 +174,7 @@ impl Instruction {
     pub fn get_type(&self, context: &Context) -> Option<Type> {
         match self {
             Instruction::AsmBlock(asm_block, _) => asm_block.get_type(context),
+            Instruction::BinaryOp { lhs, .. } => lhs.get_type(context),
             Instruction::Call(function, _) => Some(context.functions[function.0].return_type),
             Instruction::Cmp(..) => Some(Type::Bool),
             Instruction::ContractCall { .. } => None, // TODO fix this
@@ -162,12 +189,13 @@ impl Instruction {
                     None
                 }
             }
+            Instruction::Not(value) => value.get_type(context),
             Instruction::ReadRegister(_) => Some(Type::Uint(64)),
             Instruction::StateLoadWord(_) => Some(Type::Uint(64)),
             Instruction::Phi(alts) => {
-                // Assuming each alt has the same type, we can take the first one. Note: `verify()`
-                // confirms the types are all the same.
-                alts.get(0).and_then(|(_, val)| val.get_type(context))
+                // Assuming each alt has the same type, we can take the first one we can find.
+                // Note: `verify()` confirms the types are all the same.
+                get_phi_type(context, alts)
             }
 
             // These can be recursed to via Load, so we return the pointer type.
@@ -218,6 +246,90 @@ impl Instruction {
         }
     }
 
+    /// Return the values this instruction refers to as arguments.
+    pub fn get_operands(&self) -> Vec<Value> {
+        match self {
+            Instruction::AsmBlock(_, args) => {
+                args.iter().filter_map(|arg| arg.initializer).collect()
+            }
+            Instruction::BinaryOp { lhs, rhs, .. } => vec![*lhs, *rhs],
+            Instruction::Branch(_) => Vec::new(),
+            Instruction::Call(_, args) => args.clone(),
+            Instruction::Cmp(_, lhs, rhs) => vec![*lhs, *rhs],
+            Instruction::ConditionalBranch { cond_value, .. } => vec![*cond_value],
+            Instruction::ContractCall {
+                params,
+                coins,
+                asset_id,
+                gas,
+            } => vec![*params, *coins, *asset_id, *gas],
+            Instruction::ExtractElement {
+                array, index_val, ..
+            } => vec![*array, *index_val],
+            Instruction::ExtractValue { aggregate, .. } => vec![*aggregate],
+            Instruction::GetPointer { .. } => Vec::new(),
+            Instruction::InsertElement {
+                array,
+                value,
+                index_val,
+                ..
+            } => vec![*array, *value, *index_val],
+            Instruction::InsertValue {
+                aggregate, value, ..
+            } => vec![*aggregate, *value],
+            Instruction::Load(src_val) => vec![*src_val],
+            Instruction::Nop => Vec::new(),
+            Instruction::Not(value) => vec![*value],
+            Instruction::Phi(pairs) => pairs.iter().map(|(_, val)| *val).collect(),
+            Instruction::ReadRegister(_) => Vec::new(),
+            Instruction::Ret(ret_val, _) => vec![*ret_val],
+            Instruction::StateLoadQuadWord { load_val, key } => vec![*load_val, *key],
+            Instruction::StateLoadWord(key) => vec![*key],
+            Instruction::StateStoreQuadWord { stored_val, key } => vec![*stored_val, *key],
+            Instruction::StateStoreWord { stored_val, key } => vec![*stored_val, *key],
+            Instruction::Store {
+                dst_val,
+                stored_val,
+            } => vec![*dst_val, *stored_val],
+        }
+    }
+
+    /// Return whether this instruction may have an effect other than producing its value.
+    ///
+    /// Such instructions must never be removed, even if their value is unused.  Accessing an
+    /// array element is included since the index is checked at run time and may revert, and
+    /// inserting into an aggregate is included since it writes to the aggregate in place.
+    pub fn may_have_side_effect(&self) -> bool {
+        match self {
+            Instruction::AsmBlock(..)
+            | Instruction::Call(..)
+            | Instruction::ContractCall { .. }
+            | Instruction::ExtractElement { .. }
+            | Instruction::InsertElement { .. }
+            | Instruction::InsertValue { .. }
+            | Instruction::StateLoadQuadWord { .. }
+            | Instruction::StateStoreQuadWord { .. }
+            | Instruction::StateStoreWord { .. }
+            | Instruction::Store { .. } => true,
+
+            // Terminators and phis are structural and are never removed by value.
+            Instruction::Branch(_)
+            | Instruction::ConditionalBranch { .. }
+            | Instruction::Phi(_)
+            | Instruction::Ret(..) => true,
+
+            Instruction::BinaryOp { .. }
+            | Instruction::Cmp(..)
+            | Instruction::ExtractValue { .. }
+            | Instruction::GetPointer { .. }
+            | Instruction::Load(_)
+            | Instruction::Nop
+            | Instruction::Not(_)
+            | Instruction::ReadRegister(_)
+            | Instruction::StateLoadWord(_) => false,
+        }
+    }
+
     /// Replace `old_val` with `new_val` if it is referenced by this instruction's arguments.
     pub fn replace_value(&mut self, old_val: Value, new_val: Value) {
         let replace = |val: &mut Value| {
@@ -232,6 +344,10 @@ impl Instruction {
                     .iter_mut()
                     .for_each(|init_val| replace(init_val))
             }),
+            Instruction::BinaryOp { lhs, rhs, .. } => {
+                replace(lhs);
+                replace(rhs);
+            }
             Instruction::Branch(_) => (),
             Instruction::Call(_, args) => args.iter_mut().for_each(replace),
             Instruction::Cmp(_, lhs_val, rhs_val) => {
@@ -275,8 +391,9 @@ impl Instruction {
                 replace(index_val);
             }
             Instruction::ExtractValue { aggregate, .. } => replace(aggregate),
-            Instruction::Load(_) => (),
+            Instruction::Load(src_val) => replace(src_val),
             Instruction::Nop => (),
+            Instruction::Not(value) => replace(value),
             Instruction::Phi(pairs) => pairs.iter_mut().for_each(|(_, val)| replace(val)),
             Instruction::ReadRegister { .. } => (),
             Instruction::Ret(ret_val, _) => replace(ret_val),
@@ -295,13 +412,48 @@ impl Instruction {
                 replace(key);
                 replace(stored_val);
             }
-            Instruction::Store { stored_val, .. } => {
+            Instruction::Store {
+                dst_val,
+                stored_val,
+            } => {
+                replace(dst_val);
                 replace(stored_val);
             }
         }
     }
 }
 
+// Find the type of a phi from its values.  In a loop the first value may be computed from the phi
+// itself, so the values which determine a type are followed until one is found, skipping cycles.
+fn get_phi_type(context: &Context, alts: &[(Block, Value)]) -> Option<Type> {
+    let mut visited = HashSet::new();
+    let mut worklist: Vec<Value> = alts.iter().rev().map(|(_, val)| *val).collect();
+    while let Some(value) = worklist.pop() {
+        if !visited.insert(value) {
+            continue;
+        }
+        match &context.values[value.0].value {
+            ValueDatum::Instruction(Instruction::Phi(alts)) => {
+                worklist.extend(alts.iter().rev().map(|(_, val)| *val))
+            }
+            ValueDatum::Instruction(
+                Instruction::BinaryOp { lhs: operand, .. }
+                | Instruction::Not(operand)
+                | Instruction::InsertElement { array: operand, .. }
+                | Instruction::InsertValue {
+                    aggregate: operand, ..
+                },
+            ) => worklist.push(*operand),
+            _otherwise => {
+                if let Some(ty) = value.get_type(context) {
+                    return Some(ty);
+                }
+            }
+        }
+    }
+    None
+}
+
 /// Iterate over all [`Instruction`]s in a specific [`Block`].
 pub struct InstructionIterator {
     instructions: Vec<generational_arena::Index>,
@@ -386,6 +538,24 @@ impl<'a> InstructionInserter<'a> {
         asm_val
     }
 
+    pub fn binary_op(
+        self,
+        op: BinaryOpKind,
+        lhs: Value,
+        rhs: Value,
+        span_md_idx: Option<MetadataIndex>,
+    ) -> Value {
+        let binary_op_val = Value::new_instruction(
+            self.context,
+            Instruction::BinaryOp { op, lhs, rhs },
+            span_md_idx,
+        );
+        self.context.blocks[self.block.0]
+            .instructions
+            .push(binary_op_val);
+        binary_op_val
+    }
+
     pub fn branch(
         self,
         to_block: Block,
@@ -613,6 +783,12 @@ impl<'a> InstructionInserter<'a> {
         nop_val
     }
 
+    pub fn not(self, value: Value, span_md_idx: Option<MetadataIndex>) -> Value {
+        let not_val = Value::new_instruction(self.context, Instruction::Not(value), span_md_idx);
+        self.context.blocks[self.block.0].instructions.push(not_val);
+        not_val
+    }
+
     pub fn read_register(self, reg: Register, span_md_idx: Option<MetadataIndex>) -> Value {
         let read_register_val =
             Value::new_instruction(self.context, Instruction::ReadRegister(reg), span_md_idx);
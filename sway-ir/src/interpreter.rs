@@ -0,0 +1,917 @@
+//! An interpreter for IR functions.
+//!
+//! The [`Interpreter`] executes a [`Function`] directly from the IR, which allows optimizations to
+//! be tested by comparing the results of a program before and after they're run, and allows the
+//! compiler to evaluate constant expressions.
+//!
+//! Values are modelled the same way the code generator represents them: the scalar types (unit,
+//! bool and integers) are single words, while everything else lives in memory and is referred to
+//! by its address.  So `insert_value` and `insert_element` update an aggregate in place and a
+//! `load` of a non-scalar returns the address it was loaded from rather than a copy.  Each field or
+//! element is word aligned.  Each constant aggregate used by a function gets its own memory once
+//! per call, again like the code generator.
+//!
+//! The program's environment is simulated:
+//!
+//! - Contract storage is a map from keys to 32 byte slots, initially empty.  A word is stored in
+//!   the first 8 bytes of its slot.
+//! - Contract calls aren't executed.  They're recorded and return zero.
+//! - Special registers read as zero, except for `$of` and `$err` within `asm` blocks.
+//!
+//! Arithmetic is done like the VM's ALU, which works on whole words whatever the integer type.
+//! Results wrap on overflow and set `$of`, division by zero gives zero and sets `$err`, and each
+//! other arithmetic, logic or comparison instruction clears both.  The code generator may emit
+//! arithmetic of its own between instructions, so the flags are only dependable straight after
+//! the instruction which set them.  The first `binary_op` instruction which overflows its type or
+//! divides by zero is also recorded, so that constant evaluation can reject it.
+//!
+//! Only a small set of arithmetic and memory instructions is supported within `asm` blocks.
+//!
+//! Memory is never reclaimed, and the number of instructions executed is limited, so the
+//! interpreter is intended for small programs only.
+
+use std::collections::HashMap;
+
+use crate::{
+    asm::AsmArg,
+    block::Block,
+    constant::{Constant, ConstantValue},
+    context::Context,
+    error::IrError,
+    function::Function,
+    instruction::{BinaryOpKind, Instruction, Predicate},
+    irtype::{Aggregate, Type},
+    pointer::Pointer,
+    value::{Value, ValueDatum},
+};
+
+/// The default maximum number of instructions to execute.
+const DEFAULT_STEP_LIMIT: u64 = 1_000_000;
+
+/// A contract call made by an interpreted program.  The address of the user arguments is not
+/// recorded as it's specific to the interpreter's memory layout.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StubbedContractCall {
+    pub contract_id: [u8; 32],
+    pub selector: u64,
+    pub coins: u64,
+    pub asset_id: [u8; 32],
+    pub gas: u64,
+}
+
+/// Executes functions within a [`Context`].  Storage and memory persist between calls.
+pub struct Interpreter<'a> {
+    context: &'a Context,
+    memory: Vec<u8>,
+    storage: HashMap<[u8; 32], [u8; 32]>,
+    contract_calls: Vec<StubbedContractCall>,
+    forbid_external_state: bool,
+    step_limit: u64,
+    steps: u64,
+    // The `$of` and `$err` registers.
+    overflow: u64,
+    error: u64,
+    // The first binary operation which overflowed its type or divided by zero.
+    arithmetic_fault: Option<Value>,
+}
+
+// The state of a function being executed.
+struct Frame {
+    block: Block,
+    // The position of the next instruction within `block`.
+    pos: usize,
+    values: HashMap<Value, u64>,
+    locals: HashMap<Pointer, u64>,
+    // The call instruction in the caller which receives the returned value.
+    ret_val: Option<Value>,
+}
+
+enum Step {
+    Continue,
+    Call(Function, Vec<u64>),
+    Return(u64, Type),
+}
+
+impl<'a> Interpreter<'a> {
+    /// Create an interpreter for the functions in `context`.
+    pub fn new(context: &'a Context) -> Self {
+        Interpreter {
+            context,
+            memory: Vec::new(),
+            storage: HashMap::new(),
+            contract_calls: Vec::new(),
+            forbid_external_state: false,
+            step_limit: DEFAULT_STEP_LIMIT,
+            steps: 0,
+            overflow: 0,
+            error: 0,
+            arithmetic_fault: None,
+        }
+    }
+
+    /// Limit the total number of instructions executed.
+    pub fn step_limit(self, step_limit: u64) -> Self {
+        Interpreter { step_limit, ..self }
+    }
+
+    /// Fail when accessing storage, calling a contract or reading a special register other than
+    /// `$of` and `$err` in an `asm` block, none of which are known when evaluating an expression at
+    /// compile time.
+    pub fn forbid_external_state(self) -> Self {
+        Interpreter {
+            forbid_external_state: true,
+            ..self
+        }
+    }
+
+    /// The first binary operation instruction executed which overflowed its type or divided by
+    /// zero, if any.  Arithmetic within `asm` blocks may rely on wrapping, so isn't included.
+    pub fn arithmetic_fault(&self) -> Option<Value> {
+        self.arithmetic_fault
+    }
+
+    /// The contract storage slots which have been written.
+    pub fn storage(&self) -> &HashMap<[u8; 32], [u8; 32]> {
+        &self.storage
+    }
+
+    /// The contract storage, which may be initialized before a call.
+    pub fn storage_mut(&mut self) -> &mut HashMap<[u8; 32], [u8; 32]> {
+        &mut self.storage
+    }
+
+    /// Every contract call made so far, in order.
+    pub fn contract_calls(&self) -> &[StubbedContractCall] {
+        &self.contract_calls
+    }
+
+    /// Call `function` with `args` and return its result.
+    pub fn call(&mut self, function: &Function, args: &[Constant]) -> Result<Constant, IrError> {
+        let context = self.context;
+        let fn_name = function.get_name(context);
+        let arg_count = context.functions[function.0].arguments.len();
+        if args.len() != arg_count {
+            return Err(IrError::ExecutionUnsupported(format!(
+                "function '{fn_name}' expects {arg_count} arguments, given {}.",
+                args.len()
+            )));
+        }
+        for ((_, arg_val), arg) in function.args_iter(context).zip(args) {
+            if !arg_val
+                .get_type(context)
+                .map(|arg_ty| arg_ty.eq(context, &arg.ty))
+                .unwrap_or(false)
+            {
+                return Err(IrError::ExecutionUnsupported(format!(
+                    "mismatched argument type for function '{fn_name}'."
+                )));
+            }
+        }
+
+        let args = args
+            .iter()
+            .map(|arg| self.materialize(arg))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut frames = vec![self.enter(function, args, None)?];
+        loop {
+            self.steps += 1;
+            if self.steps > self.step_limit {
+                return Err(IrError::ExecutionStepLimit(self.step_limit));
+            }
+
+            let frame = frames
+                .last_mut()
+                .expect("There is always a frame to execute.");
+            match self.step(frame)? {
+                Step::Continue => (),
+                Step::Call(callee, args) => {
+                    let ret_val = context.blocks[frame.block.0].instructions[frame.pos - 1];
+                    let callee_frame = self.enter(&callee, args, Some(ret_val))?;
+                    frames.push(callee_frame);
+                }
+                Step::Return(ret_word, ret_ty) => {
+                    let callee_frame = frames.pop().expect("The returning frame is on the stack.");
+                    match (frames.last_mut(), callee_frame.ret_val) {
+                        (Some(caller_frame), Some(ret_val)) => {
+                            caller_frame.values.insert(ret_val, ret_word);
+                        }
+                        _otherwise => return self.read_constant(&ret_ty, ret_word),
+                    }
+                }
+            }
+        }
+    }
+
+    // Create the frame for a call to `function`, allocating and initializing its locals.
+    fn enter(
+        &mut self,
+        function: &Function,
+        args: Vec<u64>,
+        ret_val: Option<Value>,
+    ) -> Result<Frame, IrError> {
+        let context = self.context;
+        let values = function
+            .args_iter(context)
+            .map(|(_, arg_val)| *arg_val)
+            .zip(args)
+            .collect();
+        let mut locals = HashMap::new();
+        for (_, ptr) in function.locals_iter(context) {
+            let addr = self.alloc(size_in_bytes(context, ptr.get_type(context)));
+            if let Some(initializer) = &context.pointers[ptr.0].initializer {
+                self.write_constant(addr, initializer)?;
+            }
+            locals.insert(*ptr, addr);
+        }
+        Ok(Frame {
+            block: function.get_entry_block(context),
+            pos: 0,
+            values,
+            locals,
+            ret_val,
+        })
+    }
+
+    // Execute the next instruction in `frame`.
+    fn step(&mut self, frame: &mut Frame) -> Result<Step, IrError> {
+        let context = self.context;
+        let ins_val = *context.blocks[frame.block.0]
+            .instructions
+            .get(frame.pos)
+            .ok_or_else(|| IrError::MissingTerminator(frame.block.get_label(context)))?;
+        frame.pos += 1;
+
+        let ins = match &context.values[ins_val.0].value {
+            ValueDatum::Instruction(ins) => ins,
+            _otherwise => unreachable!("Block instructions must be instructions."),
+        };
+        let result = match ins {
+            Instruction::AsmBlock(asm_block, args) => {
+                self.execute_asm_block(frame, &context.asm_blocks[asm_block.0], args)?
+            }
+            Instruction::BinaryOp { op, lhs, rhs } => {
+                let nbits = match lhs.get_type(context) {
+                    Some(Type::Uint(nbits)) => nbits,
+                    _otherwise => {
+                        return Err(IrError::ExecutionUnsupported(
+                            "binary operation on non-integers.".to_owned(),
+                        ))
+                    }
+                };
+                let lhs = self.eval(frame, lhs)?;
+                let rhs = self.eval(frame, rhs)?;
+                let result = self.alu(op, lhs, rhs);
+                let fits = nbits >= 64 || result >> nbits == 0;
+                if self.arithmetic_fault.is_none()
+                    && (self.overflow != 0 || self.error != 0 || !fits)
+                {
+                    self.arithmetic_fault = Some(ins_val);
+                }
+                result
+            }
+            Instruction::Branch(to_block) => {
+                self.branch(frame, *to_block)?;
+                return Ok(Step::Continue);
+            }
+            Instruction::Call(callee, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval(frame, arg))
+                    .collect::<Result<Vec<_>, _>>()?;
+                return Ok(Step::Call(*callee, args));
+            }
+            Instruction::Cmp(pred, lhs, rhs) => {
+                let lhs = self.eval(frame, lhs)?;
+                let rhs = self.eval(frame, rhs)?;
+                self.alu_set(u64::from(match pred {
+                    Predicate::Equal => lhs == rhs,
+                    Predicate::LessThan => lhs < rhs,
+                    Predicate::GreaterThan => lhs > rhs,
+                }))
+            }
+            Instruction::ConditionalBranch {
+                cond_value,
+                true_block,
+                false_block,
+            } => {
+                let to_block = if self.eval(frame, cond_value)? != 0 {
+                    *true_block
+                } else {
+                    *false_block
+                };
+                self.branch(frame, to_block)?;
+                return Ok(Step::Continue);
+            }
+            Instruction::ContractCall {
+                params,
+                coins,
+                asset_id,
+                gas,
+            } => {
+                self.check_external_state("contract call")?;
+                let params = self.eval(frame, params)?;
+                let coins = self.eval(frame, coins)?;
+                let asset_id = self.eval(frame, asset_id)?;
+                let gas = self.eval(frame, gas)?;
+                let call = StubbedContractCall {
+                    contract_id: self.read_b256(params)?,
+                    selector: self.read_word(params + 32)?,
+                    coins,
+                    asset_id: self.read_b256(asset_id)?,
+                    gas,
+                };
+                self.contract_calls.push(call);
+                0
+            }
+            Instruction::ExtractElement {
+                array,
+                ty,
+                index_val,
+            } => {
+                let elem_addr = self.element_addr(frame, array, ty, index_val)?;
+                let elem_ty = array_elem_type(context, ty).0;
+                self.read_field(&elem_ty, elem_addr)?
+            }
+            Instruction::ExtractValue {
+                aggregate,
+                ty,
+                indices,
+            } => {
+                let (field_offs, field_ty) = field_layout(context, ty, indices)?;
+                let field_addr = self.eval(frame, aggregate)? + field_offs;
+                self.read_field(&field_ty, field_addr)?
+            }
+            Instruction::GetPointer {
+                base_ptr,
+                ptr_ty,
+                offset,
+            } => {
+                let base_addr = frame.locals.get(base_ptr).copied().ok_or_else(|| {
+                    IrError::ExecutionUnsupported("pointer to a non-local.".to_owned())
+                })?;
+                base_addr + size_in_bytes(context, ptr_ty) * offset
+            }
+            Instruction::InsertElement {
+                array,
+                ty,
+                value,
+                index_val,
+            } => {
+                let elem_addr = self.element_addr(frame, array, ty, index_val)?;
+                let elem_ty = array_elem_type(context, ty).0;
+                let value = self.eval(frame, value)?;
+                self.write_field(&elem_ty, elem_addr, value)?;
+                self.eval(frame, array)?
+            }
+            Instruction::InsertValue {
+                aggregate,
+                ty,
+                value,
+                indices,
+            } => {
+                let (field_offs, field_ty) = field_layout(context, ty, indices)?;
+                let aggregate = self.eval(frame, aggregate)?;
+                let value = self.eval(frame, value)?;
+                self.write_field(&field_ty, aggregate + field_offs, value)?;
+                aggregate
+            }
+            Instruction::Load(src_val) => {
+                let ty = ins.get_type(context).ok_or_else(|| {
+                    IrError::ExecutionUnsupported("load of unknown type.".to_owned())
+                })?;
+                let src_addr = self.eval(frame, src_val)?;
+                self.read_field(&ty, src_addr)?
+            }
+            Instruction::Nop => return Ok(Step::Continue),
+            Instruction::Not(value) => {
+                let is_bool = matches!(value.get_type(context), Some(Type::Bool));
+                let value = self.eval(frame, value)?;
+                if is_bool {
+                    self.alu_set(u64::from(value == 0))
+                } else {
+                    self.alu_set(!value)
+                }
+            }
+            // Phis are assigned when their block is branched to.
+            Instruction::Phi(_) => return Ok(Step::Continue),
+            Instruction::ReadRegister(_) => {
+                self.check_external_state("special register")?;
+                0
+            }
+            Instruction::Ret(ret_val, ty) => {
+                let ret_word = self.eval(frame, ret_val)?;
+                return Ok(Step::Return(ret_word, *ty));
+            }
+            Instruction::StateLoadQuadWord { load_val, key } => {
+                let slot = self.storage_slot(frame, key)?;
+                let load_addr = self.eval(frame, load_val)?;
+                self.write_bytes(load_addr, &slot)?;
+                return Ok(Step::Continue);
+            }
+            Instruction::StateLoadWord(key) => {
+                let slot = self.storage_slot(frame, key)?;
+                u64::from_be_bytes(slot[..8].try_into().unwrap())
+            }
+            Instruction::StateStoreQuadWord { stored_val, key } => {
+                let key = self.storage_key(frame, key)?;
+                let stored_addr = self.eval(frame, stored_val)?;
+                let slot = self.read_b256(stored_addr)?;
+                self.storage.insert(key, slot);
+                return Ok(Step::Continue);
+            }
+            Instruction::StateStoreWord { stored_val, key } => {
+                let key = self.storage_key(frame, key)?;
+                let mut slot = [0; 32];
+                slot[..8].copy_from_slice(&self.eval(frame, stored_val)?.to_be_bytes());
+                self.storage.insert(key, slot);
+                return Ok(Step::Continue);
+            }
+            Instruction::Store {
+                dst_val,
+                stored_val,
+            } => {
+                let ty = stored_val.get_type(context).ok_or_else(|| {
+                    IrError::ExecutionUnsupported("store of unknown type.".to_owned())
+                })?;
+                let dst_addr = self.eval(frame, dst_val)?;
+                let value = self.eval(frame, stored_val)?;
+                self.write_field(&ty, dst_addr, value)?;
+                return Ok(Step::Continue);
+            }
+        };
+        frame.values.insert(ins_val, result);
+        Ok(Step::Continue)
+    }
+
+    // Move execution to `to_block`, assigning its phis their values from the current block.  All
+    // the phis are evaluated before any are assigned, as they may refer to each other.
+    fn branch(&mut self, frame: &mut Frame, to_block: Block) -> Result<(), IrError> {
+        let context = self.context;
+        let mut phi_values = Vec::new();
+        for ins_val in &context.blocks[to_block.0].instructions {
+            match &context.values[ins_val.0].value {
+                ValueDatum::Instruction(Instruction::Phi(alts)) if !alts.is_empty() => {
+                    let value = alts
+                        .iter()
+                        .find(|(from_block, _)| *from_block == frame.block)
+                        .map(|(_, value)| value)
+                        .ok_or_else(|| {
+                            IrError::ValueNotFound(format!(
+                                "phi in block {} has no value from block {}.",
+                                to_block.get_label(context),
+                                frame.block.get_label(context)
+                            ))
+                        })?;
+                    phi_values.push((*ins_val, self.eval(frame, value)?));
+                }
+                ValueDatum::Instruction(Instruction::Phi(_)) => (),
+                _otherwise => break,
+            }
+        }
+        frame.values.extend(phi_values);
+        frame.block = to_block;
+        frame.pos = 0;
+        Ok(())
+    }
+
+    // Get the word for `value`, which is either its scalar value or its address.
+    fn eval(&mut self, frame: &mut Frame, value: &Value) -> Result<u64, IrError> {
+        if let Some(word) = frame.values.get(value) {
+            return Ok(*word);
+        }
+        match &self.context.values[value.0].value {
+            ValueDatum::Constant(constant) => {
+                let word = self.materialize(constant)?;
+                frame.values.insert(*value, word);
+                Ok(word)
+            }
+            _otherwise => Err(IrError::ValueNotFound(
+                "value used before it is defined.".to_owned(),
+            )),
+        }
+    }
+
+    fn execute_asm_block(
+        &mut self,
+        frame: &mut Frame,
+        asm_block: &crate::asm::AsmBlockContent,
+        args: &[AsmArg],
+    ) -> Result<u64, IrError> {
+        let forbid_external_state = self.forbid_external_state;
+        let mut regs: HashMap<&str, u64> = HashMap::new();
+        for arg in args {
+            let init = match &arg.initializer {
+                Some(init_val) => self.eval(frame, init_val)?,
+                None => 0,
+            };
+            regs.insert(arg.name.as_str(), init);
+        }
+
+        for asm_ins in &asm_block.body {
+            let name = asm_ins.name.as_str();
+            let imm = asm_ins
+                .immediate
+                .as_ref()
+                .map(|imm| {
+                    imm.as_str()[1..].parse::<u64>().map_err(|_| {
+                        IrError::ExecutionUnsupported(format!("asm immediate '{}'.", imm.as_str()))
+                    })
+                })
+                .transpose()?;
+            let (overflow, error) = (self.overflow, self.error);
+            let get = |regs: &HashMap<&str, u64>, idx: usize| -> Result<u64, IrError> {
+                let reg = asm_ins
+                    .args
+                    .get(idx)
+                    .map(|reg| reg.as_str())
+                    .ok_or_else(|| {
+                        IrError::ExecutionUnsupported(format!("missing argument for '{name}'."))
+                    })?;
+                match (reg, regs.get(reg)) {
+                    (_, Some(value)) => Ok(*value),
+                    ("zero", None) => Ok(0),
+                    ("one", None) => Ok(1),
+                    ("of", None) => Ok(overflow),
+                    ("err", None) => Ok(error),
+                    (_, None) if forbid_external_state => Err(IrError::ExecutionUnsupported(
+                        "special register is not available.".to_owned(),
+                    )),
+                    (_, None) => Ok(0),
+                }
+            };
+            let imm_or_reg = |regs: &HashMap<&str, u64>, idx: usize| match imm {
+                Some(imm) => Ok(imm),
+                None => get(regs, idx),
+            };
+            let result = match name {
+                "add" | "addi" => {
+                    Some(self.alu(&BinaryOpKind::Add, get(&regs, 1)?, imm_or_reg(&regs, 2)?))
+                }
+                "sub" | "subi" => {
+                    Some(self.alu(&BinaryOpKind::Sub, get(&regs, 1)?, imm_or_reg(&regs, 2)?))
+                }
+                "mul" | "muli" => {
+                    Some(self.alu(&BinaryOpKind::Mul, get(&regs, 1)?, imm_or_reg(&regs, 2)?))
+                }
+                "div" | "divi" => {
+                    Some(self.alu(&BinaryOpKind::Div, get(&regs, 1)?, imm_or_reg(&regs, 2)?))
+                }
+                "mod" | "modi" => {
+                    Some(self.alu(&BinaryOpKind::Mod, get(&regs, 1)?, imm_or_reg(&regs, 2)?))
+                }
+                "and" | "andi" => {
+                    Some(self.alu(&BinaryOpKind::And, get(&regs, 1)?, imm_or_reg(&regs, 2)?))
+                }
+                "or" | "ori" => {
+                    Some(self.alu(&BinaryOpKind::Or, get(&regs, 1)?, imm_or_reg(&regs, 2)?))
+                }
+                "xor" | "xori" => {
+                    Some(self.alu(&BinaryOpKind::Xor, get(&regs, 1)?, imm_or_reg(&regs, 2)?))
+                }
+                "sll" | "slli" => {
+                    Some(self.alu(&BinaryOpKind::Shl, get(&regs, 1)?, imm_or_reg(&regs, 2)?))
+                }
+                "srl" | "srli" => {
+                    Some(self.alu(&BinaryOpKind::Shr, get(&regs, 1)?, imm_or_reg(&regs, 2)?))
+                }
+                "eq" => Some(self.alu_set(u64::from(get(&regs, 1)? == get(&regs, 2)?))),
+                "lt" => Some(self.alu_set(u64::from(get(&regs, 1)? < get(&regs, 2)?))),
+                "gt" => Some(self.alu_set(u64::from(get(&regs, 1)? > get(&regs, 2)?))),
+                "not" => Some(self.alu_set(!get(&regs, 1)?)),
+                "move" => Some(self.alu_set(get(&regs, 1)?)),
+                "lw" => Some(self.read_word(get(&regs, 1)? + imm.unwrap_or(0) * 8)?),
+                "lb" => Some(u64::from(
+                    self.read_bytes(get(&regs, 1)? + imm.unwrap_or(0), 1)?[0],
+                )),
+                "sw" => {
+                    let addr = get(&regs, 0)? + imm.unwrap_or(0) * 8;
+                    self.write_bytes(addr, &get(&regs, 1)?.to_be_bytes())?;
+                    None
+                }
+                "sb" => {
+                    let addr = get(&regs, 0)? + imm.unwrap_or(0);
+                    self.write_bytes(addr, &[get(&regs, 1)? as u8])?;
+                    None
+                }
+                "mcp" | "mcpi" => {
+                    let len = imm_or_reg(&regs, 2)?;
+                    let bytes = self.read_bytes(get(&regs, 1)?, len)?.to_vec();
+                    self.write_bytes(get(&regs, 0)?, &bytes)?;
+                    None
+                }
+                "meq" => {
+                    let len = get(&regs, 3)?;
+                    Some(u64::from(
+                        self.read_bytes(get(&regs, 1)?, len)?
+                            == self.read_bytes(get(&regs, 2)?, len)?,
+                    ))
+                }
+                "rvrt" => return Err(IrError::ExecutionReverted(get(&regs, 0)?)),
+                "log" | "logd" | "noop" => None,
+                _otherwise => {
+                    return Err(IrError::ExecutionUnsupported(format!(
+                        "asm instruction '{name}'."
+                    )))
+                }
+            };
+            if let Some(result) = result {
+                let dst = asm_ins.args[0].as_str();
+                regs.insert(dst, result);
+            }
+        }
+
+        Ok(match &asm_block.return_name {
+            Some(ret_name) => regs.get(ret_name.as_str()).copied().unwrap_or(0),
+            None => 0,
+        })
+    }
+
+    // Execute an arithmetic or logic instruction like the VM's ALU, setting `$of` and `$err`.
+    fn alu(&mut self, op: &BinaryOpKind, lhs: u64, rhs: u64) -> u64 {
+        let (result, overflow, error) = match op {
+            BinaryOpKind::Add => with_overflow(lhs.overflowing_add(rhs)),
+            BinaryOpKind::Sub => with_overflow(lhs.overflowing_sub(rhs)),
+            BinaryOpKind::Mul => with_overflow(lhs.overflowing_mul(rhs)),
+            BinaryOpKind::Div | BinaryOpKind::Mod if rhs == 0 => (0, false, true),
+            BinaryOpKind::Div => (lhs / rhs, false, false),
+            BinaryOpKind::Mod => (lhs % rhs, false, false),
+            BinaryOpKind::And => (lhs & rhs, false, false),
+            BinaryOpKind::Or => (lhs | rhs, false, false),
+            BinaryOpKind::Xor => (lhs ^ rhs, false, false),
+            // The VM truncates the shift amount to 32 bits, and then shifts by it modulo 64.
+            BinaryOpKind::Shl => with_overflow(lhs.overflowing_shl(rhs as u32)),
+            BinaryOpKind::Shr => with_overflow(lhs.overflowing_shr(rhs as u32)),
+        };
+        self.overflow = u64::from(overflow);
+        self.error = u64::from(error);
+        result
+    }
+
+    // Set a register like the VM's ALU does for comparisons and moves, clearing `$of` and `$err`.
+    fn alu_set(&mut self, result: u64) -> u64 {
+        self.overflow = 0;
+        self.error = 0;
+        result
+    }
+
+    fn check_external_state(&self, what: &str) -> Result<(), IrError> {
+        if self.forbid_external_state {
+            Err(IrError::ExecutionUnsupported(format!(
+                "{what} is not available."
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn storage_key(&mut self, frame: &mut Frame, key: &Value) -> Result<[u8; 32], IrError> {
+        self.check_external_state("storage")?;
+        let key_addr = self.eval(frame, key)?;
+        self.read_b256(key_addr)
+    }
+
+    fn storage_slot(&mut self, frame: &mut Frame, key: &Value) -> Result<[u8; 32], IrError> {
+        let key = self.storage_key(frame, key)?;
+        Ok(self.storage.get(&key).copied().unwrap_or([0; 32]))
+    }
+
+    fn element_addr(
+        &mut self,
+        frame: &mut Frame,
+        array: &Value,
+        ty: &Aggregate,
+        index_val: &Value,
+    ) -> Result<u64, IrError> {
+        let (elem_ty, count) = array_elem_type(self.context, ty);
+        let index = self.eval(frame, index_val)?;
+        if index >= count {
+            return Err(IrError::ExecutionPanic(format!(
+                "array index {index} is out of bounds for {count} elements."
+            )));
+        }
+        Ok(self.eval(frame, array)? + index * size_in_bytes(self.context, &elem_ty))
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // Memory.
+
+    fn alloc(&mut self, size: u64) -> u64 {
+        let addr = self.memory.len() as u64;
+        self.memory.resize(self.memory.len() + size as usize, 0);
+        addr
+    }
+
+    fn read_bytes(&self, addr: u64, len: u64) -> Result<&[u8], IrError> {
+        addr.checked_add(len)
+            .and_then(|end| self.memory.get(addr as usize..end as usize))
+            .ok_or_else(|| IrError::ExecutionPanic(format!("invalid memory read at {addr}.")))
+    }
+
+    fn write_bytes(&mut self, addr: u64, bytes: &[u8]) -> Result<(), IrError> {
+        addr.checked_add(bytes.len() as u64)
+            .and_then(|end| self.memory.get_mut(addr as usize..end as usize))
+            .ok_or_else(|| IrError::ExecutionPanic(format!("invalid memory write at {addr}.")))?
+            .copy_from_slice(bytes);
+        Ok(())
+    }
+
+    fn read_word(&self, addr: u64) -> Result<u64, IrError> {
+        Ok(u64::from_be_bytes(
+            self.read_bytes(addr, 8)?.try_into().unwrap(),
+        ))
+    }
+
+    fn read_b256(&self, addr: u64) -> Result<[u8; 32], IrError> {
+        Ok(self.read_bytes(addr, 32)?.try_into().unwrap())
+    }
+
+    // Read a value of type `ty` at `addr`, which for non-scalars is the address itself.
+    fn read_field(&self, ty: &Type, addr: u64) -> Result<u64, IrError> {
+        if is_word(ty) {
+            self.read_word(addr)
+        } else {
+            Ok(addr)
+        }
+    }
+
+    // Write a value of type `ty` to `addr`, copying non-scalars from the memory they're in.
+    fn write_field(&mut self, ty: &Type, addr: u64, value: u64) -> Result<(), IrError> {
+        if is_word(ty) {
+            self.write_bytes(addr, &value.to_be_bytes())
+        } else {
+            let size = size_in_bytes(self.context, ty);
+            let bytes = self.read_bytes(value, size)?.to_vec();
+            self.write_bytes(addr, &bytes)
+        }
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // Conversion to and from constants.
+
+    // Get the word for a constant, putting non-scalars into newly allocated memory.
+    fn materialize(&mut self, constant: &Constant) -> Result<u64, IrError> {
+        if is_word(&constant.ty) {
+            Ok(constant_word(constant))
+        } else {
+            let addr = self.alloc(size_in_bytes(self.context, &constant.ty));
+            self.write_constant(addr, constant)?;
+            Ok(addr)
+        }
+    }
+
+    fn write_constant(&mut self, addr: u64, constant: &Constant) -> Result<(), IrError> {
+        let context = self.context;
+        match (&constant.value, &constant.ty) {
+            (ConstantValue::Undef, _) => Ok(()),
+            (ConstantValue::Unit | ConstantValue::Bool(_) | ConstantValue::Uint(_), _) => {
+                self.write_bytes(addr, &constant_word(constant).to_be_bytes())
+            }
+            (ConstantValue::B256(bytes), _) => self.write_bytes(addr, bytes),
+            (ConstantValue::String(string), _) => self.write_bytes(addr, string.as_bytes()),
+            (ConstantValue::Array(elems), Type::Array(aggregate)) => {
+                let elem_size = size_in_bytes(context, &array_elem_type(context, aggregate).0);
+                for (idx, elem) in elems.iter().enumerate() {
+                    self.write_constant(addr + idx as u64 * elem_size, elem)?;
+                }
+                Ok(())
+            }
+            (ConstantValue::Struct(fields), Type::Struct(aggregate)) => {
+                let mut field_addr = addr;
+                for (field, field_ty) in fields
+                    .iter()
+                    .zip(context.aggregates[aggregate.0].field_types())
+                {
+                    self.write_constant(field_addr, field)?;
+                    field_addr += size_in_bytes(context, field_ty);
+                }
+                Ok(())
+            }
+            _otherwise => Err(IrError::ExecutionUnsupported(
+                "constant of mismatched type.".to_owned(),
+            )),
+        }
+    }
+
+    // Convert the word for a value of type `ty` to a constant.  A union is read as its largest
+    // variant.
+    fn read_constant(&self, ty: &Type, word: u64) -> Result<Constant, IrError> {
+        let context = self.context;
+        Ok(match ty {
+            Type::Unit => Constant::new_unit(),
+            Type::Bool => Constant::new_bool(word != 0),
+            Type::Uint(nbits) => Constant::new_uint(*nbits, word),
+            Type::B256 => Constant::new_b256(self.read_b256(word)?),
+            Type::String(len) => Constant {
+                ty: *ty,
+                value: ConstantValue::String(
+                    String::from_utf8_lossy(self.read_bytes(word, *len)?).into_owned(),
+                ),
+            },
+            Type::Array(aggregate) => {
+                let (elem_ty, count) = array_elem_type(context, aggregate);
+                let elem_size = size_in_bytes(context, &elem_ty);
+                let elems = (0..count)
+                    .map(|idx| {
+                        let elem_addr = word + idx * elem_size;
+                        self.read_constant(&elem_ty, self.read_field(&elem_ty, elem_addr)?)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Constant::new_array(aggregate, elems)
+            }
+            Type::Struct(aggregate) => {
+                let mut field_addr = word;
+                let mut fields = Vec::new();
+                for field_ty in context.aggregates[aggregate.0].field_types() {
+                    fields.push(
+                        self.read_constant(field_ty, self.read_field(field_ty, field_addr)?)?,
+                    );
+                    field_addr += size_in_bytes(context, field_ty);
+                }
+                Constant::new_struct(aggregate, fields)
+            }
+            Type::Union(aggregate) => {
+                let variant_ty = context.aggregates[aggregate.0]
+                    .field_types()
+                    .iter()
+                    .max_by_key(|variant_ty| size_in_bytes(context, variant_ty))
+                    .copied()
+                    .unwrap_or(Type::Unit);
+                self.read_constant(&variant_ty, self.read_field(&variant_ty, word)?)?
+            }
+        })
+    }
+}
+
+fn with_overflow((result, overflow): (u64, bool)) -> (u64, bool, bool) {
+    (result, overflow, false)
+}
+
+fn is_word(ty: &Type) -> bool {
+    matches!(ty, Type::Unit | Type::Bool | Type::Uint(_))
+}
+
+fn constant_word(constant: &Constant) -> u64 {
+    match &constant.value {
+        ConstantValue::Bool(b) => u64::from(*b),
+        ConstantValue::Uint(n) => *n,
+        _otherwise => 0,
+    }
+}
+
+// The size of a type in memory, with every field and element word aligned.
+fn size_in_bytes(context: &Context, ty: &Type) -> u64 {
+    match ty {
+        Type::Unit | Type::Bool | Type::Uint(_) => 8,
+        Type::B256 => 32,
+        Type::String(len) => (len + 7) & !7,
+        Type::Array(aggregate) => {
+            let (elem_ty, count) = array_elem_type(context, aggregate);
+            size_in_bytes(context, &elem_ty) * count
+        }
+        Type::Struct(aggregate) => context.aggregates[aggregate.0]
+            .field_types()
+            .iter()
+            .map(|field_ty| size_in_bytes(context, field_ty))
+            .sum(),
+        Type::Union(aggregate) => context.aggregates[aggregate.0]
+            .field_types()
+            .iter()
+            .map(|field_ty| size_in_bytes(context, field_ty))
+            .max()
+            .unwrap_or(0),
+    }
+}
+
+fn array_elem_type(context: &Context, aggregate: &Aggregate) -> (Type, u64) {
+    let (elem_ty, count) = context.aggregates[aggregate.0].array_type();
+    (*elem_ty, *count)
+}
+
+// Find the offset and type of a (nested) field.  Every union variant is at offset 0.
+fn field_layout(
+    context: &Context,
+    aggregate: &Aggregate,
+    indices: &[u64],
+) -> Result<(u64, Type), IrError> {
+    indices
+        .iter()
+        .try_fold((0, Type::Struct(*aggregate)), |(offs, ty), idx| match ty {
+            Type::Struct(aggregate) => {
+                let field_types = context.aggregates[aggregate.0].field_types();
+                let field_offs = field_types
+                    .iter()
+                    .take(*idx as usize)
+                    .map(|field_ty| size_in_bytes(context, field_ty))
+                    .sum::<u64>();
+                field_types
+                    .get(*idx as usize)
+                    .map(|field_ty| (offs + field_offs, *field_ty))
+            }
+            Type::Union(aggregate) => context.aggregates[aggregate.0]
+                .field_types()
+                .get(*idx as usize)
+                .map(|variant_ty| (offs, *variant_ty)),
+            _otherwise => None,
+        })
+        .ok_or_else(|| IrError::ExecutionUnsupported("invalid aggregate indices.".to_owned()))
+}
@@ -17,7 +17,8 @@
 //! Other important data types are [`Value`], [`Type`] and [`Constant`].  Function arguments, local
 //! variables, instructions and constants are all [`Value`]s.
 //!
-//! The optimization passes are found in the [optimize] module.
+//! The optimization passes are found in the [optimize] module, and the analyses they depend on in
+//! the [analysis] module.  They are run by name, or as a pipeline, by a [`PassManager`].
 //!
 //! # Note:
 //!
@@ -37,6 +38,8 @@
 // For now it's easiest to just export absolutely everything to core_lang, we can refine the public
 // API when it's closer to finished.
 
+pub mod analysis;
+pub use analysis::*;
 pub mod asm;
 pub use asm::*;
 pub mod block;
@@ -51,6 +54,8 @@ pub mod function;
 pub use function::*;
 pub mod instruction;
 pub use instruction::*;
+pub mod interpreter;
+pub use interpreter::*;
 pub mod irtype;
 pub use irtype::*;
 pub mod metadata;
@@ -61,6 +66,8 @@ pub mod optimize;
 pub use optimize::*;
 pub mod parser;
 pub use parser::*;
+pub mod pass_manager;
+pub use pass_manager::*;
 pub mod pointer;
 pub use pointer::*;
 pub mod printer;
@@ -2,12 +2,11 @@
 //!
 //! Each of these modules are a collection of typical code optimisation passes.
 //!
-//! Currently there is no pass manager, as there are only a couple of passes, but this is something
-//! which will be added in the future.
-//!
-//! So each of the functions under this module will return a boolean indicating whether a
+//! Each of the functions under this module will return a boolean indicating whether a
 //! modification to the IR was made.  Typically the passes will be just re-run until they no longer
-//! make any such modifications, implying they've optimized as much possible.
+//! make any such modifications, implying they've optimized as much possible.  They are registered
+//! by name with the [`PassManager`](crate::pass_manager::PassManager), which is the usual way to
+//! run them.
 //!
 //! When writing passes one should keep in mind that when a modification is made then any iterators
 //! over blocks or instructions can be invalidated, and starting over is a safer option than trying
@@ -17,3 +16,15 @@ pub mod inline;
 pub use inline::*;
 pub mod constants;
 pub use constants::*;
+pub mod cse;
+pub use cse::*;
+pub mod dce;
+pub use dce::*;
+pub mod mem2reg;
+pub use mem2reg::*;
+pub mod redundant_loads;
+pub use redundant_loads::*;
+pub mod simplify_cfg;
+pub use simplify_cfg::*;
+pub mod storage_access;
+pub use storage_access::*;
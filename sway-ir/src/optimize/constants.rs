@@ -3,13 +3,20 @@
 //! - combining - compile time evaluation of constant expressions.
 //!   - combine insert_values - reduce expressions which insert a constant value into a constant
 //!     struct.
+//!   - fold instructions - evaluate arithmetic, comparisons and aggregate accesses whose
+//!     operands are all constant, propagating the result to their uses.
+//!   - combine conditional branches - replace a `cbr` on a constant condition with a `br`.
+//!   - combine phis - replace a phi which has the same value from every predecessor with that
+//!     value.
 
 use crate::{
+    block::Block,
     constant::{Constant, ConstantValue},
     context::Context,
     error::IrError,
     function::Function,
-    instruction::Instruction,
+    instruction::{BinaryOpKind, Instruction, Predicate},
+    irtype::Type,
     value::{Value, ValueContent, ValueDatum},
 };
 
@@ -21,6 +28,18 @@ pub fn combine_constants(context: &mut Context, function: &Function) -> Result<b
             modified = true;
             continue;
         }
+        if fold_const_instructions(context, function) {
+            modified = true;
+            continue;
+        }
+        if combine_const_cond_branches(context, function)? {
+            modified = true;
+            continue;
+        }
+        if combine_phis(context, function) {
+            modified = true;
+            continue;
+        }
 
         // Other passes here... always continue to the top if pass returns true.
         break;
@@ -139,3 +158,197 @@ fn inject_constant_into_aggregate(aggregate: &mut Constant, value: Constant, ind
         }
     }
 }
+
+fn fold_const_instructions(context: &mut Context, function: &Function) -> bool {
+    // Find an instruction which evaluates to a constant.
+    let candidate = function
+        .instruction_iter(context)
+        .find_map(|(block, ins_val)| match &context.values[ins_val.0] {
+            ValueContent {
+                value: ValueDatum::Instruction(instruction),
+                span_md_idx,
+            } => fold_instruction(context, instruction)
+                .map(|const_val| (block, ins_val, const_val, *span_md_idx)),
+            _otherwise => None,
+        });
+
+    if let Some((block, ins_val, const_val, span_md_idx)) = candidate {
+        // Replace uses of the instruction with the new constant and then remove it.
+        let const_val = Value::new_constant(context, const_val, span_md_idx);
+        function.replace_value(context, ins_val, const_val, None);
+        block.remove_instruction(context, ins_val);
+        return true;
+    }
+
+    false
+}
+
+fn fold_instruction(context: &Context, instruction: &Instruction) -> Option<Constant> {
+    let get_const = |val: &Value| match &context.values[val.0].value {
+        ValueDatum::Constant(c) => Some(c),
+        _otherwise => None,
+    };
+    match instruction {
+        Instruction::BinaryOp { op, lhs, rhs } => match (get_const(lhs)?, get_const(rhs)?) {
+            (
+                Constant {
+                    ty: Type::Uint(nbits),
+                    value: ConstantValue::Uint(lhs),
+                },
+                Constant {
+                    value: ConstantValue::Uint(rhs),
+                    ..
+                },
+            ) => fold_binary_op(op, *lhs, *rhs, *nbits).map(|n| Constant::new_uint(*nbits, n)),
+            _otherwise => None,
+        },
+        Instruction::Cmp(pred, lhs, rhs) => {
+            match (&get_const(lhs)?.value, &get_const(rhs)?.value) {
+                (ConstantValue::Uint(lhs), ConstantValue::Uint(rhs)) => {
+                    Some(Constant::new_bool(match pred {
+                        Predicate::Equal => lhs == rhs,
+                        Predicate::LessThan => lhs < rhs,
+                        Predicate::GreaterThan => lhs > rhs,
+                    }))
+                }
+                _otherwise => None,
+            }
+        }
+        Instruction::Not(value) => match get_const(value)? {
+            Constant {
+                value: ConstantValue::Bool(b),
+                ..
+            } => Some(Constant::new_bool(!b)),
+            // Only a full word may be inverted, narrower integers would have their upper bits set
+            // by the VM.
+            Constant {
+                ty: Type::Uint(64),
+                value: ConstantValue::Uint(n),
+            } => Some(Constant::new_uint(64, !n)),
+            _otherwise => None,
+        },
+        Instruction::ExtractValue {
+            aggregate, indices, ..
+        } => indices
+            .iter()
+            .try_fold(get_const(aggregate)?, |field, idx| match &field.value {
+                ConstantValue::Struct(fields) => fields.get(*idx as usize),
+                _otherwise => None,
+            })
+            .filter(|field| !matches!(field.value, ConstantValue::Undef))
+            .cloned(),
+        Instruction::ExtractElement {
+            array, index_val, ..
+        } => match (&get_const(array)?.value, &get_const(index_val)?.value) {
+            // An out of bounds index is left to fail at run time.
+            (ConstantValue::Array(elems), ConstantValue::Uint(idx)) => elems
+                .get(*idx as usize)
+                .filter(|elem| !matches!(elem.value, ConstantValue::Undef))
+                .cloned(),
+            _otherwise => None,
+        },
+        _otherwise => None,
+    }
+}
+
+/// Evaluate a binary operation the same way the VM would, or return `None` if it would overflow
+/// its type or otherwise fail, which must be left to happen at run time.
+pub(crate) fn fold_binary_op(op: &BinaryOpKind, lhs: u64, rhs: u64, nbits: u8) -> Option<u64> {
+    let result = match op {
+        BinaryOpKind::Add => lhs.checked_add(rhs),
+        BinaryOpKind::Sub => lhs.checked_sub(rhs),
+        BinaryOpKind::Mul => lhs.checked_mul(rhs),
+        BinaryOpKind::Div => lhs.checked_div(rhs),
+        BinaryOpKind::Mod => lhs.checked_rem(rhs),
+        BinaryOpKind::And => Some(lhs & rhs),
+        BinaryOpKind::Or => Some(lhs | rhs),
+        BinaryOpKind::Xor => Some(lhs ^ rhs),
+        BinaryOpKind::Shl => (rhs < 64).then(|| lhs << rhs),
+        BinaryOpKind::Shr => (rhs < 64).then(|| lhs >> rhs),
+    }?;
+    if nbits >= 64 || result < (1 << nbits) {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+fn combine_const_cond_branches(
+    context: &mut Context,
+    function: &Function,
+) -> Result<bool, IrError> {
+    // Find a conditional branch on a constant condition.
+    let candidate = function
+        .instruction_iter(context)
+        .find_map(|(block, ins_val)| match &context.values[ins_val.0].value {
+            ValueDatum::Instruction(Instruction::ConditionalBranch {
+                cond_value,
+                true_block,
+                false_block,
+            }) => match &context.values[cond_value.0].value {
+                ValueDatum::Constant(Constant {
+                    value: ConstantValue::Bool(cond),
+                    ..
+                }) => Some(if *cond {
+                    (block, ins_val, *true_block, *false_block)
+                } else {
+                    (block, ins_val, *false_block, *true_block)
+                }),
+                _otherwise => None,
+            },
+            _otherwise => None,
+        });
+
+    if let Some((block, cbr_val, taken_block, untaken_block)) = candidate {
+        // The untaken block is no longer a successor so must forget any phi value from here.  The
+        // taken block already has any phi value from the `cbr`.
+        if untaken_block != taken_block {
+            untaken_block.remove_phi_val_coming_from(context, &block);
+        }
+
+        let span_md_idx = context.values[cbr_val.0].span_md_idx;
+        let br_val = Value::new_instruction(context, Instruction::Branch(taken_block), span_md_idx);
+        block.replace_instruction(context, cbr_val, br_val)?;
+        return Ok(true);
+    }
+
+    Ok(false)
+}
+
+fn combine_phis(context: &mut Context, function: &Function) -> bool {
+    // Find a phi which has the same value coming from all of its predecessors, ignoring any which
+    // are the phi itself, e.g., from a loop.  That value must then dominate the phi and can be used
+    // directly instead.
+    let candidate = function.block_iter(context).find_map(|block: Block| {
+        block.get_phis(context).into_iter().find_map(|phi_val| {
+            match &context.values[phi_val.0].value {
+                ValueDatum::Instruction(Instruction::Phi(pairs)) => {
+                    let mut incoming = pairs
+                        .iter()
+                        .map(|(_, val)| *val)
+                        .filter(|val| *val != phi_val);
+                    let first_val = incoming.next()?;
+                    if incoming.all(|val| val == first_val) {
+                        Some((block, phi_val, first_val))
+                    } else {
+                        None
+                    }
+                }
+                _otherwise => None,
+            }
+        })
+    });
+
+    if let Some((block, phi_val, new_val)) = candidate {
+        if phi_val == block.get_phi(context) {
+            // Empty the phi rather than removing it, blocks must always start with one.
+            context.values[phi_val.0].value = ValueDatum::Instruction(Instruction::Phi(Vec::new()));
+        } else {
+            block.remove_instruction(context, phi_val);
+        }
+        function.replace_value(context, phi_val, new_val, None);
+        return true;
+    }
+
+    false
+}
@@ -0,0 +1,202 @@
+//! Common subexpression elimination.
+//!
+//! Pure instructions which compute the same expression from the same operands as an instruction in
+//! a dominating block are replaced by that earlier instruction.  This uses a scoped value
+//! numbering table which is pushed and popped as the dominator tree is walked, so an expression
+//! is only ever available to the blocks its definition dominates.
+//!
+//! Only instructions whose result depends solely on their operands are considered:
+//! `binary_op`, `cmp`, `not` and `get_ptr`.  Instructions which read memory, such as `load` and
+//! `extract_value`, may see a different value after an intervening write and are handled instead
+//! by [`eliminate_redundant_loads`](super::eliminate_redundant_loads).
+
+use std::collections::HashMap;
+
+use crate::{
+    analysis::DominatorTree,
+    block::Block,
+    constant::{Constant, ConstantValue},
+    context::Context,
+    error::IrError,
+    function::Function,
+    instruction::{BinaryOpKind, Instruction, Predicate},
+    pointer::Pointer,
+    value::{Value, ValueDatum},
+};
+
+/// Replace every recomputation of an available pure expression in `function`.
+pub fn cse(context: &mut Context, function: &Function) -> Result<bool, IrError> {
+    let dom_tree = DominatorTree::compute(context, function);
+    cse_with_dominators(context, function, &dom_tree)
+}
+
+/// Replace every recomputation of an available pure expression in `function`, using an already
+/// computed dominator tree for `function`.
+pub fn cse_with_dominators(
+    context: &mut Context,
+    function: &Function,
+    dom_tree: &DominatorTree,
+) -> Result<bool, IrError> {
+    let mut available: HashMap<Expr, Vec<Value>> = HashMap::new();
+    let mut replacements: HashMap<Value, Value> = HashMap::new();
+    let mut dead_instructions = Vec::new();
+
+    // Each entry in the worklist is either a block to enter, or a block to leave along with the
+    // expressions which it made available.
+    enum Visit {
+        Enter(Block),
+        Leave(Vec<Expr>),
+    }
+    let mut worklist = vec![Visit::Enter(function.get_entry_block(context))];
+    while let Some(visit) = worklist.pop() {
+        let block = match visit {
+            Visit::Enter(block) => block,
+            Visit::Leave(pushed_exprs) => {
+                for expr in pushed_exprs {
+                    available.get_mut(&expr).unwrap().pop();
+                }
+                continue;
+            }
+        };
+
+        let mut pushed_exprs = Vec::new();
+        for ins_val in context.blocks[block.0].instructions.clone() {
+            let expr = match Expr::from_instruction(context, &replacements, ins_val) {
+                Some(expr) => expr,
+                None => continue,
+            };
+
+            // The same expression may have a different type, e.g., a `get_ptr` cast, so the
+            // candidates are checked in turn.
+            let ins_ty = ins_val.get_type(context);
+            let prev_val = available.get(&expr).and_then(|candidates| {
+                candidates.iter().rev().copied().find(|candidate| {
+                    match (candidate.get_type(context), ins_ty) {
+                        (Some(candidate_ty), Some(ins_ty)) => candidate_ty.eq(context, &ins_ty),
+                        _otherwise => false,
+                    }
+                })
+            });
+            match prev_val {
+                Some(prev_val) => {
+                    replacements.insert(ins_val, prev_val);
+                    dead_instructions.push((block, ins_val));
+                }
+                None => {
+                    available.entry(expr.clone()).or_default().push(ins_val);
+                    pushed_exprs.push(expr);
+                }
+            }
+        }
+
+        worklist.push(Visit::Leave(pushed_exprs));
+        for child in dom_tree.children(&block).iter().rev() {
+            worklist.push(Visit::Enter(*child));
+        }
+    }
+
+    if replacements.is_empty() {
+        return Ok(false);
+    }
+
+    for (block, ins_val) in dead_instructions {
+        block.remove_instruction(context, ins_val);
+    }
+    for (_, ins_val) in function.instruction_iter(context).collect::<Vec<_>>() {
+        if let ValueDatum::Instruction(ins) = &context.values[ins_val.0].value {
+            for operand in ins.get_operands() {
+                if let Some(new_val) = replacements.get(&operand) {
+                    ins_val.replace_instruction_value(context, operand, *new_val);
+                }
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+// An operand of an expression.  Constants are compared by value since each use of a constant is
+// usually a distinct value.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Operand {
+    Value(Value),
+    Unit,
+    Bool(bool),
+    Uint(u64),
+    B256([u8; 32]),
+}
+
+impl Operand {
+    fn new(context: &Context, replacements: &HashMap<Value, Value>, value: Value) -> Self {
+        match &context.values[value.0].value {
+            ValueDatum::Constant(Constant { value: cv, .. }) => match cv {
+                ConstantValue::Unit => Operand::Unit,
+                ConstantValue::Bool(b) => Operand::Bool(*b),
+                ConstantValue::Uint(n) => Operand::Uint(*n),
+                ConstantValue::B256(bs) => Operand::B256(*bs),
+                _otherwise => Operand::Value(value),
+            },
+            _otherwise => Operand::Value(replacements.get(&value).copied().unwrap_or(value)),
+        }
+    }
+}
+
+// A pure expression, with commutative operands in a canonical order.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Expr {
+    BinaryOp(BinaryOpKind, Operand, Operand),
+    Cmp(Predicate, Operand, Operand),
+    Not(Operand),
+    GetPointer(Pointer, u64),
+}
+
+impl Expr {
+    fn from_instruction(
+        context: &Context,
+        replacements: &HashMap<Value, Value>,
+        ins_val: Value,
+    ) -> Option<Self> {
+        let operand = |value: &Value| Operand::new(context, replacements, *value);
+        let ordered = |lhs: Operand, rhs: Operand| {
+            if operand_order(&rhs) < operand_order(&lhs) {
+                (rhs, lhs)
+            } else {
+                (lhs, rhs)
+            }
+        };
+        match &context.values[ins_val.0].value {
+            ValueDatum::Instruction(Instruction::BinaryOp { op, lhs, rhs }) => {
+                let (lhs, rhs) = match op {
+                    BinaryOpKind::Add
+                    | BinaryOpKind::Mul
+                    | BinaryOpKind::And
+                    | BinaryOpKind::Or
+                    | BinaryOpKind::Xor => ordered(operand(lhs), operand(rhs)),
+                    _otherwise => (operand(lhs), operand(rhs)),
+                };
+                Some(Expr::BinaryOp(*op, lhs, rhs))
+            }
+            ValueDatum::Instruction(Instruction::Cmp(pred, lhs, rhs)) => {
+                let (lhs, rhs) = match pred {
+                    Predicate::Equal => ordered(operand(lhs), operand(rhs)),
+                    _otherwise => (operand(lhs), operand(rhs)),
+                };
+                Some(Expr::Cmp(*pred, lhs, rhs))
+            }
+            ValueDatum::Instruction(Instruction::Not(value)) => Some(Expr::Not(operand(value))),
+            ValueDatum::Instruction(Instruction::GetPointer {
+                base_ptr, offset, ..
+            }) => Some(Expr::GetPointer(*base_ptr, *offset)),
+            _otherwise => None,
+        }
+    }
+}
+
+// Any total order will do for commutative operands, so long as it's consistent.  Constants are
+// put first.
+fn operand_order(operand: &Operand) -> Option<generational_arena::Index> {
+    match operand {
+        Operand::Value(value) => Some(value.0),
+        _otherwise => None,
+    }
+}
@@ -0,0 +1,85 @@
+//! Dead code elimination.
+//!
+//! Remove instructions whose values are never used and which have no side effects.  Inlining in
+//! particular leaves many of these behind, e.g., the unused return value of an inlined call.
+
+use std::collections::HashMap;
+
+use crate::{
+    analysis::compute_users,
+    context::Context,
+    error::IrError,
+    function::Function,
+    value::{Value, ValueDatum},
+};
+
+/// Remove every unused, side effect free instruction from `function`.
+///
+/// Removing an instruction may leave its own arguments unused, so these are then reconsidered
+/// until no more can be removed.
+pub fn dce(context: &mut Context, function: &Function) -> Result<bool, IrError> {
+    let users = compute_users(context, function);
+    dce_with_users(context, function, &users)
+}
+
+/// Remove every unused, side effect free instruction from `function`, using the already computed
+/// users of every value in `function`.
+pub fn dce_with_users(
+    context: &mut Context,
+    function: &Function,
+    users: &HashMap<Value, Vec<Value>>,
+) -> Result<bool, IrError> {
+    // Count the uses of every instruction value within the function.
+    let mut num_uses: HashMap<Value, usize> = users
+        .iter()
+        .map(|(value, value_users)| (*value, value_users.len()))
+        .collect();
+
+    let mut worklist = function
+        .instruction_iter(context)
+        .filter(|(_, ins_val)| is_removable(context, &num_uses, ins_val))
+        .collect::<Vec<_>>();
+
+    let mut modified = false;
+    while let Some((block, dead_val)) = worklist.pop() {
+        if !is_removable(context, &num_uses, &dead_val) {
+            // Already removed via another route.
+            continue;
+        }
+
+        let operands = match &context.values[dead_val.0].value {
+            ValueDatum::Instruction(ins) => ins.get_operands(),
+            _otherwise => unreachable!("Only instructions are removed."),
+        };
+        block.remove_instruction(context, dead_val);
+        num_uses.insert(dead_val, usize::MAX);
+        modified = true;
+
+        // Any argument which is now unused may be removed too.  It may be in a different block,
+        // so we need to find which.
+        for operand in operands {
+            if let Some(count) = num_uses.get_mut(&operand) {
+                *count -= 1;
+            }
+            if is_removable(context, &num_uses, &operand) {
+                if let Some((operand_block, _)) = function
+                    .instruction_iter(context)
+                    .find(|(_, ins_val)| *ins_val == operand)
+                {
+                    worklist.push((operand_block, operand));
+                }
+            }
+        }
+    }
+
+    Ok(modified)
+}
+
+fn is_removable(context: &Context, num_uses: &HashMap<Value, usize>, value: &Value) -> bool {
+    // Removed values are marked with a use count of usize::MAX.
+    num_uses.get(value).copied().unwrap_or(0) == 0
+        && match &context.values[value.0].value {
+            ValueDatum::Instruction(ins) => !ins.may_have_side_effect(),
+            _otherwise => false,
+        }
+}
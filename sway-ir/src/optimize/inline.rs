@@ -1,15 +1,21 @@
 //! Function inlining.
 //!
 //! Function inlining is pretty hairy so these passes must be maintained with care.
+//!
+//! Calls may either all be inlined, which is required while the backend can't compile calls, or be
+//! chosen using an [`InlineCostModel`] so that helpers shared by many callers aren't duplicated.
+//!
+//! Until the backend can compile calls the compiler only uses the former, and the cost model is
+//! only run by the `inlinecost` pass of the `opt` tool.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     asm::AsmArg,
     block::Block,
     context::Context,
     error::IrError,
-    function::Function,
+    function::{Function, InlineHint},
     instruction::Instruction,
     pointer::Pointer,
     value::{Value, ValueContent, ValueDatum},
@@ -45,6 +51,195 @@ pub fn inline_all_function_calls(
     Ok(modified)
 }
 
+/// The parameters for choosing whether to inline a call.
+///
+/// The cost of a call is the number of instructions in the callee, less a bonus for each constant
+/// argument since these are likely to be folded once inlined.  Calls to a function with a single
+/// call site are always inlined, as nothing is duplicated, unless the function has an
+/// [`InlineHint`] saying otherwise.
+pub struct InlineCostModel {
+    /// The maximum cost of a call which will be inlined.
+    pub max_cost: usize,
+    /// The reduction in cost for each constant argument.
+    pub constant_arg_bonus: usize,
+}
+
+impl Default for InlineCostModel {
+    fn default() -> Self {
+        InlineCostModel {
+            max_cost: 12,
+            constant_arg_bonus: 2,
+        }
+    }
+}
+
+/// Inline the calls made from a specific function which are chosen by `cost_model`.
+///
+/// Calls to recursive functions are never inlined.  Calls which are inlined may bring further
+/// calls with them, which are considered in turn.
+pub fn inline_function_calls_by_cost(
+    context: &mut Context,
+    function: &Function,
+    cost_model: &InlineCostModel,
+) -> Result<bool, IrError> {
+    inline_function_calls_by_cost_with_decisions(context, function, cost_model, &mut Vec::new())
+}
+
+/// Inline the calls made from a specific function which are chosen by `cost_model`, recording
+/// the callee and the decision made for each call site in `decisions`.
+pub fn inline_function_calls_by_cost_with_decisions(
+    context: &mut Context,
+    function: &Function,
+    cost_model: &InlineCostModel,
+    decisions: &mut Vec<(Function, InlineDecision)>,
+) -> Result<bool, IrError> {
+    let mut modified = false;
+    let mut kept_calls = HashSet::new();
+    loop {
+        // Find the next call site which hasn't been considered yet.
+        let call_data = function
+            .instruction_iter(context)
+            .find_map(
+                |(block, call_val)| match &context.values[call_val.0].value {
+                    ValueDatum::Instruction(Instruction::Call(inlined_function, args))
+                        if !kept_calls.contains(&call_val) =>
+                    {
+                        Some((block, call_val, *inlined_function, args.clone()))
+                    }
+                    _ => None,
+                },
+            );
+        let (block, call_val, inlined_function, args) = match call_data {
+            Some(call_data) => call_data,
+            None => break,
+        };
+
+        let decision = decide_inline(context, inlined_function, &args, cost_model);
+        if decision.is_inlined() {
+            inline_function_call(context, *function, block, call_val, inlined_function)?;
+            modified = true;
+        } else {
+            kept_calls.insert(call_val);
+        }
+        decisions.push((inlined_function, decision));
+    }
+    Ok(modified)
+}
+
+/// The reason a call was or wasn't inlined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InlineDecision {
+    Hinted(InlineHint),
+    Recursive,
+    SingleCallSite,
+    Cost { cost: usize, max_cost: usize },
+}
+
+impl InlineDecision {
+    /// Whether the call was inlined.
+    pub fn is_inlined(&self) -> bool {
+        match self {
+            InlineDecision::Hinted(hint) => *hint == InlineHint::Always,
+            InlineDecision::Recursive => false,
+            InlineDecision::SingleCallSite => true,
+            InlineDecision::Cost { cost, max_cost } => cost <= max_cost,
+        }
+    }
+}
+
+impl std::fmt::Display for InlineDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let verdict = if self.is_inlined() {
+            "inlined"
+        } else {
+            "not inlined"
+        };
+        match self {
+            InlineDecision::Hinted(InlineHint::Always) => {
+                write!(f, "{verdict}, marked inline(always)")
+            }
+            InlineDecision::Hinted(InlineHint::Never) => {
+                write!(f, "{verdict}, marked inline(never)")
+            }
+            InlineDecision::Recursive => write!(f, "{verdict}, recursive"),
+            InlineDecision::SingleCallSite => write!(f, "{verdict}, single call site"),
+            InlineDecision::Cost { cost, max_cost } => {
+                write!(f, "{verdict}, cost {cost} with limit {max_cost}")
+            }
+        }
+    }
+}
+
+fn decide_inline(
+    context: &Context,
+    inlined_function: Function,
+    args: &[Value],
+    cost_model: &InlineCostModel,
+) -> InlineDecision {
+    if is_recursive(context, &inlined_function) {
+        // Even an inline(always) hint can't be honoured here.
+        return InlineDecision::Recursive;
+    }
+    if let Some(hint) = inlined_function.get_inline_hint(context) {
+        return InlineDecision::Hinted(hint);
+    }
+
+    let num_call_sites = context
+        .functions
+        .iter()
+        .flat_map(|(idx, _)| Function(idx).instruction_iter(context))
+        .filter(|(_, ins_val)| {
+            matches!(
+                &context.values[ins_val.0].value,
+                ValueDatum::Instruction(Instruction::Call(callee, _)) if *callee == inlined_function
+            )
+        })
+        .count();
+    if num_call_sites == 1 {
+        return InlineDecision::SingleCallSite;
+    }
+
+    // Empty phis aren't real instructions.
+    let size = inlined_function
+        .instruction_iter(context)
+        .filter(|(_, ins_val)| {
+            !matches!(
+                &context.values[ins_val.0].value,
+                ValueDatum::Instruction(Instruction::Phi(alts)) if alts.is_empty()
+            )
+        })
+        .count();
+    let num_constant_args = args.iter().filter(|arg| arg.is_constant(context)).count();
+    InlineDecision::Cost {
+        cost: size.saturating_sub(num_constant_args * cost_model.constant_arg_bonus),
+        max_cost: cost_model.max_cost,
+    }
+}
+
+// Whether a function may call itself, directly or otherwise.
+fn is_recursive(context: &Context, function: &Function) -> bool {
+    let callees = |caller: &Function| {
+        caller
+            .instruction_iter(context)
+            .filter_map(|(_, ins_val)| match &context.values[ins_val.0].value {
+                ValueDatum::Instruction(Instruction::Call(callee, _)) => Some(*callee),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut visited = HashSet::new();
+    let mut worklist = callees(function);
+    while let Some(callee) = worklist.pop() {
+        if callee == *function {
+            return true;
+        }
+        if visited.insert(callee) {
+            worklist.append(&mut callees(&callee));
+        }
+    }
+    false
+}
+
 /// Inline a function to a specific call site within another function.
 ///
 /// The destination function, block and call site must be specified along with the function to
@@ -132,10 +327,19 @@ pub fn inline_function_call(
     // Note: inline_instruction() doesn't translate `phi` instructions here.
     let inlined_blocks = context.functions[inlined_function.0].blocks.clone();
     for block in &inlined_blocks {
+        // Any phis following the block's own are given new empty phis too.
+        let new_block = block_map.get(block).unwrap();
+        for old_phi_val in block.get_phis(context).into_iter().skip(1) {
+            let new_phi_val = new_block.new_phi(context);
+            value_map.insert(old_phi_val, new_phi_val);
+        }
         for ins in context.blocks[block.0].instructions.clone() {
+            if value_map.contains_key(&ins) {
+                continue;
+            }
             inline_instruction(
                 context,
-                block_map.get(block).unwrap(),
+                new_block,
                 &post_block,
                 &ins,
                 &block_map,
@@ -150,16 +354,19 @@ pub fn inline_function_call(
     // don't need a peristent `&Context` to access them.
     for old_block in inlined_blocks {
         let new_block = block_map.get(&old_block).unwrap();
-        let old_phi_val = old_block.get_phi(context);
-        if let ValueDatum::Instruction(Instruction::Phi(pairs)) =
-            context.values[old_phi_val.0].value.clone()
-        {
-            for (from_block, phi_value) in pairs {
-                new_block.add_phi(
-                    context,
-                    block_map.get(&from_block).copied().unwrap(),
-                    value_map.get(&phi_value).copied().unwrap_or(phi_value),
-                );
+        for old_phi_val in old_block.get_phis(context) {
+            let new_phi_val = value_map.get(&old_phi_val).copied().unwrap();
+            if let ValueDatum::Instruction(Instruction::Phi(pairs)) =
+                context.values[old_phi_val.0].value.clone()
+            {
+                for (from_block, phi_value) in pairs {
+                    new_block.add_phi_to(
+                        context,
+                        new_phi_val,
+                        block_map.get(&from_block).copied().unwrap(),
+                        value_map.get(&phi_value).copied().unwrap_or(phi_value),
+                    );
+                }
             }
         }
     }
@@ -212,6 +419,11 @@ fn inline_instruction(
                     .ins(context)
                     .asm_block_from_asm(asm, new_args, span_md_idx)
             }
+            Instruction::BinaryOp { op, lhs, rhs } => {
+                new_block
+                    .ins(context)
+                    .binary_op(op, map_value(lhs), map_value(rhs), span_md_idx)
+            }
             // For `br` and `cbr` below we don't need to worry about the phi values, they're
             // adjusted later in `inline_function_call()`.
             Instruction::Branch(b) => {
@@ -310,6 +522,7 @@ fn inline_instruction(
                 new_block.ins(context).load(map_value(src_val), span_md_idx)
             }
             Instruction::Nop => new_block.ins(context).nop(),
+            Instruction::Not(value) => new_block.ins(context).not(map_value(value), span_md_idx),
             Instruction::ReadRegister(reg) => {
                 new_block.ins(context).read_register(reg, span_md_idx)
             }
@@ -0,0 +1,359 @@
+//! Promotion of local variables to SSA values, a.k.a. mem2reg.
+//!
+//! Every local variable is initially accessed via `get_ptr`, `load` and `store` instructions.  For
+//! scalar locals whose pointer never escapes, i.e., is only ever loaded from or stored to, we can
+//! instead track the value directly, removing the memory accesses and inserting phis where control
+//! flow merges.
+//!
+//! This is the classic algorithm from _Efficiently Computing Static Single Assignment Form and the
+//! Control Dependence Graph_ by Cytron et al.  Phis are placed at the iterated dominance frontier
+//! of the blocks which store to the local and the dominator tree is then walked to rename the
+//! loaded values.  Any phi which is never used is removed afterwards.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    analysis::DominatorTree,
+    block::Block,
+    constant::Constant,
+    context::Context,
+    error::IrError,
+    function::Function,
+    instruction::Instruction,
+    irtype::Type,
+    pointer::Pointer,
+    value::{Value, ValueDatum},
+};
+
+/// Promote the non-escaping scalar locals in `function` to SSA values.
+pub fn mem2reg(context: &mut Context, function: &Function) -> Result<bool, IrError> {
+    let dom_tree = DominatorTree::compute(context, function);
+    mem2reg_with_dominators(context, function, &dom_tree)
+}
+
+/// Promote the non-escaping scalar locals in `function` to SSA values, using an already computed
+/// dominator tree for `function`.
+pub fn mem2reg_with_dominators(
+    context: &mut Context,
+    function: &Function,
+    dom_tree: &DominatorTree,
+) -> Result<bool, IrError> {
+    // A phi can't be placed in the entry block, so give up if it is the target of a branch.
+    let entry_block = function.get_entry_block(context);
+    if function
+        .block_iter(context)
+        .any(|block| block.successors(context).contains(&entry_block))
+    {
+        return Ok(false);
+    }
+
+    let (promoted_ptrs, ptr_vals) = find_promotable_locals(context, function, dom_tree);
+    if promoted_ptrs.is_empty() {
+        return Ok(false);
+    }
+
+    let phis = place_phis(context, function, dom_tree, &promoted_ptrs, &ptr_vals);
+    rename(
+        context,
+        function,
+        dom_tree,
+        &promoted_ptrs,
+        &ptr_vals,
+        &phis,
+    );
+    remove_unused_phis(context, function, &phis);
+
+    // The locals themselves are no longer needed.
+    context.functions[function.0]
+        .local_storage
+        .retain(|_, ptr| !promoted_ptrs.contains(ptr));
+
+    Ok(true)
+}
+
+// Find the scalar locals which are only accessed by loads and stores via `get_ptr`s.  Returns
+// them along with a map from each of their `get_ptr` values to the local.
+fn find_promotable_locals(
+    context: &Context,
+    function: &Function,
+    dom_tree: &DominatorTree,
+) -> (HashSet<Pointer>, HashMap<Value, Pointer>) {
+    let mut candidates: HashSet<Pointer> = function
+        .locals_iter(context)
+        .filter(|(_, ptr)| {
+            matches!(
+                ptr.get_type(context),
+                Type::Unit | Type::Bool | Type::Uint(_)
+            )
+        })
+        .map(|(_, ptr)| *ptr)
+        .collect();
+
+    // Find every `get_ptr` for the candidates.  They must be to the whole local and reachable.
+    let mut ptr_vals = HashMap::new();
+    for (block, ins_val) in function.instruction_iter(context) {
+        if let ValueDatum::Instruction(Instruction::GetPointer {
+            base_ptr,
+            ptr_ty,
+            offset,
+        }) = &context.values[ins_val.0].value
+        {
+            if candidates.contains(base_ptr) {
+                if *offset == 0
+                    && ptr_ty.eq(context, base_ptr.get_type(context))
+                    && dom_tree.is_reachable(&block)
+                {
+                    ptr_vals.insert(ins_val, *base_ptr);
+                } else {
+                    candidates.remove(base_ptr);
+                }
+            }
+        }
+    }
+
+    // Any use of a `get_ptr` other than as the pointer in a reachable load or store escapes.
+    for (block, ins_val) in function.instruction_iter(context) {
+        if let ValueDatum::Instruction(ins) = &context.values[ins_val.0].value {
+            let escaped = match ins {
+                Instruction::Load(_) if dom_tree.is_reachable(&block) => Vec::new(),
+                Instruction::Store { stored_val, .. } if dom_tree.is_reachable(&block) => {
+                    vec![*stored_val]
+                }
+                _otherwise => ins.get_operands(),
+            };
+            for operand in escaped {
+                if let Some(ptr) = ptr_vals.get(&operand) {
+                    candidates.remove(ptr);
+                }
+            }
+        }
+    }
+
+    ptr_vals.retain(|_, ptr| candidates.contains(ptr));
+    (candidates, ptr_vals)
+}
+
+// Place an empty phi for each promoted local at the iterated dominance frontier of the blocks
+// which store to it.  Returns the phis for each block.
+fn place_phis(
+    context: &mut Context,
+    function: &Function,
+    dom_tree: &DominatorTree,
+    promoted_ptrs: &HashSet<Pointer>,
+    ptr_vals: &HashMap<Value, Pointer>,
+) -> HashMap<Block, Vec<(Pointer, Value)>> {
+    let mut def_blocks: HashMap<Pointer, HashSet<Block>> = HashMap::new();
+    for (block, ins_val) in function.instruction_iter(context) {
+        if let ValueDatum::Instruction(Instruction::Store { dst_val, .. }) =
+            &context.values[ins_val.0].value
+        {
+            if let Some(ptr) = ptr_vals.get(dst_val) {
+                def_blocks.entry(*ptr).or_default().insert(block);
+            }
+        }
+    }
+
+    let frontiers = dom_tree.dominance_frontiers();
+    let mut phis: HashMap<Block, Vec<(Pointer, Value)>> = HashMap::new();
+
+    // The locals are iterated in name order so the phis are created deterministically.
+    let ptrs = function
+        .locals_iter(context)
+        .filter(|(_, ptr)| promoted_ptrs.contains(ptr))
+        .map(|(_, ptr)| *ptr)
+        .collect::<Vec<_>>();
+    for ptr in ptrs {
+        let defs = def_blocks.remove(&ptr).unwrap_or_default();
+        let mut worklist = defs.iter().copied().collect::<Vec<_>>();
+        let mut has_phi = HashSet::new();
+        while let Some(block) = worklist.pop() {
+            for frontier_block in &frontiers[&block] {
+                if has_phi.insert(*frontier_block) {
+                    // Reuse the block's own phi if it's unused, otherwise add a new one.
+                    let block_phis = phis.entry(*frontier_block).or_default();
+                    let own_phi = frontier_block.get_phi(context);
+                    let phi_val = if block_phis.iter().all(|(_, phi)| *phi != own_phi)
+                        && matches!(
+                            &context.values[own_phi.0].value,
+                            ValueDatum::Instruction(Instruction::Phi(pairs)) if pairs.is_empty()
+                        ) {
+                        own_phi
+                    } else {
+                        frontier_block.new_phi(context)
+                    };
+                    block_phis.push((ptr, phi_val));
+                    if !defs.contains(frontier_block) {
+                        worklist.push(*frontier_block);
+                    }
+                }
+            }
+        }
+    }
+
+    phis
+}
+
+// Walk the dominator tree replacing loads with the value most recently stored to the local, and
+// filling in the phi values for each successor block.  Then remove the `get_ptr`s, loads and
+// stores.
+fn rename(
+    context: &mut Context,
+    function: &Function,
+    dom_tree: &DominatorTree,
+    promoted_ptrs: &HashSet<Pointer>,
+    ptr_vals: &HashMap<Value, Pointer>,
+    phis: &HashMap<Block, Vec<(Pointer, Value)>>,
+) {
+    // Each local starts with its initializer, or zero if it has none.
+    let mut cur_vals: HashMap<Pointer, Vec<Value>> = HashMap::new();
+    for ptr in promoted_ptrs {
+        let init_val = match &context.pointers[ptr.0].initializer {
+            Some(constant) => Value::new_constant(context, constant.clone(), None),
+            None => match ptr.get_type(context) {
+                Type::Bool => Constant::get_bool(context, false, None),
+                Type::Uint(nbits) => Constant::get_uint(context, *nbits, 0, None),
+                _otherwise => Constant::get_unit(context, None),
+            },
+        };
+        cur_vals.insert(*ptr, vec![init_val]);
+    }
+
+    let mut replacements: HashMap<Value, Value> = HashMap::new();
+    let mut dead_instructions = Vec::new();
+
+    // Each entry in the worklist is either a block to enter, or a block to leave along with the
+    // locals which it pushed a value for.
+    enum Visit {
+        Enter(Block),
+        Leave(Vec<Pointer>),
+    }
+    let mut worklist = vec![Visit::Enter(function.get_entry_block(context))];
+    while let Some(visit) = worklist.pop() {
+        let block = match visit {
+            Visit::Enter(block) => block,
+            Visit::Leave(pushed_ptrs) => {
+                for ptr in pushed_ptrs {
+                    cur_vals.get_mut(&ptr).unwrap().pop();
+                }
+                continue;
+            }
+        };
+
+        let mut pushed_ptrs = Vec::new();
+        for (ptr, phi_val) in phis.get(&block).into_iter().flatten() {
+            cur_vals.get_mut(ptr).unwrap().push(*phi_val);
+            pushed_ptrs.push(*ptr);
+        }
+
+        for ins_val in context.blocks[block.0].instructions.clone() {
+            match &context.values[ins_val.0].value {
+                ValueDatum::Instruction(Instruction::GetPointer { .. })
+                    if ptr_vals.contains_key(&ins_val) =>
+                {
+                    dead_instructions.push((block, ins_val));
+                }
+                ValueDatum::Instruction(Instruction::Load(src_val)) => {
+                    if let Some(ptr) = ptr_vals.get(src_val) {
+                        let cur_val = *cur_vals[ptr].last().unwrap();
+                        replacements.insert(ins_val, cur_val);
+                        dead_instructions.push((block, ins_val));
+                    }
+                }
+                ValueDatum::Instruction(Instruction::Store {
+                    dst_val,
+                    stored_val,
+                }) => {
+                    if let Some(ptr) = ptr_vals.get(dst_val) {
+                        // The stored value is dominated by its definition, so if it's a load
+                        // which has been replaced then it has been seen already.
+                        let stored_val =
+                            replacements.get(stored_val).copied().unwrap_or(*stored_val);
+                        cur_vals.get_mut(ptr).unwrap().push(stored_val);
+                        pushed_ptrs.push(*ptr);
+                        dead_instructions.push((block, ins_val));
+                    }
+                }
+                _otherwise => (),
+            }
+        }
+
+        for succ in block.successors(context) {
+            for (ptr, phi_val) in phis.get(&succ).into_iter().flatten() {
+                let cur_val = *cur_vals[ptr].last().unwrap();
+                succ.add_phi_to(context, *phi_val, block, cur_val);
+            }
+        }
+
+        worklist.push(Visit::Leave(pushed_ptrs));
+        for child in dom_tree.children(&block).iter().rev() {
+            worklist.push(Visit::Enter(*child));
+        }
+    }
+
+    for (block, ins_val) in dead_instructions {
+        block.remove_instruction(context, ins_val);
+    }
+    for (_, ins_val) in function.instruction_iter(context).collect::<Vec<_>>() {
+        if let ValueDatum::Instruction(ins) = &context.values[ins_val.0].value {
+            for operand in ins.get_operands() {
+                if let Some(new_val) = replacements.get(&operand) {
+                    ins_val.replace_instruction_value(context, operand, *new_val);
+                }
+            }
+        }
+    }
+}
+
+// Remove the placed phis which aren't used by anything other than themselves or other unused
+// phis.
+fn remove_unused_phis(
+    context: &mut Context,
+    function: &Function,
+    phis: &HashMap<Block, Vec<(Pointer, Value)>>,
+) {
+    let placed_phis: HashSet<Value> = phis
+        .values()
+        .flatten()
+        .map(|(_, phi_val)| *phi_val)
+        .collect();
+
+    // Mark the phis used by any other instruction as live, and then any phis which they use.
+    let mut live_phis = HashSet::new();
+    let mut worklist = Vec::new();
+    for (_, ins_val) in function.instruction_iter(context) {
+        if placed_phis.contains(&ins_val) {
+            continue;
+        }
+        if let ValueDatum::Instruction(ins) = &context.values[ins_val.0].value {
+            for operand in ins.get_operands() {
+                if placed_phis.contains(&operand) && live_phis.insert(operand) {
+                    worklist.push(operand);
+                }
+            }
+        }
+    }
+    while let Some(phi_val) = worklist.pop() {
+        if let ValueDatum::Instruction(ins) = &context.values[phi_val.0].value {
+            for operand in ins.get_operands() {
+                if placed_phis.contains(&operand) && live_phis.insert(operand) {
+                    worklist.push(operand);
+                }
+            }
+        }
+    }
+
+    for (block, block_phis) in phis {
+        for (_, phi_val) in block_phis {
+            if live_phis.contains(phi_val) {
+                continue;
+            }
+            if *phi_val == block.get_phi(context) {
+                // Blocks must always start with their own phi, so just empty it.
+                context.values[phi_val.0].value =
+                    ValueDatum::Instruction(Instruction::Phi(Vec::new()));
+            } else {
+                block.remove_instruction(context, *phi_val);
+            }
+        }
+    }
+}
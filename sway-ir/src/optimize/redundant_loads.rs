@@ -0,0 +1,207 @@
+//! Redundant load elimination.
+//!
+//! Within a block, a `load` from a pointer which has already been loaded from, or stored to, can
+//! use the value already known to be in memory.  Similarly an `extract_value` of a field which has
+//! already been extracted can reuse the earlier value.
+//!
+//! Aggregates are kept in memory and `insert_value` and `insert_element` modify them in place, so
+//! these are tracked along with stores.  Each known value is associated with the local it was read
+//! from, if known, and a write to a local forgets every value which may have come from it.  A
+//! `call`, `contract_call` or `asm` block may write to any memory and so forgets everything.
+
+use std::collections::HashMap;
+
+use crate::{
+    context::Context,
+    error::IrError,
+    function::Function,
+    instruction::Instruction,
+    irtype::Type,
+    pointer::Pointer,
+    value::{Value, ValueDatum},
+};
+
+/// Replace every `load` and `extract_value` in `function` whose value is already known within
+/// its block.
+pub fn eliminate_redundant_loads(
+    context: &mut Context,
+    function: &Function,
+) -> Result<bool, IrError> {
+    let mut replacements: HashMap<Value, Value> = HashMap::new();
+    let mut dead_instructions = Vec::new();
+
+    for block in function.block_iter(context) {
+        let mut known: Vec<KnownRead> = Vec::new();
+        for ins_val in context.blocks[block.0].instructions.clone() {
+            let canonical = |value: &Value| replacements.get(value).copied().unwrap_or(*value);
+            let ins = match &context.values[ins_val.0].value {
+                ValueDatum::Instruction(ins) => ins,
+                _otherwise => unreachable!("Block instructions must be instructions."),
+            };
+            match ins {
+                Instruction::Load(src_val) => {
+                    if let Some((base_ptr, offset)) = get_ptr_location(context, src_val) {
+                        let read = Read::Load(base_ptr, offset);
+                        match find_known(context, &known, &read, ins_val) {
+                            Some(known_val) => {
+                                replacements.insert(ins_val, known_val);
+                                dead_instructions.push((block, ins_val));
+                            }
+                            None => known.push(KnownRead {
+                                read,
+                                root: Some(base_ptr),
+                                value: ins_val,
+                            }),
+                        }
+                    }
+                }
+                Instruction::ExtractValue {
+                    aggregate, indices, ..
+                } => {
+                    let aggregate = canonical(aggregate);
+                    let read = Read::ExtractValue(aggregate, indices.clone());
+                    match find_known(context, &known, &read, ins_val) {
+                        Some(known_val) => {
+                            replacements.insert(ins_val, known_val);
+                            dead_instructions.push((block, ins_val));
+                        }
+                        None => known.push(KnownRead {
+                            read,
+                            root: memory_root(context, &aggregate),
+                            value: ins_val,
+                        }),
+                    }
+                }
+                Instruction::Store {
+                    dst_val,
+                    stored_val,
+                } => {
+                    let stored_val = canonical(stored_val);
+                    match get_ptr_location(context, dst_val) {
+                        Some((base_ptr, offset)) => {
+                            forget_writes_to(&mut known, Some(base_ptr));
+
+                            // Scalars are copied to memory, so the stored value may be used
+                            // directly by a following load.  Aggregates are copied from the memory
+                            // of the stored value, which may be modified later.
+                            if matches!(
+                                stored_val.get_type(context),
+                                Some(Type::Unit | Type::Bool | Type::Uint(_))
+                            ) {
+                                known.push(KnownRead {
+                                    read: Read::Load(base_ptr, offset),
+                                    root: Some(base_ptr),
+                                    value: stored_val,
+                                });
+                            }
+                        }
+                        None => known.clear(),
+                    }
+                }
+                Instruction::InsertElement { array: dst, .. }
+                | Instruction::InsertValue { aggregate: dst, .. }
+                | Instruction::StateLoadQuadWord { load_val: dst, .. } => {
+                    let root = memory_root(context, &canonical(dst));
+                    forget_writes_to(&mut known, root);
+                }
+                Instruction::AsmBlock(..)
+                | Instruction::Call(..)
+                | Instruction::ContractCall { .. } => known.clear(),
+                _otherwise => (),
+            }
+        }
+    }
+
+    if replacements.is_empty() {
+        return Ok(false);
+    }
+
+    for (block, ins_val) in dead_instructions {
+        block.remove_instruction(context, ins_val);
+    }
+    for (_, ins_val) in function.instruction_iter(context).collect::<Vec<_>>() {
+        if let ValueDatum::Instruction(ins) = &context.values[ins_val.0].value {
+            for operand in ins.get_operands() {
+                if let Some(new_val) = replacements.get(&operand) {
+                    ins_val.replace_instruction_value(context, operand, *new_val);
+                }
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+#[derive(PartialEq)]
+enum Read {
+    Load(Pointer, u64),
+    ExtractValue(Value, Vec<u64>),
+}
+
+// A value known to be in memory, along with the local it was read from if known.
+struct KnownRead {
+    read: Read,
+    root: Option<Pointer>,
+    value: Value,
+}
+
+fn find_known(
+    context: &Context,
+    known: &[KnownRead],
+    read: &Read,
+    ins_val: Value,
+) -> Option<Value> {
+    // A `get_ptr` may cast the local to a different type, so the types must match too.
+    let ins_ty = ins_val.get_type(context)?;
+    known
+        .iter()
+        .rev()
+        .find(|known_read| {
+            known_read.read == *read
+                && known_read
+                    .value
+                    .get_type(context)
+                    .map(|known_ty| known_ty.eq(context, &ins_ty))
+                    .unwrap_or(false)
+        })
+        .map(|known_read| known_read.value)
+}
+
+// Forget every known value which may be changed by a write to `root`, or by a write to anywhere
+// if `root` is unknown.
+fn forget_writes_to(known: &mut Vec<KnownRead>, root: Option<Pointer>) {
+    match root {
+        Some(root) => {
+            known.retain(|known_read| known_read.root.is_some() && known_read.root != Some(root))
+        }
+        None => known.clear(),
+    }
+}
+
+fn get_ptr_location(context: &Context, ptr_val: &Value) -> Option<(Pointer, u64)> {
+    match &context.values[ptr_val.0].value {
+        ValueDatum::Instruction(Instruction::GetPointer {
+            base_ptr, offset, ..
+        }) => Some((*base_ptr, *offset)),
+        _otherwise => None,
+    }
+}
+
+// Find the local whose memory an aggregate value refers to, if it can be determined.
+pub(crate) fn memory_root(context: &Context, value: &Value) -> Option<Pointer> {
+    match &context.values[value.0].value {
+        ValueDatum::Instruction(Instruction::GetPointer { base_ptr, .. }) => Some(*base_ptr),
+        ValueDatum::Instruction(
+            Instruction::Load(src_val)
+            | Instruction::ExtractValue {
+                aggregate: src_val, ..
+            }
+            | Instruction::InsertValue {
+                aggregate: src_val, ..
+            }
+            | Instruction::ExtractElement { array: src_val, .. }
+            | Instruction::InsertElement { array: src_val, .. },
+        ) => memory_root(context, src_val),
+        _otherwise => None,
+    }
+}
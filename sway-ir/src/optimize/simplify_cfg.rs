@@ -0,0 +1,268 @@
+//! Control flow graph simplification.
+//!
+//! - remove unreachable blocks - blocks which can't be reached from the entry block, such as
+//!   those left behind when a conditional branch on a constant is combined.
+//! - merge blocks - append a block to its only predecessor when that predecessor unconditionally
+//!   branches to it.
+//! - thread jumps - bypass blocks which only branch elsewhere, either unconditionally or on a
+//!   phi value which is known to be constant when coming from a particular predecessor.
+
+use std::collections::HashSet;
+
+use crate::{
+    analysis::compute_predecessors,
+    block::Block,
+    constant::{Constant, ConstantValue},
+    context::Context,
+    error::IrError,
+    function::Function,
+    instruction::Instruction,
+    value::{Value, ValueDatum},
+};
+
+/// Run each of the CFG simplifications until none of them make any more changes.
+pub fn simplify_cfg(context: &mut Context, function: &Function) -> Result<bool, IrError> {
+    let mut modified = false;
+    loop {
+        if remove_unreachable_blocks(context, function)? {
+            modified = true;
+            continue;
+        }
+        if merge_blocks(context, function)? {
+            modified = true;
+            continue;
+        }
+        if thread_jumps(context, function)? {
+            modified = true;
+            continue;
+        }
+        break;
+    }
+    Ok(modified)
+}
+
+/// Remove every block which isn't reachable from the entry block.
+pub fn remove_unreachable_blocks(
+    context: &mut Context,
+    function: &Function,
+) -> Result<bool, IrError> {
+    let entry_block = function.get_entry_block(context);
+    let mut reachable = HashSet::from([entry_block]);
+    let mut worklist = vec![entry_block];
+    while let Some(block) = worklist.pop() {
+        for succ in block.successors(context) {
+            if reachable.insert(succ) {
+                worklist.push(succ);
+            }
+        }
+    }
+
+    let unreachable_blocks = function
+        .block_iter(context)
+        .filter(|block| !reachable.contains(block))
+        .collect::<Vec<_>>();
+    for block in &unreachable_blocks {
+        // Any reachable successors must forget the phi values from this block.
+        for succ in block.successors(context) {
+            succ.remove_phi_val_coming_from(context, block);
+        }
+    }
+    context.functions[function.0]
+        .blocks
+        .retain(|block| reachable.contains(block));
+    for block in &unreachable_blocks {
+        context.blocks.remove(block.0);
+    }
+
+    Ok(!unreachable_blocks.is_empty())
+}
+
+/// Merge a block into its predecessor where the predecessor has an unconditional branch to it and
+/// it has no other predecessors.
+pub fn merge_blocks(context: &mut Context, function: &Function) -> Result<bool, IrError> {
+    let preds = compute_predecessors(context, function);
+    let entry_block = function.get_entry_block(context);
+    let candidate = function.block_iter(context).find_map(|from_block| {
+        match from_block.get_term_inst(context) {
+            Some(Instruction::Branch(to_block))
+                if *to_block != from_block
+                    && *to_block != entry_block
+                    && preds[to_block].len() == 1 =>
+            {
+                Some((from_block, *to_block))
+            }
+            _otherwise => None,
+        }
+    });
+
+    if let Some((from_block, to_block)) = candidate {
+        // The phis in `to_block` can only have a value from `from_block`, which is used directly
+        // instead.
+        let num_phis = to_block.get_phis(context).len();
+        for (to_phi, phi_val) in to_block.get_phi_vals_coming_from(context, &from_block) {
+            function.replace_value(context, to_phi, phi_val, None);
+        }
+
+        // Replace the branch in `from_block` with the instructions from `to_block`, skipping its
+        // phis.
+        let mut to_instructions = context.blocks[to_block.0].instructions.split_off(num_phis);
+        let from_instructions = &mut context.blocks[from_block.0].instructions;
+        from_instructions.pop();
+        from_instructions.append(&mut to_instructions);
+
+        // The successors of `to_block` are now the successors of `from_block`.
+        for succ in from_block.successors(context) {
+            succ.update_phi_source_block(context, to_block, from_block);
+        }
+
+        context.functions[function.0]
+            .blocks
+            .retain(|block| *block != to_block);
+        context.blocks.remove(to_block.0);
+        return Ok(true);
+    }
+
+    Ok(false)
+}
+
+/// Redirect a branch which leads to a block which only branches on to somewhere else.
+///
+/// The intermediate block may either contain only an unconditional branch, in which case every
+/// predecessor is redirected, or only a conditional branch on its own phi value, in which case
+/// predecessors which pass a constant to the phi are redirected to the corresponding destination.
+pub fn thread_jumps(context: &mut Context, function: &Function) -> Result<bool, IrError> {
+    let preds = compute_predecessors(context, function);
+    let entry_block = function.get_entry_block(context);
+
+    for via_block in function.block_iter(context) {
+        if via_block == entry_block || context.blocks[via_block.0].instructions.len() != 2 {
+            continue;
+        }
+        let via_phi = via_block.get_phi(context);
+        let via_preds = &preds[&via_block];
+
+        let threads = match via_block.get_term_inst(context) {
+            // Only an unconditional branch and an unused phi: thread every predecessor.
+            Some(Instruction::Branch(dest_block))
+                if *dest_block != via_block && !is_used(context, function, via_phi) =>
+            {
+                via_preds
+                    .iter()
+                    .map(|pred| (*pred, *dest_block))
+                    .collect::<Vec<_>>()
+            }
+
+            // A conditional branch on the phi: thread each predecessor which passes a constant
+            // via an unconditional branch.
+            Some(Instruction::ConditionalBranch {
+                cond_value,
+                true_block,
+                false_block,
+            }) if *cond_value == via_phi && use_count(context, function, via_phi) == 1 => via_preds
+                .iter()
+                .filter(|pred| matches!(pred.get_term_inst(context), Some(Instruction::Branch(_))))
+                .filter_map(|pred| {
+                    match via_block
+                        .get_phi_val_coming_from(context, pred)
+                        .map(|val| &context.values[val.0].value)
+                    {
+                        Some(ValueDatum::Constant(Constant {
+                            value: ConstantValue::Bool(cond),
+                            ..
+                        })) => Some((*pred, if *cond { *true_block } else { *false_block })),
+                        _otherwise => None,
+                    }
+                })
+                .collect::<Vec<_>>(),
+
+            _otherwise => Vec::new(),
+        };
+
+        // A predecessor which already branches to the destination would need two different phi
+        // values from it, so it's left alone.
+        let threads = threads
+            .into_iter()
+            .filter(|(pred, dest_block)| {
+                *dest_block != via_block && !preds[dest_block].contains(pred)
+            })
+            .collect::<Vec<_>>();
+        if threads.is_empty() {
+            continue;
+        }
+
+        for (pred, dest_block) in threads {
+            // The destination phi value passed on from the intermediate block must now come
+            // directly from the predecessor.  If it is the intermediate phi then it is the value
+            // which was passed to it.
+            let pred_phi_val = via_block.get_phi_val_coming_from(context, &pred);
+            for (dest_phi, dest_phi_val) in dest_block.get_phi_vals_coming_from(context, &via_block)
+            {
+                let dest_phi_val = if dest_phi_val == via_phi {
+                    pred_phi_val.expect("Intermediate phi must have a value from predecessor.")
+                } else {
+                    dest_phi_val
+                };
+                dest_block.add_phi_to(context, dest_phi, pred, dest_phi_val);
+            }
+            via_block.remove_phi_val_coming_from(context, &pred);
+            redirect_branch(context, &pred, &via_block, &dest_block);
+        }
+
+        // If the intermediate block has no predecessors left then it will be removed as
+        // unreachable.  Its phi values in the destinations must be removed now though, since
+        // they may refer to its own phi.
+        if preds[&via_block]
+            .iter()
+            .all(|pred| !pred.successors(context).contains(&via_block))
+        {
+            for succ in via_block.successors(context) {
+                succ.remove_phi_val_coming_from(context, &via_block);
+            }
+        }
+        return Ok(true);
+    }
+
+    Ok(false)
+}
+
+fn use_count(context: &Context, function: &Function, value: Value) -> usize {
+    function
+        .instruction_iter(context)
+        .map(|(_, ins_val)| match &context.values[ins_val.0].value {
+            ValueDatum::Instruction(ins) => ins
+                .get_operands()
+                .iter()
+                .filter(|operand| **operand == value)
+                .count(),
+            _otherwise => 0,
+        })
+        .sum()
+}
+
+fn is_used(context: &Context, function: &Function, value: Value) -> bool {
+    use_count(context, function, value) > 0
+}
+
+fn redirect_branch(context: &mut Context, block: &Block, old_dest: &Block, new_dest: &Block) {
+    let term_val = *context.blocks[block.0]
+        .instructions
+        .last()
+        .expect("Predecessor must have a terminator.");
+    let redirect = |dest: &mut Block| {
+        if dest == old_dest {
+            *dest = *new_dest;
+        }
+    };
+    match &mut context.values[term_val.0].value {
+        ValueDatum::Instruction(Instruction::Branch(dest)) => redirect(dest),
+        ValueDatum::Instruction(Instruction::ConditionalBranch {
+            true_block,
+            false_block,
+            ..
+        }) => {
+            redirect(true_block);
+            redirect(false_block);
+        }
+        _otherwise => unreachable!("Predecessor terminator must be a branch."),
+    }
+}
@@ -0,0 +1,307 @@
+//! Storage access coalescing.
+//!
+//! Reading and writing contract storage is expensive, but each access to a storage field in Sway
+//! is compiled to its own `state_load_word`, `state_store_word` or quad word equivalent.  Here
+//! we track the value most recently loaded from or stored to each storage slot so that:
+//!
+//! - a load can reuse a value already known to be in the slot, and
+//! - a store which is overwritten before the slot is read again is removed.
+//!
+//! Storage keys are always given via a `b256` local which is initialized with a constant key just
+//! before the access, so the constants stored to these locals are tracked to identify the slots.
+//!
+//! Values are tracked within each block, and carried into any block which has that block as its
+//! only predecessor.  A `call`, `contract_call` or `asm` block may access storage in any
+//! way, so nothing is known or coalesced across them.  Accesses are never moved, only removed.
+
+use std::collections::HashMap;
+
+use crate::{
+    analysis::{compute_predecessors, reverse_post_order},
+    block::Block,
+    constant::{Constant, ConstantValue},
+    context::Context,
+    error::IrError,
+    function::Function,
+    instruction::Instruction,
+    irtype::Type,
+    pointer::Pointer,
+    value::{Value, ValueDatum},
+};
+
+use super::redundant_loads::memory_root;
+
+/// Remove the redundant storage loads and overwritten storage stores in `function`.
+pub fn coalesce_storage_accesses(
+    context: &mut Context,
+    function: &Function,
+) -> Result<bool, IrError> {
+    let preds = compute_predecessors(context, function);
+    coalesce_storage_accesses_with_predecessors(context, function, &preds)
+}
+
+/// Remove the redundant storage loads and overwritten storage stores in `function`, using the
+/// already computed predecessors of every block in `function`.
+pub fn coalesce_storage_accesses_with_predecessors(
+    context: &mut Context,
+    function: &Function,
+    preds: &HashMap<Block, Vec<Block>>,
+) -> Result<bool, IrError> {
+    let mut block_states: HashMap<Block, KnownStorage> = HashMap::new();
+    let mut replacements: HashMap<Value, Value> = HashMap::new();
+    let mut modified = false;
+
+    for block in reverse_post_order(context, function) {
+        // A block with a single predecessor can only be reached once its predecessor is done.
+        let mut state = match preds[&block].as_slice() {
+            [pred] => block_states.get(pred).cloned().unwrap_or_default(),
+            _otherwise => KnownStorage::default(),
+        };
+
+        // The most recent store to each slot which hasn't yet been read.
+        let mut unread_stores: HashMap<[u8; 32], Value> = HashMap::new();
+
+        for ins_val in context.blocks[block.0].instructions.clone() {
+            let ins = match &context.values[ins_val.0].value {
+                ValueDatum::Instruction(ins) => ins.clone(),
+                _otherwise => unreachable!("Block instructions must be instructions."),
+            };
+            match ins {
+                Instruction::Store {
+                    dst_val,
+                    stored_val,
+                } => match get_ptr_base(context, &dst_val) {
+                    Some(base_ptr) => {
+                        state.forget_writes_to(Some(base_ptr));
+                        if let Some(key) = get_b256_key(context, &dst_val, &stored_val) {
+                            state.keys.insert(base_ptr, key);
+                        }
+                    }
+                    None => state.forget_writes_to(None),
+                },
+                Instruction::InsertElement { array: dst, .. }
+                | Instruction::InsertValue { aggregate: dst, .. } => {
+                    state.forget_writes_to(memory_root(context, &dst));
+                }
+
+                Instruction::StateLoadWord(key_val) => match state.get_key(context, &key_val) {
+                    Some(key) => match state.slots.get(&key) {
+                        Some(SlotValue::Word(known_val)) => {
+                            replacements.insert(ins_val, *known_val);
+                            block.remove_instruction(context, ins_val);
+                            modified = true;
+                        }
+                        _otherwise => {
+                            unread_stores.remove(&key);
+                            state.slots.insert(key, SlotValue::Word(ins_val));
+                        }
+                    },
+                    None => unread_stores.clear(),
+                },
+                Instruction::StateStoreWord { stored_val, key } => {
+                    let stored_val = replacements.get(&stored_val).copied().unwrap_or(stored_val);
+                    match state.get_key(context, &key) {
+                        Some(key) => {
+                            if let Some(dead_store) = unread_stores.insert(key, ins_val) {
+                                block.remove_instruction(context, dead_store);
+                                modified = true;
+                            }
+                            // Bools are stored as words too, but only a `u64` can replace a
+                            // `state_load_word`.
+                            if matches!(stored_val.get_type(context), Some(Type::Uint(64))) {
+                                state.slots.insert(key, SlotValue::Word(stored_val));
+                            } else {
+                                state.slots.remove(&key);
+                            }
+                        }
+                        None => state.slots.clear(),
+                    }
+                }
+
+                Instruction::StateLoadQuadWord { load_val, key } => {
+                    let key = state.get_key(context, &key);
+                    let known_ptr_val = key.and_then(|key| match state.slots.get(&key) {
+                        Some(SlotValue::Quad(known_ptr_val, _)) => Some(*known_ptr_val),
+                        _otherwise => None,
+                    });
+                    match (key, known_ptr_val) {
+                        (Some(key), Some(known_ptr_val)) => {
+                            // Copy the value from the memory which already holds it, unless it's
+                            // already there.
+                            if get_ptr_location(context, &known_ptr_val)
+                                != get_ptr_location(context, &load_val)
+                            {
+                                copy_b256(context, &block, ins_val, known_ptr_val, load_val);
+                                state.forget_writes_to(memory_root(context, &load_val));
+                                state.insert_quad(context, key, known_ptr_val);
+                            }
+                            block.remove_instruction(context, ins_val);
+                            modified = true;
+                        }
+                        (Some(key), None) => {
+                            unread_stores.remove(&key);
+                            state.forget_writes_to(memory_root(context, &load_val));
+                            state.insert_quad(context, key, load_val);
+                        }
+                        (None, _) => {
+                            unread_stores.clear();
+                            state.forget_writes_to(memory_root(context, &load_val));
+                        }
+                    }
+                }
+                Instruction::StateStoreQuadWord { stored_val, key } => {
+                    match state.get_key(context, &key) {
+                        Some(key) => {
+                            if let Some(dead_store) = unread_stores.insert(key, ins_val) {
+                                block.remove_instruction(context, dead_store);
+                                modified = true;
+                            }
+                            state.insert_quad(context, key, stored_val);
+                        }
+                        None => state.slots.clear(),
+                    }
+                }
+
+                Instruction::AsmBlock(..)
+                | Instruction::Call(..)
+                | Instruction::ContractCall { .. } => {
+                    state = KnownStorage::default();
+                    unread_stores.clear();
+                }
+                _otherwise => (),
+            }
+        }
+
+        block_states.insert(block, state);
+    }
+
+    if !replacements.is_empty() {
+        for (_, ins_val) in function.instruction_iter(context).collect::<Vec<_>>() {
+            if let ValueDatum::Instruction(ins) = &context.values[ins_val.0].value {
+                for operand in ins.get_operands() {
+                    if let Some(new_val) = replacements.get(&operand) {
+                        ins_val.replace_instruction_value(context, operand, *new_val);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(modified)
+}
+
+// The value held in a storage slot, either a word value or a `get_ptr` to the local holding a
+// quad word.
+#[derive(Clone, Copy)]
+enum SlotValue {
+    Word(Value),
+    Quad(Value, Pointer),
+}
+
+#[derive(Clone, Default)]
+struct KnownStorage {
+    // The constant key held by each `b256` local.
+    keys: HashMap<Pointer, [u8; 32]>,
+    // The known value of each storage slot.
+    slots: HashMap<[u8; 32], SlotValue>,
+}
+
+impl KnownStorage {
+    fn get_key(&self, context: &Context, key_val: &Value) -> Option<[u8; 32]> {
+        match &context.values[key_val.0].value {
+            ValueDatum::Instruction(Instruction::GetPointer {
+                base_ptr,
+                ptr_ty: Type::B256,
+                offset: 0,
+            }) => self.keys.get(base_ptr).copied(),
+            _otherwise => None,
+        }
+    }
+
+    // Record that the slot for `key` holds the quad word at `ptr_val`, if it's a known local.
+    fn insert_quad(&mut self, context: &Context, key: [u8; 32], ptr_val: Value) {
+        match get_ptr_base(context, &ptr_val) {
+            Some(base_ptr) => self.slots.insert(key, SlotValue::Quad(ptr_val, base_ptr)),
+            None => self.slots.remove(&key),
+        };
+    }
+
+    // Forget everything which may be changed by a write to the memory of `root`, or by a write to
+    // anywhere if `root` is unknown.
+    fn forget_writes_to(&mut self, root: Option<Pointer>) {
+        match root {
+            Some(root) => {
+                self.keys.remove(&root);
+                self.slots.retain(|_, slot_val| match slot_val {
+                    SlotValue::Word(_) => true,
+                    SlotValue::Quad(_, base_ptr) => *base_ptr != root,
+                })
+            }
+            None => {
+                self.keys.clear();
+                self.slots
+                    .retain(|_, slot_val| matches!(slot_val, SlotValue::Word(_)));
+            }
+        }
+    }
+}
+
+fn get_ptr_base(context: &Context, ptr_val: &Value) -> Option<Pointer> {
+    get_ptr_location(context, ptr_val).map(|(base_ptr, _)| base_ptr)
+}
+
+fn get_ptr_location(context: &Context, ptr_val: &Value) -> Option<(Pointer, u64)> {
+    match &context.values[ptr_val.0].value {
+        ValueDatum::Instruction(Instruction::GetPointer {
+            base_ptr, offset, ..
+        }) => Some((*base_ptr, *offset)),
+        _otherwise => None,
+    }
+}
+
+// Return the key stored by a constant `b256` store to the start of a local.
+fn get_b256_key(context: &Context, dst_val: &Value, stored_val: &Value) -> Option<[u8; 32]> {
+    match (
+        &context.values[dst_val.0].value,
+        &context.values[stored_val.0].value,
+    ) {
+        (
+            ValueDatum::Instruction(Instruction::GetPointer {
+                ptr_ty: Type::B256,
+                offset: 0,
+                ..
+            }),
+            ValueDatum::Constant(Constant {
+                value: ConstantValue::B256(key),
+                ..
+            }),
+        ) => Some(*key),
+        _otherwise => None,
+    }
+}
+
+// Replace `ins_val` with a copy of the `b256` at `src_ptr_val` to `dst_ptr_val`.
+fn copy_b256(
+    context: &mut Context,
+    block: &Block,
+    ins_val: Value,
+    src_ptr_val: Value,
+    dst_ptr_val: Value,
+) {
+    let span_md_idx = context.values[ins_val.0].span_md_idx;
+    let loaded_val = Value::new_instruction(context, Instruction::Load(src_ptr_val), span_md_idx);
+    let store_val = Value::new_instruction(
+        context,
+        Instruction::Store {
+            dst_val: dst_ptr_val,
+            stored_val: loaded_val,
+        },
+        span_md_idx,
+    );
+    let instructions = &mut context.blocks[block.0].instructions;
+    let pos = instructions
+        .iter()
+        .position(|iv| *iv == ins_val)
+        .expect("Instruction must be in its block.");
+    instructions.splice(pos..pos, [loaded_val, store_val]);
+}
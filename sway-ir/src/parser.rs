@@ -34,6 +34,9 @@ mod ir_builder {
                 / _ s:contract() eoi() {
                     s
                 }
+                / _ s:predicate() eoi() {
+                    s
+                }
 
             rule script() -> IrAstModule
                 = "script" _ "{" _ fn_decls:fn_decl()* "}" _ metadata:metadata_decl()* {
@@ -53,8 +56,17 @@ mod ir_builder {
                     }
                 }
 
+            rule predicate() -> IrAstModule
+                = "predicate" _ "{" _ fn_decls:fn_decl()* "}" _ metadata:metadata_decl()* {
+                    IrAstModule {
+                        kind: crate::module::Kind::Predicate,
+                        fn_decls,
+                        metadata
+                    }
+                }
+
             rule fn_decl() -> IrAstFnDecl
-                = "fn" _ name:id() _ selector:selector_id()? _ "(" _
+                = inline_hint:inline_hint()? "fn" _ name:id() _ selector:selector_id()? _ "(" _
                       args:(fn_arg() ** comma()) ")" _ "->" _ ret_type:ast_ty() "{" _
                       locals:fn_local()*
                       blocks:block_decl()*
@@ -65,10 +77,19 @@ mod ir_builder {
                         ret_type,
                         locals,
                         blocks,
-                        selector
+                        selector,
+                        inline_hint
                     }
                 }
 
+            rule inline_hint() -> InlineHint
+                = "inline" _ "(" _ "always" _ ")" _ {
+                    InlineHint::Always
+                }
+                / "inline" _ "(" _ "never" _ ")" _ {
+                    InlineHint::Never
+                }
+
             rule selector_id() -> [u8; 4]
                 = "<" _ s:$(['0'..='9' | 'a'..='f' | 'A'..='F']*<8>) _ ">" _ {
                     string_to_hex::<4>(s)
@@ -123,6 +144,7 @@ mod ir_builder {
 
             rule operation() -> IrAstOperation
                 = op_asm()
+                / op_binary()
                 / op_branch()
                 / op_call()
                 / op_cbr()
@@ -136,6 +158,7 @@ mod ir_builder {
                 / op_insert_value()
                 / op_load()
                 / op_nop()
+                / op_not()
                 / op_phi()
                 / op_read_register()
                 / op_ret()
@@ -158,6 +181,11 @@ mod ir_builder {
                     )
                 }
 
+            rule op_binary() -> IrAstOperation
+                = op:binary_op_kind() l:id() r:id() {
+                    IrAstOperation::BinaryOp(op, l, r)
+                }
+
             rule op_branch() -> IrAstOperation
                 = "br" _ to_block:id() {
                     IrAstOperation::Br(to_block)
@@ -225,6 +253,11 @@ mod ir_builder {
                     IrAstOperation::Nop
                 }
 
+            rule op_not() -> IrAstOperation
+                = "not" _ val:id() {
+                    IrAstOperation::Not(val)
+                }
+
             rule op_phi() -> IrAstOperation
                 = "phi" _ "(" _ pairs:((bl:id() ":" _ vn:id() { (bl, vn) }) ** comma()) ")" _ {
                     IrAstOperation::Phi(pairs)
@@ -266,10 +299,15 @@ mod ir_builder {
                 }
 
             rule cmp_pred() -> String
-                = p:$("eq") _ {
+                = p:$("eq" / "lt" / "gt") _ {
                     p.to_string()
                 }
 
+            rule binary_op_kind() -> String
+                = op:$("add" / "sub" / "mul" / "div" / "mod" / "and" / "or" / "xor" / "shl" / "shr") _ {
+                    op.to_string()
+                }
+
             rule reg_name() -> String
                 = r:$("of" / "pc" / "ssp" / "sp" / "fp" / "hp" / "err" / "ggas" / "cgas" / "bal" / "is" / "ret" / "retl" / "flag") _ {
                     r.to_string()
@@ -293,8 +331,10 @@ mod ir_builder {
                     (ty, ret)
                 }
 
+            // Each asm instruction is on its own line, so its arguments mustn't run on to the next
+            // one when it has neither an immediate nor metadata.
             rule asm_op() -> IrAstAsmOp
-                = name:id_id() args:asm_op_arg()* imm:asm_op_arg_imm()? meta_idx:comma_metadata_idx()? {
+                = name:asm_op_id() args:asm_op_arg()* imm:asm_op_arg_imm()? meta_idx:comma_metadata_idx()? _ {
                     IrAstAsmOp {
                         name,
                         args,
@@ -304,10 +344,18 @@ mod ir_builder {
                 }
 
             rule asm_op_arg() -> Ident
-                = !asm_op_arg_imm() arg:id_id() {
+                = !asm_op_arg_imm() arg:asm_op_id() {
                     arg
                 }
 
+            rule asm_op_id() -> Ident
+                = !ast_ty() id:$(id_char0() id_char()*) [' ' | '\t']* {
+                    Ident::new(Span {
+                        span: pest::Span::new(id.into(), 0, id.len()).unwrap(),
+                        path: None,
+                    })
+                }
+
             rule asm_op_arg_imm() -> Ident
                 = imm:$("i" d:decimal()) {
                     Ident::new(Span {
@@ -413,7 +461,7 @@ mod ir_builder {
 
             rule metadata_item() -> IrMetadatum
                 = "filepath" _ ['"'] path:$(([^ '"' | '\\'] / ['\\'] ['\\' | '"' ])+) ['"'] _ {
-                    IrMetadatum::FilePath(PathBuf::from(path))
+                    IrMetadatum::FilePath(PathBuf::from(unescape(path)))
                 }
                 / "span" _ "!" li:decimal() s:decimal() e:decimal() {
                     IrMetadatum::Span { loc_idx: li, start: s as usize, end: e as usize }
@@ -467,8 +515,8 @@ mod ir_builder {
         constant::Constant,
         context::Context,
         error::IrError,
-        function::Function,
-        instruction::{Instruction, Predicate, Register},
+        function::{Function, InlineHint},
+        instruction::{BinaryOpKind, Instruction, Predicate, Register},
         irtype::{Aggregate, Type},
         metadata::{MetadataIndex, Metadatum},
         module::{Kind, Module},
@@ -491,6 +539,7 @@ mod ir_builder {
         locals: Vec<(IrAstTy, String, bool, Option<IrAstOperation>)>,
         blocks: Vec<IrAstBlock>,
         selector: Option<[u8; 4]>,
+        inline_hint: Option<InlineHint>,
     }
 
     #[derive(Debug)]
@@ -515,6 +564,7 @@ mod ir_builder {
             Vec<IrAstAsmOp>,
             Option<MdIdxRef>,
         ),
+        BinaryOp(String, String, String),
         Br(String),
         Call(String, Vec<String>),
         Cbr(String, String, String),
@@ -528,6 +578,7 @@ mod ir_builder {
         InsertValue(String, IrAstTy, String, Vec<u64>),
         Load(String),
         Nop,
+        Not(String),
         Phi(Vec<(String, String)>),
         ReadRegister(String),
         Ret(IrAstTy, String),
@@ -718,6 +769,7 @@ mod ir_builder {
             fn_decl.selector,
             false,
         );
+        func.set_inline_hint(context, fn_decl.inline_hint);
 
         // Gather all the (new) arg values by name into a map.
         let mut arg_map: HashMap<String, Value> =
@@ -753,6 +805,7 @@ mod ir_builder {
             )
         }));
 
+        let mut unresolved_phis = Vec::new();
         for block in fn_decl.blocks {
             build_add_block_instructions(
                 context,
@@ -762,12 +815,23 @@ mod ir_builder {
                 &mut arg_map,
                 md_map,
                 unresolved_calls,
+                &mut unresolved_phis,
             );
         }
+        for (phi_val, block, pairs) in unresolved_phis {
+            for (block_name, val_name) in pairs {
+                block.add_phi_to(
+                    context,
+                    phi_val,
+                    *named_blocks.get(&block_name).unwrap(),
+                    *arg_map.get(&val_name).unwrap(),
+                );
+            }
+        }
         Ok(())
     }
 
-    #[allow(clippy::type_complexity)]
+    #[allow(clippy::type_complexity, clippy::too_many_arguments)]
     fn build_add_block_instructions(
         context: &mut Context,
         ir_block: IrAstBlock,
@@ -776,8 +840,10 @@ mod ir_builder {
         val_map: &mut HashMap<String, Value>,
         md_map: &HashMap<MdIdxRef, MetadataIndex>,
         unresolved_calls: &mut Vec<(Block, Value, String, Vec<Value>, Option<MetadataIndex>)>,
+        unresolved_phis: &mut Vec<(Value, Block, Vec<(String, String)>)>,
     ) {
         let block = named_blocks.get(&ir_block.label).unwrap();
+        let mut seen_phi = false;
         for ins in ir_block.instructions {
             let opt_ins_md_idx = ins.meta_idx.map(|mdi| md_map.get(&mdi).unwrap()).copied();
             let ins_val = match ins.op {
@@ -819,6 +885,24 @@ mod ir_builder {
                         .ins(context)
                         .asm_block(args, body, return_type, return_name, md_idx)
                 }
+                IrAstOperation::BinaryOp(op_str, lhs, rhs) => block.ins(context).binary_op(
+                    match op_str.as_str() {
+                        "add" => BinaryOpKind::Add,
+                        "sub" => BinaryOpKind::Sub,
+                        "mul" => BinaryOpKind::Mul,
+                        "div" => BinaryOpKind::Div,
+                        "mod" => BinaryOpKind::Mod,
+                        "and" => BinaryOpKind::And,
+                        "or" => BinaryOpKind::Or,
+                        "xor" => BinaryOpKind::Xor,
+                        "shl" => BinaryOpKind::Shl,
+                        "shr" => BinaryOpKind::Shr,
+                        _ => unreachable!("Bug in binary op kind rule."),
+                    },
+                    *val_map.get(&lhs).unwrap(),
+                    *val_map.get(&rhs).unwrap(),
+                    opt_ins_md_idx,
+                ),
                 IrAstOperation::Br(to_block_name) => {
                     let to_block = named_blocks.get(&to_block_name).unwrap();
                     block.ins(context).branch(*to_block, None, opt_ins_md_idx)
@@ -852,13 +936,20 @@ mod ir_builder {
                 IrAstOperation::Cmp(pred_str, lhs, rhs) => block.ins(context).cmp(
                     match pred_str.as_str() {
                         "eq" => Predicate::Equal,
+                        "lt" => Predicate::LessThan,
+                        "gt" => Predicate::GreaterThan,
                         _ => unreachable!("Bug in `cmp` predicate rule."),
                     },
                     *val_map.get(&lhs).unwrap(),
                     *val_map.get(&rhs).unwrap(),
                     opt_ins_md_idx,
                 ),
-                IrAstOperation::Const(val) => val.value.as_value(context, opt_ins_md_idx),
+                IrAstOperation::Const(val) => {
+                    // The metadata may follow the constant directly rather than the instruction.
+                    let md_idx = opt_ins_md_idx
+                        .or_else(|| val.meta_idx.and_then(|mdi| md_map.get(&mdi).copied()));
+                    val.value.as_value(context, md_idx)
+                }
                 IrAstOperation::ContractCall(params, coins, asset_id, gas) => {
                     block.ins(context).contract_call(
                         *val_map.get(&params).unwrap(),
@@ -919,15 +1010,20 @@ mod ir_builder {
                     .ins(context)
                     .load(*val_map.get(&src_name).unwrap(), opt_ins_md_idx),
                 IrAstOperation::Nop => block.ins(context).nop(),
+                IrAstOperation::Not(val) => block
+                    .ins(context)
+                    .not(*val_map.get(&val).unwrap(), opt_ins_md_idx),
                 IrAstOperation::Phi(pairs) => {
-                    for (block_name, val_name) in pairs {
-                        block.add_phi(
-                            context,
-                            *named_blocks.get(&block_name).unwrap(),
-                            *val_map.get(&val_name).unwrap(),
-                        );
-                    }
-                    block.get_phi(context)
+                    // The first phi is the block's own, any others are new.  The values may be
+                    // from later blocks so they're added once every block has been built.
+                    let phi_val = if seen_phi {
+                        block.new_phi(context)
+                    } else {
+                        block.get_phi(context)
+                    };
+                    seen_phi = true;
+                    unresolved_phis.push((phi_val, *block, pairs));
+                    phi_val
                 }
                 IrAstOperation::ReadRegister(reg_name) => block.ins(context).read_register(
                     match reg_name.as_str() {
@@ -998,7 +1094,10 @@ mod ir_builder {
                 _otherwise => None,
             })
             .fold(HashMap::new(), |mut md_map, (idx_ref, path)| {
-                let path_content = Arc::from(std::fs::read_to_string(path).unwrap().as_str());
+                // The source may not be available, e.g., when reading IR produced elsewhere.  The
+                // spans are still kept so they can be printed again, but can't be resolved.
+                let path_content =
+                    Arc::from(std::fs::read_to_string(path).unwrap_or_default().as_str());
                 let md_idx = context.metadata.insert(Metadatum::FileLocation(
                     Arc::new(path.clone()),
                     path_content,
@@ -1033,8 +1132,14 @@ mod ir_builder {
         // All of the call instructions are currently NOPs which need to be replaced with actual
         // calls.  We couldn't do it above until we'd gone and created all the functions first.
         //
-        // Now we can loop and find the callee function for each call and replace the NOPs.
+        // Now we can loop and find the callee function for each call and replace the NOPs.  A call
+        // may be passed the result of an earlier call, which must be replaced too.
+        let mut call_map: HashMap<Value, Value> = HashMap::new();
         for (block, nop, callee, args, opt_ins_md_idx) in unresolved_calls {
+            let args = args
+                .into_iter()
+                .map(|arg| call_map.get(&arg).copied().unwrap_or(arg))
+                .collect();
             let function = context
                 .functions
                 .iter()
@@ -1049,6 +1154,7 @@ mod ir_builder {
             let call_val =
                 Value::new_instruction(context, Instruction::Call(function, args), opt_ins_md_idx);
             block.replace_instruction(context, nop, call_val)?;
+            call_map.insert(nop, call_val);
         }
         Ok(())
     }
@@ -1065,6 +1171,17 @@ mod ir_builder {
         }
         bytes
     }
-}
 
-// -------------------------------------------------------------------------------------------------
+    // Remove the backslash from each `\\` or `\"` escape in a quoted path.
+    fn unescape(s: &str) -> String {
+        let mut unescaped = String::with_capacity(s.len());
+        let mut chars = s.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => unescaped.extend(chars.next()),
+                _otherwise => unescaped.push(ch),
+            }
+        }
+        unescaped
+    }
+}
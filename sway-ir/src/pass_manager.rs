@@ -0,0 +1,333 @@
+//! Management of the optimization passes.
+//!
+//! A [`PassManager`] holds a registry of named passes, each with a short description, and runs
+//! them in sequence over a set of functions.  The [`OptLevel`]s provide the standard sequences, or
+//! pipelines, used by the compiler.
+//!
+//! Passes may request analyses from the [`AnalysisResults`] they're given, which caches them per
+//! function until a pass reports that it has modified that function.
+
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{
+    analysis::{compute_predecessors, compute_users, DominatorTree},
+    block::Block,
+    context::Context,
+    error::IrError,
+    function::Function,
+    optimize,
+    value::Value,
+};
+
+/// The signature for every pass.  Returns whether `function` was modified.
+pub type PassFn = fn(&mut Context, &mut AnalysisResults, &Function) -> Result<bool, IrError>;
+
+/// A named optimization pass.
+#[derive(Clone, Copy)]
+pub struct Pass {
+    pub name: &'static str,
+    pub descr: &'static str,
+    pub run: PassFn,
+}
+
+/// Analyses computed for each function, kept until the function is modified.
+#[derive(Default)]
+pub struct AnalysisResults {
+    predecessors: HashMap<Function, HashMap<Block, Vec<Block>>>,
+    dominators: HashMap<Function, DominatorTree>,
+    users: HashMap<Function, HashMap<Value, Vec<Value>>>,
+}
+
+impl AnalysisResults {
+    /// Get the predecessors of every block in `function`.
+    pub fn predecessors(
+        &mut self,
+        context: &Context,
+        function: &Function,
+    ) -> &HashMap<Block, Vec<Block>> {
+        self.predecessors
+            .entry(*function)
+            .or_insert_with(|| compute_predecessors(context, function))
+    }
+
+    /// Get the dominator tree for `function`.
+    pub fn dominators(&mut self, context: &Context, function: &Function) -> &DominatorTree {
+        self.dominators
+            .entry(*function)
+            .or_insert_with(|| DominatorTree::compute(context, function))
+    }
+
+    /// Get the users of every value used within `function`.
+    pub fn users(&mut self, context: &Context, function: &Function) -> &HashMap<Value, Vec<Value>> {
+        self.users
+            .entry(*function)
+            .or_insert_with(|| compute_users(context, function))
+    }
+
+    /// Forget every analysis of `function`, which must be done once it is modified.
+    pub fn invalidate(&mut self, function: &Function) {
+        self.predecessors.remove(function);
+        self.dominators.remove(function);
+        self.users.remove(function);
+    }
+}
+
+/// The standard pass pipelines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OptLevel {
+    /// Only the passes required to generate code.
+    O0,
+    /// The general purpose optimizations.
+    #[default]
+    O1,
+    /// The same optimizations as `O1`, repeated for as long as each run reduces the number of
+    /// instructions.  There are no passes specific to size, so this is `O1` run to a fixed point,
+    /// which only shrinks the code by cleaning up after the previous run.
+    Os,
+}
+
+impl OptLevel {
+    /// The names of the passes run at this level, in order.
+    pub fn pipeline(&self) -> &'static [&'static str] {
+        match self {
+            OptLevel::O0 => &["inline"],
+            OptLevel::O1 | OptLevel::Os => &[
+                "inline",
+                "mem2reg",
+                "loadelim",
+                "storage",
+                "constcombine",
+                "cse",
+                "simplifycfg",
+                "dce",
+            ],
+        }
+    }
+
+    /// Whether the pipeline should be repeated while it reduces the number of instructions.
+    pub fn repeat_pipeline(&self) -> bool {
+        matches!(self, OptLevel::Os)
+    }
+}
+
+impl FromStr for OptLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "0" => Ok(OptLevel::O0),
+            "1" => Ok(OptLevel::O1),
+            "s" => Ok(OptLevel::Os),
+            _otherwise => Err(format!(
+                "Unknown optimization level '{s}', expecting one of '0', '1' or 's'."
+            )),
+        }
+    }
+}
+
+/// The maximum number of times an [`OptLevel`] pipeline is repeated, in case a pair of passes
+/// keep undoing each other's modifications.
+const MAX_PIPELINE_REPEATS: usize = 10;
+
+/// A registry of passes which can be run by name.
+pub struct PassManager {
+    passes: Vec<Pass>,
+    print_after: Vec<String>,
+    verify_each: bool,
+}
+
+impl Default for PassManager {
+    fn default() -> Self {
+        PassManager::new()
+    }
+}
+
+impl PassManager {
+    /// Create a pass manager with every pass in [`optimize`] registered.
+    pub fn new() -> Self {
+        let mut pass_mgr = PassManager {
+            passes: Vec::new(),
+            print_after: Vec::new(),
+            verify_each: false,
+        };
+        for pass in [
+            Pass {
+                name: "inline",
+                descr: "Inline every function call.",
+                run: |context, _, function| optimize::inline_all_function_calls(context, function),
+            },
+            Pass {
+                name: "inlinecost",
+                descr: "Inline the function calls chosen by a cost model and inlining hints.",
+                run: |context, _, function| {
+                    optimize::inline_function_calls_by_cost(
+                        context,
+                        function,
+                        &optimize::InlineCostModel::default(),
+                    )
+                },
+            },
+            Pass {
+                name: "mem2reg",
+                descr: "Promote non-escaping scalar locals to SSA values.",
+                run: |context, analyses, function| {
+                    let dom_tree = analyses.dominators(context, function);
+                    optimize::mem2reg_with_dominators(context, function, dom_tree)
+                },
+            },
+            Pass {
+                name: "constcombine",
+                descr: "Fold constant expressions and branches.",
+                run: |context, _, function| optimize::combine_constants(context, function),
+            },
+            Pass {
+                name: "cse",
+                descr: "Reuse the value of an identical pure instruction in a dominating block.",
+                run: |context, analyses, function| {
+                    let dom_tree = analyses.dominators(context, function);
+                    optimize::cse_with_dominators(context, function, dom_tree)
+                },
+            },
+            Pass {
+                name: "loadelim",
+                descr: "Reuse values already loaded from or stored to memory within a block.",
+                run: |context, _, function| optimize::eliminate_redundant_loads(context, function),
+            },
+            Pass {
+                name: "storage",
+                descr: "Reuse known storage slot values and remove overwritten storage stores.",
+                run: |context, analyses, function| {
+                    let preds = analyses.predecessors(context, function);
+                    optimize::coalesce_storage_accesses_with_predecessors(context, function, preds)
+                },
+            },
+            Pass {
+                name: "dce",
+                descr: "Remove unused instructions which have no side effects.",
+                run: |context, analyses, function| {
+                    let users = analyses.users(context, function);
+                    optimize::dce_with_users(context, function, users)
+                },
+            },
+            Pass {
+                name: "unreachable",
+                descr: "Remove blocks which are unreachable from the entry block.",
+                run: |context, _, function| optimize::remove_unreachable_blocks(context, function),
+            },
+            Pass {
+                name: "mergeblocks",
+                descr: "Merge blocks into their single unconditionally branching predecessor.",
+                run: |context, _, function| optimize::merge_blocks(context, function),
+            },
+            Pass {
+                name: "jumpthreading",
+                descr: "Redirect branches to blocks which only branch elsewhere.",
+                run: |context, _, function| optimize::thread_jumps(context, function),
+            },
+            Pass {
+                name: "simplifycfg",
+                descr: "Remove unreachable blocks, merge blocks and thread jumps to a fixpoint.",
+                run: |context, _, function| optimize::simplify_cfg(context, function),
+            },
+        ] {
+            pass_mgr.register(pass);
+        }
+        pass_mgr
+    }
+
+    /// Register a pass, replacing any already registered with the same name.
+    pub fn register(&mut self, pass: Pass) {
+        match self.passes.iter_mut().find(|reg| reg.name == pass.name) {
+            Some(reg) => *reg = pass,
+            None => self.passes.push(pass),
+        }
+    }
+
+    /// Find a registered pass by name.
+    pub fn lookup(&self, name: &str) -> Option<&Pass> {
+        self.passes.iter().find(|pass| pass.name == name)
+    }
+
+    /// Return every registered pass, in the order they were registered.
+    pub fn passes(&self) -> &[Pass] {
+        &self.passes
+    }
+
+    /// Print the IR to stdout after every run of the named pass.
+    pub fn print_after(&mut self, name: &str) {
+        self.print_after.push(name.to_owned());
+    }
+
+    /// Verify the IR after every pass, failing with the verification error if it's invalid.
+    pub fn verify_each(&mut self, verify_each: bool) {
+        self.verify_each = verify_each;
+    }
+
+    /// Run the named passes in order over each of `functions`.  Returns whether any modifications
+    /// were made.
+    pub fn run(
+        &self,
+        context: &mut Context,
+        pass_names: &[&str],
+        functions: &[Function],
+    ) -> Result<bool, IrError> {
+        let passes = pass_names
+            .iter()
+            .map(|name| {
+                self.lookup(name)
+                    .ok_or_else(|| IrError::UnknownPass(name.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut analyses = AnalysisResults::default();
+        let mut modified = false;
+        for pass in passes {
+            for function in functions {
+                if (pass.run)(context, &mut analyses, function)? {
+                    analyses.invalidate(function);
+                    modified = true;
+                }
+            }
+            if self.print_after.iter().any(|name| name == pass.name) {
+                println!("// IR after {}:\n{}", pass.name, context);
+            }
+            if self.verify_each {
+                *context = std::mem::take(context).verify()?;
+            }
+        }
+        Ok(modified)
+    }
+
+    /// Run the pipeline for `opt_level` over each of `functions`.  Returns whether any
+    /// modifications were made.
+    pub fn run_pipeline(
+        &self,
+        context: &mut Context,
+        opt_level: OptLevel,
+        functions: &[Function],
+    ) -> Result<bool, IrError> {
+        let mut modified = self.run(context, opt_level.pipeline(), functions)?;
+        if opt_level.repeat_pipeline() {
+            let mut size = num_instructions(context, functions);
+            for _ in 1..MAX_PIPELINE_REPEATS {
+                if !self.run(context, opt_level.pipeline(), functions)? {
+                    break;
+                }
+                modified = true;
+                let new_size = num_instructions(context, functions);
+                if new_size >= size {
+                    break;
+                }
+                size = new_size;
+            }
+        }
+        Ok(modified)
+    }
+}
+
+fn num_instructions(context: &Context, functions: &[Function]) -> usize {
+    functions
+        .iter()
+        .map(|function| function.instruction_iter(context).count())
+        .sum()
+}
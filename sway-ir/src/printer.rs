@@ -11,8 +11,8 @@ use crate::{
     block::Block,
     constant::{Constant, ConstantValue},
     context::Context,
-    function::{Function, FunctionContent},
-    instruction::{Instruction, Predicate, Register},
+    function::{Function, FunctionContent, InlineHint},
+    instruction::{BinaryOpKind, Instruction, Predicate, Register},
     irtype::Type,
     metadata::{MetadataIndex, Metadatum},
     module::{Kind, ModuleContent},
@@ -100,7 +100,7 @@ fn module_to_doc<'a>(
         match module.kind {
             Kind::Contract => "contract",
             Kind::Library => "library",
-            Kind::Predicate => "predicate ",
+            Kind::Predicate => "predicate",
             Kind::Script => "script",
         }
     )))
@@ -133,8 +133,13 @@ fn function_to_doc<'a>(
 ) -> Doc {
     Doc::line(
         Doc::text(format!(
-            "{}fn {}{}",
+            "{}{}fn {}{}",
             if function.is_public { "pub " } else { "" },
+            match function.inline_hint {
+                None => "",
+                Some(InlineHint::Always) => "inline(always) ",
+                Some(InlineHint::Never) => "inline(never) ",
+            },
             function.name,
             match function.selector {
                 None => "".to_owned(),
@@ -259,24 +264,30 @@ fn maybe_constant_to_doc(
     }
 }
 
-fn maybe_constant_phi_to_doc(
+// Prints each distinct constant among the operands of an instruction once.
+fn maybe_constants_to_doc(
     context: &Context,
     md_namer: &mut MetadataNamer,
     namer: &mut Namer,
-    caller: &Block,
-    callee: &Block,
+    values: &[Value],
 ) -> Doc {
-    if let ValueDatum::Instruction(Instruction::Phi(pairs)) =
-        &context.values[callee.get_phi(context).0].value
-    {
-        pairs
-            .iter()
-            .find(|(block, _)| block == caller)
-            .map(|(_, phi_val)| maybe_constant_to_doc(context, md_namer, namer, phi_val))
-            .unwrap_or(Doc::Empty)
-    } else {
-        unreachable!("Phi must be an instruction.")
-    }
+    let mut printed = Vec::new();
+    values.iter().fold(Doc::Empty, |doc, value| {
+        if printed.contains(value) {
+            doc
+        } else {
+            printed.push(*value);
+            doc.append(maybe_constant_to_doc(context, md_namer, namer, value))
+        }
+    })
+}
+
+fn phi_vals_from(context: &Context, caller: &Block, callee: &Block) -> Vec<Value> {
+    callee
+        .get_phi_vals_coming_from(context, caller)
+        .into_iter()
+        .map(|(_, phi_val)| phi_val)
+        .collect()
 }
 
 fn instruction_to_doc<'a>(
@@ -294,8 +305,34 @@ fn instruction_to_doc<'a>(
             Instruction::AsmBlock(asm, args) => {
                 asm_block_to_doc(context, md_namer, namer, ins_value, asm, args, span_md_idx)
             }
-            Instruction::Branch(to_block) => maybe_constant_phi_to_doc(
-                context, md_namer, namer, block, to_block,
+            Instruction::BinaryOp { op, lhs, rhs } => {
+                let op_str = match op {
+                    BinaryOpKind::Add => "add",
+                    BinaryOpKind::Sub => "sub",
+                    BinaryOpKind::Mul => "mul",
+                    BinaryOpKind::Div => "div",
+                    BinaryOpKind::Mod => "mod",
+                    BinaryOpKind::And => "and",
+                    BinaryOpKind::Or => "or",
+                    BinaryOpKind::Xor => "xor",
+                    BinaryOpKind::Shl => "shl",
+                    BinaryOpKind::Shr => "shr",
+                };
+                maybe_constant_to_doc(context, md_namer, namer, lhs)
+                    .append(maybe_constant_to_doc(context, md_namer, namer, rhs))
+                    .append(Doc::text_line(format!(
+                        "{} = {op_str} {} {}{}",
+                        namer.name(context, ins_value),
+                        namer.name(context, lhs),
+                        namer.name(context, rhs),
+                        md_namer.meta_as_string(context, span_md_idx, true)
+                    )))
+            }
+            Instruction::Branch(to_block) => maybe_constants_to_doc(
+                context,
+                md_namer,
+                namer,
+                &phi_vals_from(context, block, to_block),
             )
             .append(Doc::text_line(format!(
                 "br {}{}",
@@ -326,6 +363,8 @@ fn instruction_to_doc<'a>(
             Instruction::Cmp(pred, lhs_value, rhs_value) => {
                 let pred_str = match pred {
                     Predicate::Equal => "eq",
+                    Predicate::LessThan => "lt",
+                    Predicate::GreaterThan => "gt",
                 };
                 maybe_constant_to_doc(context, md_namer, namer, lhs_value)
                     .append(maybe_constant_to_doc(context, md_namer, namer, rhs_value))
@@ -344,13 +383,16 @@ fn instruction_to_doc<'a>(
             } => {
                 let true_label = &context.blocks[true_block.0].label;
                 let false_label = &context.blocks[false_block.0].label;
-                maybe_constant_phi_to_doc(context, md_namer, namer, block, true_block)
-                    .append(maybe_constant_to_doc(context, md_namer, namer, cond_value))
-                    .append(Doc::text_line(format!(
+                let mut operands = phi_vals_from(context, block, true_block);
+                operands.extend(phi_vals_from(context, block, false_block));
+                operands.push(*cond_value);
+                maybe_constants_to_doc(context, md_namer, namer, &operands).append(Doc::text_line(
+                    format!(
                         "cbr {}, {true_label}, {false_label}{}",
                         namer.name(context, cond_value),
                         md_namer.meta_as_string(context, span_md_idx, true),
-                    )))
+                    ),
+                ))
             }
             Instruction::ContractCall {
                 params,
@@ -373,21 +415,21 @@ fn instruction_to_doc<'a>(
                 array,
                 ty,
                 index_val,
-            } => maybe_constant_to_doc(context, md_namer, namer, index_val).append(Doc::text_line(
-                format!(
+            } => maybe_constant_to_doc(context, md_namer, namer, array)
+                .append(maybe_constant_to_doc(context, md_namer, namer, index_val))
+                .append(Doc::text_line(format!(
                     "{} = extract_element {}, {}, {}{}",
                     namer.name(context, ins_value),
                     namer.name(context, array),
                     Type::Array(*ty).as_string(context),
                     namer.name(context, index_val),
                     md_namer.meta_as_string(context, span_md_idx, true),
-                ),
-            )),
+                ))),
             Instruction::ExtractValue {
                 aggregate,
                 ty,
                 indices,
-            } => Doc::line(
+            } => maybe_constant_to_doc(context, md_namer, namer, aggregate).append(Doc::line(
                 Doc::text(format!(
                     "{} = extract_value {}, {}, ",
                     namer.name(context, ins_value),
@@ -405,7 +447,7 @@ fn instruction_to_doc<'a>(
                     None => Doc::Empty,
                     Some(_) => Doc::text(md_namer.meta_as_string(context, span_md_idx, true)),
                 }),
-            ),
+            )),
             Instruction::GetPointer {
                 base_ptr,
                 ptr_ty,
@@ -475,6 +517,13 @@ fn instruction_to_doc<'a>(
                 md_namer.meta_as_string(context, span_md_idx, true),
             )),
             Instruction::Nop => Doc::text_line(format!("{} = nop", namer.name(context, ins_value))),
+            Instruction::Not(value) => maybe_constant_to_doc(context, md_namer, namer, value)
+                .append(Doc::text_line(format!(
+                    "{} = not {}{}",
+                    namer.name(context, ins_value),
+                    namer.name(context, value),
+                    md_namer.meta_as_string(context, span_md_idx, true)
+                ))),
             Instruction::Phi(pairs) => {
                 if pairs.is_empty() {
                     Doc::Empty
@@ -679,7 +728,8 @@ fn metadata_to_doc(context: &Context, md_namer: &MetadataNamer) -> Doc {
 }
 
 impl Constant {
-    fn as_lit_string(&self, context: &Context) -> String {
+    /// Return the constant as it would be written in the IR, e.g., `u64 42`.
+    pub fn as_lit_string(&self, context: &Context) -> String {
         match &self.value {
             ConstantValue::Undef => format!("{} undef", self.ty.as_string(context)),
             ConstantValue::Unit => "unit ()".into(),
@@ -73,15 +73,13 @@ impl Value {
         Value(context.values.insert(content))
     }
 
-    /// Return this value's source span.
+    /// Return this value's source span, if it has one and its source is available.
     pub fn get_span(&self, context: &Context) -> Option<Span> {
-        // We unwrap the Result for now, until we refactor Span to not need a source string, in
-        // which case there will be no need to open and read a file, and no Result involved.
+        // A span read from textual IR refers to a source file which may not have been found, in
+        // which case it can't be converted until we refactor Span to not need a source string.
         context.values[self.0]
             .span_md_idx
-            .map(|idx| idx.to_span(context))
-            .transpose()
-            .expect("A valid span.")
+            .and_then(|idx| idx.to_span(context).ok())
     }
 
     /// Return whether this is a constant value.
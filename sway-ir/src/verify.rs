@@ -95,6 +95,7 @@ impl<'a> InstructionVerifier<'a> {
             if let ValueDatum::Instruction(instruction) = instruction {
                 match instruction {
                     Instruction::AsmBlock(..) => (),
+                    Instruction::BinaryOp { lhs, rhs, .. } => self.verify_binary_op(lhs, rhs)?,
                     Instruction::Branch(block) => self.verify_br(block)?,
                     Instruction::Call(func, args) => self.verify_call(func, args)?,
                     Instruction::Cmp(pred, lhs_value, rhs_value) => {
@@ -140,6 +141,7 @@ impl<'a> InstructionVerifier<'a> {
                     } => self.verify_insert_value(aggregate, ty, value, indices)?,
                     Instruction::Load(ptr) => self.verify_load(ptr)?,
                     Instruction::Nop => (),
+                    Instruction::Not(value) => self.verify_not(value)?,
                     Instruction::Phi(pairs) => self.verify_phi(&pairs[..])?,
                     Instruction::ReadRegister(_) => (),
                     Instruction::Ret(val, ty) => self.verify_ret(self.cur_function, val, ty)?,
@@ -243,6 +245,25 @@ impl<'a> InstructionVerifier<'a> {
         }
     }
 
+    fn verify_binary_op(&self, lhs_value: &Value, rhs_value: &Value) -> Result<(), IrError> {
+        // Both operands must be integers of the same width.
+        match (
+            lhs_value.get_type(self.context),
+            rhs_value.get_type(self.context),
+        ) {
+            (Some(Type::Uint(lhs_nbits)), Some(Type::Uint(rhs_nbits)))
+                if lhs_nbits == rhs_nbits =>
+            {
+                Ok(())
+            }
+            (Some(lhs_ty), Some(rhs_ty)) => Err(IrError::VerifyBinaryOpBadTypes(
+                lhs_ty.as_string(self.context),
+                rhs_ty.as_string(self.context),
+            )),
+            _otherwise => Err(IrError::VerifyBinaryOpUnknownTypes),
+        }
+    }
+
     fn verify_cmp(
         &self,
         _pred: &Predicate,
@@ -439,6 +460,14 @@ impl<'a> InstructionVerifier<'a> {
         }
     }
 
+    fn verify_not(&self, value: &Value) -> Result<(), IrError> {
+        match value.get_type(self.context) {
+            Some(Type::Uint(_)) | Some(Type::Bool) => Ok(()),
+            Some(ty) => Err(IrError::VerifyNotBadType(ty.as_string(self.context))),
+            None => Err(IrError::VerifyNotUnknownType),
+        }
+    }
+
     fn verify_phi(&self, pairs: &[(Block, Value)]) -> Result<(), IrError> {
         if pairs.is_empty() {
             Ok(())
@@ -19,10 +19,32 @@ fn ir_to_ir_tests() {
 
                 println!("--- TESTING: {}", path.display());
                 let path_str = path.file_name().unwrap().to_string_lossy();
-                if path_str.starts_with("inline") {
+                if path_str.starts_with("inlinecost") {
+                    test_function_pass(path, |context, function| {
+                        sway_ir::optimize::inline_function_calls_by_cost(
+                            context,
+                            function,
+                            &sway_ir::optimize::InlineCostModel::default(),
+                        )
+                    });
+                } else if path_str.starts_with("inline") {
                     test_inline(path);
                 } else if path_str.starts_with("constants") {
                     test_constants(path);
+                } else if path_str.starts_with("cse") {
+                    test_function_pass(path, sway_ir::optimize::cse);
+                } else if path_str.starts_with("dce") {
+                    test_function_pass(path, sway_ir::optimize::dce);
+                } else if path_str.starts_with("loadelim") {
+                    test_function_pass(path, sway_ir::optimize::eliminate_redundant_loads);
+                } else if path_str.starts_with("mem2reg") {
+                    test_function_pass(path, sway_ir::optimize::mem2reg);
+                } else if path_str.starts_with("pipeline_o1") {
+                    test_pipeline(path, sway_ir::OptLevel::O1);
+                } else if path_str.starts_with("simplifycfg") {
+                    test_function_pass(path, sway_ir::optimize::simplify_cfg);
+                } else if path_str.starts_with("storage") {
+                    test_function_pass(path, sway_ir::optimize::coalesce_storage_accesses);
                 } else {
                     panic!(
                         "File which doesn't match valid passes: {:?}",
@@ -106,3 +128,178 @@ fn test_constants(mut path: PathBuf) {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+fn test_function_pass(
+    mut path: PathBuf,
+    pass: fn(
+        &mut sway_ir::context::Context,
+        &sway_ir::function::Function,
+    ) -> Result<bool, sway_ir::error::IrError>,
+) {
+    let input_bytes = std::fs::read(&path).unwrap();
+    let input = String::from_utf8_lossy(&input_bytes);
+
+    path.set_extension("out_ir");
+
+    let expected_bytes = std::fs::read(&path).unwrap();
+    let expected = String::from_utf8_lossy(&expected_bytes);
+
+    let mut ir = match sway_ir::parser::parse(&input) {
+        Ok(ir) => ir,
+        Err(parse_err) => {
+            println!("{parse_err}");
+            panic!()
+        }
+    };
+
+    let fn_idcs: Vec<_> = ir.functions.iter().map(|func| func.0).collect();
+    for fn_idx in fn_idcs {
+        pass(&mut ir, &sway_ir::function::Function(fn_idx)).unwrap();
+    }
+    let output = sway_ir::printer::to_string(&ir);
+
+    if output != expected {
+        println!("{}", prettydiff::diff_lines(&expected, &output));
+    }
+    assert_eq!(output, expected);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+fn test_pipeline(mut path: PathBuf, opt_level: sway_ir::OptLevel) {
+    let input_bytes = std::fs::read(&path).unwrap();
+    let input = String::from_utf8_lossy(&input_bytes);
+
+    path.set_extension("out_ir");
+
+    let expected_bytes = std::fs::read(&path).unwrap();
+    let expected = String::from_utf8_lossy(&expected_bytes);
+
+    let mut ir = match sway_ir::parser::parse(&input) {
+        Ok(ir) => ir,
+        Err(parse_err) => {
+            println!("{parse_err}");
+            panic!()
+        }
+    };
+
+    let functions: Vec<_> = ir
+        .functions
+        .iter()
+        .map(|func| sway_ir::function::Function(func.0))
+        .collect();
+    let mut pass_mgr = sway_ir::PassManager::default();
+    pass_mgr.verify_each(true);
+    pass_mgr
+        .run_pipeline(&mut ir, opt_level, &functions)
+        .unwrap();
+    let output = sway_ir::printer::to_string(&ir);
+
+    if output != expected {
+        println!("{}", prettydiff::diff_lines(&expected, &output));
+    }
+    assert_eq!(output, expected);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[test]
+fn interpreter_tests() {
+    // Each program is run before and after being optimized, and must give the same result each
+    // time, which is expected in the first line comment.  The storage and contract calls must be
+    // the same too.
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let dir: PathBuf = format!("{}/tests/interpreter", manifest_dir).into();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        println!("--- TESTING: {}", path.display());
+
+        let input_bytes = std::fs::read(&path).unwrap();
+        let input = String::from_utf8_lossy(&input_bytes);
+        let expected = input
+            .lines()
+            .next()
+            .and_then(|line| line.strip_prefix("// result: "))
+            .expect("Interpreter tests must begin with a '// result: ' comment.");
+
+        let unoptimized = run_main(&input, None);
+        assert_eq!(unoptimized.result, expected);
+        for opt_level in [sway_ir::OptLevel::O1, sway_ir::OptLevel::Os] {
+            assert_eq!(run_main(&input, Some(opt_level)), unoptimized);
+        }
+    }
+}
+
+#[test]
+fn interpreter_arithmetic_fault() {
+    // The first `add` in overflow.ir overflows, whereas the arithmetic in loops.ir doesn't.
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    for (name, faults) in [("overflow", true), ("loops", false)] {
+        let path = format!("{}/tests/interpreter/{}.ir", manifest_dir, name);
+        let ir = sway_ir::parser::parse(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let main_fn = ir
+            .functions
+            .iter()
+            .map(|func| sway_ir::function::Function(func.0))
+            .find(|function| function.get_name(&ir) == "main")
+            .unwrap();
+        let mut interpreter = sway_ir::Interpreter::new(&ir);
+        interpreter.call(&main_fn, &[]).unwrap();
+        assert_eq!(interpreter.arithmetic_fault().is_some(), faults, "{}", name);
+    }
+}
+
+// The observable effects of running a program.
+#[derive(Debug, PartialEq)]
+struct Outcome {
+    result: String,
+    storage: Vec<([u8; 32], [u8; 32])>,
+    contract_calls: Vec<sway_ir::StubbedContractCall>,
+}
+
+// Run `main()`, optimizing the program first if `opt_level` is given.
+fn run_main(input: &str, opt_level: Option<sway_ir::OptLevel>) -> Outcome {
+    let mut ir = match sway_ir::parser::parse(input) {
+        Ok(ir) => ir,
+        Err(parse_err) => {
+            println!("{parse_err}");
+            panic!()
+        }
+    };
+
+    let functions: Vec<_> = ir
+        .functions
+        .iter()
+        .map(|func| sway_ir::function::Function(func.0))
+        .collect();
+    if let Some(opt_level) = opt_level {
+        let mut pass_mgr = sway_ir::PassManager::default();
+        pass_mgr.verify_each(true);
+        pass_mgr
+            .run_pipeline(&mut ir, opt_level, &functions)
+            .unwrap();
+    }
+
+    let main_fn = functions
+        .iter()
+        .find(|function| function.get_name(&ir) == "main")
+        .unwrap();
+    let mut interpreter = sway_ir::Interpreter::new(&ir);
+    let result = match interpreter.call(main_fn, &[]) {
+        Ok(constant) => constant.as_lit_string(&ir),
+        Err(ir_error) => ir_error.to_string(),
+    };
+    let mut storage: Vec<_> = interpreter
+        .storage()
+        .iter()
+        .map(|(key, slot)| (*key, *slot))
+        .collect();
+    storage.sort();
+    Outcome {
+        result,
+        storage,
+        contract_calls: interpreter.contract_calls().to_vec(),
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
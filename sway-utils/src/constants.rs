@@ -10,5 +10,6 @@ pub const SWAY_LIBRARY: &str = "library";
 pub const SWAY_SCRIPT: &str = "script";
 pub const SWAY_CONTRACT: &str = "contract";
 pub const DEFAULT_NODE_URL: &str = "127.0.0.1:4000";
+pub const DEFAULT_CONTRACT_SIZE_LIMIT: u64 = 16 * 1024 * 1024;
 pub const LANGUAGE_NAME: &str = "Sway";
 pub const STORAGE_DOMAIN_SEPARATOR: &str = "storage_";
@@ -155,11 +155,42 @@ pub fn run(filter_regex: Option<regex::Regex>) {
             ProgramState::Return(42),
         ),
         ("should_pass/language/array_basics", ProgramState::Return(1)), // true
-        // Disabled, pending decision on runtime OOB checks. ("array_dynamic_oob", ProgramState::Revert(1)),
+        (
+            "should_pass/language/array_dynamic_oob",
+            ProgramState::Revert(0xffff_ffff_ffff_0001),
+        ),
+        (
+            "should_pass/language/signed_integers",
+            ProgramState::Return(42),
+        ),
+        (
+            "should_pass/language/vec_basics",
+            ProgramState::Return(981234),
+        ),
+        (
+            "should_pass/language/wide_integers",
+            ProgramState::Return(42),
+        ),
+        (
+            "should_pass/language/wide_integer_overflow",
+            ProgramState::Revert(0xffff_ffff_ffff_0002),
+        ),
+        (
+            "should_pass/language/vec_index_oob",
+            ProgramState::Revert(0xffff_ffff_ffff_0001),
+        ),
+        (
+            "should_pass/language/vec_pop_empty",
+            ProgramState::Revert(0xffff_ffff_ffff_0001),
+        ),
         (
             "should_pass/language/abort_control_flow",
             ProgramState::Revert(42),
         ),
+        (
+            "should_pass/language/register_spilling",
+            ProgramState::Return(2080),
+        ),
         (
             "should_pass/language/array_generics",
             ProgramState::Return(1),
@@ -168,6 +199,10 @@ pub fn run(filter_regex: Option<regex::Regex>) {
             "should_pass/language/match_expressions_structs",
             ProgramState::Return(4),
         ),
+        (
+            "should_pass/language/match_expressions_integer_switch",
+            ProgramState::Return(1),
+        ), // true
         ("should_pass/stdlib/b512_test", ProgramState::Return(1)), // true
         ("should_pass/stdlib/block_height", ProgramState::Return(1)), // true
         (
@@ -370,6 +405,10 @@ pub fn run(filter_regex: Option<regex::Regex>) {
             "should_pass/test_contracts/test_fuel_coin_contract",
             "should_pass/require_contract_deployment/token_ops_test",
         ),
+        (
+            "should_pass/test_contracts/vec_contract",
+            "should_pass/require_contract_deployment/vec_contract_call",
+        ),
         /* Requires IR - TODO:enable when the IR pipeline is enabled by default
          * https://github.com/FuelLabs/sway/issues/981
         (
@@ -1,8 +1,8 @@
 script;
 
-fn main() -> u32 {
+fn main() -> u64 {
     let ary = [1, 2, 3, 4];
     let idx = 45;
-    // thread 'main' panicked at 'index out of bounds: the len is 3 but the index is 45'
+    // Reverts with the out of bounds revert code.
     ary[idx]
 }
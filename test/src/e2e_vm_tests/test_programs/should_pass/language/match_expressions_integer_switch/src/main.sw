@@ -0,0 +1,58 @@
+script;
+
+fn days_in_month(month: u64) -> u64 {
+    match month {
+        1 => { 31 },
+        2 => { 28 },
+        3 => { 31 },
+        4 => { 30 },
+        5 => { 31 },
+        6 => { 30 },
+        7 => { 31 },
+        8 => { 31 },
+        9 => { 30 },
+        10 => { 31 },
+        11 => { 30 },
+        12 => { 31 },
+        _ => { 0 },
+    }
+}
+
+fn sparse(x: u64) -> u64 {
+    match x {
+        1000000 => { 1 },
+        3 => { 2 },
+        18446744073709551615 => { 3 },
+        42 => { 4 },
+        3 => { 5 },
+        0 => { 6 },
+        other => { other + 100 },
+    }
+}
+
+fn small(x: u8) -> u64 {
+    match x {
+        0u8 => { 10 },
+        1u8 => { 11 },
+        2u8 => { 12 },
+        3u8 => { 13 },
+        4u8 => { 14 },
+        _ => { 15 },
+    }
+}
+
+fn main() -> bool {
+    days_in_month(1) == 31
+        && days_in_month(2) == 28
+        && days_in_month(6) == 30
+        && days_in_month(11) == 30
+        && days_in_month(12) == 31
+        && days_in_month(0) == 0
+        && days_in_month(13) == 0
+        && sparse(3) == 2
+        && sparse(18446744073709551615) == 3
+        && sparse(0) == 6
+        && sparse(7) == 107
+        && small(3u8) == 13
+        && small(200u8) == 15
+}
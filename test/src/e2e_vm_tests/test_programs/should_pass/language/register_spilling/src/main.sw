@@ -0,0 +1,84 @@
+script;
+
+// There are more values live at once than there are registers, so some must be spilled to
+// the stack.
+
+fn opaque(value: u64) -> u64 {
+    asm(r1: value) {
+        r1: u64
+    }
+}
+
+fn main() -> u64 {
+    let x = opaque(1);
+    let a0 = x + 0;
+    let a1 = x + 1;
+    let a2 = x + 2;
+    let a3 = x + 3;
+    let a4 = x + 4;
+    let a5 = x + 5;
+    let a6 = x + 6;
+    let a7 = x + 7;
+    let a8 = x + 8;
+    let a9 = x + 9;
+    let a10 = x + 10;
+    let a11 = x + 11;
+    let a12 = x + 12;
+    let a13 = x + 13;
+    let a14 = x + 14;
+    let a15 = x + 15;
+    let a16 = x + 16;
+    let a17 = x + 17;
+    let a18 = x + 18;
+    let a19 = x + 19;
+    let a20 = x + 20;
+    let a21 = x + 21;
+    let a22 = x + 22;
+    let a23 = x + 23;
+    let a24 = x + 24;
+    let a25 = x + 25;
+    let a26 = x + 26;
+    let a27 = x + 27;
+    let a28 = x + 28;
+    let a29 = x + 29;
+    let a30 = x + 30;
+    let a31 = x + 31;
+    let a32 = x + 32;
+    let a33 = x + 33;
+    let a34 = x + 34;
+    let a35 = x + 35;
+    let a36 = x + 36;
+    let a37 = x + 37;
+    let a38 = x + 38;
+    let a39 = x + 39;
+    let a40 = x + 40;
+    let a41 = x + 41;
+    let a42 = x + 42;
+    let a43 = x + 43;
+    let a44 = x + 44;
+    let a45 = x + 45;
+    let a46 = x + 46;
+    let a47 = x + 47;
+    let a48 = x + 48;
+    let a49 = x + 49;
+    let a50 = x + 50;
+    let a51 = x + 51;
+    let a52 = x + 52;
+    let a53 = x + 53;
+    let a54 = x + 54;
+    let a55 = x + 55;
+    let a56 = x + 56;
+    let a57 = x + 57;
+    let a58 = x + 58;
+    let a59 = x + 59;
+    let a60 = x + 60;
+    let a61 = x + 61;
+    let a62 = x + 62;
+    let a63 = x + 63;
+
+    a0 + a1 + a2 + a3 + a4 + a5 + a6 + a7 + a8 + a9 + a10 + a11 + a12 + a13 + a14 + a15 + a16
+        + a17 + a18 + a19 + a20 + a21 + a22 + a23 + a24 + a25 + a26 + a27 + a28 + a29 + a30
+        + a31 + a32 + a33 + a34 + a35 + a36 + a37 + a38 + a39 + a40 + a41 + a42 + a43 + a44
+        + a45 + a46 + a47 + a48 + a49 + a50 + a51 + a52 + a53 + a54 + a55 + a56 + a57 + a58
+        + a59 + a60 + a61 + a62 + a63
+}
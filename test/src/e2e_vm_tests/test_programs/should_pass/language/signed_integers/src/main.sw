@@ -0,0 +1,42 @@
+script;
+
+fn classify(x: i16) -> u64 {
+    // Literal patterns match negative values.
+    match x {
+        -1 => { 42 },
+        0 => { 1 },
+        _ => { 2 },
+    }
+}
+
+// Each failed check returns its own code, so that a failure says which one it was.
+fn main() -> u64 {
+    let a: i8 = 100;
+    let b: i16 = -300;
+    let c = 2147483647i32;
+    let d: i32 = -7;
+    let e: i64 = -1;
+
+    // Arithmetic wraps to the width of the type.
+    if a + a != -56 {
+        1
+    } else if b * 300 != -24464 {
+        2
+    } else if c + 1 != -2147483648 {
+        3
+    // Division and remainder truncate towards zero.
+    } else if d / 2 != -3 {
+        4
+    } else if d % 2 != -1 {
+        5
+    } else if 7 / d != -1 {
+        6
+    // Comparisons take the sign into account.
+    } else if e > 1 {
+        7
+    } else if -2 >= e {
+        8
+    } else {
+        classify(-1i16)
+    }
+}
@@ -0,0 +1,51 @@
+script;
+
+// The arithmetic is in asm so that this has no dependencies.
+fn add(a: u64, b: u64) -> u64 {
+    asm(a: a, b: b, r) {
+        add r a b;
+        r: u64
+    }
+}
+
+fn mul(a: u64, b: u64) -> u64 {
+    asm(a: a, b: b, r) {
+        mul r a b;
+        r: u64
+    }
+}
+
+fn lt(a: u64, b: u64) -> bool {
+    asm(a: a, b: b, r) {
+        lt r a b;
+        r: bool
+    }
+}
+
+fn main() -> u64 {
+    // The vector starts with no buffer and is reallocated as it grows, keeping the elements it
+    // already has.
+    let v: Vec<u64> = Vec::new();
+    let mut i = 0;
+    while lt(i, 10) {
+        v.push(mul(i, i));
+        i = add(i, 1);
+    }
+    let last = v.pop();
+    let mut sum = 0;
+    let mut j = 0;
+    while lt(j, v.len()) {
+        sum = add(sum, v[j]);
+        j = add(j, 1);
+    }
+
+    // Copies of a vector share its elements.
+    let w: Vec<u64> = Vec::with_capacity(2);
+    let x = w;
+    w.push(3);
+    x.push(4);
+    w.push(5);
+
+    // 81 * 1000 + 9 * 100000 + 204 + 3 * 10
+    add(mul(last, 1000), add(mul(v.len(), 100000), add(sum, mul(x.len(), 10))))
+}
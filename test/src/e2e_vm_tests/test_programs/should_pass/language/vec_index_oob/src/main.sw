@@ -0,0 +1,8 @@
+script;
+
+fn main() -> u64 {
+    let v: Vec<u64> = Vec::new();
+    v.push(1);
+    // Reverts with the out of bounds revert code.
+    v[v.len()]
+}
@@ -0,0 +1,7 @@
+script;
+
+fn main() -> u64 {
+    let v: Vec<u64> = Vec::new();
+    // Reverts with the out of bounds revert code.
+    v.pop()
+}
@@ -0,0 +1,7 @@
+script;
+
+fn main() -> bool {
+    let max: u128 = 340282366920938463463374607431768211455u128;
+    // Reverts with the integer overflow revert code.
+    max + 1 == 0
+}
@@ -0,0 +1,41 @@
+script;
+
+fn classify(x: u256) -> u64 {
+    // Literal patterns match wide values.
+    match x {
+        18446744073709551616u256 => { 42 },
+        0 => { 1 },
+        _ => { 2 },
+    }
+}
+
+// Each failed check returns its own code, so that a failure says which one it was.
+fn main() -> u64 {
+    let max_word: u128 = 18446744073709551615;
+    let a: u128 = max_word + 1;
+    let b: u256 = 340282366920938463463374607431768211459u256;
+    let c: u256 = 1267650600228229401496703205383u256;
+
+    // Carries cross words.
+    if a != 18446744073709551616u128 {
+        1
+    } else if a - 1 != max_word {
+        2
+    } else if a * max_word != 340282366920938463444927863358058659840u128 {
+        3
+    } else if b * c != 431359146674410236714672241394696067350443831805577470154170251083797u256 {
+        4
+    // Division and remainder, kept to `u128` since each one loops over every bit.
+    } else if (a * max_word) / max_word != a {
+        5
+    } else if (a + 5) % max_word != 6 {
+        6
+    // Comparisons decide on the most significant word which differs.
+    } else if a <= max_word {
+        7
+    } else if b < c {
+        8
+    } else {
+        classify(18446744073709551616u256)
+    }
+}
@@ -0,0 +1,50 @@
+script;
+
+abi VecTest {
+    fn append(vals: Vec<u64>, val: u64) -> Vec<u64>;
+    fn doubled(vals: Vec<u64>) -> Vec<u64>;
+}
+
+// The arithmetic is in asm so that this has no dependencies.
+fn add(a: u64, b: u64) -> u64 {
+    asm(a: a, b: b, r) {
+        add r a b;
+        r: u64
+    }
+}
+
+fn mul(a: u64, b: u64) -> u64 {
+    asm(a: a, b: b, r) {
+        mul r a b;
+        r: u64
+    }
+}
+
+fn main() -> u64 {
+    let caller = abi(VecTest, 0x918ecf861b66f4be64ef212025fe73e3b83820d2e577dee3efc4bded26bcf2f2);
+    let v: Vec<u64> = Vec::new();
+    v.push(1);
+    v.push(2);
+
+    // The contract pushes to its own copy of `v`, and the vectors it returns are copied here.
+    let w = caller.append {
+        gas: 50000
+    }
+    (v, 3);
+    let d = caller.doubled {
+        gas: 50000
+    }
+    (w);
+
+    // An empty vector makes the round trip too.
+    let e: Vec<u64> = Vec::new();
+    let de = caller.doubled {
+        gas: 50000
+    }
+    (e);
+    de.push(7);
+
+    // 2 * 1000 + 3 * 100 + 3 * 10 + 6 + (1 + 7) * 10000
+    let r = add(mul(v.len(), 1000), add(mul(d.len(), 100), add(mul(w[2], 10), d[2])));
+    add(mul(add(de.len(), de[0]), 10000), r)
+}
@@ -0,0 +1,45 @@
+contract;
+
+abi VecTest {
+    fn append(vals: Vec<u64>, val: u64) -> Vec<u64>;
+    fn doubled(vals: Vec<u64>) -> Vec<u64>;
+}
+
+// The arithmetic is in asm so that this has no dependencies.
+fn add(a: u64, b: u64) -> u64 {
+    asm(a: a, b: b, r) {
+        add r a b;
+        r: u64
+    }
+}
+
+fn mul(a: u64, b: u64) -> u64 {
+    asm(a: a, b: b, r) {
+        mul r a b;
+        r: u64
+    }
+}
+
+fn lt(a: u64, b: u64) -> bool {
+    asm(a: a, b: b, r) {
+        lt r a b;
+        r: bool
+    }
+}
+
+impl VecTest for Contract {
+    fn append(vals: Vec<u64>, val: u64) -> Vec<u64> {
+        vals.push(val);
+        vals
+    }
+
+    fn doubled(vals: Vec<u64>) -> Vec<u64> {
+        let doubled: Vec<u64> = Vec::new();
+        let mut i = 0;
+        while lt(i, vals.len()) {
+            doubled.push(mul(vals[i], 2));
+            i = add(i, 1);
+        }
+        doubled
+    }
+}